@@ -1,12 +1,166 @@
+use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Structs of the Arrow C data and stream interfaces, as defined by the Arrow specification, for the functions
+/// exporting results. Guarded so the header can be combined with Arrow's own definitions.
+const ARROW_C_DATA_INTERFACE: &str = r#"
+#ifndef ARROW_C_DATA_INTERFACE
+#define ARROW_C_DATA_INTERFACE
+
+#define ARROW_FLAG_DICTIONARY_ORDERED 1
+#define ARROW_FLAG_NULLABLE 2
+#define ARROW_FLAG_MAP_KEYS_SORTED 4
+
+struct ArrowSchema {
+  const char* format;
+  const char* name;
+  const char* metadata;
+  int64_t flags;
+  int64_t n_children;
+  struct ArrowSchema** children;
+  struct ArrowSchema* dictionary;
+  void (*release)(struct ArrowSchema*);
+  void* private_data;
+};
+
+struct ArrowArray {
+  int64_t length;
+  int64_t null_count;
+  int64_t offset;
+  int64_t n_buffers;
+  int64_t n_children;
+  const void** buffers;
+  struct ArrowArray** children;
+  struct ArrowArray* dictionary;
+  void (*release)(struct ArrowArray*);
+  void* private_data;
+};
+
+#endif  // ARROW_C_DATA_INTERFACE
+
+#ifndef ARROW_C_STREAM_INTERFACE
+#define ARROW_C_STREAM_INTERFACE
+
+struct ArrowArrayStream {
+  int (*get_schema)(struct ArrowArrayStream*, struct ArrowSchema* out);
+  int (*get_next)(struct ArrowArrayStream*, struct ArrowArray* out);
+  const char* (*get_last_error)(struct ArrowArrayStream*);
+  void (*release)(struct ArrowArrayStream*);
+  void* private_data;
+};
+
+#endif  // ARROW_C_STREAM_INTERFACE
+
+typedef struct ArrowSchema ArrowSchema;
+typedef struct ArrowArray ArrowArray;
+typedef struct ArrowArrayStream ArrowArrayStream;
+"#;
+
+/// Look up the resolved version of a dependency in Cargo.lock
+fn locked_version(lockfile: &str, package: &str) -> Option<String> {
+    let header = format!("name = \"{package}\"\n");
+    let start = lockfile.find(&header)? + header.len();
+    let line = lockfile[start..].lines().next()?;
+    let version = line.strip_prefix("version = \"")?.strip_suffix('"')?;
+    Some(version.to_string())
+}
+
+/// Files whose changes move HEAD to another commit: HEAD itself, the ref it points to and the packed refs
+fn git_head_files(crate_dir: &str) -> Vec<String> {
+    let Some(git_dir) = Command::new("git")
+        .args(["rev-parse", "--absolute-git-dir"])
+        .current_dir(crate_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+    else {
+        return Vec::new();
+    };
+    let git_dir = Path::new(git_dir.trim());
+    let mut files = vec![git_dir.join("HEAD"), git_dir.join("packed-refs")];
+    let head = fs::read_to_string(git_dir.join("HEAD")).unwrap_or_default();
+    if let Some(head_ref) = head.strip_prefix("ref: ") {
+        files.push(git_dir.join(head_ref.trim()));
+    }
+    files.into_iter().filter(|file| file.exists()).map(|file| file.display().to_string()).collect()
+}
+
+fn git_hash(crate_dir: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(crate_dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
 
 fn main() {
     let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
 
+    // Listing any file replaces cargo's default of rerunning on every change to the package, so the
+    // sources read by cbindgen are listed along with the inputs of the build information
+    for input in ["build.rs", "src", "Cargo.lock"] {
+        println!("cargo:rerun-if-changed={input}");
+    }
+    for file in git_head_files(&crate_dir) {
+        println!("cargo:rerun-if-changed={file}");
+    }
+
+    // Build information exposed through datafusion_build_info()
+    let lockfile = fs::read_to_string(Path::new(&crate_dir).join("Cargo.lock")).unwrap_or_default();
+    for (package, var) in [
+        ("datafusion", "DATAFUSION_C_API_DATAFUSION_VERSION"),
+        ("arrow", "DATAFUSION_C_API_ARROW_VERSION"),
+        ("iceberg-rust", "DATAFUSION_C_API_ICEBERG_RUST_VERSION"),
+    ] {
+        let version = locked_version(&lockfile, package).unwrap_or_else(|| "unknown".to_string());
+        println!("cargo:rustc-env={var}={version}");
+    }
+
+    let git_hash = git_hash(&crate_dir).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=DATAFUSION_C_API_GIT_HASH={git_hash}");
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=DATAFUSION_C_API_FEATURES={}", features.join(","));
+    println!("cargo:rustc-env=DATAFUSION_C_API_TARGET={}", env::var("TARGET").unwrap());
+    println!("cargo:rustc-env=DATAFUSION_C_API_PROFILE={}", env::var("PROFILE").unwrap());
+
+    // Functions of optional features are declared under a guard that C code built against a library
+    // with the feature defines. The guard is not defined in the header, which is the same whatever
+    // features are built, so building with a feature leaves the tracked header unchanged.
+    let optional_features = [("avro", "DATAFUSION_C_API_AVRO")];
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        after_includes: Some(ARROW_C_DATA_INTERFACE.to_string()),
+        defines: optional_features
+            .iter()
+            .map(|(feature, define)| (format!("feature = {feature}"), define.to_string()))
+            .collect(),
+        export: cbindgen::ExportConfig {
+            rename: HashMap::from([
+                ("FFI_ArrowArray".to_string(), "ArrowArray".to_string()),
+                ("FFI_ArrowSchema".to_string(), "ArrowSchema".to_string()),
+                ("FFI_ArrowArrayStream".to_string(), "ArrowArrayStream".to_string()),
+            ]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
     cbindgen::Builder::new()
         .with_crate(crate_dir)
-        .with_language(cbindgen::Language::C)
+        .with_config(config)
         .generate()
         .expect("Unable to generate bindings")
         .write_to_file("include/datafusion.h");
-} 
\ No newline at end of file
+}
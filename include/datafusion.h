@@ -3,14 +3,335 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+#ifndef ARROW_C_DATA_INTERFACE
+#define ARROW_C_DATA_INTERFACE
+
+#define ARROW_FLAG_DICTIONARY_ORDERED 1
+#define ARROW_FLAG_NULLABLE 2
+#define ARROW_FLAG_MAP_KEYS_SORTED 4
+
+struct ArrowSchema {
+  const char* format;
+  const char* name;
+  const char* metadata;
+  int64_t flags;
+  int64_t n_children;
+  struct ArrowSchema** children;
+  struct ArrowSchema* dictionary;
+  void (*release)(struct ArrowSchema*);
+  void* private_data;
+};
+
+struct ArrowArray {
+  int64_t length;
+  int64_t null_count;
+  int64_t offset;
+  int64_t n_buffers;
+  int64_t n_children;
+  const void** buffers;
+  struct ArrowArray** children;
+  struct ArrowArray* dictionary;
+  void (*release)(struct ArrowArray*);
+  void* private_data;
+};
+
+#endif  // ARROW_C_DATA_INTERFACE
+
+#ifndef ARROW_C_STREAM_INTERFACE
+#define ARROW_C_STREAM_INTERFACE
+
+struct ArrowArrayStream {
+  int (*get_schema)(struct ArrowArrayStream*, struct ArrowSchema* out);
+  int (*get_next)(struct ArrowArrayStream*, struct ArrowArray* out);
+  const char* (*get_last_error)(struct ArrowArrayStream*);
+  void (*release)(struct ArrowArrayStream*);
+  void* private_data;
+};
+
+#endif  // ARROW_C_STREAM_INTERFACE
+
+typedef struct ArrowSchema ArrowSchema;
+typedef struct ArrowArray ArrowArray;
+typedef struct ArrowArrayStream ArrowArrayStream;
+
+
 #define DATAFUSION_OK 0
 
 #define DATAFUSION_ERROR -1
 
+#define DATAFUSION_INVALID_ARGUMENT -2
+
+/**
+ * A handle was already freed or is not of the expected type
+ */
+#define DATAFUSION_INVALID_HANDLE -3
+
+/**
+ * The library panicked; the panic message is available as the last error
+ */
+#define DATAFUSION_PANIC -4
+
+/**
+ * The requested value is null
+ */
+#define DATAFUSION_NULL_VALUE -5
+
+/**
+ * The requested value does not have the expected type
+ */
+#define DATAFUSION_TYPE_MISMATCH -6
+
+/**
+ * A configured resource limit, such as the context's maximum result size, was exceeded
+ */
+#define DATAFUSION_RESOURCE_EXHAUSTED -7
+
+/**
+ * The query was cancelled
+ */
+#define DATAFUSION_CANCELLED -8
+
+/**
+ * The query did not finish within its timeout
+ */
+#define DATAFUSION_TIMEOUT -9
+
+/**
+ * A table of the same name is already registered with the context
+ */
+#define DATAFUSION_ALREADY_EXISTS -10
+
+#define DATAFUSION_JOIN_INNER 0
+
+#define DATAFUSION_JOIN_LEFT 1
+
+#define DATAFUSION_JOIN_RIGHT 2
+
+#define DATAFUSION_JOIN_FULL 3
+
+/**
+ * Rows of the left DataFrame with a match in the right one, with only the left columns
+ */
+#define DATAFUSION_JOIN_LEFT_SEMI 4
+
+#define DATAFUSION_JOIN_RIGHT_SEMI 5
+
+/**
+ * Rows of the left DataFrame without a match in the right one, with only the left columns
+ */
+#define DATAFUSION_JOIN_LEFT_ANTI 6
+
+#define DATAFUSION_JOIN_RIGHT_ANTI 7
+
+#define DATAFUSION_IPC_STREAM 0
+
+#define DATAFUSION_IPC_FILE 1
+
+#define DATAFUSION_OP_EQ 0
+
+#define DATAFUSION_OP_NOT_EQ 1
+
+#define DATAFUSION_OP_LT 2
+
+#define DATAFUSION_OP_LT_EQ 3
+
+#define DATAFUSION_OP_GT 4
+
+#define DATAFUSION_OP_GT_EQ 5
+
+#define DATAFUSION_OP_PLUS 6
+
+#define DATAFUSION_OP_MINUS 7
+
+#define DATAFUSION_OP_MULTIPLY 8
+
+#define DATAFUSION_OP_DIVIDE 9
+
+#define DATAFUSION_OP_MODULO 10
+
+#define DATAFUSION_OP_AND 11
+
+#define DATAFUSION_OP_OR 12
+
+#define DATAFUSION_WRITE_APPEND 0
+
+/**
+ * Replace all rows of the table
+ */
+#define DATAFUSION_WRITE_OVERWRITE 1
+
+#define DATAFUSION_LOG_OFF 0
+
+#define DATAFUSION_LOG_ERROR 1
+
+#define DATAFUSION_LOG_WARN 2
+
+#define DATAFUSION_LOG_INFO 3
+
+#define DATAFUSION_LOG_DEBUG 4
+
+#define DATAFUSION_LOG_TRACE 5
+
+#define DATAFUSION_FORMAT_CSV 0
+
+#define DATAFUSION_FORMAT_PARQUET 1
+
+#define DATAFUSION_FORMAT_JSON 2
+
+#define DATAFUSION_FORMAT_ARROW 3
+
+#define DATAFUSION_FORMAT_AVRO 4
+
+#define DATAFUSION_TYPE_NULL 0
+
+#define DATAFUSION_TYPE_BOOLEAN 1
+
+#define DATAFUSION_TYPE_INT8 2
+
+#define DATAFUSION_TYPE_INT16 3
+
+#define DATAFUSION_TYPE_INT32 4
+
+#define DATAFUSION_TYPE_INT64 5
+
+#define DATAFUSION_TYPE_UINT8 6
+
+#define DATAFUSION_TYPE_UINT16 7
+
+#define DATAFUSION_TYPE_UINT32 8
+
+#define DATAFUSION_TYPE_UINT64 9
+
+#define DATAFUSION_TYPE_FLOAT16 10
+
+#define DATAFUSION_TYPE_FLOAT32 11
+
+#define DATAFUSION_TYPE_FLOAT64 12
+
+#define DATAFUSION_TYPE_UTF8 13
+
+#define DATAFUSION_TYPE_LARGE_UTF8 14
+
+#define DATAFUSION_TYPE_BINARY 15
+
+#define DATAFUSION_TYPE_LARGE_BINARY 16
+
+#define DATAFUSION_TYPE_FIXED_SIZE_BINARY 17
+
+#define DATAFUSION_TYPE_DATE32 18
+
+#define DATAFUSION_TYPE_DATE64 19
+
+#define DATAFUSION_TYPE_TIMESTAMP 20
+
+#define DATAFUSION_TYPE_TIME32 21
+
+#define DATAFUSION_TYPE_TIME64 22
+
+#define DATAFUSION_TYPE_DURATION 23
+
+#define DATAFUSION_TYPE_INTERVAL 24
+
+#define DATAFUSION_TYPE_DECIMAL128 25
+
+#define DATAFUSION_TYPE_DECIMAL256 26
+
+#define DATAFUSION_TYPE_LIST 27
+
+#define DATAFUSION_TYPE_LARGE_LIST 28
+
+#define DATAFUSION_TYPE_FIXED_SIZE_LIST 29
+
+#define DATAFUSION_TYPE_STRUCT 30
+
+#define DATAFUSION_TYPE_MAP 31
+
+#define DATAFUSION_TYPE_DICTIONARY 32
+
+/**
+ * Any other type (views, unions, run-end encoded); see datafusion_result_column_type_name
+ */
+#define DATAFUSION_TYPE_OTHER 99
+
+#define DATAFUSION_TIME_UNIT_SECOND 0
+
+#define DATAFUSION_TIME_UNIT_MILLISECOND 1
+
+#define DATAFUSION_TIME_UNIT_MICROSECOND 2
+
+#define DATAFUSION_TIME_UNIT_NANOSECOND 3
+
+#define DATAFUSION_TIME_UNIT_DAY 4
+
 typedef struct DataFusionContext DataFusionContext;
 
+/**
+ * A query planned but not executed, to be transformed into new DataFrames or executed any number
+ * of times
+ */
+typedef struct DataFusionDataFrame DataFusionDataFrame;
+
+/**
+ * Structured error describing the last failure on a thread.
+ *
+ * Each error carries a code, a message and an optional source error, so nested
+ * DataFusion/Iceberg failures can be walked from the outermost context down to the root cause.
+ *
+ * Fallible calls do not return errors through a `DataFusionError**` out-parameter: that slot is
+ * taken by the `char** error_out` message every entry point has, so the structured error of a
+ * failed call is retrieved afterwards with datafusion_last_error.
+ */
+typedef struct DataFusionError DataFusionError;
+
+/**
+ * An expression built from columns, literals, operators and function calls, to use in place of
+ * the SQL expressions parsed by the datafusion_dataframe_* functions. Expressions are immutable:
+ * building on one leaves it unchanged, and it can be used any number of times.
+ */
+typedef struct DataFusionExpr DataFusionExpr;
+
+/**
+ * A query running in the background that can be waited for or cancelled
+ */
+typedef struct DataFusionQuery DataFusionQuery;
+
 typedef struct DataFusionResult DataFusionResult;
 
+/**
+ * Forward-only cursor over the rows of a result, across all of its batches
+ */
+typedef struct DataFusionRowCursor DataFusionRowCursor;
+
+/**
+ * A standalone Arrow schema, or the children of a nested field
+ */
+typedef struct DataFusionSchema DataFusionSchema;
+
+/**
+ * The outcomes of the statements of a SQL script, in script order
+ */
+typedef struct DataFusionScript DataFusionScript;
+
+/**
+ * A query planned once with `$1`, `$2`, ... placeholders, executed with the values bound to them
+ */
+typedef struct DataFusionStatement DataFusionStatement;
+
+/**
+ * A query whose batches are produced one at a time as the caller asks for them
+ */
+typedef struct DataFusionStream DataFusionStream;
+
+/**
+ * A list of strings, such as the names of the tables of a schema
+ */
+typedef struct DataFusionStringList DataFusionStringList;
+
+/**
+ * Columns copied from C arrays, to register as an in-memory table without an Arrow library
+ */
+typedef struct DataFusionTableBuilder DataFusionTableBuilder;
+
 typedef struct IcebergCatalog IcebergCatalog;
 
 typedef struct IcebergPartitionSpec IcebergPartitionSpec;
@@ -19,133 +340,670 @@ typedef struct IcebergSchema IcebergSchema;
 
 typedef struct IcebergTable IcebergTable;
 
+/**
+ * Summary statistics of a result
+ */
+typedef struct DataFusionResultStats {
+  int64_t batch_count;
+  int64_t total_rows;
+  /**
+   * Approximate memory used by the result's Arrow buffers, in bytes
+   */
+  int64_t memory_bytes;
+} DataFusionResultStats;
+
+/**
+ * Options for writing Parquet files from a DataFrame. Pass null to use the defaults of
+ * datafusion_parquet_write_options_default.
+ */
+typedef struct DataFusionParquetWriteOptions {
+  /**
+   * Compression codec as accepted by the `datafusion.execution.parquet.compression` setting, e.g.
+   * "snappy", "zstd(3)" or "uncompressed", or null for the context's setting ("zstd(3)" by default)
+   */
+  const char *compression;
+  /**
+   * Maximum number of rows per row group, or 0 for the context's setting
+   */
+  int64_t max_row_group_size;
+  /**
+   * Columns to partition the files by into hive-style `column=value` directories, leaving the
+   * columns out of the files
+   */
+  const char *const *partition_columns;
+  uintptr_t partition_column_count;
+} DataFusionParquetWriteOptions;
+
+/**
+ * The unscaled value of a Decimal128 as a 128-bit two's complement integer split into two halves
+ */
+typedef struct DataFusionDecimal128 {
+  uint64_t low;
+  uint64_t high;
+} DataFusionDecimal128;
+
+/**
+ * Options for writing CSV. Pass null to use the defaults of datafusion_csv_options_default.
+ */
+typedef struct DataFusionCsvOptions {
+  /**
+   * Field delimiter, ',' by default
+   */
+  uint8_t delimiter;
+  /**
+   * Whether to write a header row with the column names, true by default
+   */
+  bool header;
+  /**
+   * Quote character, '"' by default
+   */
+  uint8_t quote;
+} DataFusionCsvOptions;
+
+/**
+ * Number of live handles of each type, as counted since handle tracking was enabled
+ */
+typedef struct DataFusionHandleCounts {
+  int64_t contexts;
+  int64_t results;
+  int64_t schemas;
+  int64_t cursors;
+  int64_t streams;
+  int64_t queries;
+  int64_t statements;
+  int64_t scripts;
+  int64_t dataframes;
+  int64_t exprs;
+  int64_t table_builders;
+  int64_t string_lists;
+  int64_t errors;
+  int64_t iceberg_catalogs;
+  int64_t iceberg_schemas;
+  int64_t iceberg_partition_specs;
+  int64_t iceberg_tables;
+} DataFusionHandleCounts;
+
+/**
+ * Callback receiving log records: level, target (e.g. `datafusion_optimizer`), message and user data.
+ * The strings are only valid for the duration of the call. The callback may be invoked
+ * concurrently from any thread that runs queries, including tokio worker threads.
+ */
+typedef void (*DataFusionLogCallback)(int level,
+                                      const char *target,
+                                      const char *message,
+                                      void *user_data);
+
+/**
+ * The value of a DataFusionScalar, in the field selected by its type
+ */
+typedef union DataFusionScalarValue {
+  bool boolean;
+  int64_t int64;
+  double float64;
+  /**
+   * Null terminated UTF-8, copied when the scalar is used
+   */
+  const char *utf8;
+} DataFusionScalarValue;
+
+/**
+ * A scalar value passed from C, such as the value of a query parameter. `data_type` is
+ * DATAFUSION_TYPE_NULL, DATAFUSION_TYPE_BOOLEAN, DATAFUSION_TYPE_INT64, DATAFUSION_TYPE_FLOAT64 or
+ * DATAFUSION_TYPE_UTF8 and selects the field of `value` holding the value (none for NULL).
+ */
+typedef struct DataFusionScalar {
+  int data_type;
+  union DataFusionScalarValue value;
+} DataFusionScalar;
+
+/**
+ * Callback receiving the progress of a query: the rows produced so far by the scans at the leaves
+ * of its plan, the bytes they read from storage (reported by Parquet scans, 0 for other sources),
+ * and the user data
+ */
+typedef void (*DataFusionProgressCallback)(int64_t rows_produced,
+                                           int64_t bytes_scanned,
+                                           void *user_data);
+
+/**
+ * Callback receiving the outcome of an asynchronous query: either a result or an error, the other
+ * being null, and the user data. Both are owned by the callback, which must free them with
+ * datafusion_result_free or datafusion_error_free. The callback runs on a runtime worker thread.
+ */
+typedef void (*DataFusionQueryCallback)(struct DataFusionResult *result,
+                                        struct DataFusionError *error,
+                                        void *user_data);
+
+/**
+ * Options for registering CSV files. Pass null to use the defaults of
+ * datafusion_csv_read_options_default.
+ */
+typedef struct DataFusionCsvReadOptions {
+  /**
+   * Field delimiter, ',' by default
+   */
+  uint8_t delimiter;
+  /**
+   * Quote character, '"' by default
+   */
+  uint8_t quote;
+  /**
+   * Escape character of quotes within quoted fields, or 0 for none (quotes are then escaped by
+   * doubling them), 0 by default
+   */
+  uint8_t escape;
+  /**
+   * Whether the first line of each file is a header with the column names, true by default.
+   * Without a header the columns are named column_1, column_2, ...
+   */
+  bool has_header;
+  /**
+   * Number of rows read to infer the column types, 1000 by default, or SIZE_MAX to read every
+   * row. Rows after them holding values of another type fail the queries reading them.
+   */
+  uintptr_t schema_infer_max_records;
+  /**
+   * Whether to infer the column types, true by default. Otherwise every column is read as Utf8,
+   * keeping values such as zip codes with leading zeros as written, to be cast in queries.
+   */
+  bool infer_types;
+  /**
+   * Compression of the files: "gzip", "bzip2", "xz", "zstd" or "uncompressed", or null to infer
+   * it from the extension of a single file, such as ".gz", and read a directory uncompressed
+   */
+  const char *compression;
+  /**
+   * Extension of the files read, or null for ".csv" followed by the extension of the
+   * compression, such as ".csv.gz"
+   */
+  const char *file_extension;
+  /**
+   * Schema of the files, exported through the Arrow C data interface, or null to infer the
+   * column types from the first rows. Only read: the caller still releases it.
+   */
+  const ArrowSchema *schema;
+} DataFusionCsvReadOptions;
+
+/**
+ * Options for registering Parquet files. Pass null to use the defaults of
+ * datafusion_parquet_read_options_default.
+ */
+typedef struct DataFusionParquetReadOptions {
+  /**
+   * Extension of the files read from a directory, or null for ".parquet"
+   */
+  const char *file_extension;
+  /**
+   * Whether to skip row groups whose statistics show they cannot match a query's filters, true by
+   * default
+   */
+  bool pruning;
+  /**
+   * Whether to evaluate a query's filters while decoding rows, to skip decoding the other columns
+   * of rows that do not match, false by default. Requires `pruning`, since DataFusion only hands
+   * the filters to Parquet scans that prune.
+   */
+  bool pushdown_filters;
+  /**
+   * Whether to read the page index of files that have one, to skip the pages within row groups
+   * that cannot match a query's filters, true by default. Used only with `pruning`.
+   */
+  bool page_index;
+  /**
+   * Whether to read the bloom filters of files that have them, to skip the row groups that cannot
+   * hold the values of equality filters, true by default. Used only with `pruning`.
+   */
+  bool bloom_filters;
+  /**
+   * Whether to read binary columns as strings, for files of writers that store strings without
+   * marking them as UTF-8, false by default
+   */
+  bool binary_as_string;
+  /**
+   * Number of bytes read from the end of each file in the first request, to fetch its metadata
+   * with the footer in one request instead of two when large enough, such as 524288 for object
+   * stores, or 0 to read the footer first, 0 by default
+   */
+  uintptr_t metadata_size_hint;
+  /**
+   * Names of the partition columns of a directory whose files are in hive-style `column=value`
+   * directories, in the order of the directory levels, or null for none. Filters on them skip
+   * the directories of other values.
+   */
+  const char *const *partition_columns;
+  /**
+   * DATAFUSION_TYPE_* types without parameters of the partition columns, such as
+   * DATAFUSION_TYPE_INT32 or DATAFUSION_TYPE_DATE32, to which the directory values are cast
+   */
+  const int *partition_types;
+  uintptr_t partition_column_count;
+} DataFusionParquetReadOptions;
+
+/**
+ * Options for registering newline delimited JSON files. Pass null to use the defaults of
+ * datafusion_json_read_options_default.
+ */
+typedef struct DataFusionJsonReadOptions {
+  /**
+   * Extension of the files read from a directory, or null for ".json" followed by the extension
+   * of the compression, such as ".json.gz"
+   */
+  const char *file_extension;
+  /**
+   * Number of rows read to infer the schema of the files, 1000 by default, or SIZE_MAX to read
+   * every row. Rows after them holding values of another type fail the queries reading them.
+   */
+  uintptr_t schema_infer_max_records;
+  /**
+   * Compression of the files: "gzip", "bzip2", "xz", "zstd" or "uncompressed", or null to infer
+   * it from the extension of a single file, such as ".gz", and read a directory uncompressed
+   */
+  const char *compression;
+  /**
+   * Schema of the files, exported through the Arrow C data interface, or null to infer it from
+   * the first rows. Only read: the caller still releases it.
+   */
+  const ArrowSchema *schema;
+} DataFusionJsonReadOptions;
+
+/**
+ * Options for registering listing tables. Pass null to use the defaults of
+ * datafusion_listing_table_options_default.
+ */
+typedef struct DataFusionListingTableOptions {
+  /**
+   * Extension of the files read, or null for the extension of the format, such as ".parquet"
+   */
+  const char *file_extension;
+  /**
+   * Whether to add a partition column for each `key=value` directory level of the files' paths,
+   * true by default. Partition columns hold strings, and filters on them skip the directories of
+   * other values.
+   */
+  bool infer_partitions;
+  /**
+   * Names of the columns the rows of every file are sorted by, most significant first, as declared
+   * by `WITH ORDER` in CREATE EXTERNAL TABLE, or null if the files are not sorted. Queries then
+   * skip sorts the files already satisfy and can merge sorted files instead of sorting them. The
+   * order is trusted: files not sorted this way give wrong results.
+   */
+  const char *const *sort_columns;
+  /**
+   * Whether each sort column is in ascending order
+   */
+  const bool *sort_ascending;
+  /**
+   * Whether the nulls of each sort column come before its other values
+   */
+  const bool *sort_nulls_first;
+  uintptr_t sort_column_count;
+} DataFusionListingTableOptions;
+
+/**
+ * Credentials and headers sent to an Arrow Flight server. Pass null to use the defaults of
+ * datafusion_flight_options_default, which send none.
+ */
+typedef struct DataFusionFlightOptions {
+  /**
+   * Bearer token sent as "authorization: Bearer <token>" with every call, or null
+   */
+  const char *token;
+  /**
+   * User name of Flight's basic authentication, or null. The user name and password are sent to
+   * the server's Handshake method when the table is registered, and the bearer token the server
+   * returns, if any, authenticates the later calls instead of them.
+   */
+  const char *username;
+  /**
+   * Password of `username`
+   */
+  const char *password;
+  /**
+   * Names of more headers sent with every call, such as "x-api-key", or null for none
+   */
+  const char *const *header_names;
+  /**
+   * Values of the headers, in the order of their names
+   */
+  const char *const *header_values;
+  uintptr_t header_count;
+} DataFusionFlightOptions;
+
+/**
+ * An interval value. Year-month intervals only set `months`; day-time intervals set `days` and
+ * `nanoseconds`, with their millisecond part converted to nanoseconds.
+ */
+typedef struct DataFusionInterval {
+  int32_t months;
+  int32_t days;
+  int64_t nanoseconds;
+} DataFusionInterval;
+
+/**
+ * Versions and build settings of the library, for checking ABI compatibility at load time.
+ * All strings are static and must not be freed.
+ */
+typedef struct DataFusionBuildInfo {
+  /**
+   * Version of this C API library
+   */
+  const char *c_api_version;
+  /**
+   * Version of DataFusion the library was built against
+   */
+  const char *datafusion_version;
+  /**
+   * Version of arrow-rs the library was built against
+   */
+  const char *arrow_version;
+  /**
+   * Version of iceberg-rust the library was built against
+   */
+  const char *iceberg_rust_version;
+  /**
+   * Git commit the library was built from, or "unknown"
+   */
+  const char *git_hash;
+  /**
+   * Comma separated list of enabled cargo features
+   */
+  const char *features;
+  /**
+   * Target triple, e.g. "x86_64-unknown-linux-gnu"
+   */
+  const char *target;
+  /**
+   * Cargo profile, "debug" or "release"
+   */
+  const char *profile;
+} DataFusionBuildInfo;
+
 /**
  * Create a new DataFusion context
  * Returns a pointer to the context or null on error
  */
-struct DataFusionContext *datafusion_context_new(void);
+struct DataFusionContext *datafusion_context_new(char **error_out);
 
 /**
  * Free a DataFusion context
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_context_free(struct DataFusionContext *ctx);
+
+/**
+ * Fail queries run by datafusion_sql, datafusion_sql_async and datafusion_sql_submit with
+ * DATAFUSION_TIMEOUT when they do not finish within `timeout_ms` milliseconds, or remove the
+ * timeout with 0. The timeout covers planning and execution, including waits on object stores.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_context_set_query_timeout_ms(struct DataFusionContext *ctx,
+                                            int64_t timeout_ms,
+                                            char **error_out);
+
+/**
+ * Limit the memory of results collected by datafusion_sql to `max_bytes` bytes, or remove the limit with 0.
+ * Queries whose result would exceed the limit fail with DATAFUSION_RESOURCE_EXHAUSTED.
+ * Returns DATAFUSION_OK on success or an error code on failure
  */
-void datafusion_context_free(struct DataFusionContext *ctx);
+int datafusion_context_set_max_result_bytes(struct DataFusionContext *ctx,
+                                            int64_t max_bytes,
+                                            char **error_out);
 
 /**
- * Register a CSV file with the context
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Limit the number of rows of results collected by datafusion_sql to `max_rows`, or remove the
+ * limit with 0. Queries whose result would exceed the limit fail with DATAFUSION_RESOURCE_EXHAUSTED
+ * as soon as it is exceeded, without collecting the rest of the result.
+ * Returns DATAFUSION_OK on success or an error code on failure
  */
-int datafusion_register_csv(struct DataFusionContext *ctx,
+int datafusion_context_set_max_result_rows(struct DataFusionContext *ctx,
+                                           int64_t max_rows,
+                                           char **error_out);
+
+/**
+ * Allow or forbid DDL statements in SQL run with the context, such as CREATE EXTERNAL TABLE,
+ * CREATE VIEW and DROP TABLE. DDL is allowed by default; forbidding it keeps SQL from registering
+ * tables over files and object stores, while tables registered through the C API stay usable.
+ * Forbidden statements fail before they execute.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_context_set_allow_ddl(struct DataFusionContext *ctx, bool allow, char **error_out);
+
+/**
+ * Allow or forbid DML statements in SQL run with the context, such as INSERT INTO and COPY TO,
+ * which write to tables and files. DML is allowed by default. Forbidden statements fail before
+ * they execute.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_context_set_allow_dml(struct DataFusionContext *ctx, bool allow, char **error_out);
+
+/**
+ * Register a CSV file with the context, read with the default options of
+ * datafusion_register_csv_with_options
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_csv(const struct DataFusionContext *ctx,
                             const char *table_name,
-                            const char *file_path);
+                            const char *file_path,
+                            char **error_out);
 
 /**
  * Execute a SQL query
  * Returns a pointer to the result or null on error
  */
-struct DataFusionResult *datafusion_sql(struct DataFusionContext *ctx, const char *sql);
+struct DataFusionResult *datafusion_sql(const struct DataFusionContext *ctx,
+                                        const char *sql,
+                                        char **error_out);
+
+/**
+ * Execute a SQL query that fails with DATAFUSION_TIMEOUT if it does not finish within `timeout_ms`
+ * milliseconds, overriding the context's query timeout; 0 runs the query without a timeout
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_sql_with_timeout(const struct DataFusionContext *ctx,
+                                                     const char *sql,
+                                                     int64_t timeout_ms,
+                                                     char **error_out);
+
+/**
+ * Execute a SQL statement that changes data, such as INSERT INTO, COPY or CREATE TABLE AS, without
+ * returning a result handle. The number of rows written, as reported by DataFusion, is stored in
+ * `rows_affected` if it is not null; statements that report no count, such as DDL statements
+ * including CREATE TABLE AS, store -1.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_execute_update(const struct DataFusionContext *ctx,
+                              const char *sql,
+                              int64_t *rows_affected,
+                              char **error_out);
+
+/**
+ * Get the number of rows written by the statement that produced a result, such as the rows
+ * exported by COPY or inserted by INSERT INTO, stored in `rows_affected`. Results that are not
+ * such a count, including those of queries, store -1.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_rows_affected(const struct DataFusionResult *result,
+                                    int64_t *rows_affected,
+                                    char **error_out);
 
 /**
  * Get the number of batches in a result
+ * Returns 0 on error
+ */
+int datafusion_result_batch_count(const struct DataFusionResult *result, char **error_out);
+
+/**
+ * Get the total number of rows in a result, across all batches
+ * Returns 0 on error
+ */
+int64_t datafusion_result_total_rows(const struct DataFusionResult *result, char **error_out);
+
+/**
+ * Get the approximate memory used by a result's Arrow buffers, in bytes
+ * Returns 0 on error
+ */
+int64_t datafusion_result_memory_bytes(const struct DataFusionResult *result, char **error_out);
+
+/**
+ * Get the batch count, row count and approximate memory size of a result
+ * Returns DATAFUSION_OK on success or an error code on failure
  */
-int datafusion_result_batch_count(const struct DataFusionResult *result);
+int datafusion_result_stats(const struct DataFusionResult *result,
+                            struct DataFusionResultStats *out,
+                            char **error_out);
 
 /**
  * Get the number of rows in a specific batch
+ * Returns 0 on error
  */
-int datafusion_result_batch_num_rows(const struct DataFusionResult *result, int batch_index);
+int datafusion_result_batch_num_rows(const struct DataFusionResult *result,
+                                     int batch_index,
+                                     char **error_out);
 
 /**
  * Get the number of columns in a specific batch
+ * Returns 0 on error
  */
-int datafusion_result_batch_num_columns(const struct DataFusionResult *result, int batch_index);
+int datafusion_result_batch_num_columns(const struct DataFusionResult *result,
+                                        int batch_index,
+                                        char **error_out);
 
 /**
  * Print a result as a formatted table (for debugging)
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_print(const struct DataFusionResult *result, char **error_out);
+
+/**
+ * Create a result holding rows `offset..offset + length` of another result, without copying data.
+ * The range is clamped to the rows available, so slicing past the end gives an empty result.
+ * Returns a pointer to the new result or null on error
+ */
+struct DataFusionResult *datafusion_result_slice(const struct DataFusionResult *result,
+                                                 int64_t offset,
+                                                 int64_t length,
+                                                 char **error_out);
+
+/**
+ * Create a result with the same rows as another, concatenated into batches of `target_rows` rows
+ * (the last batch may be smaller), or into a single batch if `target_rows` is 0
+ * Returns a pointer to the new result or null on error
+ */
+struct DataFusionResult *datafusion_result_coalesce(const struct DataFusionResult *result,
+                                                    int64_t target_rows,
+                                                    char **error_out);
+
+/**
+ * Create a result with the rows at the given indices (counted across all batches), in that order.
+ * Indices may repeat. The rows are copied into a single batch.
+ * Returns a pointer to the new result or null on error
+ */
+struct DataFusionResult *datafusion_result_take(const struct DataFusionResult *result,
+                                                const int64_t *indices,
+                                                uintptr_t count,
+                                                char **error_out);
+
+/**
+ * Format a result as a table, showing at most `max_rows` rows (all rows if `max_rows` <= 0).
+ * A truncated table is followed by a line giving the number of rows shown.
+ * Returns a string to be freed with datafusion_string_free, or null on error
  */
-int datafusion_result_print(const struct DataFusionResult *result);
+char *datafusion_result_format(const struct DataFusionResult *result,
+                               int max_rows,
+                               char **error_out);
 
 /**
  * Free a DataFusion result
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
  */
-void datafusion_result_free(struct DataFusionResult *result);
+int datafusion_result_free(struct DataFusionResult *result);
 
 /**
- * Get last error message (simplified for this example)
+ * Free a string allocated by the library, such as an `error_out` message
  */
-const char *datafusion_get_last_error(void);
+void datafusion_string_free(char *s);
 
 /**
  * Create a new SQL catalog for Iceberg
  * Returns a pointer to the catalog or null on error
  */
-struct IcebergCatalog *iceberg_catalog_new_sql(const char *database_url, const char *name);
+struct IcebergCatalog *iceberg_catalog_new_sql(const char *database_url,
+                                               const char *name,
+                                               char **error_out);
 
 /**
  * Free an Iceberg catalog
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
  */
-void iceberg_catalog_free(struct IcebergCatalog *catalog);
+int iceberg_catalog_free(struct IcebergCatalog *catalog);
 
 /**
  * Create a new Iceberg schema builder
  * Returns a pointer to the schema or null on error
  */
-struct IcebergSchema *iceberg_schema_new(void);
+struct IcebergSchema *iceberg_schema_new(char **error_out);
 
 /**
  * Add a long field to the schema
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Returns true on success, false on failure
  */
 bool iceberg_schema_add_long_field(struct IcebergSchema *schema,
                                    uint32_t id,
                                    const char *name,
-                                   bool required);
+                                   bool required,
+                                   char **error_out);
 
 /**
  * Add an int field to the schema
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Returns true on success, false on failure
  */
 bool iceberg_schema_add_int_field(struct IcebergSchema *schema,
                                   uint32_t id,
                                   const char *name,
-                                  bool required);
+                                  bool required,
+                                  char **error_out);
 
 /**
  * Add a date field to the schema
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Returns true on success, false on failure
  */
 bool iceberg_schema_add_date_field(struct IcebergSchema *schema,
                                    uint32_t id,
                                    const char *name,
-                                   bool required);
+                                   bool required,
+                                   char **error_out);
 
 /**
  * Free an Iceberg schema
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
  */
-void iceberg_schema_free(struct IcebergSchema *schema);
+int iceberg_schema_free(struct IcebergSchema *schema);
 
 /**
  * Create a new Iceberg partition spec
  * Returns a pointer to the partition spec or null on error
  */
-struct IcebergPartitionSpec *iceberg_partition_spec_new(void);
+struct IcebergPartitionSpec *iceberg_partition_spec_new(char **error_out);
 
 /**
  * Add a day partition field to the partition spec
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Returns true on success, false on failure
  */
 bool iceberg_partition_spec_add_day_field(struct IcebergPartitionSpec *spec,
                                           uint32_t source_id,
                                           uint32_t field_id,
-                                          const char *name);
+                                          const char *name,
+                                          char **error_out);
 
 /**
  * Free an Iceberg partition spec
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
  */
-void iceberg_partition_spec_free(struct IcebergPartitionSpec *spec);
+int iceberg_partition_spec_free(struct IcebergPartitionSpec *spec);
 
 /**
  * Create a new Iceberg table
+ * The schema and partition spec remain owned by the caller and must still be freed
  * Returns a pointer to the table or null on error
  */
 struct IcebergTable *iceberg_table_create(const char *name,
@@ -153,17 +1011,2038 @@ struct IcebergTable *iceberg_table_create(const char *name,
                                           struct IcebergSchema *schema,
                                           struct IcebergPartitionSpec *partition_spec,
                                           struct IcebergCatalog *catalog,
-                                          const char *namespace_name);
+                                          const char *namespace_name,
+                                          char **error_out);
 
 /**
  * Free an Iceberg table
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
  */
-void iceberg_table_free(struct IcebergTable *table);
+int iceberg_table_free(struct IcebergTable *table);
 
 /**
  * Register an Iceberg table with the DataFusion context
- * Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+ * Returns DATAFUSION_OK on success or an error code on failure
  */
-int datafusion_register_iceberg_table(struct DataFusionContext *ctx,
+int datafusion_register_iceberg_table(const struct DataFusionContext *ctx,
                                       const char *table_name,
-                                      struct IcebergTable *table);
+                                      struct IcebergTable *table,
+                                      char **error_out);
+
+/**
+ * Get pointers into the Arrow buffers of a column in a batch, for reading it without per-cell calls.
+ * All pointers stay valid until the result is freed.
+ *
+ * - `validity`: the validity bitmap (bit set = valid, least significant bit first) starting at bit
+ *   `validity_offset`, or null if the column has no nulls
+ * - `offsets`: for Utf8/Binary columns, `len + 1` int32 offsets into `data` (int64 for LargeUtf8 and
+ *   LargeBinary); null for primitive columns
+ * - `data`: the `len` fixed width values of a primitive column, or the value bytes of a string or
+ *   binary column
+ *
+ * Primitive columns are the numeric, date, time, timestamp, duration, interval and decimal types.
+ * Returns DATAFUSION_OK on success, DATAFUSION_TYPE_MISMATCH for other column types,
+ * or another error code on failure
+ */
+int datafusion_result_column_buffers(const struct DataFusionResult *result,
+                                     int batch_index,
+                                     int column_index,
+                                     const uint8_t **validity,
+                                     uintptr_t *validity_offset,
+                                     const void **offsets,
+                                     const void **data,
+                                     uintptr_t *len,
+                                     char **error_out);
+
+/**
+ * List the names of the tables registered in a schema of a catalog, sorted, including views.
+ * `catalog` and `schema` may be null for the context's default catalog and schema, "datafusion"
+ * and "public" unless configured otherwise.
+ * Returns a pointer to the list, or null on error with DATAFUSION_INVALID_ARGUMENT if the catalog
+ * or schema does not exist
+ */
+struct DataFusionStringList *datafusion_table_names(const struct DataFusionContext *ctx,
+                                                    const char *catalog,
+                                                    const char *schema,
+                                                    char **error_out);
+
+/**
+ * Check whether a table is registered with the context. The name is resolved as in SQL: it may be
+ * qualified as `schema.table` or `catalog.schema.table`, and unquoted parts are lower cased.
+ * Returns true if the table exists, or false if it does not or on error
+ */
+bool datafusion_table_exists(const struct DataFusionContext *ctx,
+                             const char *table_name,
+                             char **error_out);
+
+/**
+ * Get the number of strings in a list
+ * Returns 0 on error
+ */
+int datafusion_string_list_count(const struct DataFusionStringList *list, char **error_out);
+
+/**
+ * Get a string of a list
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_string_list_get(const struct DataFusionStringList *list,
+                                 int index,
+                                 char **error_out);
+
+/**
+ * Free a string list
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_string_list_free(struct DataFusionStringList *list);
+
+/**
+ * Create a cursor over the rows of a result, positioned before the first row.
+ * The cursor shares the result's data, so it stays valid after the result is freed.
+ * Returns a pointer to the cursor or null on error
+ */
+struct DataFusionRowCursor *datafusion_result_cursor(const struct DataFusionResult *result,
+                                                     char **error_out);
+
+/**
+ * Move the cursor to the next row
+ * Returns true if the cursor is on a row, or false at the end of the rows or on error
+ */
+bool datafusion_cursor_next(struct DataFusionRowCursor *cursor, char **error_out);
+
+/**
+ * Get the index of the current row across all batches of the result
+ * Returns -1 before the first row or on error
+ */
+int64_t datafusion_cursor_row_number(const struct DataFusionRowCursor *cursor, char **error_out);
+
+/**
+ * Check whether a value of the current row is null, for columns of any type
+ * Returns false on error
+ */
+bool datafusion_cursor_is_null(const struct DataFusionRowCursor *cursor,
+                               int column_index,
+                               char **error_out);
+
+/**
+ * Get an Int64 value of the current row
+ * Returns the same codes as datafusion_result_get_int64
+ */
+int datafusion_cursor_get_int64(const struct DataFusionRowCursor *cursor,
+                                int column_index,
+                                int64_t *out,
+                                char **error_out);
+
+/**
+ * Get an Int32 value of the current row
+ * Returns the same codes as datafusion_result_get_int32
+ */
+int datafusion_cursor_get_int32(const struct DataFusionRowCursor *cursor,
+                                int column_index,
+                                int32_t *out,
+                                char **error_out);
+
+/**
+ * Get a Float64 value of the current row
+ * Returns the same codes as datafusion_result_get_float64
+ */
+int datafusion_cursor_get_float64(const struct DataFusionRowCursor *cursor,
+                                  int column_index,
+                                  double *out,
+                                  char **error_out);
+
+/**
+ * Get a Boolean value of the current row
+ * Returns the same codes as datafusion_result_get_bool
+ */
+int datafusion_cursor_get_bool(const struct DataFusionRowCursor *cursor,
+                               int column_index,
+                               bool *out,
+                               char **error_out);
+
+/**
+ * Get a string value of the current row without copying. `data` stays valid until the cursor is
+ * freed, even after the cursor moves on, and is not null terminated.
+ * Returns the same codes as datafusion_result_get_string
+ */
+int datafusion_cursor_get_string(const struct DataFusionRowCursor *cursor,
+                                 int column_index,
+                                 const char **data,
+                                 uintptr_t *len,
+                                 char **error_out);
+
+/**
+ * Get a copy of a string value of the current row as a null terminated string
+ * Returns a string to be freed with datafusion_string_free, or null on error (including null values)
+ */
+char *datafusion_cursor_get_string_copy(const struct DataFusionRowCursor *cursor,
+                                        int column_index,
+                                        char **error_out);
+
+/**
+ * Free a cursor
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_cursor_free(struct DataFusionRowCursor *cursor);
+
+/**
+ * Parse and plan a SQL query without executing it, returning a DataFrame to build on with the
+ * datafusion_dataframe_* functions and execute later. DDL statements such as CREATE TABLE are
+ * executed right away, as they are planned. The DataFrame uses the context's maximum result sizes
+ * and query timeout as they are when it is created, and stays valid after the context is freed.
+ * Returns a pointer to the DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_sql_to_dataframe(const struct DataFusionContext *ctx,
+                                                        const char *sql,
+                                                        char **error_out);
+
+/**
+ * Filter the rows of a DataFrame with a SQL predicate such as `price > 100 AND region = 'EU'`,
+ * parsed with the context's SQL dialect and resolved against the DataFrame's columns.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_filter(const struct DataFusionDataFrame *df,
+                                                        const char *predicate,
+                                                        char **error_out);
+
+/**
+ * Filter the rows of a DataFrame with a predicate built as an expression handle, like
+ * datafusion_dataframe_filter without parsing SQL. The predicate stays valid.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_filter_with_expr(const struct DataFusionDataFrame *df,
+                                                                  const struct DataFusionExpr *predicate,
+                                                                  char **error_out);
+
+/**
+ * Keep only the named columns of a DataFrame, in the given order, such as to read only the
+ * columns needed from a wide table. `columns` is an array of `count` column names.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_select(const struct DataFusionDataFrame *df,
+                                                        const char *const *columns,
+                                                        uintptr_t count,
+                                                        char **error_out);
+
+/**
+ * Compute the columns of a new DataFrame from `count` SQL expressions over the columns of a
+ * DataFrame, such as `price * quantity AS total` or `upper(name)`.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_select_exprs(const struct DataFusionDataFrame *df,
+                                                              const char *const *exprs,
+                                                              uintptr_t count,
+                                                              char **error_out);
+
+/**
+ * Compute the columns of a new DataFrame from `count` expression handles, like
+ * datafusion_dataframe_select_exprs without parsing SQL. The expressions stay valid.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_select_with_exprs(const struct DataFusionDataFrame *df,
+                                                                   const struct DataFusionExpr *const *exprs,
+                                                                   uintptr_t count,
+                                                                   char **error_out);
+
+/**
+ * Add a column computed from a SQL expression over the columns of a DataFrame, such as
+ * `CAST(amount AS DOUBLE)` or `date_trunc('day', ts)`, or replace the column if the DataFrame already
+ * has one named `name`
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_with_column(const struct DataFusionDataFrame *df,
+                                                             const char *name,
+                                                             const char *expr,
+                                                             char **error_out);
+
+/**
+ * Rename a column of a DataFrame, which may be given qualified by its table such as `t.id`.
+ * Returns a pointer to a new DataFrame, or null on error such as if there is no column `old_name`
+ */
+struct DataFusionDataFrame *datafusion_dataframe_with_column_renamed(const struct DataFusionDataFrame *df,
+                                                                     const char *old_name,
+                                                                     const char *new_name,
+                                                                     char **error_out);
+
+/**
+ * Skip the first `offset` rows of a DataFrame and keep at most `limit` of the rest, or all of them
+ * if `limit` is negative. Applied to a sorted DataFrame, this pages through its rows.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_limit(const struct DataFusionDataFrame *df,
+                                                       int64_t offset,
+                                                       int64_t limit,
+                                                       char **error_out);
+
+/**
+ * Group the rows of a DataFrame by `group_count` SQL expressions, such as `region` or
+ * `date_trunc('month', ts) AS month`, and compute `aggr_count` aggregate expressions for each
+ * group, such as `sum(amount) AS total` or `count(*)`. The new DataFrame has the group columns
+ * followed by the aggregate columns. With no group expressions, the aggregates are computed over
+ * all rows into a single row.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_aggregate(const struct DataFusionDataFrame *df,
+                                                           const char *const *group_exprs,
+                                                           uintptr_t group_count,
+                                                           const char *const *aggr_exprs,
+                                                           uintptr_t aggr_count,
+                                                           char **error_out);
+
+/**
+ * Group the rows of a DataFrame by `group_count` expression handles and compute `aggr_count`
+ * aggregate expression handles for each group, such as calls of aggregate functions made with
+ * datafusion_expr_function, like datafusion_dataframe_aggregate without parsing SQL. The
+ * expressions stay valid.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_aggregate_with_exprs(const struct DataFusionDataFrame *df,
+                                                                      const struct DataFusionExpr *const *group_exprs,
+                                                                      uintptr_t group_count,
+                                                                      const struct DataFusionExpr *const *aggr_exprs,
+                                                                      uintptr_t aggr_count,
+                                                                      char **error_out);
+
+/**
+ * Sort the rows of a DataFrame by `count` SQL expressions, such as `region` or `price * quantity`,
+ * each in the order given by `ascending[i]` and with nulls before or after the other values as
+ * given by `nulls_first[i]`. Rows equal on all expressions may come in any order, so to page
+ * through a DataFrame with datafusion_dataframe_limit, sort it by a unique key.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_sort(const struct DataFusionDataFrame *df,
+                                                      const char *const *exprs,
+                                                      const bool *ascending,
+                                                      const bool *nulls_first,
+                                                      uintptr_t count,
+                                                      char **error_out);
+
+/**
+ * Add a column to a DataFrame for each of `window_count` window expressions: calls of window
+ * functions such as `row_number`, `rank`, `lag` and `lead`, or of aggregate functions such as
+ * `sum` for running totals, made with datafusion_expr_function and named with
+ * datafusion_expr_alias. Each is evaluated over the rows sharing its row's values of the
+ * `partition_count` expressions of `partition_by` (all rows without any), ordered by the
+ * `order_count` expressions of `order_by`, each in the order given by `ascending[i]` and with
+ * nulls first as given by `nulls_first[i]`. Ordered, aggregates cover the rows of the partition up
+ * to the current row and the rows equal to it in the order; unordered, the whole partition.
+ * The rows of the new DataFrame come in no particular order.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_window(const struct DataFusionDataFrame *df,
+                                                        const struct DataFusionExpr *const *window_exprs,
+                                                        uintptr_t window_count,
+                                                        const struct DataFusionExpr *const *partition_by,
+                                                        uintptr_t partition_count,
+                                                        const struct DataFusionExpr *const *order_by,
+                                                        const bool *ascending,
+                                                        const bool *nulls_first,
+                                                        uintptr_t order_count,
+                                                        char **error_out);
+
+/**
+ * Join two DataFrames on equality of `count` pairs of key columns, `left_columns[i]` of `left` with
+ * `right_columns[i]` of `right`, with a DATAFUSION_JOIN_* join type. The DataFrames may come from
+ * different contexts, such as a table of one joined with files registered with another; the
+ * joined DataFrame uses the limits of `left`. Columns with the same name on both sides must be
+ * told apart by their table, such as `left_table.id`, or renamed beforehand.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_join(const struct DataFusionDataFrame *left,
+                                                      const struct DataFusionDataFrame *right,
+                                                      int join_type,
+                                                      const char *const *left_columns,
+                                                      const char *const *right_columns,
+                                                      uintptr_t count,
+                                                      char **error_out);
+
+/**
+ * Append the rows of `b` to those of `a`, keeping duplicates, like UNION ALL. Both DataFrames must
+ * have the same column names in the same order, with types that have a common type, such as Int32
+ * and Int64; otherwise DATAFUSION_INVALID_ARGUMENT is returned, naming the first mismatched column.
+ * The DataFrames may come from different contexts; the union uses the limits of `a`.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_union(const struct DataFusionDataFrame *a,
+                                                       const struct DataFusionDataFrame *b,
+                                                       char **error_out);
+
+/**
+ * Combine the rows of two DataFrames without duplicates, like UNION, with the same requirements as
+ * datafusion_dataframe_union
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_union_distinct(const struct DataFusionDataFrame *a,
+                                                                const struct DataFusionDataFrame *b,
+                                                                char **error_out);
+
+/**
+ * Remove duplicate rows from a DataFrame, like SELECT DISTINCT
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_distinct(const struct DataFusionDataFrame *df,
+                                                          char **error_out);
+
+/**
+ * Keep one row of a DataFrame for each distinct combination of the `count` named columns, like
+ * SELECT DISTINCT ON, such as to deduplicate records by key. Which of the rows sharing a
+ * combination is kept is unspecified.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_distinct_on(const struct DataFusionDataFrame *df,
+                                                             const char *const *columns,
+                                                             uintptr_t count,
+                                                             char **error_out);
+
+/**
+ * Redistribute the rows of a DataFrame evenly across `partitions` partitions, batch by batch, to
+ * change how many partitions the operators after it, such as joins and aggregations, run in
+ * parallel, and how many partitions datafusion_dataframe_cache keeps.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_repartition(const struct DataFusionDataFrame *df,
+                                                             uintptr_t partitions,
+                                                             char **error_out);
+
+/**
+ * Redistribute the rows of a DataFrame across `partitions` partitions by a hash of the `count`
+ * columns named in `columns`, so rows with equal values in those columns end up in the same
+ * partition.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_repartition_by_hash(const struct DataFusionDataFrame *df,
+                                                                     const char *const *columns,
+                                                                     uintptr_t count,
+                                                                     uintptr_t partitions,
+                                                                     char **error_out);
+
+/**
+ * Execute a DataFrame and keep its rows in memory, returning a new DataFrame that reads them
+ * instead of executing the original plan again, so DataFrames derived from it do not rescan its
+ * sources. The output partitions are collected in parallel and kept apart, so scans of the cached
+ * rows stay parallel. Collecting the rows is limited by the maximum result sizes of the context.
+ * The columns of the new DataFrame keep their names but lose their table qualifiers.
+ * Returns a pointer to a new DataFrame or null on error
+ */
+struct DataFusionDataFrame *datafusion_dataframe_cache(const struct DataFusionDataFrame *df,
+                                                       char **error_out);
+
+/**
+ * Get the schema of the rows a DataFrame produces, without executing it
+ * Returns a pointer to the schema or null on error
+ */
+struct DataFusionSchema *datafusion_dataframe_schema(const struct DataFusionDataFrame *df,
+                                                     char **error_out);
+
+/**
+ * Execute a DataFrame and collect its result like datafusion_sql. The DataFrame stays valid and
+ * can be executed again.
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_dataframe_collect(const struct DataFusionDataFrame *df,
+                                                      char **error_out);
+
+/**
+ * Count the rows of a DataFrame into `out` by executing a `count(*)` aggregation of it rather
+ * than collecting its rows. The optimizer answers the count from the statistics of sources that
+ * know their row counts, such as Parquet files, without scanning them.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_dataframe_count(const struct DataFusionDataFrame *df,
+                               int64_t *out,
+                               char **error_out);
+
+/**
+ * Describe the plan of a DataFrame like SQL EXPLAIN, as a `plan_type:` line followed by the plan
+ * for each of its optimized logical plan and physical plan. With `verbose`, the plan is also shown
+ * as it was before and after every optimizer pass, like EXPLAIN VERBOSE. With `analyze`, the
+ * DataFrame is executed, discarding its rows, and the executed physical plan is shown with its
+ * metrics, like EXPLAIN ANALYZE.
+ * The description is stored in `out` and must be freed with datafusion_string_free.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_dataframe_explain(const struct DataFusionDataFrame *df,
+                                 bool verbose,
+                                 bool analyze,
+                                 char **out,
+                                 char **error_out);
+
+/**
+ * Start executing a DataFrame as a stream, like datafusion_sql_stream, to consume results of any
+ * size in bounded memory with datafusion_stream_next. The DataFrame can be executed again, and
+ * the stream stays valid after the DataFrame is freed.
+ * Returns a pointer to the stream or null on error
+ */
+struct DataFusionStream *datafusion_dataframe_execute_stream(const struct DataFusionDataFrame *df,
+                                                             char **error_out);
+
+/**
+ * Write the rows of a DataFrame as Parquet to `path`, a local path or a URL of an object store
+ * registered with the context, such as `s3://bucket/export/`. Rows are written as they are
+ * produced rather than collected first. A path with an extension, such as `export.parquet`, is
+ * written as a single file unless partitioning by columns; any other path is written as a
+ * directory of files.
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_dataframe_write_parquet(const struct DataFusionDataFrame *df,
+                                       const char *path,
+                                       const struct DataFusionParquetWriteOptions *options,
+                                       char **error_out);
+
+/**
+ * Write the rows of a DataFrame into an Iceberg table as a new snapshot, appending them to the
+ * table's rows with DATAFUSION_WRITE_APPEND or replacing them with DATAFUSION_WRITE_OVERWRITE. Rows
+ * are written as they are produced rather than collected first, into files partitioned by the
+ * table's partition spec. The columns of the DataFrame are matched to the table's by name, in any
+ * order, and cast to their types; a column missing from either side is an invalid argument.
+ * Readers of the table, such as contexts it is registered with, see the rows once the write
+ * returns.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_dataframe_write_iceberg(const struct DataFusionDataFrame *df,
+                                       const struct IcebergTable *table,
+                                       int mode,
+                                       char **error_out);
+
+/**
+ * Free a DataFrame. DataFrames built from it stay valid.
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_dataframe_free(struct DataFusionDataFrame *df);
+
+/**
+ * Get the unscaled value of a Decimal128 result cell. Combine it with the column's scale from
+ * datafusion_result_column_decimal to get the decimal value.
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not Decimal128, or another error code on failure
+ */
+int datafusion_result_get_decimal128(const struct DataFusionResult *result,
+                                     int batch_index,
+                                     int row_index,
+                                     int column_index,
+                                     struct DataFusionDecimal128 *out,
+                                     char **error_out);
+
+/**
+ * Get a Decimal128 or Decimal256 result cell formatted with its scale, e.g. "-1234.50"
+ * Returns a string to be freed with datafusion_string_free, or null on error (including null values)
+ */
+char *datafusion_result_get_decimal_string(const struct DataFusionResult *result,
+                                           int batch_index,
+                                           int row_index,
+                                           int column_index,
+                                           char **error_out);
+
+/**
+ * Get the precision and scale of a Decimal128 or Decimal256 column
+ * Returns DATAFUSION_OK on success, DATAFUSION_TYPE_MISMATCH if the column is not a decimal column,
+ * or another error code on failure
+ */
+int datafusion_result_column_decimal(const struct DataFusionResult *result,
+                                     int column_index,
+                                     uint8_t *precision,
+                                     int8_t *scale,
+                                     char **error_out);
+
+/**
+ * Get the code of an error
+ * Returns DATAFUSION_ERROR if the error is null and DATAFUSION_INVALID_HANDLE if it is not a valid error
+ */
+int datafusion_error_code(const struct DataFusionError *error);
+
+/**
+ * Get the message of an error
+ * The string is owned by the error and valid until it is freed
+ */
+const char *datafusion_error_message(const struct DataFusionError *error);
+
+/**
+ * Get the error that caused this one
+ * Returns null at the end of the chain; the source is owned by the outermost error
+ */
+const struct DataFusionError *datafusion_error_source(const struct DataFusionError *error);
+
+/**
+ * Free an error returned by `datafusion_last_error`
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if the error was already freed
+ */
+int datafusion_error_free(struct DataFusionError *error);
+
+/**
+ * Get the message of the last error raised on the calling thread
+ * Returns an empty string if no error has occurred
+ */
+const char *datafusion_get_last_error(void);
+
+/**
+ * Get a copy of the last error raised on the calling thread, including its code and source chain
+ * Returns null if no error has occurred; the error must be freed with datafusion_error_free
+ */
+struct DataFusionError *datafusion_last_error(void);
+
+/**
+ * Plan a SQL query without executing it and describe its optimized logical plan and its physical
+ * plan, one operator per line, as EXPLAIN does. With `verbose`, the logical plan also shows the
+ * schema of every operator and the physical plan shows full operator details.
+ * The plans are stored in `logical_out` and `physical_out`, either of which may be null to skip
+ * it, and must be freed with datafusion_string_free.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_explain(const struct DataFusionContext *ctx,
+                       const char *sql,
+                       bool verbose,
+                       char **logical_out,
+                       char **physical_out,
+                       char **error_out);
+
+/**
+ * Execute a SQL query, discarding its result, and describe the executed physical plan like
+ * EXPLAIN ANALYZE: one operator per line with its metrics aggregated across partitions, such as
+ * `output_rows` and `elapsed_compute`. With `json`, the plan is described in the format of
+ * datafusion_result_metrics_json instead.
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_explain_analyze(const struct DataFusionContext *ctx,
+                                 const char *sql,
+                                 bool json,
+                                 char **error_out);
+
+/**
+ * Plan a SQL query without executing it and render its physical plan as a Graphviz DOT graph,
+ * stored in `dot_out` and to be freed with datafusion_string_free
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_plan_graphviz(const struct DataFusionContext *ctx,
+                             const char *sql,
+                             char **dot_out,
+                             char **error_out);
+
+/**
+ * Get the default CSV options
+ */
+struct DataFusionCsvOptions datafusion_csv_options_default(void);
+
+/**
+ * Get the default Parquet write options
+ */
+struct DataFusionParquetWriteOptions datafusion_parquet_write_options_default(void);
+
+/**
+ * Export a batch of a result through the Arrow C data interface, as a struct array with one child
+ * per column and its schema. The exported data shares the result's buffers and stays valid after
+ * the result is freed; the caller must call the `release` callbacks of both structs when done.
+ * `out_array` and `out_schema` may point to uninitialized memory.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_export_batch(const struct DataFusionResult *result,
+                                   int batch_index,
+                                   ArrowArray *out_array,
+                                   ArrowSchema *out_schema,
+                                   char **error_out);
+
+/**
+ * Export all batches of a result through the Arrow C stream interface. The stream shares the
+ * result's buffers and stays valid after the result is freed; the caller must call its `release`
+ * callback when done. `out` may point to uninitialized memory.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_export_stream(const struct DataFusionResult *result,
+                                    ArrowArrayStream *out,
+                                    char **error_out);
+
+/**
+ * Serialize a result in the Arrow IPC stream (DATAFUSION_IPC_STREAM) or file (DATAFUSION_IPC_FILE) format.
+ * `buf` is set to a buffer of `len` bytes to be freed with datafusion_buffer_free.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_to_ipc(const struct DataFusionResult *result,
+                             int format,
+                             uint8_t **buf,
+                             uintptr_t *len,
+                             char **error_out);
+
+/**
+ * Free a buffer allocated by the library, passing the length it was returned with
+ */
+void datafusion_buffer_free(uint8_t *buf, uintptr_t len);
+
+/**
+ * Render a result as CSV, with `options` or the defaults if null
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_result_to_csv(const struct DataFusionResult *result,
+                               const struct DataFusionCsvOptions *options,
+                               char **error_out);
+
+/**
+ * Write a result to a CSV file, with `options` or the defaults if null
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_write_csv(const struct DataFusionResult *result,
+                                const char *path,
+                                const struct DataFusionCsvOptions *options,
+                                char **error_out);
+
+/**
+ * Render a result as JSON: one object per row, either as newline delimited JSON (`line_delimited`)
+ * or as a single JSON array. Null values are omitted from the objects.
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_result_to_json(const struct DataFusionResult *result,
+                                bool line_delimited,
+                                char **error_out);
+
+/**
+ * Write a result to a Parquet file. `compression` is a codec as accepted by the
+ * `datafusion.execution.parquet.compression` setting, e.g. "snappy", "zstd(3)" or "uncompressed",
+ * or null for DataFusion's default of "zstd(3)". `row_group_size` is the maximum number of rows
+ * per row group, or 0 for the Parquet writer's default.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_write_parquet(const struct DataFusionResult *result,
+                                    const char *path,
+                                    const char *compression,
+                                    int64_t row_group_size,
+                                    char **error_out);
+
+/**
+ * Write a result to a newline delimited JSON file with one object per row, compressed with
+ * `compression` ("gzip", "bzip2", "xz", "zstd" or "uncompressed"; null for no compression)
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_result_write_ndjson(const struct DataFusionResult *result,
+                                   const char *path,
+                                   const char *compression,
+                                   char **error_out);
+
+/**
+ * Create an expression referring to the column `name`, taken as is: it is neither parsed nor
+ * case folded, so it needs no quoting. The column is resolved when the expression is used.
+ * Returns a pointer to the expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_col(const char *name, char **error_out);
+
+/**
+ * Create an Int64 literal expression
+ * Returns a pointer to the expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_lit_int64(int64_t value, char **error_out);
+
+/**
+ * Create a Float64 literal expression
+ * Returns a pointer to the expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_lit_float64(double value, char **error_out);
+
+/**
+ * Create a Boolean literal expression
+ * Returns a pointer to the expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_lit_bool(bool value, char **error_out);
+
+/**
+ * Create a Utf8 literal expression from null terminated UTF-8, copied into the expression
+ * Returns a pointer to the expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_lit_string(const char *value, char **error_out);
+
+/**
+ * Combine two expressions with a DATAFUSION_OP_* operator, such as DATAFUSION_OP_GT for
+ * `left > right`
+ * Returns a pointer to a new expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_binary(int op,
+                                              const struct DataFusionExpr *left,
+                                              const struct DataFusionExpr *right,
+                                              char **error_out);
+
+/**
+ * Cast an expression to a DATAFUSION_TYPE_* type without parameters, such as
+ * DATAFUSION_TYPE_FLOAT64 or DATAFUSION_TYPE_UTF8. Types with parameters, such as timestamps and
+ * decimals, are invalid arguments.
+ * Returns a pointer to a new expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_cast(const struct DataFusionExpr *expr,
+                                            int data_type,
+                                            char **error_out);
+
+/**
+ * Name the output of an expression, such as the column it computes in a select
+ * Returns a pointer to a new expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_alias(const struct DataFusionExpr *expr,
+                                             const char *name,
+                                             char **error_out);
+
+/**
+ * Call a scalar, aggregate or window function registered with a context, such as `upper`, `sum`
+ * or `row_number`, with `count` argument expressions. The name is looked up as registered, which
+ * is lower case for the built-in functions. Calls of aggregate functions are used as the aggregate
+ * expressions of datafusion_dataframe_aggregate_with_exprs, and calls of window and aggregate
+ * functions as the window expressions of datafusion_dataframe_window.
+ * Returns a pointer to a new expression or null on error
+ */
+struct DataFusionExpr *datafusion_expr_function(const struct DataFusionContext *ctx,
+                                                const char *name,
+                                                const struct DataFusionExpr *const *args,
+                                                uintptr_t count,
+                                                char **error_out);
+
+/**
+ * Describe an expression as DataFusion displays it, such as `salary * Int64(2) AS doubled`
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_expr_to_string(const struct DataFusionExpr *expr, char **error_out);
+
+/**
+ * Free an expression. Expressions built from it stay valid.
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_expr_free(struct DataFusionExpr *expr);
+
+/**
+ * Start counting live handles per type, for leak tests of binding finalizers.
+ * Only handles created after this call are counted. Tracking stays enabled for the life of the process.
+ */
+void datafusion_enable_handle_tracking(void);
+
+/**
+ * Get the number of live handles of each type created since handle tracking was enabled
+ */
+struct DataFusionHandleCounts datafusion_live_handle_counts(void);
+
+/**
+ * Install a callback receiving DataFusion, object store and Iceberg log records up to `level`
+ * (one of the DATAFUSION_LOG_* constants). Passing a null callback stops forwarding.
+ * Calling this again replaces the previous callback. Contexts with their own log level or
+ * filter override `level` for the work they run.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_set_log_callback(int level,
+                                DataFusionLogCallback callback,
+                                void *user_data,
+                                char **error_out);
+
+/**
+ * Set the log level for work run by this context, overriding the level of the log callback
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_context_set_log_level(struct DataFusionContext *ctx, int level, char **error_out);
+
+/**
+ * Set an env-filter style log filter for work run by this context, overriding the level of the
+ * log callback, e.g. "warn,datafusion::physical_plan=debug,iceberg_rust=trace".
+ * Targets without a matching directive are not logged unless a bare default level is given.
+ * Passing null removes the override so the context follows the callback level again.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_context_set_log_filter(struct DataFusionContext *ctx,
+                                      const char *filter,
+                                      char **error_out);
+
+/**
+ * Get operator-level execution metrics of the query that produced a result as JSON:
+ * `{"operators": [{"operator", "depth", "partitions", "metrics": {name: value}}]}` in plan order.
+ * Metric names follow DataFusion, e.g. `output_rows`, `elapsed_compute` (nanoseconds),
+ * `spill_count`, `spilled_bytes` and `bytes_scanned`. Results not produced by a query have no operators.
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_result_metrics_json(const struct DataFusionResult *result,
+                                     char **error_out);
+
+/**
+ * Get the number of fields of a struct cell, or the number of elements of a list or map cell
+ * Returns -1 on error, including when the value is null
+ */
+int64_t datafusion_result_get_child_count(const struct DataFusionResult *result,
+                                          int batch_index,
+                                          int row_index,
+                                          int column_index,
+                                          char **error_out);
+
+/**
+ * Get the range of a List, LargeList, FixedSizeList or Map cell within the column's child values:
+ * `offset` is set to the index of the first element and `length` to the number of elements
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not a list or map, or another error code on failure
+ */
+int datafusion_result_get_list_range(const struct DataFusionResult *result,
+                                     int batch_index,
+                                     int row_index,
+                                     int column_index,
+                                     int64_t *offset,
+                                     int64_t *length,
+                                     char **error_out);
+
+/**
+ * Check whether a nested value is null. `path` is a column name followed by `.field` selectors
+ * for struct fields and `[index]` selectors for list elements and map entries, e.g. "a.b[2].c";
+ * map entries are structs with "key" and "value" fields. A value is null if any value on its path is.
+ * Returns false on error
+ */
+bool datafusion_result_is_null_by_path(const struct DataFusionResult *result,
+                                       int batch_index,
+                                       int row_index,
+                                       const char *path,
+                                       char **error_out);
+
+/**
+ * Get the number of fields of a nested struct, or of elements of a nested list or map
+ * Returns -1 on error, including when the value is null
+ */
+int64_t datafusion_result_get_child_count_by_path(const struct DataFusionResult *result,
+                                                  int batch_index,
+                                                  int row_index,
+                                                  const char *path,
+                                                  char **error_out);
+
+/**
+ * Get a nested Int64 value
+ * Returns the same codes as datafusion_result_get_int64
+ */
+int datafusion_result_get_int64_by_path(const struct DataFusionResult *result,
+                                        int batch_index,
+                                        int row_index,
+                                        const char *path,
+                                        int64_t *out,
+                                        char **error_out);
+
+/**
+ * Get a nested Int32 value
+ * Returns the same codes as datafusion_result_get_int32
+ */
+int datafusion_result_get_int32_by_path(const struct DataFusionResult *result,
+                                        int batch_index,
+                                        int row_index,
+                                        const char *path,
+                                        int32_t *out,
+                                        char **error_out);
+
+/**
+ * Get a nested Float64 value
+ * Returns the same codes as datafusion_result_get_float64
+ */
+int datafusion_result_get_float64_by_path(const struct DataFusionResult *result,
+                                          int batch_index,
+                                          int row_index,
+                                          const char *path,
+                                          double *out,
+                                          char **error_out);
+
+/**
+ * Get a nested Boolean value
+ * Returns the same codes as datafusion_result_get_bool
+ */
+int datafusion_result_get_bool_by_path(const struct DataFusionResult *result,
+                                       int batch_index,
+                                       int row_index,
+                                       const char *path,
+                                       bool *out,
+                                       char **error_out);
+
+/**
+ * Get a nested string value without copying
+ * Returns the same codes as datafusion_result_get_string
+ */
+int datafusion_result_get_string_by_path(const struct DataFusionResult *result,
+                                         int batch_index,
+                                         int row_index,
+                                         const char *path,
+                                         const char **data,
+                                         uintptr_t *len,
+                                         char **error_out);
+
+/**
+ * Register the object store of the tables and files at the URLs with the scheme and authority of
+ * `url`, such as "s3://bucket", configured with `count` options named as in the object_store crate,
+ * such as "aws_access_key_id", "aws_region", "aws_endpoint", "google_service_account" or
+ * "azure_storage_account_key". Options not given are read from the environment variables of the
+ * store's cloud, such as AWS_SECRET_ACCESS_KEY, as they are for stores created on first use of an
+ * URL that has none registered. Supported schemes are s3 and s3a, gs, az, azure, abfs, abfss and
+ * adl, and http and https. A store registered for the URL before is replaced.
+ * Returns DATAFUSION_OK on success, DATAFUSION_INVALID_ARGUMENT for an unsupported scheme or an
+ * unknown option, or another error code on failure
+ */
+int datafusion_register_object_store(const struct DataFusionContext *ctx,
+                                     const char *url,
+                                     const char *const *keys,
+                                     const char *const *values,
+                                     uintptr_t count,
+                                     char **error_out);
+
+/**
+ * Execute a SQL query with named `:name` placeholders, substituting the values of `count`
+ * parameters: `names[i]`, given without the colon, names the placeholder that gets `values[i]`.
+ * Values are bound as typed values rather than spliced into the SQL text, so they need no quoting.
+ * Every placeholder of the query must be given a value.
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_sql_with_params(const struct DataFusionContext *ctx,
+                                                    const char *sql,
+                                                    const char *const *names,
+                                                    const struct DataFusionScalar *values,
+                                                    uintptr_t count,
+                                                    char **error_out);
+
+/**
+ * Execute a SQL query like datafusion_sql, but collect each output partition of the plan in
+ * parallel and keep the partitions apart in the result, so the host can process them in parallel.
+ * The batches of the result are stored one partition after another and can be read with the usual
+ * result functions; datafusion_result_partition_first_batch gives where each partition starts.
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_sql_collect_partitioned(const struct DataFusionContext *ctx,
+                                                            const char *sql,
+                                                            char **error_out);
+
+/**
+ * Get the number of output partitions of a result. Results not collected with
+ * datafusion_sql_collect_partitioned have a single partition holding all batches.
+ * Returns -1 on error
+ */
+int datafusion_result_partition_count(const struct DataFusionResult *result, char **error_out);
+
+/**
+ * Get the number of batches of an output partition of a result, which may be 0
+ * Returns -1 on error
+ */
+int datafusion_result_partition_batch_count(const struct DataFusionResult *result,
+                                            int partition_index,
+                                            char **error_out);
+
+/**
+ * Get the index of the first batch of an output partition of a result, so batch `i` of the
+ * partition is batch `first + i` of the result
+ * Returns -1 on error
+ */
+int datafusion_result_partition_first_batch(const struct DataFusionResult *result,
+                                            int partition_index,
+                                            char **error_out);
+
+/**
+ * Execute a SQL query like datafusion_sql, invoking `callback` with the query's progress every
+ * `interval_ms` milliseconds while it executes and once more when execution ends, successfully or
+ * not, so hosts can show progress of long scans. Queries that fail before executing, such as with
+ * a syntax error, report no progress. The callback runs on a runtime worker thread or the calling
+ * thread, never concurrently with itself, and is not invoked after this function returns.
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_sql_with_progress(const struct DataFusionContext *ctx,
+                                                      const char *sql,
+                                                      DataFusionProgressCallback callback,
+                                                      int64_t interval_ms,
+                                                      void *user_data,
+                                                      char **error_out);
+
+/**
+ * Execute a SQL query in the background on the context's runtime and invoke `callback` with its
+ * result or error when it finishes, without blocking the calling thread. The callback is invoked
+ * exactly once for every query that was submitted successfully, unless the context is freed while
+ * the query is still running, in which case the query is abandoned. The callback must not free
+ * the context.
+ * Returns DATAFUSION_OK if the query was submitted or an error code on failure
+ */
+int datafusion_sql_async(const struct DataFusionContext *ctx,
+                         const char *sql,
+                         DataFusionQueryCallback callback,
+                         void *user_data,
+                         char **error_out);
+
+/**
+ * Start executing a SQL query in the background on the context's runtime, returning a handle to
+ * wait for its result or cancel it. The query keeps running after the context is freed.
+ * Returns a pointer to the query or null on error
+ */
+struct DataFusionQuery *datafusion_sql_submit(const struct DataFusionContext *ctx,
+                                              const char *sql,
+                                              char **error_out);
+
+/**
+ * Cancel a query, stopping its execution as soon as possible. Waiting for a cancelled query fails
+ * with DATAFUSION_CANCELLED unless it had already finished. May be called from any thread,
+ * including while another thread waits for the query.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_query_cancel(const struct DataFusionQuery *query, char **error_out);
+
+/**
+ * Check whether a query has finished, successfully or not, so waiting for it will not block
+ * Returns false on error
+ */
+bool datafusion_query_is_finished(const struct DataFusionQuery *query, char **error_out);
+
+/**
+ * Block until a query finishes and take its result. The result can only be taken once.
+ * Returns a pointer to the result, or null on error with DATAFUSION_CANCELLED if the query was
+ * cancelled
+ */
+struct DataFusionResult *datafusion_query_wait(const struct DataFusionQuery *query,
+                                               char **error_out);
+
+/**
+ * Free a query, cancelling it if it is still running
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_query_free(struct DataFusionQuery *query);
+
+/**
+ * Get the default CSV read options
+ */
+struct DataFusionCsvReadOptions datafusion_csv_read_options_default(void);
+
+/**
+ * Register a CSV file, or a directory of CSV files read as one table, with the context. Unless
+ * given a schema, the column types are inferred from the first rows of the files. Empty fields are
+ * read as null.
+ * `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+ * (see datafusion_register_object_store).
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_csv_with_options(const struct DataFusionContext *ctx,
+                                         const char *table_name,
+                                         const char *path,
+                                         const struct DataFusionCsvReadOptions *options,
+                                         char **error_out);
+
+/**
+ * Get the default Parquet read options
+ */
+struct DataFusionParquetReadOptions datafusion_parquet_read_options_default(void);
+
+/**
+ * Register a Parquet file, or a directory of Parquet files read as one table, with the context.
+ * `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+ * (see datafusion_register_object_store).
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_parquet(const struct DataFusionContext *ctx,
+                                const char *table_name,
+                                const char *path,
+                                const struct DataFusionParquetReadOptions *options,
+                                char **error_out);
+
+/**
+ * Get the default JSON read options
+ */
+struct DataFusionJsonReadOptions datafusion_json_read_options_default(void);
+
+/**
+ * Register a newline delimited JSON file, with one object per line, or a directory of such files
+ * read as one table, with the context. Unless given, the schema is inferred from the first rows of
+ * the files.
+ * `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+ * (see datafusion_register_object_store).
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_json(const struct DataFusionContext *ctx,
+                             const char *table_name,
+                             const char *path,
+                             const struct DataFusionJsonReadOptions *options,
+                             char **error_out);
+
+/**
+ * Register `len` bytes of CSV held in memory, such as data received over a socket, as an in-memory
+ * table. The bytes are parsed before returning and the caller keeps ownership of them. The read
+ * options apply as to files, except that `file_extension` is ignored and no `compression` means
+ * uncompressed bytes.
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_csv_bytes(const struct DataFusionContext *ctx,
+                                  const char *table_name,
+                                  const uint8_t *data,
+                                  uintptr_t len,
+                                  const struct DataFusionCsvReadOptions *options,
+                                  char **error_out);
+
+/**
+ * Register `len` bytes of newline delimited JSON held in memory as an in-memory table. The bytes
+ * are parsed before returning and the caller keeps ownership of them. The read options apply as to
+ * files, except that `file_extension` is ignored and no `compression` means uncompressed bytes.
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_json_bytes(const struct DataFusionContext *ctx,
+                                   const char *table_name,
+                                   const uint8_t *data,
+                                   uintptr_t len,
+                                   const struct DataFusionJsonReadOptions *options,
+                                   char **error_out);
+
+/**
+ * Register an Arrow IPC file (Feather v2), or a directory of ".arrow" files read as one table, with
+ * the context. A single file is read whatever its extension, such as ".arrow", ".feather" or
+ * ".ipc". The schema is read from the files, and their record batches are scanned without parsing.
+ * `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+ * (see datafusion_register_object_store).
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_arrow(const struct DataFusionContext *ctx,
+                              const char *table_name,
+                              const char *path,
+                              char **error_out);
+
+#if defined(DATAFUSION_C_API_AVRO)
+/**
+ * Register an Avro object container file, or a directory of ".avro" files read as one table, with
+ * the context. The schema is read from the files.
+ * `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+ * (see datafusion_register_object_store).
+ * Only available when the library is built with the `avro` feature, which defines
+ * DATAFUSION_C_API_AVRO in the header.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_avro(const struct DataFusionContext *ctx,
+                             const char *table_name,
+                             const char *path,
+                             char **error_out);
+#endif
+
+/**
+ * Get the default listing table options
+ */
+struct DataFusionListingTableOptions datafusion_listing_table_options_default(void);
+
+/**
+ * Register the files matching a URL or path as one table, read as a DATAFUSION_FORMAT_* format
+ * with its default options. `url` is a directory, a file, or a glob pattern such as
+ * "s3://bucket/data/part-*.parquet", matching files of an object store (see
+ * datafusion_register_object_store) or local files. Files in `key=value` directories, such as
+ * "data/year=2024/month=01/", get partition columns of their keys unless `options` disables it; a
+ * glob is matched against the paths without these directories, which are selected by filtering on
+ * their columns instead. Files in other subdirectories are left out unless the context's
+ * `datafusion.execution.listing_table_ignore_subdirectory` setting is false. The schema is
+ * inferred from the files, and `options` may declare the order of their rows.
+ * DATAFUSION_FORMAT_AVRO requires the library to be built with the `avro` feature.
+ * `options` may be null to use the defaults.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_listing_table(const struct DataFusionContext *ctx,
+                                      const char *table_name,
+                                      const char *url,
+                                      int format,
+                                      const struct DataFusionListingTableOptions *options,
+                                      char **error_out);
+
+/**
+ * Register a view: a table defined by a SQL query, such as a commonly used join or filter, that
+ * later queries refer to by name, like one created by `CREATE VIEW name AS query` with
+ * datafusion_sql. The query is planned when the view is registered, so the tables it reads must be
+ * registered already, and it runs each time the view is read. It must be a query: DDL, DML and
+ * other statements are invalid arguments.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_view(const struct DataFusionContext *ctx,
+                             const char *view_name,
+                             const char *sql,
+                             char **error_out);
+
+/**
+ * Register the record batches of an Arrow C stream as an in-memory table. The stream is read to
+ * its end and released before returning, and the table is a copy of its batches: the caller's
+ * struct is moved from and marked released whether or not the call succeeds, and must not be
+ * released again.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_arrow_stream(const struct DataFusionContext *ctx,
+                                     const char *table_name,
+                                     ArrowArrayStream *stream,
+                                     char **error_out);
+
+/**
+ * Register one record batch, passed through the Arrow C data interface as a struct array with one
+ * child per column and its schema, as an in-memory table. The array is moved: the table takes
+ * ownership of its buffers, and the caller's struct is marked released whether or not the call
+ * succeeds, and must not be released again. The schema is only read: the caller still releases
+ * it.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_record_batch(const struct DataFusionContext *ctx,
+                                     const char *table_name,
+                                     ArrowArray *array,
+                                     const ArrowSchema *schema,
+                                     char **error_out);
+
+/**
+ * Get the default Flight options
+ */
+struct DataFusionFlightOptions datafusion_flight_options_default(void);
+
+/**
+ * Register a table whose record batches are streamed from an Arrow Flight server whenever it is
+ * scanned, to query them together with the context's other tables. `uri` is the location of the
+ * server, such as "grpc://host:port", or "grpc+tls://host:port" to connect with TLS, verifying the
+ * server's certificate with the web's root authorities.
+ * Exactly one of `ticket`, of `ticket_len` bytes, and `sql` is given. A ticket is redeemed with
+ * DoGet by every scan, so it must stay valid as long as the table is registered. A Flight SQL
+ * query is planned with GetFlightInfo by every scan, which reads the endpoints returned as its
+ * partitions.
+ * The schema of the table is read when it is registered, from the query's FlightInfo or the first
+ * message streamed for the ticket. Scans that read other column types fail.
+ * `options` may be null to send no credentials.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_register_flight_table(const struct DataFusionContext *ctx,
+                                     const char *table_name,
+                                     const char *uri,
+                                     const uint8_t *ticket,
+                                     uintptr_t ticket_len,
+                                     const char *sql,
+                                     const struct DataFusionFlightOptions *options,
+                                     char **error_out);
+
+/**
+ * Deregister a table, such as one to register again after its files changed. Queries, DataFrames
+ * and statements already planned keep reading it.
+ * Returns DATAFUSION_OK on success, DATAFUSION_INVALID_ARGUMENT if no table of that name is
+ * registered, or another error code on failure
+ */
+int datafusion_deregister_table(const struct DataFusionContext *ctx,
+                                const char *table_name,
+                                char **error_out);
+
+/**
+ * List the queries running on a context: collected queries, whether synchronous, asynchronous,
+ * submitted or prepared statements, until they finish, and streams until they are freed. Each
+ * query is assigned an ID when it starts, unique within the context. May be called from any
+ * thread, including while other threads run queries on the context.
+ * Returns a result with one row per query, ordered by ID, with the columns `query_id` (Int64),
+ * `sql` (Utf8, null for plans not given as SQL, such as Substrait plans and DataFrames) and `elapsed_ms`
+ * (Int64, the time since the query started), or null on error
+ */
+struct DataFusionResult *datafusion_queries_list(const struct DataFusionContext *ctx,
+                                                 char **error_out);
+
+/**
+ * Kill a running query of a context by the ID listed by datafusion_queries_list. The query stops
+ * at its next batch and fails with DATAFUSION_CANCELLED. May be called from any thread.
+ * Returns DATAFUSION_OK on success, DATAFUSION_INVALID_ARGUMENT if no query with the ID is
+ * running, or another error code on failure
+ */
+int datafusion_query_kill(const struct DataFusionContext *ctx, int64_t query_id, char **error_out);
+
+/**
+ * Get the number of columns in a result, which is known even if it has no batches
+ * Returns 0 on error
+ */
+int datafusion_result_column_count(const struct DataFusionResult *result, char **error_out);
+
+/**
+ * Get the name of a column
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_result_column_name(const struct DataFusionResult *result,
+                                    int column_index,
+                                    char **error_out);
+
+/**
+ * Get the Arrow type of a column as one of the DATAFUSION_TYPE_* constants
+ * Returns -1 on error
+ */
+int datafusion_result_column_type(const struct DataFusionResult *result,
+                                  int column_index,
+                                  char **error_out);
+
+/**
+ * Get the full Arrow type of a column as a string, e.g. "Int64" or "Timestamp(Nanosecond, None)"
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_result_column_type_name(const struct DataFusionResult *result,
+                                         int column_index,
+                                         char **error_out);
+
+/**
+ * Check whether a column may contain nulls
+ * Returns false on error
+ */
+bool datafusion_result_column_nullable(const struct DataFusionResult *result,
+                                       int column_index,
+                                       char **error_out);
+
+/**
+ * Get the schema of a result
+ * Returns a pointer to the schema or null on error
+ */
+struct DataFusionSchema *datafusion_result_schema(const struct DataFusionResult *result,
+                                                  char **error_out);
+
+/**
+ * Get the schema of a table registered with the context
+ * Returns a pointer to the schema or null on error
+ */
+struct DataFusionSchema *datafusion_table_schema(const struct DataFusionContext *ctx,
+                                                 const char *table_name,
+                                                 char **error_out);
+
+/**
+ * Get the number of fields in a schema
+ * Returns 0 on error
+ */
+int datafusion_schema_field_count(const struct DataFusionSchema *schema, char **error_out);
+
+/**
+ * Get the name of a field
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_schema_field_name(const struct DataFusionSchema *schema,
+                                   int field_index,
+                                   char **error_out);
+
+/**
+ * Get the Arrow type of a field as one of the DATAFUSION_TYPE_* constants
+ * Returns -1 on error
+ */
+int datafusion_schema_field_type(const struct DataFusionSchema *schema,
+                                 int field_index,
+                                 char **error_out);
+
+/**
+ * Get the full Arrow type of a field as a string, e.g. "Int64" or "Timestamp(Nanosecond, None)"
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_schema_field_type_name(const struct DataFusionSchema *schema,
+                                        int field_index,
+                                        char **error_out);
+
+/**
+ * Check whether a field may contain nulls
+ * Returns false on error
+ */
+bool datafusion_schema_field_nullable(const struct DataFusionSchema *schema,
+                                      int field_index,
+                                      char **error_out);
+
+/**
+ * Get the child fields of a nested field as a schema: the members of a struct, or the single
+ * item field of a list or map. Fields of other types have no children.
+ * Returns a pointer to the schema or null on error
+ */
+struct DataFusionSchema *datafusion_schema_field_children(const struct DataFusionSchema *schema,
+                                                          int field_index,
+                                                          char **error_out);
+
+/**
+ * Export a schema through the Arrow C data interface, as a struct type with one child per field.
+ * The caller must call the `release` callback when done; `out` may point to uninitialized memory.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_schema_export(const struct DataFusionSchema *schema,
+                             ArrowSchema *out,
+                             char **error_out);
+
+/**
+ * Free a schema
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_schema_free(struct DataFusionSchema *schema);
+
+/**
+ * Execute a script of `;`-separated SQL statements, such as DDL followed by queries, in order.
+ * Execution stops at the first failing statement; the statements after it are not executed. Each
+ * statement uses the context's maximum result size and query timeout.
+ * Returns a pointer to the script's outcomes, or null on error if the script could not be parsed
+ * (in which case no statement was executed)
+ */
+struct DataFusionScript *datafusion_sql_script(const struct DataFusionContext *ctx,
+                                               const char *sql,
+                                               char **error_out);
+
+/**
+ * Get the number of statements in a script
+ * Returns -1 on error
+ */
+int datafusion_script_statement_count(const struct DataFusionScript *script, char **error_out);
+
+/**
+ * Get the status of a statement of a script. A failed statement's error is reported like the
+ * error of any failed call, through error_out and datafusion_last_error.
+ * Returns DATAFUSION_OK if the statement succeeded, the error code it failed with,
+ * DATAFUSION_CANCELLED if it was not executed because an earlier statement failed, or another
+ * error code for an invalid script or index
+ */
+int datafusion_script_status(const struct DataFusionScript *script,
+                             int statement_index,
+                             char **error_out);
+
+/**
+ * Get the result of a statement of a script, as a new result to be freed with
+ * datafusion_result_free. DDL statements have empty results.
+ * Returns a pointer to the result, or null on error, which for a failed statement is the
+ * statement's error
+ */
+struct DataFusionResult *datafusion_script_result(const struct DataFusionScript *script,
+                                                  int statement_index,
+                                                  char **error_out);
+
+/**
+ * Free a script and the results of its statements
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_script_free(struct DataFusionScript *script);
+
+/**
+ * Parse and plan a SQL query with positional placeholders (`$1`, `$2`, ...) for values bound later
+ * with the datafusion_stmt_bind_* functions, so values never have to be spliced into the SQL text.
+ * The statement can be executed any number of times, with bindings kept between executions. It
+ * uses the context's maximum result size and query timeout as they are when it is prepared, and
+ * stays valid after the context is freed. INSERT INTO and COPY statements can be prepared too, and
+ * write their rows each time they are executed; DDL statements such as CREATE TABLE cannot.
+ * Returns a pointer to the statement or null on error
+ */
+struct DataFusionStatement *datafusion_prepare(const struct DataFusionContext *ctx,
+                                               const char *sql,
+                                               char **error_out);
+
+/**
+ * Get the number of parameters of a statement, so the valid indexes are 1 to the count
+ * Returns -1 on error
+ */
+int datafusion_stmt_parameter_count(const struct DataFusionStatement *stmt, char **error_out);
+
+/**
+ * Bind an integer to the parameter at `index`, counting from 1 like `$1`
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_stmt_bind_int64(struct DataFusionStatement *stmt,
+                               int index,
+                               int64_t value,
+                               char **error_out);
+
+/**
+ * Bind a double to the parameter at `index`, counting from 1 like `$1`
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_stmt_bind_double(struct DataFusionStatement *stmt,
+                                int index,
+                                double value,
+                                char **error_out);
+
+/**
+ * Bind a copy of a UTF-8 string to the parameter at `index`, counting from 1 like `$1`
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_stmt_bind_string(struct DataFusionStatement *stmt,
+                                int index,
+                                const char *value,
+                                char **error_out);
+
+/**
+ * Bind SQL NULL to the parameter at `index`, counting from 1 like `$1`
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_stmt_bind_null(struct DataFusionStatement *stmt, int index, char **error_out);
+
+/**
+ * Execute a statement with its current bindings. Every parameter must be bound.
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_stmt_execute(const struct DataFusionStatement *stmt,
+                                                 char **error_out);
+
+/**
+ * Serialize the logical plan of a statement, including its placeholders but not its bindings, with
+ * DataFusion's protobuf format, to be cached or sent to another process linking this library and
+ * loaded with datafusion_plan_deserialize. Plans reading tables created in memory, such as with
+ * CREATE TABLE AS, cannot be serialized; plans reading files registered as tables can.
+ * `out` is set to a buffer of `len` bytes to be freed with datafusion_buffer_free.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_plan_serialize(const struct DataFusionStatement *stmt,
+                              uint8_t **out,
+                              uintptr_t *len,
+                              char **error_out);
+
+/**
+ * Load a logical plan serialized by datafusion_plan_serialize as a statement of the context, ready
+ * to be bound and executed like a prepared statement. Tables read by the plan are restored from
+ * their serialized definitions rather than looked up in the context.
+ * Returns a pointer to the statement or null on error
+ */
+struct DataFusionStatement *datafusion_plan_deserialize(const struct DataFusionContext *ctx,
+                                                        const uint8_t *plan,
+                                                        uintptr_t len,
+                                                        char **error_out);
+
+/**
+ * Free a prepared statement
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_stmt_free(struct DataFusionStatement *stmt);
+
+/**
+ * Get the number of null values in a column across all batches
+ * Returns -1 on error
+ */
+int64_t datafusion_result_column_null_count(const struct DataFusionResult *result,
+                                            int column_index,
+                                            char **error_out);
+
+/**
+ * Get the smallest and largest values of an integer column across all batches
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the column has no non-null values,
+ * DATAFUSION_TYPE_MISMATCH if the column is not an integer column, or another error code on failure
+ */
+int datafusion_result_column_min_max_int64(const struct DataFusionResult *result,
+                                           int column_index,
+                                           int64_t *min,
+                                           int64_t *max,
+                                           char **error_out);
+
+/**
+ * Get the smallest and largest values of a numeric column (integer, floating point or decimal)
+ * across all batches, converted to double
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the column has no non-null values,
+ * DATAFUSION_TYPE_MISMATCH if the column is not numeric, or another error code on failure
+ */
+int datafusion_result_column_min_max_float64(const struct DataFusionResult *result,
+                                             int column_index,
+                                             double *min,
+                                             double *max,
+                                             char **error_out);
+
+/**
+ * Get the smallest value of a column of any orderable type across all batches, formatted like
+ * datafusion_result_get_display
+ * Returns a string to be freed with datafusion_string_free, or null if the column has no
+ * non-null values or on error (check error_out to tell them apart)
+ */
+char *datafusion_result_column_min(const struct DataFusionResult *result,
+                                   int column_index,
+                                   char **error_out);
+
+/**
+ * Get the largest value of a column of any orderable type across all batches, formatted like
+ * datafusion_result_get_display
+ * Returns a string to be freed with datafusion_string_free, or null if the column has no
+ * non-null values or on error (check error_out to tell them apart)
+ */
+char *datafusion_result_column_max(const struct DataFusionResult *result,
+                                   int column_index,
+                                   char **error_out);
+
+/**
+ * Plan a SQL query and start executing it, without collecting its results. Batches are computed
+ * as they are fetched with datafusion_stream_next, so results of any size can be consumed in
+ * bounded memory; the context's maximum result size does not apply. The stream keeps running
+ * on the context's runtime and stays valid after the context is freed. It is listed as a running
+ * query of the context until it is freed; once killed, fetching fails with DATAFUSION_CANCELLED.
+ * Returns a pointer to the stream or null on error
+ */
+struct DataFusionStream *datafusion_sql_stream(const struct DataFusionContext *ctx,
+                                               const char *sql,
+                                               char **error_out);
+
+/**
+ * Fetch the next batch of a stream as a result with a single batch, to be freed with
+ * datafusion_result_free. Metrics of the result cover the stream's execution so far.
+ * Returns a pointer to the result, or null when the stream is exhausted or on error
+ * (check error_out to tell them apart)
+ */
+struct DataFusionResult *datafusion_stream_next(struct DataFusionStream *stream, char **error_out);
+
+/**
+ * Get the schema of the batches produced by a stream
+ * Returns a pointer to the schema or null on error
+ */
+struct DataFusionSchema *datafusion_stream_schema(const struct DataFusionStream *stream,
+                                                  char **error_out);
+
+/**
+ * Free a stream, stopping the query if it has not finished
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_stream_free(struct DataFusionStream *stream);
+
+/**
+ * Execute a Substrait plan, serialized as `len` bytes of protobuf, against the tables registered
+ * with the context. Table references in the plan are resolved by name like in SQL queries. The
+ * plan is executed like datafusion_sql, with the context's maximum result size and query timeout.
+ * Returns a pointer to the result or null on error
+ */
+struct DataFusionResult *datafusion_execute_substrait(const struct DataFusionContext *ctx,
+                                                      const uint8_t *plan,
+                                                      uintptr_t len,
+                                                      char **error_out);
+
+/**
+ * Plan a SQL query without executing it and serialize its optimized logical plan as a Substrait
+ * plan in protobuf form, for execution by another engine or with datafusion_execute_substrait.
+ * `out` is set to a buffer of `len` bytes to be freed with datafusion_buffer_free.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_sql_to_substrait(const struct DataFusionContext *ctx,
+                                const char *sql,
+                                uint8_t **out,
+                                uintptr_t *len,
+                                char **error_out);
+
+/**
+ * Create a builder of an in-memory table, to add columns to from C arrays
+ * Returns a pointer to the builder or null on error
+ */
+struct DataFusionTableBuilder *datafusion_table_builder_new(char **error_out);
+
+/**
+ * Add an Int64 column of `len` values, copied from `values`. `validity` is a bitmap of `len`
+ * bits, set for the valid values from the least significant bit of its first byte as in Arrow, or
+ * null if all values are valid. Every column of a table has the same number of values.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_table_builder_add_int64(struct DataFusionTableBuilder *builder,
+                                       const char *name,
+                                       const int64_t *values,
+                                       const uint8_t *validity,
+                                       uintptr_t len,
+                                       char **error_out);
+
+/**
+ * Add a Float64 column of `len` values, copied from `values`, with the nulls of `validity` as in
+ * datafusion_table_builder_add_int64
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_table_builder_add_float64(struct DataFusionTableBuilder *builder,
+                                         const char *name,
+                                         const double *values,
+                                         const uint8_t *validity,
+                                         uintptr_t len,
+                                         char **error_out);
+
+/**
+ * Add a Boolean column of `len` values, copied from an array of `bool`, with the nulls of
+ * `validity` as in datafusion_table_builder_add_int64
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_table_builder_add_bool(struct DataFusionTableBuilder *builder,
+                                      const char *name,
+                                      const bool *values,
+                                      const uint8_t *validity,
+                                      uintptr_t len,
+                                      char **error_out);
+
+/**
+ * Add a Utf8 column of `len` strings, copied from Arrow's layout: the bytes of string `i` are
+ * `data[offsets[i]..offsets[i + 1]]`, so `offsets` has `len + 1` non-decreasing entries and `data`
+ * holds `offsets[len]` bytes of UTF-8 (it may be null if there are none). Nulls are given by
+ * `validity` as in datafusion_table_builder_add_int64.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_table_builder_add_string(struct DataFusionTableBuilder *builder,
+                                        const char *name,
+                                        const int32_t *offsets,
+                                        const uint8_t *data,
+                                        const uint8_t *validity,
+                                        uintptr_t len,
+                                        char **error_out);
+
+/**
+ * Register the columns added to a builder as an in-memory table with the context. The builder
+ * keeps its columns: more can be added to register another table.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_table_builder_register(const struct DataFusionTableBuilder *builder,
+                                      const struct DataFusionContext *ctx,
+                                      const char *table_name,
+                                      char **error_out);
+
+/**
+ * Append the rows of the columns added to a builder to a registered table that accepts inserts,
+ * such as the in-memory tables registered by datafusion_table_builder_register, like INSERT INTO
+ * does. Columns are matched to those of the table by name and cast to their types; every column
+ * of the table needs a column in the builder. The number of rows inserted is stored in
+ * `rows_inserted` if it is not null. The builder keeps its columns.
+ * Returns DATAFUSION_OK on success or an error code on failure
+ */
+int datafusion_table_builder_insert(const struct DataFusionTableBuilder *builder,
+                                    const struct DataFusionContext *ctx,
+                                    const char *table_name,
+                                    int64_t *rows_inserted,
+                                    char **error_out);
+
+/**
+ * Free a table builder. Tables registered from it stay valid.
+ * Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+ */
+int datafusion_table_builder_free(struct DataFusionTableBuilder *builder);
+
+/**
+ * Get a date value from a Date32 or Date64 result cell. `value` is set to the number of days
+ * (Date32, DATAFUSION_TIME_UNIT_DAY) or milliseconds (Date64, DATAFUSION_TIME_UNIT_MILLISECOND)
+ * since the Unix epoch and `unit` to its unit.
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not a date column, or another error code on failure
+ */
+int datafusion_result_get_date(const struct DataFusionResult *result,
+                               int batch_index,
+                               int row_index,
+                               int column_index,
+                               int64_t *value,
+                               int *unit,
+                               char **error_out);
+
+/**
+ * Get a timestamp value from a result cell. `value` is set to the time since the Unix epoch in UTC
+ * and `unit` to its unit (one of DATAFUSION_TIME_UNIT_SECOND through _NANOSECOND). The column's
+ * timezone is available from datafusion_result_column_timezone.
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not a timestamp column, or another error code on failure
+ */
+int datafusion_result_get_timestamp(const struct DataFusionResult *result,
+                                    int batch_index,
+                                    int row_index,
+                                    int column_index,
+                                    int64_t *value,
+                                    int *unit,
+                                    char **error_out);
+
+/**
+ * Get a time of day value from a Time32 or Time64 result cell. `value` is set to the time since
+ * midnight and `unit` to its unit.
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not a time column, or another error code on failure
+ */
+int datafusion_result_get_time(const struct DataFusionResult *result,
+                               int batch_index,
+                               int row_index,
+                               int column_index,
+                               int64_t *value,
+                               int *unit,
+                               char **error_out);
+
+/**
+ * Get an interval value from a result cell of any interval unit
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not an interval column, or another error code on failure
+ */
+int datafusion_result_get_interval(const struct DataFusionResult *result,
+                                   int batch_index,
+                                   int row_index,
+                                   int column_index,
+                                   struct DataFusionInterval *out,
+                                   char **error_out);
+
+/**
+ * Get the unit of a temporal column (date, timestamp, time or duration) as a DATAFUSION_TIME_UNIT_* constant
+ * Returns -1 on error
+ */
+int datafusion_result_column_time_unit(const struct DataFusionResult *result,
+                                       int column_index,
+                                       char **error_out);
+
+/**
+ * Get the timezone of a timestamp column, e.g. "UTC" or "+02:00"
+ * Returns a string to be freed with datafusion_string_free, or null if the column has no timezone
+ * or on error (check error_out to tell them apart)
+ */
+char *datafusion_result_column_timezone(const struct DataFusionResult *result,
+                                        int column_index,
+                                        char **error_out);
+
+/**
+ * Parse, plan and analyze a SQL statement without executing it, not even DDL, and describe any
+ * problem as JSON: `{"valid": true}` for a valid statement, otherwise
+ * `{"valid": false, "kind", "message", "line", "column", "name", "candidates"}` where `kind` is
+ * `syntax`, `unknown_table`, `unknown_column` or `plan` (any other planning error, such as a type
+ * error). `line` and `column` are 1-based and point at the syntax error or the first occurrence
+ * of the unresolved `name`; they and `name` are null when unknown. `candidates` lists the columns
+ * available where an unknown column was referenced.
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_sql_validate(const struct DataFusionContext *ctx,
+                              const char *sql,
+                              char **error_out);
+
+/**
+ * Get the index of the column with the given name
+ * Returns -1 on error, including when there is no such column
+ */
+int datafusion_result_column_index(const struct DataFusionResult *result,
+                                   const char *column_name,
+                                   char **error_out);
+
+/**
+ * Check whether a result cell is null, for columns of any type
+ * Returns false on error
+ */
+bool datafusion_result_is_null(const struct DataFusionResult *result,
+                               int batch_index,
+                               int row_index,
+                               int column_index,
+                               char **error_out);
+
+/**
+ * Get an Int64 value from a result cell
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not Int64, or another error code on failure
+ */
+int datafusion_result_get_int64(const struct DataFusionResult *result,
+                                int batch_index,
+                                int row_index,
+                                int column_index,
+                                int64_t *out,
+                                char **error_out);
+
+/**
+ * Get an Int32 value from a result cell
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not Int32, or another error code on failure
+ */
+int datafusion_result_get_int32(const struct DataFusionResult *result,
+                                int batch_index,
+                                int row_index,
+                                int column_index,
+                                int32_t *out,
+                                char **error_out);
+
+/**
+ * Get a Float64 value from a result cell
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not Float64, or another error code on failure
+ */
+int datafusion_result_get_float64(const struct DataFusionResult *result,
+                                  int batch_index,
+                                  int row_index,
+                                  int column_index,
+                                  double *out,
+                                  char **error_out);
+
+/**
+ * Get a Boolean value from a result cell
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not Boolean, or another error code on failure
+ */
+int datafusion_result_get_bool(const struct DataFusionResult *result,
+                               int batch_index,
+                               int row_index,
+                               int column_index,
+                               bool *out,
+                               char **error_out);
+
+/**
+ * Get a string value from a result cell without copying. `data` is set to the UTF-8 bytes, which are
+ * not null terminated and stay valid until the result is freed, and `len` to their length in bytes.
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not a string column, or another error code on failure
+ */
+int datafusion_result_get_string(const struct DataFusionResult *result,
+                                 int batch_index,
+                                 int row_index,
+                                 int column_index,
+                                 const char **data,
+                                 uintptr_t *len,
+                                 char **error_out);
+
+/**
+ * Get a copy of a string value from a result cell as a null terminated string
+ * Returns a string to be freed with datafusion_string_free, or null on error (including null values)
+ */
+char *datafusion_result_get_string_copy(const struct DataFusionResult *result,
+                                        int batch_index,
+                                        int row_index,
+                                        int column_index,
+                                        char **error_out);
+
+/**
+ * Format a result cell of any type as a string, the way it appears in datafusion_result_format:
+ * temporal values in ISO 8601, decimals with their scale and nested values in brackets.
+ * Null values are formatted as empty strings.
+ * Returns a string to be freed with datafusion_string_free, or null on error
+ */
+char *datafusion_result_get_display(const struct DataFusionResult *result,
+                                    int batch_index,
+                                    int row_index,
+                                    int column_index,
+                                    char **error_out);
+
+/**
+ * Get a binary value from a result cell without copying. `data` is set to the bytes, which stay
+ * valid until the result is freed, and `len` to their length. Works for Binary, LargeBinary,
+ * FixedSizeBinary and BinaryView columns.
+ * Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+ * DATAFUSION_TYPE_MISMATCH if the column is not a binary column, or another error code on failure
+ */
+int datafusion_result_get_binary(const struct DataFusionResult *result,
+                                 int batch_index,
+                                 int row_index,
+                                 int column_index,
+                                 const uint8_t **data,
+                                 uintptr_t *len,
+                                 char **error_out);
+
+/**
+ * Get an Int64 value from a result cell, selecting the column by name
+ * Returns the same codes as datafusion_result_get_int64
+ */
+int datafusion_result_get_int64_by_name(const struct DataFusionResult *result,
+                                        int batch_index,
+                                        int row_index,
+                                        const char *column_name,
+                                        int64_t *out,
+                                        char **error_out);
+
+/**
+ * Get an Int32 value from a result cell, selecting the column by name
+ * Returns the same codes as datafusion_result_get_int32
+ */
+int datafusion_result_get_int32_by_name(const struct DataFusionResult *result,
+                                        int batch_index,
+                                        int row_index,
+                                        const char *column_name,
+                                        int32_t *out,
+                                        char **error_out);
+
+/**
+ * Get a Float64 value from a result cell, selecting the column by name
+ * Returns the same codes as datafusion_result_get_float64
+ */
+int datafusion_result_get_float64_by_name(const struct DataFusionResult *result,
+                                          int batch_index,
+                                          int row_index,
+                                          const char *column_name,
+                                          double *out,
+                                          char **error_out);
+
+/**
+ * Get a Boolean value from a result cell, selecting the column by name
+ * Returns the same codes as datafusion_result_get_bool
+ */
+int datafusion_result_get_bool_by_name(const struct DataFusionResult *result,
+                                       int batch_index,
+                                       int row_index,
+                                       const char *column_name,
+                                       bool *out,
+                                       char **error_out);
+
+/**
+ * Get a string value from a result cell without copying, selecting the column by name
+ * Returns the same codes as datafusion_result_get_string
+ */
+int datafusion_result_get_string_by_name(const struct DataFusionResult *result,
+                                         int batch_index,
+                                         int row_index,
+                                         const char *column_name,
+                                         const char **data,
+                                         uintptr_t *len,
+                                         char **error_out);
+
+/**
+ * Get the version of DataFusion the library was built against
+ */
+const char *datafusion_version(void);
+
+/**
+ * Get the version of arrow-rs the library was built against
+ */
+const char *datafusion_arrow_version(void);
+
+/**
+ * Get the version of iceberg-rust the library was built against
+ */
+const char *iceberg_rust_version(void);
+
+/**
+ * Get the versions and build settings of the library
+ */
+struct DataFusionBuildInfo datafusion_build_info(void);
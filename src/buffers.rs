@@ -0,0 +1,76 @@
+use std::ffi::c_void;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use datafusion::arrow::datatypes::DataType;
+
+use crate::error::{ffi_code, DataFusionError};
+use crate::handle::handle_ref;
+use crate::values::write_out;
+use crate::{DataFusionResult, DATAFUSION_TYPE_MISMATCH};
+
+/// Get pointers into the Arrow buffers of a column in a batch, for reading it without per-cell calls.
+/// All pointers stay valid until the result is freed.
+///
+/// - `validity`: the validity bitmap (bit set = valid, least significant bit first) starting at bit
+///   `validity_offset`, or null if the column has no nulls
+/// - `offsets`: for Utf8/Binary columns, `len + 1` int32 offsets into `data` (int64 for LargeUtf8 and
+///   LargeBinary); null for primitive columns
+/// - `data`: the `len` fixed width values of a primitive column, or the value bytes of a string or
+///   binary column
+///
+/// Primitive columns are the numeric, date, time, timestamp, duration, interval and decimal types.
+/// Returns DATAFUSION_OK on success, DATAFUSION_TYPE_MISMATCH for other column types,
+/// or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_buffers(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    column_index: c_int,
+    validity: *mut *const u8,
+    validity_offset: *mut usize,
+    offsets: *mut *const c_void,
+    data: *mut *const c_void,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let batch = result.batch(batch_index)?;
+        result.field(column_index)?;
+        // Shares the column's buffers, so the pointers outlive this copy of the array data
+        let array = batch.column(column_index as usize).to_data();
+
+        let buffers = array.buffers();
+        let (offsets_ptr, data_ptr) = match array.data_type() {
+            DataType::Utf8 | DataType::Binary => {
+                let offsets_ptr = buffers[0].as_ptr().wrapping_add(array.offset() * size_of::<i32>());
+                (offsets_ptr, buffers[1].as_ptr())
+            }
+            DataType::LargeUtf8 | DataType::LargeBinary => {
+                let offsets_ptr = buffers[0].as_ptr().wrapping_add(array.offset() * size_of::<i64>());
+                (offsets_ptr, buffers[1].as_ptr())
+            }
+            data_type if data_type.is_primitive() => {
+                let width = data_type.primitive_width().unwrap_or_default();
+                (ptr::null(), buffers[0].as_ptr().wrapping_add(array.offset() * width))
+            }
+            other => {
+                return Err(DataFusionError::new(
+                    DATAFUSION_TYPE_MISMATCH,
+                    format!("column has type {other}, which has no primitive or string buffers"),
+                ))
+            }
+        };
+        let (validity_ptr, bit_offset) = match array.nulls() {
+            Some(nulls) => (nulls.buffer().as_ptr(), nulls.offset()),
+            None => (ptr::null(), 0),
+        };
+
+        write_out(validity, validity_ptr)?;
+        write_out(validity_offset, bit_offset)?;
+        write_out(offsets, offsets_ptr as *const c_void)?;
+        write_out(data, data_ptr as *const c_void)?;
+        write_out(len, array.len())
+    })
+}
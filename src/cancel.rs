@@ -0,0 +1,142 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{exec_err, Result};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use futures::StreamExt;
+
+use crate::{DATAFUSION_CANCELLED, DATAFUSION_TIMEOUT};
+
+/// Flag shared between a running query and the handles that can cancel it, together with the
+/// deadline of a query with a timeout
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl Cancellation {
+    /// Also cancel the query once `timeout` has elapsed from now
+    pub(crate) fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            deadline: Some((Instant::now() + timeout, timeout)),
+            ..self
+        }
+    }
+
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.deadline.map(|(deadline, _)| deadline)
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.timed_out()
+    }
+
+    fn timed_out(&self) -> bool {
+        self.deadline().is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// The error reported for a cancelled query: DATAFUSION_TIMEOUT if it ran out of time,
+    /// DATAFUSION_CANCELLED otherwise
+    pub(crate) fn error(&self) -> crate::DataFusionError {
+        match self.deadline {
+            Some((_, timeout)) if !self.cancelled.load(Ordering::Relaxed) => crate::DataFusionError::new(
+                DATAFUSION_TIMEOUT,
+                format!("query exceeded the timeout of {} ms", timeout.as_millis()),
+            ),
+            _ => crate::DataFusionError::new(DATAFUSION_CANCELLED, "query was cancelled"),
+        }
+    }
+}
+
+/// Passes batches of its input through, failing once the query is cancelled.
+///
+/// DataFusion operators poll their inputs in tight loops without yielding to the runtime, so
+/// aborting the task of a CPU bound query only takes effect when it finishes. Wrapping the leaves
+/// of the plan makes every operator see an error at its next batch instead.
+#[derive(Debug)]
+struct CancellableExec {
+    input: Arc<dyn ExecutionPlan>,
+    cancellation: Cancellation,
+}
+
+impl DisplayAs for CancellableExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CancellableExec")
+    }
+}
+
+impl ExecutionPlan for CancellableExec {
+    fn name(&self) -> &str {
+        "CancellableExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        self.input.properties()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn with_new_children(self: Arc<Self>, children: Vec<Arc<dyn ExecutionPlan>>) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            input: children[0].clone(),
+            cancellation: self.cancellation.clone(),
+        }))
+    }
+
+    // DataFusion's error type grows past clippy's limit when the avro feature adds its Avro variant
+    #[allow(clippy::result_large_err)]
+    fn execute(&self, partition: usize, context: Arc<TaskContext>) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        let schema = input.schema();
+        let cancellation = self.cancellation.clone();
+        let stream = input.map(move |batch| {
+            if cancellation.is_cancelled() {
+                return exec_err!("query was cancelled");
+            }
+            batch
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+}
+
+/// Wrap the leaves of an optimized physical plan so that it stops once `cancellation` is cancelled
+// DataFusion's error type grows past clippy's limit when the avro feature adds its Avro variant
+#[allow(clippy::result_large_err)]
+pub(crate) fn cancellable(plan: Arc<dyn ExecutionPlan>, cancellation: &Cancellation) -> Result<Arc<dyn ExecutionPlan>> {
+    let plan = plan.transform_up(|node| {
+        if !node.children().is_empty() {
+            return Ok(Transformed::no(node));
+        }
+        let wrapped: Arc<dyn ExecutionPlan> = Arc::new(CancellableExec {
+            input: node,
+            cancellation: cancellation.clone(),
+        });
+        Ok(Transformed::yes(wrapped))
+    })?;
+    Ok(plan.data)
+}
+
+/// Skip the wrapper added by `cancellable`, for reporting the plan as DataFusion built it
+pub(crate) fn unwrap_cancellable(plan: &Arc<dyn ExecutionPlan>) -> &Arc<dyn ExecutionPlan> {
+    match plan.as_any().downcast_ref::<CancellableExec>() {
+        Some(wrapper) => &wrapper.input,
+        None => plan,
+    }
+}
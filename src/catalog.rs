@@ -0,0 +1,109 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::error::{ffi_code, ffi_ptr, ffi_string, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_ref, impl_handle, HandleHeader};
+use crate::DataFusionContext;
+
+/// A list of strings, such as the names of the tables of a schema
+pub struct DataFusionStringList {
+    header: HandleHeader,
+    strings: Vec<String>,
+}
+
+impl_handle!(DataFusionStringList, 0x4446_5354_524c_0010, string_lists);
+
+/// A C string argument that may be null
+fn optional_str_arg<'a>(value: *const c_char, name: &str) -> FfiResult<Option<&'a str>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    str_arg(value, name).map(Some)
+}
+
+/// List the names of the tables registered in a schema of a catalog, sorted, including views.
+/// `catalog` and `schema` may be null for the context's default catalog and schema, "datafusion"
+/// and "public" unless configured otherwise.
+/// Returns a pointer to the list, or null on error with DATAFUSION_INVALID_ARGUMENT if the catalog
+/// or schema does not exist
+#[no_mangle]
+pub extern "C" fn datafusion_table_names(
+    ctx: *const DataFusionContext,
+    catalog: *const c_char,
+    schema: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionStringList {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let config = ctx.ctx.copied_config();
+        let defaults = &config.options().catalog;
+        let catalog_name = optional_str_arg(catalog, "catalog")?.unwrap_or(&defaults.default_catalog);
+        let schema_name = optional_str_arg(schema, "schema")?.unwrap_or(&defaults.default_schema);
+
+        let catalog = ctx
+            .ctx
+            .catalog(catalog_name)
+            .ok_or_else(|| DataFusionError::invalid_argument(format!("no catalog named {catalog_name}")))?;
+        let schema = catalog.schema(schema_name).ok_or_else(|| {
+            DataFusionError::invalid_argument(format!("no schema named {schema_name} in catalog {catalog_name}"))
+        })?;
+        let mut strings = schema.table_names();
+        strings.sort();
+        Ok(Box::new(DataFusionStringList {
+            header: HandleHeader::default(),
+            strings,
+        }))
+    })
+}
+
+/// Check whether a table is registered with the context. The name is resolved as in SQL: it may be
+/// qualified as `schema.table` or `catalog.schema.table`, and unquoted parts are lower cased.
+/// Returns true if the table exists, or false if it does not or on error
+#[no_mangle]
+pub extern "C" fn datafusion_table_exists(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_value(error_out, false, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        Ok(ctx.ctx.table_exist(str_arg(table_name, "table_name")?)?)
+    })
+}
+
+/// Get the number of strings in a list
+/// Returns 0 on error
+#[no_mangle]
+pub extern "C" fn datafusion_string_list_count(list: *const DataFusionStringList, error_out: *mut *mut c_char) -> c_int {
+    ffi_value(error_out, 0, || Ok(handle_ref(list, "list")?.strings.len() as c_int))
+}
+
+/// Get a string of a list
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_string_list_get(
+    list: *const DataFusionStringList,
+    index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let strings = &handle_ref(list, "list")?.strings;
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| strings.get(i))
+            .cloned()
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "index {index} out of range for list with {} strings",
+                    strings.len()
+                ))
+            })
+    })
+}
+
+/// Free a string list
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_string_list_free(list: *mut DataFusionStringList) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(list, "list"))
+}
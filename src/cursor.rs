@@ -0,0 +1,208 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use datafusion::arrow::array::{BooleanArray, Float64Array, Int32Array, Int64Array};
+
+use crate::error::{ffi_code, ffi_ptr, ffi_string, ffi_value, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_mut, handle_ref, impl_handle, HandleHeader};
+use crate::values::{write_out, Cell};
+use crate::DataFusionResult;
+
+/// Forward-only cursor over the rows of a result, across all of its batches
+pub struct DataFusionRowCursor {
+    header: HandleHeader,
+    /// The rows to iterate, sharing the batches of the result so it may be freed first
+    result: DataFusionResult,
+    /// Batch and row of the current row; None before the first call to next
+    position: Option<(usize, usize)>,
+}
+
+impl_handle!(DataFusionRowCursor, 0x4446_4355_5253_0008, cursors);
+
+impl DataFusionRowCursor {
+    /// Move to the next row, skipping empty batches. Returns false once the rows are exhausted.
+    fn advance(&mut self) -> bool {
+        let (mut batch, mut row) = match self.position {
+            Some((batch, row)) => (batch, row + 1),
+            None => (0, 0),
+        };
+        let batches = &self.result.batches;
+        while batch < batches.len() && row >= batches[batch].num_rows() {
+            batch += 1;
+            row = 0;
+        }
+        self.position = Some((batch, row));
+        batch < batches.len()
+    }
+
+    fn cell(&self, column_index: c_int) -> FfiResult<Cell<'_>> {
+        match self.position {
+            Some((batch, row)) if batch < self.result.batches.len() => {
+                self.result.cell(batch as c_int, row as c_int, column_index)
+            }
+            Some(_) => Err(DataFusionError::invalid_argument("cursor is past the last row")),
+            None => Err(DataFusionError::invalid_argument(
+                "cursor is before the first row; call datafusion_cursor_next first",
+            )),
+        }
+    }
+}
+
+/// Create a cursor over the rows of a result, positioned before the first row.
+/// The cursor shares the result's data, so it stays valid after the result is freed.
+/// Returns a pointer to the cursor or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_cursor(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionRowCursor {
+    ffi_ptr(error_out, || {
+        let result = handle_ref(result, "result")?;
+        Ok(Box::new(DataFusionRowCursor {
+            header: HandleHeader::default(),
+            result: DataFusionResult {
+                header: HandleHeader::valid::<DataFusionResult>(),
+                batches: result.batches.clone(),
+                schema: result.schema.clone(),
+                plan: None,
+                partitions: None,
+            },
+            position: None,
+        }))
+    })
+}
+
+/// Move the cursor to the next row
+/// Returns true if the cursor is on a row, or false at the end of the rows or on error
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_next(cursor: *mut DataFusionRowCursor, error_out: *mut *mut c_char) -> bool {
+    ffi_value(error_out, false, || Ok(handle_mut(cursor, "cursor")?.advance()))
+}
+
+/// Get the index of the current row across all batches of the result
+/// Returns -1 before the first row or on error
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_row_number(
+    cursor: *const DataFusionRowCursor,
+    error_out: *mut *mut c_char,
+) -> i64 {
+    ffi_value(error_out, -1, || {
+        let cursor = handle_ref(cursor, "cursor")?;
+        let Some((batch, row)) = cursor.position else {
+            return Ok(-1);
+        };
+        let preceding: usize = cursor.result.batches[..batch].iter().map(|b| b.num_rows()).sum();
+        Ok((preceding + row) as i64)
+    })
+}
+
+/// Check whether a value of the current row is null, for columns of any type
+/// Returns false on error
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_is_null(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_value(error_out, false, || Ok(handle_ref(cursor, "cursor")?.cell(column_index)?.is_null()))
+}
+
+/// Get an Int64 value of the current row
+/// Returns the same codes as datafusion_result_get_int64
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_get_int64(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    out: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(cursor, "cursor")?.cell(column_index)?;
+        write_out(out, cell.value_array::<Int64Array>("Int64")?.value(cell.row))
+    })
+}
+
+/// Get an Int32 value of the current row
+/// Returns the same codes as datafusion_result_get_int32
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_get_int32(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    out: *mut i32,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(cursor, "cursor")?.cell(column_index)?;
+        write_out(out, cell.value_array::<Int32Array>("Int32")?.value(cell.row))
+    })
+}
+
+/// Get a Float64 value of the current row
+/// Returns the same codes as datafusion_result_get_float64
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_get_float64(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    out: *mut f64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(cursor, "cursor")?.cell(column_index)?;
+        write_out(out, cell.value_array::<Float64Array>("Float64")?.value(cell.row))
+    })
+}
+
+/// Get a Boolean value of the current row
+/// Returns the same codes as datafusion_result_get_bool
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_get_bool(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    out: *mut bool,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(cursor, "cursor")?.cell(column_index)?;
+        write_out(out, cell.value_array::<BooleanArray>("Boolean")?.value(cell.row))
+    })
+}
+
+/// Get a string value of the current row without copying. `data` stays valid until the cursor is
+/// freed, even after the cursor moves on, and is not null terminated.
+/// Returns the same codes as datafusion_result_get_string
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_get_string(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    data: *mut *const c_char,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(cursor, "cursor")?.cell(column_index)?;
+        let value = cell.str_value()?;
+        write_out(data, value.as_ptr() as *const c_char)?;
+        write_out(len, value.len())
+    })
+}
+
+/// Get a copy of a string value of the current row as a null terminated string
+/// Returns a string to be freed with datafusion_string_free, or null on error (including null values)
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_get_string_copy(
+    cursor: *const DataFusionRowCursor,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let cell = handle_ref(cursor, "cursor")?.cell(column_index)?;
+        Ok(cell.str_value()?.to_string())
+    })
+}
+
+/// Free a cursor
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_cursor_free(cursor: *mut DataFusionRowCursor) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(cursor, "cursor"))
+}
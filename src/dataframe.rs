@@ -0,0 +1,779 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{AsArray, Int64Array};
+use datafusion::common::{Column, JoinType};
+use datafusion::datasource::file_format::format_as_file_type;
+use datafusion::datasource::{provider_as_source, MemTable};
+use datafusion::datasource::file_format::parquet::ParquetFormatFactory;
+use datafusion::functions_aggregate::expr_fn::count;
+use datafusion::logical_expr::utils::COUNT_STAR_EXPANSION;
+use datafusion::logical_expr::expr::WindowFunction;
+use datafusion::logical_expr::{
+    LogicalPlanBuilder, Partitioning, SortExpr, WindowFrame, WindowFunctionDefinition, UNNAMED_TABLE,
+};
+use datafusion::logical_expr::type_coercion::binary::comparison_coercion;
+use datafusion::prelude::{DataFrame, Expr};
+
+use crate::error::{ffi_code, ffi_ptr, slice_arg, str_arg, str_array_arg, DataFusionError, FfiResult};
+use crate::explain::string_out;
+use crate::export::DataFusionParquetWriteOptions;
+use crate::expr::{self, DataFusionExpr};
+use crate::handle::{free_handle, handle_ref, impl_handle, HandleHeader};
+use crate::iceberg_write;
+use crate::logging::{self, ContextLogFilter};
+use crate::registry::QueryRegistry;
+use crate::repartition;
+use crate::schema::DataFusionSchema;
+use crate::stream::DataFusionStream;
+use crate::values::write_out;
+use crate::{DataFusionContext, DataFusionResult, IcebergTable, QueryOptions, DATAFUSION_ERROR};
+
+// Join types
+pub const DATAFUSION_JOIN_INNER: c_int = 0;
+pub const DATAFUSION_JOIN_LEFT: c_int = 1;
+pub const DATAFUSION_JOIN_RIGHT: c_int = 2;
+pub const DATAFUSION_JOIN_FULL: c_int = 3;
+/// Rows of the left DataFrame with a match in the right one, with only the left columns
+pub const DATAFUSION_JOIN_LEFT_SEMI: c_int = 4;
+pub const DATAFUSION_JOIN_RIGHT_SEMI: c_int = 5;
+/// Rows of the left DataFrame without a match in the right one, with only the left columns
+pub const DATAFUSION_JOIN_LEFT_ANTI: c_int = 6;
+pub const DATAFUSION_JOIN_RIGHT_ANTI: c_int = 7;
+
+fn join_type(join_type: c_int) -> FfiResult<JoinType> {
+    Ok(match join_type {
+        DATAFUSION_JOIN_INNER => JoinType::Inner,
+        DATAFUSION_JOIN_LEFT => JoinType::Left,
+        DATAFUSION_JOIN_RIGHT => JoinType::Right,
+        DATAFUSION_JOIN_FULL => JoinType::Full,
+        DATAFUSION_JOIN_LEFT_SEMI => JoinType::LeftSemi,
+        DATAFUSION_JOIN_RIGHT_SEMI => JoinType::RightSemi,
+        DATAFUSION_JOIN_LEFT_ANTI => JoinType::LeftAnti,
+        DATAFUSION_JOIN_RIGHT_ANTI => JoinType::RightAnti,
+        _ => return Err(DataFusionError::invalid_argument(format!("invalid join type {join_type}"))),
+    })
+}
+
+/// A query planned but not executed, to be transformed into new DataFrames or executed any number
+/// of times
+pub struct DataFusionDataFrame {
+    header: HandleHeader,
+    df: DataFrame,
+    runtime: Arc<tokio::runtime::Runtime>,
+    log_filter: ContextLogFilter,
+    /// Limits of the context the DataFrame was created with
+    options: QueryOptions,
+    /// Where executions are listed as running queries of the context
+    queries: Arc<QueryRegistry>,
+}
+
+impl_handle!(DataFusionDataFrame, 0x4446_4652_414d_000d, dataframes);
+
+impl DataFusionDataFrame {
+    /// A DataFrame for a planned query, with the limits of the context it is created with
+    fn new(ctx: &DataFusionContext, df: DataFrame) -> Self {
+        Self {
+            header: HandleHeader::default(),
+            df,
+            runtime: ctx.runtime.clone(),
+            log_filter: ctx.log_filter.clone(),
+            options: ctx.query_options(),
+            queries: ctx.queries.clone(),
+        }
+    }
+
+    /// A DataFrame built from this one, with the same limits
+    fn derive(&self, df: DataFrame) -> Self {
+        Self {
+            header: HandleHeader::default(),
+            df,
+            runtime: self.runtime.clone(),
+            log_filter: self.log_filter.clone(),
+            options: self.options.clone(),
+            queries: self.queries.clone(),
+        }
+    }
+
+    fn collect(&self) -> FfiResult<DataFusionResult> {
+        self.execute(self.df.clone())
+    }
+
+    /// Execute a DataFrame built from this one with its limits, listed as a running query
+    fn execute(&self, df: DataFrame) -> FfiResult<DataFusionResult> {
+        self.execute_with(df, self.options.clone())
+    }
+
+    fn execute_with(&self, df: DataFrame, options: QueryOptions) -> FfiResult<DataFusionResult> {
+        let query = self.queries.execute(None, async { Ok(df) }, options);
+        let _log_filter = logging::enter_context_filter(&self.log_filter);
+        self.runtime.block_on(query)
+    }
+
+    /// Collect the partitions of the DataFrame in parallel into an in-memory table, and plan a scan
+    /// of it
+    fn cache(&self) -> FfiResult<DataFrame> {
+        let options = QueryOptions {
+            preserve_partitions: true,
+            ..self.options.clone()
+        };
+        let result = self.execute_with(self.df.clone(), options)?;
+        let table = MemTable::try_new(result.schema.clone(), result.into_partitions())?;
+        let plan = LogicalPlanBuilder::scan(UNNAMED_TABLE, provider_as_source(Arc::new(table)), None)?.build()?;
+        Ok(DataFrame::new(self.df.clone().into_parts().0, plan))
+    }
+}
+
+/// Build a new DataFrame from a DataFrame argument with `f`, leaving the argument unchanged
+fn transform(
+    df: *const DataFusionDataFrame,
+    name: &str,
+    error_out: *mut *mut c_char,
+    f: impl FnOnce(&DataFusionDataFrame) -> FfiResult<DataFrame>,
+) -> *mut DataFusionDataFrame {
+    ffi_ptr(error_out, || {
+        let df = handle_ref(df, name)?;
+        Ok(Box::new(df.derive(f(df)?)))
+    })
+}
+
+/// Parse an array of `count` SQL expressions over the columns of a DataFrame
+fn exprs_arg(df: &DataFrame, exprs: *const *const c_char, count: usize, name: &str) -> FfiResult<Vec<Expr>> {
+    str_array_arg(exprs, count, name)?
+        .into_iter()
+        .map(|expr| Ok(df.parse_sql_expr(expr)?))
+        .collect()
+}
+
+/// Resolve an array of `count` column names, given without quoting, against the columns of a
+/// DataFrame
+fn columns_arg(df: &DataFrame, columns: *const *const c_char, count: usize, name: &str) -> FfiResult<Vec<Expr>> {
+    let schema = df.schema();
+    str_array_arg(columns, count, name)?
+        .into_iter()
+        .map(|column| Ok(Expr::Column(schema.qualified_field_with_unqualified_name(column)?.into())))
+        .collect()
+}
+
+/// Parse and plan a SQL query without executing it, returning a DataFrame to build on with the
+/// datafusion_dataframe_* functions and execute later. DDL statements such as CREATE TABLE are
+/// executed right away, as they are planned. The DataFrame uses the context's maximum result sizes
+/// and query timeout as they are when it is created, and stays valid after the context is freed.
+/// Returns a pointer to the DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_to_dataframe(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let df = ctx.block_on(ctx.ctx.sql_with_options(sql_str, ctx.sql_options))?;
+        Ok(Box::new(DataFusionDataFrame::new(ctx, df)))
+    })
+}
+
+/// Filter the rows of a DataFrame with a SQL predicate such as `price > 100 AND region = 'EU'`,
+/// parsed with the context's SQL dialect and resolved against the DataFrame's columns.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_filter(
+    df: *const DataFusionDataFrame,
+    predicate: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let predicate = df.df.parse_sql_expr(str_arg(predicate, "predicate")?)?;
+        Ok(df.df.clone().filter(predicate)?)
+    })
+}
+
+/// Filter the rows of a DataFrame with a predicate built as an expression handle, like
+/// datafusion_dataframe_filter without parsing SQL. The predicate stays valid.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_filter_with_expr(
+    df: *const DataFusionDataFrame,
+    predicate: *const DataFusionExpr,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        Ok(df.df.clone().filter(expr::expr_arg(predicate, "predicate")?.clone())?)
+    })
+}
+
+/// Keep only the named columns of a DataFrame, in the given order, such as to read only the
+/// columns needed from a wide table. `columns` is an array of `count` column names.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_select(
+    df: *const DataFusionDataFrame,
+    columns: *const *const c_char,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let columns = str_array_arg(columns, count, "columns")?;
+        Ok(df.df.clone().select_columns(&columns)?)
+    })
+}
+
+/// Compute the columns of a new DataFrame from `count` SQL expressions over the columns of a
+/// DataFrame, such as `price * quantity AS total` or `upper(name)`.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_select_exprs(
+    df: *const DataFusionDataFrame,
+    exprs: *const *const c_char,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let exprs = str_array_arg(exprs, count, "exprs")?;
+        Ok(df.df.clone().select_exprs(&exprs)?)
+    })
+}
+
+/// Compute the columns of a new DataFrame from `count` expression handles, like
+/// datafusion_dataframe_select_exprs without parsing SQL. The expressions stay valid.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_select_with_exprs(
+    df: *const DataFusionDataFrame,
+    exprs: *const *const DataFusionExpr,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| Ok(df.df.clone().select(expr::handles_arg(exprs, count, "exprs")?)?))
+}
+
+/// Add a column computed from a SQL expression over the columns of a DataFrame, such as
+/// `CAST(amount AS DOUBLE)` or `date_trunc('day', ts)`, or replace the column if the DataFrame already
+/// has one named `name`
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_with_column(
+    df: *const DataFusionDataFrame,
+    name: *const c_char,
+    expr: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let name = str_arg(name, "name")?;
+        let expr = df.df.parse_sql_expr(str_arg(expr, "expr")?)?;
+        Ok(df.df.clone().with_column(name, expr)?)
+    })
+}
+
+/// Rename a column of a DataFrame, which may be given qualified by its table such as `t.id`.
+/// Returns a pointer to a new DataFrame, or null on error such as if there is no column `old_name`
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_with_column_renamed(
+    df: *const DataFusionDataFrame,
+    old_name: *const c_char,
+    new_name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let old_name = str_arg(old_name, "old_name")?;
+        let new_name = str_arg(new_name, "new_name")?;
+        // DataFusion ignores renames of missing columns
+        df.df.schema().qualified_field_from_column(&Column::from_qualified_name(old_name))?;
+        Ok(df.df.clone().with_column_renamed(old_name, new_name)?)
+    })
+}
+
+/// Skip the first `offset` rows of a DataFrame and keep at most `limit` of the rest, or all of them
+/// if `limit` is negative. Applied to a sorted DataFrame, this pages through its rows.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_limit(
+    df: *const DataFusionDataFrame,
+    offset: i64,
+    limit: i64,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let offset = usize::try_from(offset)
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid offset {offset}")))?;
+        Ok(df.df.clone().limit(offset, usize::try_from(limit).ok())?)
+    })
+}
+
+/// Group the rows of a DataFrame by `group_count` SQL expressions, such as `region` or
+/// `date_trunc('month', ts) AS month`, and compute `aggr_count` aggregate expressions for each
+/// group, such as `sum(amount) AS total` or `count(*)`. The new DataFrame has the group columns
+/// followed by the aggregate columns. With no group expressions, the aggregates are computed over
+/// all rows into a single row.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_aggregate(
+    df: *const DataFusionDataFrame,
+    group_exprs: *const *const c_char,
+    group_count: usize,
+    aggr_exprs: *const *const c_char,
+    aggr_count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let group_exprs = exprs_arg(&df.df, group_exprs, group_count, "group_exprs")?;
+        let aggr_exprs = exprs_arg(&df.df, aggr_exprs, aggr_count, "aggr_exprs")?;
+        Ok(df.df.clone().aggregate(group_exprs, aggr_exprs)?)
+    })
+}
+
+/// Group the rows of a DataFrame by `group_count` expression handles and compute `aggr_count`
+/// aggregate expression handles for each group, such as calls of aggregate functions made with
+/// datafusion_expr_function, like datafusion_dataframe_aggregate without parsing SQL. The
+/// expressions stay valid.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_aggregate_with_exprs(
+    df: *const DataFusionDataFrame,
+    group_exprs: *const *const DataFusionExpr,
+    group_count: usize,
+    aggr_exprs: *const *const DataFusionExpr,
+    aggr_count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let group_exprs = expr::handles_arg(group_exprs, group_count, "group_exprs")?;
+        let aggr_exprs = expr::handles_arg(aggr_exprs, aggr_count, "aggr_exprs")?;
+        Ok(df.df.clone().aggregate(group_exprs, aggr_exprs)?)
+    })
+}
+
+/// Sort the rows of a DataFrame by `count` SQL expressions, such as `region` or `price * quantity`,
+/// each in the order given by `ascending[i]` and with nulls before or after the other values as
+/// given by `nulls_first[i]`. Rows equal on all expressions may come in any order, so to page
+/// through a DataFrame with datafusion_dataframe_limit, sort it by a unique key.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_sort(
+    df: *const DataFusionDataFrame,
+    exprs: *const *const c_char,
+    ascending: *const bool,
+    nulls_first: *const bool,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let exprs = exprs_arg(&df.df, exprs, count, "exprs")?;
+        let ascending = slice_arg(ascending, count, "ascending")?;
+        let nulls_first = slice_arg(nulls_first, count, "nulls_first")?;
+        let sort_exprs = exprs
+            .into_iter()
+            .zip(ascending.iter().zip(nulls_first))
+            .map(|(expr, (&ascending, &nulls_first))| expr.sort(ascending, nulls_first))
+            .collect();
+        Ok(df.df.clone().sort(sort_exprs)?)
+    })
+}
+
+/// Evaluate a call of a window or aggregate function, under any alias, over windows of rows
+/// partitioned and ordered by the given expressions, with the default frame of SQL: the whole
+/// partition without ordering, and the rows up to the current row and its peers with it
+fn over(expr: Expr, partition_by: &[Expr], order_by: &[SortExpr]) -> FfiResult<Expr> {
+    let (fun, args) = match expr {
+        Expr::Alias(alias) => return Ok(over(*alias.expr, partition_by, order_by)?.alias(alias.name)),
+        Expr::AggregateFunction(call) => (WindowFunctionDefinition::AggregateUDF(call.func), call.args),
+        Expr::WindowFunction(call) => (call.fun, call.args),
+        expr => {
+            return Err(DataFusionError::invalid_argument(format!(
+                "{expr} is not a call of a window or aggregate function"
+            )))
+        }
+    };
+    let mut window = WindowFunction::new(fun, args);
+    window.partition_by = partition_by.to_vec();
+    window.order_by = order_by.to_vec();
+    window.window_frame = WindowFrame::new((!order_by.is_empty()).then_some(false));
+    Ok(Expr::WindowFunction(window))
+}
+
+/// Add a column to a DataFrame for each of `window_count` window expressions: calls of window
+/// functions such as `row_number`, `rank`, `lag` and `lead`, or of aggregate functions such as
+/// `sum` for running totals, made with datafusion_expr_function and named with
+/// datafusion_expr_alias. Each is evaluated over the rows sharing its row's values of the
+/// `partition_count` expressions of `partition_by` (all rows without any), ordered by the
+/// `order_count` expressions of `order_by`, each in the order given by `ascending[i]` and with
+/// nulls first as given by `nulls_first[i]`. Ordered, aggregates cover the rows of the partition up
+/// to the current row and the rows equal to it in the order; unordered, the whole partition.
+/// The rows of the new DataFrame come in no particular order.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_window(
+    df: *const DataFusionDataFrame,
+    window_exprs: *const *const DataFusionExpr,
+    window_count: usize,
+    partition_by: *const *const DataFusionExpr,
+    partition_count: usize,
+    order_by: *const *const DataFusionExpr,
+    ascending: *const bool,
+    nulls_first: *const bool,
+    order_count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let partition_by = expr::handles_arg(partition_by, partition_count, "partition_by")?;
+        let ascending = slice_arg(ascending, order_count, "ascending")?;
+        let nulls_first = slice_arg(nulls_first, order_count, "nulls_first")?;
+        let order_by = expr::handles_arg(order_by, order_count, "order_by")?
+            .into_iter()
+            .zip(ascending.iter().zip(nulls_first))
+            .map(|(expr, (&asc, &nulls_first))| expr.sort(asc, nulls_first))
+            .collect::<Vec<_>>();
+        let window_exprs = expr::handles_arg(window_exprs, window_count, "window_exprs")?
+            .into_iter()
+            .map(|expr| over(expr, &partition_by, &order_by))
+            .collect::<FfiResult<_>>()?;
+        Ok(df.df.clone().window(window_exprs)?)
+    })
+}
+
+/// Join two DataFrames on equality of `count` pairs of key columns, `left_columns[i]` of `left` with
+/// `right_columns[i]` of `right`, with a DATAFUSION_JOIN_* join type. The DataFrames may come from
+/// different contexts, such as a table of one joined with files registered with another; the
+/// joined DataFrame uses the limits of `left`. Columns with the same name on both sides must be
+/// told apart by their table, such as `left_table.id`, or renamed beforehand.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_join(
+    left: *const DataFusionDataFrame,
+    right: *const DataFusionDataFrame,
+    join_type: c_int,
+    left_columns: *const *const c_char,
+    right_columns: *const *const c_char,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(left, "left", error_out, |left| {
+        let right = handle_ref(right, "right")?;
+        let join_type = self::join_type(join_type)?;
+        let left_columns = str_array_arg(left_columns, count, "left_columns")?;
+        let right_columns = str_array_arg(right_columns, count, "right_columns")?;
+        Ok(left.df.clone().join(right.df.clone(), join_type, &left_columns, &right_columns, None)?)
+    })
+}
+
+/// Combine the rows of two DataFrames, checking that their columns match. DataFusion matches the
+/// columns of a union by position and would otherwise silently combine differently named columns,
+/// or fail only when the union is executed if their types have no common type.
+fn union(a: &DataFrame, b: *const DataFusionDataFrame, distinct: bool) -> FfiResult<DataFrame> {
+    let b = &handle_ref(b, "b")?.df;
+    let (a_fields, b_fields) = (a.schema().fields(), b.schema().fields());
+    if a_fields.len() != b_fields.len() {
+        return Err(DataFusionError::invalid_argument(format!(
+            "a has {} columns but b has {}",
+            a_fields.len(),
+            b_fields.len()
+        )));
+    }
+    for (index, (a_field, b_field)) in a_fields.iter().zip(b_fields).enumerate() {
+        if a_field.name() != b_field.name() {
+            return Err(DataFusionError::invalid_argument(format!(
+                "column {index} is {} in a but {} in b",
+                a_field.name(),
+                b_field.name()
+            )));
+        }
+        if comparison_coercion(a_field.data_type(), b_field.data_type()).is_none() {
+            return Err(DataFusionError::invalid_argument(format!(
+                "column {} is {} in a but {} in b",
+                a_field.name(),
+                a_field.data_type(),
+                b_field.data_type()
+            )));
+        }
+    }
+    Ok(if distinct {
+        a.clone().union_distinct(b.clone())?
+    } else {
+        a.clone().union(b.clone())?
+    })
+}
+
+/// Append the rows of `b` to those of `a`, keeping duplicates, like UNION ALL. Both DataFrames must
+/// have the same column names in the same order, with types that have a common type, such as Int32
+/// and Int64; otherwise DATAFUSION_INVALID_ARGUMENT is returned, naming the first mismatched column.
+/// The DataFrames may come from different contexts; the union uses the limits of `a`.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_union(
+    a: *const DataFusionDataFrame,
+    b: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(a, "a", error_out, |a| union(&a.df, b, false))
+}
+
+/// Combine the rows of two DataFrames without duplicates, like UNION, with the same requirements as
+/// datafusion_dataframe_union
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_union_distinct(
+    a: *const DataFusionDataFrame,
+    b: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(a, "a", error_out, |a| union(&a.df, b, true))
+}
+
+/// Remove duplicate rows from a DataFrame, like SELECT DISTINCT
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_distinct(
+    df: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| Ok(df.df.clone().distinct()?))
+}
+
+/// Keep one row of a DataFrame for each distinct combination of the `count` named columns, like
+/// SELECT DISTINCT ON, such as to deduplicate records by key. Which of the rows sharing a
+/// combination is kept is unspecified.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_distinct_on(
+    df: *const DataFusionDataFrame,
+    columns: *const *const c_char,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let on = columns_arg(&df.df, columns, count, "columns")?;
+        let select = df.df.schema().columns().into_iter().map(Expr::Column).collect();
+        Ok(df.df.clone().distinct_on(on, select, None)?)
+    })
+}
+
+fn partitions_arg(partitions: usize) -> FfiResult<usize> {
+    if partitions == 0 {
+        return Err(DataFusionError::invalid_argument("partitions must be positive"));
+    }
+    Ok(partitions)
+}
+
+/// Redistribute the rows of a DataFrame evenly across `partitions` partitions, batch by batch, to
+/// change how many partitions the operators after it, such as joins and aggregations, run in
+/// parallel, and how many partitions datafusion_dataframe_cache keeps.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_repartition(
+    df: *const DataFusionDataFrame,
+    partitions: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        repartition::repartition(df.df.clone(), Partitioning::RoundRobinBatch(partitions_arg(partitions)?))
+    })
+}
+
+/// Redistribute the rows of a DataFrame across `partitions` partitions by a hash of the `count`
+/// columns named in `columns`, so rows with equal values in those columns end up in the same
+/// partition.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_repartition_by_hash(
+    df: *const DataFusionDataFrame,
+    columns: *const *const c_char,
+    count: usize,
+    partitions: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, |df| {
+        let partitions = partitions_arg(partitions)?;
+        if count == 0 {
+            return Err(DataFusionError::invalid_argument("columns must not be empty"));
+        }
+        let columns = columns_arg(&df.df, columns, count, "columns")?;
+        repartition::repartition(df.df.clone(), Partitioning::Hash(columns, partitions))
+    })
+}
+
+/// Execute a DataFrame and keep its rows in memory, returning a new DataFrame that reads them
+/// instead of executing the original plan again, so DataFrames derived from it do not rescan its
+/// sources. The output partitions are collected in parallel and kept apart, so scans of the cached
+/// rows stay parallel. Collecting the rows is limited by the maximum result sizes of the context.
+/// The columns of the new DataFrame keep their names but lose their table qualifiers.
+/// Returns a pointer to a new DataFrame or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_cache(
+    df: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionDataFrame {
+    transform(df, "df", error_out, DataFusionDataFrame::cache)
+}
+
+/// Get the schema of the rows a DataFrame produces, without executing it
+/// Returns a pointer to the schema or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_schema(
+    df: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionSchema {
+    ffi_ptr(error_out, || Ok(DataFusionSchema::new(handle_ref(df, "df")?.df.schema().inner().clone())))
+}
+
+/// Execute a DataFrame and collect its result like datafusion_sql. The DataFrame stays valid and
+/// can be executed again.
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_collect(
+    df: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || Ok(Box::new(handle_ref(df, "df")?.collect()?)))
+}
+
+/// Count the rows of a DataFrame into `out` by executing a `count(*)` aggregation of it rather
+/// than collecting its rows. The optimizer answers the count from the statistics of sources that
+/// know their row counts, such as Parquet files, without scanning them.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_count(
+    df: *const DataFusionDataFrame,
+    out: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let df = handle_ref(df, "df")?;
+        let counted = df.df.clone().aggregate(vec![], vec![count(Expr::Literal(COUNT_STAR_EXPANSION))])?;
+        let result = df.execute(counted)?;
+        let rows = result
+            .batches
+            .iter()
+            .find(|batch| batch.num_rows() == 1)
+            .and_then(|batch| batch.column(0).as_any().downcast_ref::<Int64Array>())
+            .map(|counts| counts.value(0))
+            .ok_or_else(|| DataFusionError::new(DATAFUSION_ERROR, "count(*) produced no count"))?;
+        write_out(out, rows)
+    })
+}
+
+/// Describe the plan of a DataFrame like SQL EXPLAIN, as a `plan_type:` line followed by the plan
+/// for each of its optimized logical plan and physical plan. With `verbose`, the plan is also shown
+/// as it was before and after every optimizer pass, like EXPLAIN VERBOSE. With `analyze`, the
+/// DataFrame is executed, discarding its rows, and the executed physical plan is shown with its
+/// metrics, like EXPLAIN ANALYZE.
+/// The description is stored in `out` and must be freed with datafusion_string_free.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_explain(
+    df: *const DataFusionDataFrame,
+    verbose: bool,
+    analyze: bool,
+    out: *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let df = handle_ref(df, "df")?;
+        if out.is_null() {
+            return Err(DataFusionError::invalid_argument("out must not be null"));
+        }
+
+        let result = df.execute(df.df.clone().explain(verbose, analyze)?)?;
+        let mut explain = String::new();
+        for batch in &result.batches {
+            let plan_types = batch.column(0).as_string::<i32>();
+            let plans = batch.column(1).as_string::<i32>();
+            for (plan_type, plan) in plan_types.iter().zip(plans) {
+                let (plan_type, plan) = (plan_type.unwrap_or_default(), plan.unwrap_or_default().trim_end());
+                explain.push_str(&format!("{plan_type}:\n{plan}\n"));
+            }
+        }
+        string_out(out, CString::new(explain)?);
+        Ok(())
+    })
+}
+
+/// Start executing a DataFrame as a stream, like datafusion_sql_stream, to consume results of any
+/// size in bounded memory with datafusion_stream_next. The DataFrame can be executed again, and
+/// the stream stays valid after the DataFrame is freed.
+/// Returns a pointer to the stream or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_execute_stream(
+    df: *const DataFusionDataFrame,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionStream {
+    ffi_ptr(error_out, || {
+        let df = handle_ref(df, "df")?;
+        let plan = df.df.clone();
+        Ok(Box::new(DataFusionStream::start(
+            None,
+            async { Ok(plan) },
+            &df.runtime,
+            &df.log_filter,
+            &df.queries,
+        )?))
+    })
+}
+
+/// Write the rows of a DataFrame as Parquet to `path`, a local path or a URL of an object store
+/// registered with the context, such as `s3://bucket/export/`. Rows are written as they are
+/// produced rather than collected first. A path with an extension, such as `export.parquet`, is
+/// written as a single file unless partitioning by columns; any other path is written as a
+/// directory of files.
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_write_parquet(
+    df: *const DataFusionDataFrame,
+    path: *const c_char,
+    options: *const DataFusionParquetWriteOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let df = handle_ref(df, "df")?;
+        let path = str_arg(path, "path")?;
+        let defaults = DataFusionParquetWriteOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let (state, plan) = df.df.clone().into_parts();
+        let file_type = format_as_file_type(Arc::new(ParquetFormatFactory::new()));
+        let plan = LogicalPlanBuilder::copy_to(
+            plan,
+            path.to_string(),
+            file_type,
+            options.format_options()?,
+            options.partition_by()?,
+        )?
+        .build()?;
+        df.execute(DataFrame::new(state, plan))?;
+        Ok(())
+    })
+}
+
+/// Write the rows of a DataFrame into an Iceberg table as a new snapshot, appending them to the
+/// table's rows with DATAFUSION_WRITE_APPEND or replacing them with DATAFUSION_WRITE_OVERWRITE. Rows
+/// are written as they are produced rather than collected first, into files partitioned by the
+/// table's partition spec. The columns of the DataFrame are matched to the table's by name, in any
+/// order, and cast to their types; a column missing from either side is an invalid argument.
+/// Readers of the table, such as contexts it is registered with, see the rows once the write
+/// returns.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_write_iceberg(
+    df: *const DataFusionDataFrame,
+    table: *const IcebergTable,
+    mode: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let df = handle_ref(df, "df")?;
+        let table = handle_ref(table, "table")?;
+        df.execute(iceberg_write::insert(df.df.clone(), &table.table, mode)?)?;
+        Ok(())
+    })
+}
+
+/// Free a DataFrame. DataFrames built from it stay valid.
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_dataframe_free(df: *mut DataFusionDataFrame) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(df, "df"))
+}
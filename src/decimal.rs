@@ -0,0 +1,89 @@
+use std::os::raw::{c_char, c_int};
+
+use datafusion::arrow::array::{Decimal128Array, Decimal256Array};
+use datafusion::arrow::datatypes::DataType;
+
+use crate::error::{ffi_code, ffi_string, DataFusionError};
+use crate::handle::handle_ref;
+use crate::values::write_out;
+use crate::{DataFusionResult, DATAFUSION_TYPE_MISMATCH};
+
+/// The unscaled value of a Decimal128 as a 128-bit two's complement integer split into two halves
+#[repr(C)]
+pub struct DataFusionDecimal128 {
+    pub low: u64,
+    pub high: u64,
+}
+
+/// Get the unscaled value of a Decimal128 result cell. Combine it with the column's scale from
+/// datafusion_result_column_decimal to get the decimal value.
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not Decimal128, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_decimal128(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    out: *mut DataFusionDecimal128,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        let value = cell.value_array::<Decimal128Array>("Decimal128")?.value(cell.row) as u128;
+        write_out(
+            out,
+            DataFusionDecimal128 {
+                low: value as u64,
+                high: (value >> 64) as u64,
+            },
+        )
+    })
+}
+
+/// Get a Decimal128 or Decimal256 result cell formatted with its scale, e.g. "-1234.50"
+/// Returns a string to be freed with datafusion_string_free, or null on error (including null values)
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_decimal_string(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        match cell.array.data_type() {
+            DataType::Decimal256(_, _) => {
+                Ok(cell.value_array::<Decimal256Array>("Decimal")?.value_as_string(cell.row))
+            }
+            _ => Ok(cell.value_array::<Decimal128Array>("Decimal")?.value_as_string(cell.row)),
+        }
+    })
+}
+
+/// Get the precision and scale of a Decimal128 or Decimal256 column
+/// Returns DATAFUSION_OK on success, DATAFUSION_TYPE_MISMATCH if the column is not a decimal column,
+/// or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_decimal(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    precision: *mut u8,
+    scale: *mut i8,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let field = handle_ref(result, "result")?.field(column_index)?;
+        match field.data_type() {
+            DataType::Decimal128(p, s) | DataType::Decimal256(p, s) => {
+                write_out(precision, *p)?;
+                write_out(scale, *s)
+            }
+            other => Err(DataFusionError::new(
+                DATAFUSION_TYPE_MISMATCH,
+                format!("column has type {other}, not Decimal"),
+            )),
+        }
+    })
+}
@@ -0,0 +1,309 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::handle::{free_handle, handle_ref, impl_handle, into_handle, Handle, HandleHeader};
+use crate::{
+    DATAFUSION_ERROR, DATAFUSION_INVALID_ARGUMENT, DATAFUSION_INVALID_HANDLE, DATAFUSION_OK,
+    DATAFUSION_PANIC,
+};
+
+/// Structured error describing the last failure on a thread.
+///
+/// Each error carries a code, a message and an optional source error, so nested
+/// DataFusion/Iceberg failures can be walked from the outermost context down to the root cause.
+///
+/// Fallible calls do not return errors through a `DataFusionError**` out-parameter: that slot is
+/// taken by the `char** error_out` message every entry point has, so the structured error of a
+/// failed call is retrieved afterwards with datafusion_last_error.
+#[derive(Clone)]
+pub struct DataFusionError {
+    header: HandleHeader,
+    code: c_int,
+    message: CString,
+    source: Option<Box<DataFusionError>>,
+}
+
+impl_handle!(DataFusionError, 0x4446_4552_524f_5221, errors);
+
+pub(crate) type FfiResult<T> = Result<T, DataFusionError>;
+
+impl DataFusionError {
+    pub(crate) fn new(code: c_int, message: impl Into<String>) -> Self {
+        Self {
+            header: HandleHeader::valid::<Self>(),
+            code,
+            message: to_c_string(message.into()),
+            source: None,
+        }
+    }
+
+    pub(crate) fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(DATAFUSION_INVALID_ARGUMENT, message)
+    }
+
+    /// The error as DataFusion reports it, for errors raised while DataFusion executes a plan
+    pub(crate) fn into_datafusion(self) -> datafusion::error::DataFusionError {
+        datafusion::error::DataFusionError::Execution(self.message.to_string_lossy().into_owned())
+    }
+
+    fn from_std(code: c_int, err: &(dyn std::error::Error + 'static)) -> Self {
+        Self {
+            header: HandleHeader::valid::<Self>(),
+            code,
+            message: to_c_string(err.to_string()),
+            source: err.source().map(|source| Box::new(Self::from_std(code, source))),
+        }
+    }
+}
+
+impl<E> From<E> for DataFusionError
+where
+    E: std::error::Error + 'static,
+{
+    fn from(err: E) -> Self {
+        Self::from_std(DATAFUSION_ERROR, &err)
+    }
+}
+
+/// Interior NUL bytes cannot be represented in a C string, so they are replaced
+fn to_c_string(message: String) -> CString {
+    CString::new(message.replace('\0', " ")).unwrap_or_default()
+}
+
+static EMPTY_MESSAGE: [c_char; 1] = [0];
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<DataFusionError>> = const { RefCell::new(None) };
+}
+
+/// Record `err` as the calling thread's last error and copy its message to the caller if requested
+pub(crate) fn report_error(error_out: *mut *mut c_char, err: DataFusionError) {
+    if !error_out.is_null() {
+        unsafe { *error_out = err.message.clone().into_raw() };
+    }
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(err));
+}
+
+fn clear_error_out(error_out: *mut *mut c_char) {
+    if !error_out.is_null() {
+        unsafe { *error_out = ptr::null_mut() };
+    }
+}
+
+/// Turn the payload of a caught panic into a DATAFUSION_PANIC error
+pub(crate) fn panic_error(payload: Box<dyn Any + Send>) -> DataFusionError {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.as_str()
+    } else {
+        "unknown panic payload"
+    };
+    DataFusionError::new(DATAFUSION_PANIC, format!("panic: {message}"))
+}
+
+/// Run `f`, turning a panic into a DATAFUSION_PANIC error so it never unwinds into C
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> FfiResult<T>) -> FfiResult<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err(panic_error(payload)))
+}
+
+/// Run a fallible call that produces a heap allocated handle
+pub(crate) fn ffi_ptr<T: Handle>(
+    error_out: *mut *mut c_char,
+    f: impl FnOnce() -> FfiResult<Box<T>>,
+) -> *mut T {
+    clear_error_out(error_out);
+    match catch_panic(f) {
+        Ok(value) => into_handle(value),
+        Err(err) => {
+            report_error(error_out, err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Run a fallible call that reports its outcome as a status code
+pub(crate) fn ffi_code(
+    error_out: *mut *mut c_char,
+    f: impl FnOnce() -> FfiResult<()>,
+) -> c_int {
+    clear_error_out(error_out);
+    match catch_panic(f) {
+        Ok(()) => DATAFUSION_OK,
+        Err(err) => {
+            let code = err.code;
+            report_error(error_out, err);
+            code
+        }
+    }
+}
+
+/// Run a fallible call that reports its outcome as a boolean
+pub(crate) fn ffi_bool(
+    error_out: *mut *mut c_char,
+    f: impl FnOnce() -> FfiResult<()>,
+) -> bool {
+    ffi_code(error_out, f) == DATAFUSION_OK
+}
+
+/// Run a fallible call that produces a string, returned as a heap allocated C string
+/// that must be freed with datafusion_string_free
+pub(crate) fn ffi_string(
+    error_out: *mut *mut c_char,
+    f: impl FnOnce() -> FfiResult<String>,
+) -> *mut c_char {
+    clear_error_out(error_out);
+    match catch_panic(f).and_then(|value| Ok(CString::new(value)?)) {
+        Ok(value) => value.into_raw(),
+        Err(err) => {
+            report_error(error_out, err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Run a fallible call that returns a plain value, falling back to `default` on failure
+pub(crate) fn ffi_value<T>(
+    error_out: *mut *mut c_char,
+    default: T,
+    f: impl FnOnce() -> FfiResult<T>,
+) -> T {
+    clear_error_out(error_out);
+    match catch_panic(f) {
+        Ok(value) => value,
+        Err(err) => {
+            report_error(error_out, err);
+            default
+        }
+    }
+}
+
+/// Borrow a C string argument as UTF-8
+pub(crate) fn str_arg<'a>(value: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if value.is_null() {
+        return Err(DataFusionError::invalid_argument(format!("{name} must not be null")));
+    }
+    unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .map_err(|_| DataFusionError::invalid_argument(format!("{name} is not valid UTF-8")))
+}
+
+/// Borrow an array argument of `len` elements, such as a byte buffer, which may be null if it is empty
+pub(crate) fn slice_arg<'a, T>(value: *const T, len: usize, name: &str) -> FfiResult<&'a [T]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if value.is_null() {
+        return Err(DataFusionError::invalid_argument(format!("{name} must not be null")));
+    }
+    Ok(unsafe { std::slice::from_raw_parts(value, len) })
+}
+
+/// Borrow an array argument of `len` C strings as UTF-8, which may be null if it is empty
+pub(crate) fn str_array_arg<'a>(value: *const *const c_char, len: usize, name: &str) -> FfiResult<Vec<&'a str>> {
+    slice_arg(value, len, name)?
+        .iter()
+        .enumerate()
+        .map(|(index, &value)| str_arg(value, &format!("{name}[{index}]")))
+        .collect()
+}
+
+/// Get the code of an error
+/// Returns DATAFUSION_ERROR if the error is null and DATAFUSION_INVALID_HANDLE if it is not a valid error
+#[no_mangle]
+pub extern "C" fn datafusion_error_code(error: *const DataFusionError) -> c_int {
+    if error.is_null() {
+        return DATAFUSION_ERROR;
+    }
+    match catch_panic(|| handle_ref(error, "error").map(|error| error.code)) {
+        Ok(code) => code,
+        Err(_) => DATAFUSION_INVALID_HANDLE,
+    }
+}
+
+/// Get the message of an error
+/// The string is owned by the error and valid until it is freed
+#[no_mangle]
+pub extern "C" fn datafusion_error_message(error: *const DataFusionError) -> *const c_char {
+    match catch_panic(|| handle_ref(error, "error").map(|error| error.message.as_ptr())) {
+        Ok(message) => message,
+        Err(_) => ptr::null(),
+    }
+}
+
+/// Get the error that caused this one
+/// Returns null at the end of the chain; the source is owned by the outermost error
+#[no_mangle]
+pub extern "C" fn datafusion_error_source(error: *const DataFusionError) -> *const DataFusionError {
+    match catch_panic(|| handle_ref(error, "error").map(|error| error.source.as_deref())) {
+        Ok(Some(source)) => source,
+        _ => ptr::null(),
+    }
+}
+
+/// Free an error returned by `datafusion_last_error`
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if the error was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_error_free(error: *mut DataFusionError) -> c_int {
+    // Not routed through ffi_code so that freeing an error does not replace the last error
+    match catch_panic(|| free_handle(error, "error")) {
+        Ok(()) => DATAFUSION_OK,
+        Err(err) => err.code,
+    }
+}
+
+/// Get the message of the last error raised on the calling thread
+/// Returns an empty string if no error has occurred
+#[no_mangle]
+pub extern "C" fn datafusion_get_last_error() -> *const c_char {
+    let message = catch_panic(|| {
+        Ok(LAST_ERROR.with(|last| match last.borrow().as_ref() {
+            // The CString's heap buffer stays put until the next error on this thread replaces it
+            Some(error) => error.message.as_ptr(),
+            None => EMPTY_MESSAGE.as_ptr(),
+        }))
+    });
+    message.unwrap_or(EMPTY_MESSAGE.as_ptr())
+}
+
+/// Get a copy of the last error raised on the calling thread, including its code and source chain
+/// Returns null if no error has occurred; the error must be freed with datafusion_error_free
+#[no_mangle]
+pub extern "C" fn datafusion_last_error() -> *mut DataFusionError {
+    let error = catch_panic(|| Ok(LAST_ERROR.with(|last| last.borrow().clone())));
+    match error {
+        Ok(Some(error)) => into_handle(Box::new(error)),
+        _ => ptr::null_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catch_panic_reports_panics_as_errors() {
+        let err = catch_panic::<()>(|| panic!("boom")).unwrap_err();
+        assert_eq!(err.code, DATAFUSION_PANIC);
+        assert_eq!(err.message.to_str().unwrap(), "panic: boom");
+
+        let err = catch_panic::<()>(|| panic!("{} failed", "formatted")).unwrap_err();
+        assert_eq!(err.message.to_str().unwrap(), "panic: formatted failed");
+    }
+
+    #[test]
+    fn ffi_code_reports_panics_through_error_out_and_last_error() {
+        let mut message = ptr::null_mut();
+        assert_eq!(ffi_code(&mut message, || panic!("boom")), DATAFUSION_PANIC);
+        assert_eq!(unsafe { CStr::from_ptr(message) }.to_str().unwrap(), "panic: boom");
+        drop(unsafe { CString::from_raw(message) });
+
+        let last = datafusion_last_error();
+        assert_eq!(datafusion_error_code(last), DATAFUSION_PANIC);
+        assert_eq!(datafusion_error_free(last), DATAFUSION_OK);
+    }
+}
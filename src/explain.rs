@@ -0,0 +1,111 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::sync::Arc;
+
+use datafusion::logical_expr::LogicalPlan;
+use datafusion::physical_plan::{displayable, ExecutionPlan};
+
+use crate::error::{ffi_code, ffi_string, str_arg, FfiResult};
+use crate::handle::handle_ref;
+use crate::{metrics, DataFusionContext, DataFusionError};
+
+/// Hand a string to the caller through an optional output argument, to be freed with
+/// datafusion_string_free
+pub(crate) fn string_out(out: *mut *mut c_char, value: CString) {
+    if !out.is_null() {
+        unsafe { out.write(value.into_raw()) };
+    }
+}
+
+/// Plan a SQL statement into its optimized logical plan and its physical plan without running it,
+/// unlike datafusion_sql, which runs DDL as it is planned. DDL has no physical plan, so it fails.
+fn plan_sql(ctx: &DataFusionContext, sql: &str) -> FfiResult<(LogicalPlan, Arc<dyn ExecutionPlan>)> {
+    let state = ctx.ctx.state();
+    ctx.block_on(async {
+        let plan = state.create_logical_plan(sql).await?;
+        ctx.sql_options.verify_plan(&plan)?;
+        let physical = state.create_physical_plan(&plan).await?;
+        Ok((state.optimize(&plan)?, physical))
+    })
+}
+
+/// Plan a SQL query without executing it and describe its optimized logical plan and its physical
+/// plan, one operator per line, as EXPLAIN does. With `verbose`, the logical plan also shows the
+/// schema of every operator and the physical plan shows full operator details.
+/// The plans are stored in `logical_out` and `physical_out`, either of which may be null to skip
+/// it, and must be freed with datafusion_string_free.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_explain(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    verbose: bool,
+    logical_out: *mut *mut c_char,
+    physical_out: *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let (logical, physical) = plan_sql(ctx, sql_str)?;
+
+        let logical = if verbose {
+            logical.display_indent_schema().to_string()
+        } else {
+            logical.display_indent().to_string()
+        };
+        let physical = displayable(physical.as_ref()).indent(verbose).to_string();
+        let (logical, physical) = (CString::new(logical)?, CString::new(physical)?);
+        string_out(logical_out, logical);
+        string_out(physical_out, physical);
+        Ok(())
+    })
+}
+
+/// Execute a SQL query, discarding its result, and describe the executed physical plan like
+/// EXPLAIN ANALYZE: one operator per line with its metrics aggregated across partitions, such as
+/// `output_rows` and `elapsed_compute`. With `json`, the plan is described in the format of
+/// datafusion_result_metrics_json instead.
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_explain_analyze(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    json: bool,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let result = ctx.block_on(ctx.sql(sql_str, ctx.query_options()))?;
+        if json {
+            return Ok(metrics::metrics_json(result.plan.as_ref()));
+        }
+        Ok(result.plan.as_ref().map(metrics::metrics_text).unwrap_or_default())
+    })
+}
+
+/// Plan a SQL query without executing it and render its physical plan as a Graphviz DOT graph,
+/// stored in `dot_out` and to be freed with datafusion_string_free
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_plan_graphviz(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    dot_out: *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+        if dot_out.is_null() {
+            return Err(DataFusionError::invalid_argument("dot_out must not be null"));
+        }
+
+        let (_, plan) = plan_sql(ctx, sql_str)?;
+        string_out(dot_out, CString::new(displayable(plan.as_ref()).graphviz().to_string())?);
+        Ok(())
+    })
+}
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use arrow::ffi::{to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use datafusion::arrow::array::{Array, RecordBatchIterator, StructArray};
+use datafusion::arrow::csv::WriterBuilder as CsvWriterBuilder;
+use datafusion::arrow::ipc::writer::{FileWriter, StreamWriter};
+use datafusion::arrow::json::{ArrayWriter, LineDelimitedWriter};
+use datafusion::common::file_options::parquet_writer::parse_compression_string;
+use datafusion::common::parsers::CompressionTypeVariant;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::file::properties::WriterProperties;
+
+use crate::error::{catch_panic, ffi_code, ffi_string, str_arg, str_array_arg, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::values::write_out;
+use crate::DataFusionResult;
+
+// Arrow IPC formats
+pub const DATAFUSION_IPC_STREAM: c_int = 0;
+pub const DATAFUSION_IPC_FILE: c_int = 1;
+
+/// Options for writing CSV. Pass null to use the defaults of datafusion_csv_options_default.
+#[repr(C)]
+pub struct DataFusionCsvOptions {
+    /// Field delimiter, ',' by default
+    pub delimiter: u8,
+    /// Whether to write a header row with the column names, true by default
+    pub header: bool,
+    /// Quote character, '"' by default
+    pub quote: u8,
+}
+
+impl Default for DataFusionCsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            header: true,
+            quote: b'"',
+        }
+    }
+}
+
+/// Get the default CSV options
+#[no_mangle]
+pub extern "C" fn datafusion_csv_options_default() -> DataFusionCsvOptions {
+    DataFusionCsvOptions::default()
+}
+
+/// Options for writing Parquet files from a DataFrame. Pass null to use the defaults of
+/// datafusion_parquet_write_options_default.
+#[repr(C)]
+pub struct DataFusionParquetWriteOptions {
+    /// Compression codec as accepted by the `datafusion.execution.parquet.compression` setting, e.g.
+    /// "snappy", "zstd(3)" or "uncompressed", or null for the context's setting ("zstd(3)" by default)
+    pub compression: *const c_char,
+    /// Maximum number of rows per row group, or 0 for the context's setting
+    pub max_row_group_size: i64,
+    /// Columns to partition the files by into hive-style `column=value` directories, leaving the
+    /// columns out of the files
+    pub partition_columns: *const *const c_char,
+    pub partition_column_count: usize,
+}
+
+impl Default for DataFusionParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: ptr::null(),
+            max_row_group_size: 0,
+            partition_columns: ptr::null(),
+            partition_column_count: 0,
+        }
+    }
+}
+
+impl DataFusionParquetWriteOptions {
+    /// The options as `format.*` options of a COPY statement
+    pub(crate) fn format_options(&self) -> FfiResult<HashMap<String, String>> {
+        let mut options = HashMap::new();
+        if !self.compression.is_null() {
+            let compression = str_arg(self.compression, "compression")?;
+            parse_compression_string(compression).map_err(|err| DataFusionError::invalid_argument(err.to_string()))?;
+            options.insert("format.compression".to_string(), compression.to_string());
+        }
+        match self.max_row_group_size {
+            0 => {}
+            size if size > 0 => {
+                options.insert("format.max_row_group_size".to_string(), size.to_string());
+            }
+            size => return Err(DataFusionError::invalid_argument(format!("invalid row group size {size}"))),
+        }
+        Ok(options)
+    }
+
+    pub(crate) fn partition_by(&self) -> FfiResult<Vec<String>> {
+        let columns = str_array_arg(self.partition_columns, self.partition_column_count, "partition_columns")?;
+        Ok(columns.into_iter().map(str::to_string).collect())
+    }
+}
+
+/// Get the default Parquet write options
+#[no_mangle]
+pub extern "C" fn datafusion_parquet_write_options_default() -> DataFusionParquetWriteOptions {
+    DataFusionParquetWriteOptions::default()
+}
+
+/// Hand a byte buffer to the caller, to be freed with datafusion_buffer_free
+pub(crate) fn write_buffer(bytes: Vec<u8>, buf: *mut *mut u8, len: *mut usize) -> FfiResult<()> {
+    if buf.is_null() || len.is_null() {
+        return Err(DataFusionError::invalid_argument("buf and len must not be null"));
+    }
+    let bytes = Box::into_raw(bytes.into_boxed_slice());
+    write_out(len, bytes.len())?;
+    write_out(buf, bytes as *mut u8)
+}
+
+/// Export a batch of a result through the Arrow C data interface, as a struct array with one child
+/// per column and its schema. The exported data shares the result's buffers and stays valid after
+/// the result is freed; the caller must call the `release` callbacks of both structs when done.
+/// `out_array` and `out_schema` may point to uninitialized memory.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_export_batch(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let batch = handle_ref(result, "result")?.batch(batch_index)?;
+        let data = StructArray::from(batch.clone()).into_data();
+        let (array, schema) = to_ffi(&data)?;
+        write_out(out_schema, schema)?;
+        write_out(out_array, array)
+    })
+}
+
+/// Export all batches of a result through the Arrow C stream interface. The stream shares the
+/// result's buffers and stays valid after the result is freed; the caller must call its `release`
+/// callback when done. `out` may point to uninitialized memory.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_export_stream(
+    result: *const DataFusionResult,
+    out: *mut FFI_ArrowArrayStream,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let batches = result.batches.clone().into_iter().map(Ok);
+        let reader = RecordBatchIterator::new(batches, result.schema.clone());
+        write_out(out, FFI_ArrowArrayStream::new(Box::new(reader)))
+    })
+}
+
+fn ipc_bytes(result: &DataFusionResult, format: c_int) -> FfiResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    match format {
+        DATAFUSION_IPC_STREAM => {
+            let mut writer = StreamWriter::try_new(&mut bytes, &result.schema)?;
+            for batch in &result.batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+        }
+        DATAFUSION_IPC_FILE => {
+            let mut writer = FileWriter::try_new(&mut bytes, &result.schema)?;
+            for batch in &result.batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+        }
+        _ => return Err(DataFusionError::invalid_argument(format!("invalid IPC format {format}"))),
+    }
+    Ok(bytes)
+}
+
+/// Serialize a result in the Arrow IPC stream (DATAFUSION_IPC_STREAM) or file (DATAFUSION_IPC_FILE) format.
+/// `buf` is set to a buffer of `len` bytes to be freed with datafusion_buffer_free.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_to_ipc(
+    result: *const DataFusionResult,
+    format: c_int,
+    buf: *mut *mut u8,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        write_buffer(ipc_bytes(result, format)?, buf, len)
+    })
+}
+
+/// Free a buffer allocated by the library, passing the length it was returned with
+#[no_mangle]
+pub extern "C" fn datafusion_buffer_free(buf: *mut u8, len: usize) {
+    if !buf.is_null() {
+        let _ = catch_panic(|| {
+            drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(buf, len)) });
+            Ok(())
+        });
+    }
+}
+
+fn write_csv<W: Write>(result: &DataFusionResult, options: *const DataFusionCsvOptions, writer: W) -> FfiResult<()> {
+    let defaults = DataFusionCsvOptions::default();
+    let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+    let mut writer = CsvWriterBuilder::new()
+        .with_delimiter(options.delimiter)
+        .with_header(options.header)
+        .with_quote(options.quote)
+        .build(writer);
+    for batch in &result.batches {
+        writer.write(batch)?;
+    }
+    Ok(())
+}
+
+/// Render a result as CSV, with `options` or the defaults if null
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_to_csv(
+    result: *const DataFusionResult,
+    options: *const DataFusionCsvOptions,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let mut bytes = Vec::new();
+        write_csv(result, options, &mut bytes)?;
+        Ok(String::from_utf8(bytes)?)
+    })
+}
+
+/// Write a result to a CSV file, with `options` or the defaults if null
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_write_csv(
+    result: *const DataFusionResult,
+    path: *const c_char,
+    options: *const DataFusionCsvOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let path = str_arg(path, "path")?;
+        write_csv(result, options, File::create(path)?)
+    })
+}
+
+/// Render a result as JSON: one object per row, either as newline delimited JSON (`line_delimited`)
+/// or as a single JSON array. Null values are omitted from the objects.
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_to_json(
+    result: *const DataFusionResult,
+    line_delimited: bool,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let batches: Vec<_> = result.batches.iter().collect();
+        let mut bytes = Vec::new();
+        if line_delimited {
+            let mut writer = LineDelimitedWriter::new(&mut bytes);
+            writer.write_batches(&batches)?;
+            writer.finish()?;
+        } else {
+            let mut writer = ArrayWriter::new(&mut bytes);
+            writer.write_batches(&batches)?;
+            writer.finish()?;
+        }
+        Ok(String::from_utf8(bytes)?)
+    })
+}
+
+/// Write a result to a Parquet file. `compression` is a codec as accepted by the
+/// `datafusion.execution.parquet.compression` setting, e.g. "snappy", "zstd(3)" or "uncompressed",
+/// or null for DataFusion's default of "zstd(3)". `row_group_size` is the maximum number of rows
+/// per row group, or 0 for the Parquet writer's default.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_write_parquet(
+    result: *const DataFusionResult,
+    path: *const c_char,
+    compression: *const c_char,
+    row_group_size: i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let path = str_arg(path, "path")?;
+        let compression = if compression.is_null() {
+            "zstd(3)"
+        } else {
+            str_arg(compression, "compression")?
+        };
+        let compression = parse_compression_string(compression)
+            .map_err(|err| DataFusionError::invalid_argument(err.to_string()))?;
+        let row_group_size = usize::try_from(row_group_size)
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid row group size {row_group_size}")))?;
+
+        let mut properties = WriterProperties::builder().set_compression(compression);
+        if row_group_size > 0 {
+            properties = properties.set_max_row_group_size(row_group_size);
+        }
+        let mut writer = ArrowWriter::try_new(File::create(path)?, result.schema.clone(), Some(properties.build()))?;
+        for batch in &result.batches {
+            writer.write(batch)?;
+        }
+        writer.close()?;
+        Ok(())
+    })
+}
+
+/// Parse a compression of files: one of "gzip", "bzip2", "xz", "zstd" or "uncompressed", or null for
+/// no compression
+pub(crate) fn compression_arg(compression: *const c_char, name: &str) -> FfiResult<CompressionTypeVariant> {
+    if compression.is_null() {
+        return Ok(CompressionTypeVariant::UNCOMPRESSED);
+    }
+    str_arg(compression, name)?
+        .parse()
+        .map_err(|err: datafusion::sql::sqlparser::parser::ParserError| DataFusionError::invalid_argument(err.to_string()))
+}
+
+/// Create a file, optionally compressed, and write it with `write`. `compression` is one of
+/// "gzip", "bzip2", "xz", "zstd" or "uncompressed", or null for no compression.
+fn write_compressed_file(
+    path: &str,
+    compression: *const c_char,
+    write: impl FnOnce(&mut dyn Write) -> FfiResult<()>,
+) -> FfiResult<()> {
+    let compression = compression_arg(compression, "compression")?;
+    let file = BufWriter::new(File::create(path)?);
+    let mut file = match compression {
+        CompressionTypeVariant::UNCOMPRESSED => {
+            let mut writer = file;
+            write(&mut writer)?;
+            writer
+        }
+        CompressionTypeVariant::GZIP => {
+            let mut writer = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            write(&mut writer)?;
+            writer.finish()?
+        }
+        CompressionTypeVariant::BZIP2 => {
+            let mut writer = bzip2::write::BzEncoder::new(file, bzip2::Compression::default());
+            write(&mut writer)?;
+            writer.finish()?
+        }
+        CompressionTypeVariant::XZ => {
+            let mut writer = xz2::write::XzEncoder::new(file, 6);
+            write(&mut writer)?;
+            writer.finish()?
+        }
+        CompressionTypeVariant::ZSTD => {
+            let mut writer = zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+            write(&mut writer)?;
+            writer.finish()?
+        }
+    };
+    file.flush()?;
+    Ok(())
+}
+
+/// Write a result to a newline delimited JSON file with one object per row, compressed with
+/// `compression` ("gzip", "bzip2", "xz", "zstd" or "uncompressed"; null for no compression)
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_write_ndjson(
+    result: *const DataFusionResult,
+    path: *const c_char,
+    compression: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let path = str_arg(path, "path")?;
+        write_compressed_file(path, compression, |file| {
+            let mut writer = LineDelimitedWriter::new(file);
+            for batch in &result.batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+            Ok(())
+        })
+    })
+}
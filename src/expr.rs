@@ -0,0 +1,206 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use datafusion::common::Column;
+use datafusion::execution::FunctionRegistry;
+use datafusion::logical_expr::expr::{AggregateFunction, ScalarFunction, WindowFunction};
+use datafusion::logical_expr::{binary_expr, cast, lit, Operator};
+use datafusion::prelude::Expr;
+
+use crate::error::{ffi_code, ffi_ptr, ffi_string, slice_arg, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_ref, impl_handle, HandleHeader};
+use crate::schema;
+use crate::DataFusionContext;
+
+// Binary operators
+pub const DATAFUSION_OP_EQ: c_int = 0;
+pub const DATAFUSION_OP_NOT_EQ: c_int = 1;
+pub const DATAFUSION_OP_LT: c_int = 2;
+pub const DATAFUSION_OP_LT_EQ: c_int = 3;
+pub const DATAFUSION_OP_GT: c_int = 4;
+pub const DATAFUSION_OP_GT_EQ: c_int = 5;
+pub const DATAFUSION_OP_PLUS: c_int = 6;
+pub const DATAFUSION_OP_MINUS: c_int = 7;
+pub const DATAFUSION_OP_MULTIPLY: c_int = 8;
+pub const DATAFUSION_OP_DIVIDE: c_int = 9;
+pub const DATAFUSION_OP_MODULO: c_int = 10;
+pub const DATAFUSION_OP_AND: c_int = 11;
+pub const DATAFUSION_OP_OR: c_int = 12;
+
+fn operator(op: c_int) -> FfiResult<Operator> {
+    Ok(match op {
+        DATAFUSION_OP_EQ => Operator::Eq,
+        DATAFUSION_OP_NOT_EQ => Operator::NotEq,
+        DATAFUSION_OP_LT => Operator::Lt,
+        DATAFUSION_OP_LT_EQ => Operator::LtEq,
+        DATAFUSION_OP_GT => Operator::Gt,
+        DATAFUSION_OP_GT_EQ => Operator::GtEq,
+        DATAFUSION_OP_PLUS => Operator::Plus,
+        DATAFUSION_OP_MINUS => Operator::Minus,
+        DATAFUSION_OP_MULTIPLY => Operator::Multiply,
+        DATAFUSION_OP_DIVIDE => Operator::Divide,
+        DATAFUSION_OP_MODULO => Operator::Modulo,
+        DATAFUSION_OP_AND => Operator::And,
+        DATAFUSION_OP_OR => Operator::Or,
+        _ => return Err(DataFusionError::invalid_argument(format!("invalid operator {op}"))),
+    })
+}
+
+/// An expression built from columns, literals, operators and function calls, to use in place of
+/// the SQL expressions parsed by the datafusion_dataframe_* functions. Expressions are immutable:
+/// building on one leaves it unchanged, and it can be used any number of times.
+pub struct DataFusionExpr {
+    header: HandleHeader,
+    expr: Expr,
+}
+
+impl_handle!(DataFusionExpr, 0x4446_4558_5052_000e, exprs);
+
+fn new_expr(expr: Expr) -> FfiResult<Box<DataFusionExpr>> {
+    Ok(Box::new(DataFusionExpr {
+        header: HandleHeader::default(),
+        expr,
+    }))
+}
+
+/// The expressions of an array of `count` expression handles
+pub(crate) fn handles_arg(exprs: *const *const DataFusionExpr, count: usize, name: &str) -> FfiResult<Vec<Expr>> {
+    slice_arg(exprs, count, name)?
+        .iter()
+        .enumerate()
+        .map(|(index, &expr)| Ok(expr_arg(expr, &format!("{name}[{index}]"))?.clone()))
+        .collect()
+}
+
+pub(crate) fn expr_arg<'a>(expr: *const DataFusionExpr, name: &str) -> FfiResult<&'a Expr> {
+    Ok(&handle_ref(expr, name)?.expr)
+}
+
+/// Create an expression referring to the column `name`, taken as is: it is neither parsed nor
+/// case folded, so it needs no quoting. The column is resolved when the expression is used.
+/// Returns a pointer to the expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_col(name: *const c_char, error_out: *mut *mut c_char) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || new_expr(Expr::Column(Column::new_unqualified(str_arg(name, "name")?))))
+}
+
+/// Create an Int64 literal expression
+/// Returns a pointer to the expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_lit_int64(value: i64, error_out: *mut *mut c_char) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || new_expr(lit(value)))
+}
+
+/// Create a Float64 literal expression
+/// Returns a pointer to the expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_lit_float64(value: f64, error_out: *mut *mut c_char) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || new_expr(lit(value)))
+}
+
+/// Create a Boolean literal expression
+/// Returns a pointer to the expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_lit_bool(value: bool, error_out: *mut *mut c_char) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || new_expr(lit(value)))
+}
+
+/// Create a Utf8 literal expression from null terminated UTF-8, copied into the expression
+/// Returns a pointer to the expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_lit_string(value: *const c_char, error_out: *mut *mut c_char) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || new_expr(lit(str_arg(value, "value")?)))
+}
+
+/// Combine two expressions with a DATAFUSION_OP_* operator, such as DATAFUSION_OP_GT for
+/// `left > right`
+/// Returns a pointer to a new expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_binary(
+    op: c_int,
+    left: *const DataFusionExpr,
+    right: *const DataFusionExpr,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || {
+        let op = operator(op)?;
+        let (left, right) = (expr_arg(left, "left")?, expr_arg(right, "right")?);
+        new_expr(binary_expr(left.clone(), op, right.clone()))
+    })
+}
+
+/// Cast an expression to a DATAFUSION_TYPE_* type without parameters, such as
+/// DATAFUSION_TYPE_FLOAT64 or DATAFUSION_TYPE_UTF8. Types with parameters, such as timestamps and
+/// decimals, are invalid arguments.
+/// Returns a pointer to a new expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_cast(
+    expr: *const DataFusionExpr,
+    data_type: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || {
+        let expr = expr_arg(expr, "expr")?;
+        new_expr(cast(expr.clone(), schema::data_type(data_type)?))
+    })
+}
+
+/// Name the output of an expression, such as the column it computes in a select
+/// Returns a pointer to a new expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_alias(
+    expr: *const DataFusionExpr,
+    name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || {
+        let expr = expr_arg(expr, "expr")?;
+        new_expr(expr.clone().alias(str_arg(name, "name")?))
+    })
+}
+
+/// Call a scalar, aggregate or window function registered with a context, such as `upper`, `sum`
+/// or `row_number`, with `count` argument expressions. The name is looked up as registered, which
+/// is lower case for the built-in functions. Calls of aggregate functions are used as the aggregate
+/// expressions of datafusion_dataframe_aggregate_with_exprs, and calls of window and aggregate
+/// functions as the window expressions of datafusion_dataframe_window.
+/// Returns a pointer to a new expression or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_function(
+    ctx: *const DataFusionContext,
+    name: *const c_char,
+    args: *const *const DataFusionExpr,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionExpr {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let name = str_arg(name, "name")?;
+        let args = handles_arg(args, count, "args")?;
+
+        if let Ok(udf) = ctx.ctx.udf(name) {
+            return new_expr(Expr::ScalarFunction(ScalarFunction::new_udf(udf, args)));
+        }
+        if let Ok(udaf) = ctx.ctx.udaf(name) {
+            return new_expr(Expr::AggregateFunction(AggregateFunction::new_udf(udaf, args, false, None, None, None)));
+        }
+        if let Ok(udwf) = ctx.ctx.udwf(name) {
+            return new_expr(Expr::WindowFunction(WindowFunction::new(udwf, args)));
+        }
+        Err(DataFusionError::invalid_argument(format!("no scalar, aggregate or window function named {name}")))
+    })
+}
+
+/// Describe an expression as DataFusion displays it, such as `salary * Int64(2) AS doubled`
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_expr_to_string(expr: *const DataFusionExpr, error_out: *mut *mut c_char) -> *mut c_char {
+    ffi_string(error_out, || Ok(expr_arg(expr, "expr")?.to_string()))
+}
+
+/// Free an expression. Expressions built from it stay valid.
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_expr_free(expr: *mut DataFusionExpr) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(expr, "expr"))
+}
@@ -0,0 +1,611 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::{Buf, Bytes, BytesMut};
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::buffer::Buffer;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::ipc::convert::{fb_to_schema, try_schema_from_ipc_buffer};
+use datafusion::arrow::ipc::reader::{read_dictionary, read_record_batch};
+use datafusion::arrow::ipc::{root_as_message, MessageHeader};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::Session;
+use datafusion::common::{project_schema, Result};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::{PartitionStream, StreamingTableExec};
+use datafusion::physical_plan::ExecutionPlan;
+use futures::{stream, StreamExt, TryStreamExt};
+use h2::client::SendRequest;
+use h2::RecvStream;
+use http::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, TE};
+use http::{Request, Response, StatusCode};
+use percent_encoding::percent_decode_str;
+use prost::Message;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+use url::{Host, Position, Url};
+
+use crate::error::{DataFusionError, FfiResult};
+use crate::{
+    DATAFUSION_CANCELLED, DATAFUSION_ERROR, DATAFUSION_INVALID_ARGUMENT, DATAFUSION_RESOURCE_EXHAUSTED,
+    DATAFUSION_TIMEOUT,
+};
+
+/// The messages of the Flight and Flight SQL protocols, with the fields read or written here
+mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HandshakeRequest {
+        #[prost(uint64, tag = "1")]
+        pub protocol_version: u64,
+        #[prost(bytes = "vec", tag = "2")]
+        pub payload: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HandshakeResponse {
+        #[prost(uint64, tag = "1")]
+        pub protocol_version: u64,
+        #[prost(bytes = "vec", tag = "2")]
+        pub payload: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Ticket {
+        #[prost(bytes = "vec", tag = "1")]
+        pub ticket: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FlightDescriptor {
+        #[prost(int32, tag = "1")]
+        pub r#type: i32,
+        #[prost(bytes = "vec", tag = "2")]
+        pub cmd: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Location {
+        #[prost(string, tag = "1")]
+        pub uri: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FlightEndpoint {
+        #[prost(message, optional, tag = "1")]
+        pub ticket: Option<Ticket>,
+        #[prost(message, repeated, tag = "2")]
+        pub location: Vec<Location>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FlightInfo {
+        /// The schema as an IPC message, after the continuation marker and its length
+        #[prost(bytes = "vec", tag = "1")]
+        pub schema: Vec<u8>,
+        #[prost(message, repeated, tag = "3")]
+        pub endpoint: Vec<FlightEndpoint>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FlightData {
+        /// The metadata of an IPC message, a flatbuffer
+        #[prost(bytes = "vec", tag = "2")]
+        pub data_header: Vec<u8>,
+        /// The body of the IPC message
+        #[prost(bytes = "vec", tag = "1000")]
+        pub data_body: Vec<u8>,
+    }
+
+    /// google.protobuf.Any, which Flight SQL commands are wrapped in
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Any {
+        #[prost(string, tag = "1")]
+        pub type_url: String,
+        #[prost(bytes = "vec", tag = "2")]
+        pub value: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CommandStatementQuery {
+        #[prost(string, tag = "1")]
+        pub query: String,
+    }
+}
+
+const SERVICE: &str = "arrow.flight.protocol.FlightService";
+/// The type of FlightDescriptors holding a command, such as a Flight SQL query
+const DESCRIPTOR_CMD: i32 = 2;
+const COMMAND_STATEMENT_QUERY: &str = "type.googleapis.com/arrow.flight.protocol.sql.CommandStatementQuery";
+/// The URI of endpoint locations served by the server that returned them
+const REUSE_CONNECTION: &str = "arrow-flight-reuse-connection:";
+
+const GRPC_STATUS: &str = "grpc-status";
+const GRPC_MESSAGE: &str = "grpc-message";
+/// The names of gRPC status codes, by code
+const STATUS_NAMES: [&str; 17] = [
+    "OK",
+    "CANCELLED",
+    "UNKNOWN",
+    "INVALID_ARGUMENT",
+    "DEADLINE_EXCEEDED",
+    "NOT_FOUND",
+    "ALREADY_EXISTS",
+    "PERMISSION_DENIED",
+    "RESOURCE_EXHAUSTED",
+    "FAILED_PRECONDITION",
+    "ABORTED",
+    "OUT_OF_RANGE",
+    "UNIMPLEMENTED",
+    "INTERNAL",
+    "UNAVAILABLE",
+    "DATA_LOSS",
+    "UNAUTHENTICATED",
+];
+
+fn protocol_error(message: impl Into<String>) -> DataFusionError {
+    DataFusionError::new(DATAFUSION_ERROR, message)
+}
+
+/// Fail with the gRPC status of a response's headers or trailers unless it is OK or not among them
+fn check_status(headers: &HeaderMap) -> FfiResult<()> {
+    let Some(status) = headers.get(GRPC_STATUS) else {
+        return Ok(());
+    };
+    let code = status.to_str().ok().and_then(|code| code.parse::<usize>().ok()).unwrap_or(2);
+    if code == 0 {
+        return Ok(());
+    }
+    let message = headers
+        .get(GRPC_MESSAGE)
+        .and_then(|message| message.to_str().ok())
+        .map(|message| percent_decode_str(message).decode_utf8_lossy().into_owned())
+        .unwrap_or_default();
+    let error_code = match code {
+        1 => DATAFUSION_CANCELLED,
+        3 => DATAFUSION_INVALID_ARGUMENT,
+        4 => DATAFUSION_TIMEOUT,
+        8 => DATAFUSION_RESOURCE_EXHAUSTED,
+        _ => DATAFUSION_ERROR,
+    };
+    let name = STATUS_NAMES.get(code).unwrap_or(&"UNKNOWN");
+    Err(DataFusionError::new(error_code, format!("Flight server returned {name}: {message}")))
+}
+
+/// A message framed as gRPC sends it, uncompressed after its length
+fn grpc_frame(message: &impl Message) -> Bytes {
+    let message = message.encode_to_vec();
+    let mut frame = Vec::with_capacity(5 + message.len());
+    frame.push(0);
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&message);
+    Bytes::from(frame)
+}
+
+/// The TLS configuration of connections, verifying certificates with the web's root authorities
+fn tls_config() -> FfiResult<Arc<ClientConfig>> {
+    let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let mut config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(Arc::new(config))
+}
+
+/// Start an HTTP/2 connection over `io`, driven in the background until its requests are dropped
+async fn start_http2<T>(io: T) -> FfiResult<SendRequest<Bytes>>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (send, connection) = h2::client::handshake(io).await?;
+    tokio::spawn(connection);
+    Ok(send)
+}
+
+/// A Flight server and the headers, such as authorization, sent with every call to it
+#[derive(Clone)]
+pub(crate) struct FlightServer {
+    host: String,
+    port: u16,
+    tls: bool,
+    /// The scheme and authority of the URIs of calls, such as "http://host:port"
+    origin: String,
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+// The headers are left out, since they hold credentials
+impl fmt::Debug for FlightServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlightServer")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("tls", &self.tls)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FlightServer {
+    /// The server at a location such as "grpc://host:port", or "grpc+tls://host:port" to connect
+    /// with TLS
+    pub(crate) fn parse(uri: &str) -> FfiResult<Self> {
+        let invalid = |reason: String| DataFusionError::invalid_argument(format!("invalid Flight URI {uri}: {reason}"));
+        let url = Url::parse(uri).map_err(|err| invalid(err.to_string()))?;
+        let tls = match url.scheme() {
+            "grpc" | "grpc+tcp" => false,
+            "grpc+tls" => true,
+            scheme => return Err(invalid(format!("unsupported scheme {scheme}, expected grpc, grpc+tcp or grpc+tls"))),
+        };
+        let host = match url.host() {
+            Some(Host::Domain(domain)) => domain.to_string(),
+            Some(Host::Ipv4(address)) => address.to_string(),
+            Some(Host::Ipv6(address)) => address.to_string(),
+            None => return Err(invalid("it has no host".to_string())),
+        };
+        let port = url.port().ok_or_else(|| invalid("it has no port".to_string()))?;
+        let scheme = if tls { "https" } else { "http" };
+        Ok(Self {
+            origin: format!("{scheme}://{}", &url[Position::BeforeHost..Position::AfterPort]),
+            host,
+            port,
+            tls,
+            headers: Vec::new(),
+        })
+    }
+
+    /// Send a header with every call, replacing any header of the same name
+    pub(crate) fn with_header(mut self, name: &str, value: &str) -> FfiResult<Self> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid Flight header name {name}")))?;
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid value of Flight header {name}")))?;
+        // Sensitive values are never added to the HPACK table of a connection
+        value.set_sensitive(true);
+        self.headers.retain(|(header, _)| *header != name);
+        self.headers.push((name, value));
+        Ok(self)
+    }
+
+    /// Authenticate as Flight's basic authentication does: the user name and password are sent to
+    /// Handshake, and the bearer token the server returns, if any, authenticates the later calls
+    pub(crate) async fn authenticate(self, username: &str, password: &str) -> FfiResult<Self> {
+        let credentials = STANDARD.encode(format!("{username}:{password}"));
+        let server = self.with_header(AUTHORIZATION.as_str(), &format!("Basic {credentials}"))?;
+        let mut response = server.call("Handshake", &proto::HandshakeRequest::default()).await?;
+        while response.next::<proto::HandshakeResponse>().await?.is_some() {}
+        match response.header(AUTHORIZATION) {
+            Some(token) => server.with_header(AUTHORIZATION.as_str(), &token),
+            None => Ok(server),
+        }
+    }
+
+    /// The server of an endpoint's location, with the same headers, which is this one unless the
+    /// location names another
+    fn at(&self, location: Option<&proto::Location>) -> FfiResult<Self> {
+        match location {
+            Some(location) if !location.uri.starts_with(REUSE_CONNECTION) => Ok(Self {
+                headers: self.headers.clone(),
+                ..Self::parse(&location.uri)?
+            }),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Open an HTTP/2 connection to the server
+    async fn connect(&self) -> FfiResult<SendRequest<Bytes>> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port)).await.map_err(|err| {
+            protocol_error(format!("cannot connect to Flight server {}:{}: {err}", self.host, self.port))
+        })?;
+        tcp.set_nodelay(true)?;
+        if !self.tls {
+            return start_http2(tcp).await;
+        }
+        let name = ServerName::try_from(self.host.clone())?;
+        start_http2(TlsConnector::from(tls_config()?).connect(name, tcp).await?).await
+    }
+
+    /// Call a method of the Flight service with one request message
+    async fn call(&self, method: &str, message: &impl Message) -> FfiResult<FlightResponse> {
+        let mut request = Request::post(format!("{}/{SERVICE}/{method}", self.origin))
+            .header(CONTENT_TYPE, "application/grpc")
+            .header(TE, "trailers");
+        for (name, value) in &self.headers {
+            request = request.header(name.clone(), value.clone());
+        }
+        let request = request.body(())?;
+
+        let mut send = self.connect().await?.ready().await?;
+        let (response, mut body) = send.send_request(request, false)?;
+        body.send_data(grpc_frame(message), true)?;
+        FlightResponse::new(response.await?)
+    }
+
+    /// Plan a Flight SQL query, getting the schema of its rows and the tickets that stream them
+    async fn get_flight_info(&self, sql: &str) -> FfiResult<proto::FlightInfo> {
+        let query = proto::CommandStatementQuery { query: sql.to_string() };
+        let command = proto::Any {
+            type_url: COMMAND_STATEMENT_QUERY.to_string(),
+            value: query.encode_to_vec(),
+        };
+        let descriptor = proto::FlightDescriptor {
+            r#type: DESCRIPTOR_CMD,
+            cmd: command.encode_to_vec(),
+        };
+        let mut response = self.call("GetFlightInfo", &descriptor).await?;
+        let info = response.next().await?.ok_or_else(|| protocol_error("Flight server returned no FlightInfo"))?;
+        while response.next::<proto::FlightInfo>().await?.is_some() {}
+        Ok(info)
+    }
+
+    /// Redeem a ticket, streaming the record batches it stands for
+    async fn do_get(&self, ticket: &[u8]) -> FfiResult<FlightStream> {
+        let response = self.call("DoGet", &proto::Ticket { ticket: ticket.to_vec() }).await?;
+        Ok(FlightStream {
+            response,
+            schema: None,
+            dictionaries: HashMap::new(),
+        })
+    }
+}
+
+/// The response to a Flight call, read as the messages it streams
+struct FlightResponse {
+    headers: HeaderMap,
+    body: RecvStream,
+    /// Bytes received of messages not read yet
+    buffer: BytesMut,
+    /// The trailers ending the response, once it was read to the end
+    trailers: Option<HeaderMap>,
+}
+
+impl FlightResponse {
+    fn new(response: Response<RecvStream>) -> FfiResult<Self> {
+        if response.status() != StatusCode::OK {
+            return Err(protocol_error(format!("Flight server returned HTTP status {}", response.status())));
+        }
+        // Responses without messages may end with their headers, which then hold the status
+        check_status(response.headers())?;
+        let (parts, body) = response.into_parts();
+        Ok(Self {
+            headers: parts.headers,
+            body,
+            buffer: BytesMut::new(),
+            trailers: None,
+        })
+    }
+
+    /// The next message of the response, or None once it ended with an OK status
+    async fn next<M: Message + Default>(&mut self) -> FfiResult<Option<M>> {
+        while self.trailers.is_none() {
+            if self.buffer.len() >= 5 {
+                if self.buffer[0] != 0 {
+                    return Err(protocol_error("Flight server sent a compressed message"));
+                }
+                let len = (&self.buffer[1..5]).get_u32() as usize;
+                if self.buffer.len() >= 5 + len {
+                    let mut frame = self.buffer.split_to(5 + len);
+                    frame.advance(5);
+                    return Ok(Some(M::decode(frame.freeze())?));
+                }
+            }
+            match self.body.data().await {
+                Some(data) => {
+                    let data = data?;
+                    self.body.flow_control().release_capacity(data.len())?;
+                    self.buffer.extend_from_slice(&data);
+                }
+                None => {
+                    if !self.buffer.is_empty() {
+                        return Err(protocol_error("Flight response ended within a message"));
+                    }
+                    let trailers = self.body.trailers().await?.unwrap_or_default();
+                    if !trailers.contains_key(GRPC_STATUS) && !self.headers.contains_key(GRPC_STATUS) {
+                        return Err(protocol_error("Flight response ended without a gRPC status"));
+                    }
+                    check_status(&trailers)?;
+                    self.trailers = Some(trailers);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// A header of the response, from its headers or its trailers
+    fn header(&self, name: HeaderName) -> Option<String> {
+        let value = self.headers.get(&name).or_else(|| self.trailers.as_ref()?.get(&name))?;
+        Some(value.to_str().ok()?.to_string())
+    }
+}
+
+/// The record batches streamed for a ticket, as IPC messages split into their metadata and body
+struct FlightStream {
+    response: FlightResponse,
+    /// The schema of the batches, from the first message
+    schema: Option<SchemaRef>,
+    dictionaries: HashMap<i64, ArrayRef>,
+}
+
+impl FlightStream {
+    /// The schema of the record batches, read from the first message
+    async fn schema(&mut self) -> FfiResult<SchemaRef> {
+        loop {
+            if let Some(schema) = &self.schema {
+                return Ok(schema.clone());
+            }
+            let data = self.response.next().await?;
+            self.decode(data.ok_or_else(|| protocol_error("Flight server streamed no schema"))?)?;
+        }
+    }
+
+    /// The next record batch, or None once the stream ended
+    async fn next(&mut self) -> FfiResult<Option<RecordBatch>> {
+        while let Some(data) = self.response.next().await? {
+            if let Some(batch) = self.decode(data)? {
+                return Ok(Some(batch));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The record batch of a message, or None for the schema and the dictionaries
+    fn decode(&mut self, mut data: proto::FlightData) -> FfiResult<Option<RecordBatch>> {
+        // Messages may carry only application metadata
+        if data.data_header.is_empty() {
+            return Ok(None);
+        }
+        let message = root_as_message(&data.data_header)
+            .map_err(|err| protocol_error(format!("Flight server sent an invalid IPC message: {err}")))?;
+        let body = Buffer::from_vec(std::mem::take(&mut data.data_body));
+        let invalid = || protocol_error("Flight server sent an invalid IPC message");
+        let missing_schema = || protocol_error("Flight server streamed a batch before its schema");
+        match message.header_type() {
+            MessageHeader::Schema => {
+                self.schema = Some(Arc::new(fb_to_schema(message.header_as_schema().ok_or_else(invalid)?)));
+                Ok(None)
+            }
+            MessageHeader::DictionaryBatch => {
+                let schema = self.schema.as_ref().ok_or_else(missing_schema)?;
+                let dictionary = message.header_as_dictionary_batch().ok_or_else(invalid)?;
+                read_dictionary(&body, dictionary, schema, &mut self.dictionaries, &message.version())?;
+                Ok(None)
+            }
+            MessageHeader::RecordBatch => {
+                let schema = self.schema.clone().ok_or_else(missing_schema)?;
+                let batch = message.header_as_record_batch().ok_or_else(invalid)?;
+                Ok(Some(read_record_batch(&body, batch, schema, &self.dictionaries, None, &message.version())?))
+            }
+            header => Err(protocol_error(format!("Flight server sent an unexpected {header:?} message"))),
+        }
+    }
+}
+
+/// What a Flight table streams: the batches of a ticket, or the rows of a Flight SQL query
+#[derive(Debug)]
+pub(crate) enum FlightSource {
+    Ticket(Vec<u8>),
+    Sql(String),
+}
+
+/// A table streaming its record batches from a Flight server whenever it is scanned
+#[derive(Debug)]
+pub(crate) struct FlightTable {
+    server: FlightServer,
+    source: FlightSource,
+    schema: SchemaRef,
+}
+
+impl FlightTable {
+    /// A table of the batches of `source`, whose schema is read from the query's FlightInfo or from
+    /// the first message streamed for the ticket
+    pub(crate) async fn try_new(server: FlightServer, source: FlightSource) -> FfiResult<Self> {
+        let schema = match &source {
+            FlightSource::Ticket(ticket) => server.do_get(ticket).await?.schema().await?,
+            FlightSource::Sql(sql) => Arc::new(try_schema_from_ipc_buffer(&server.get_flight_info(sql).await?.schema)?),
+        };
+        Ok(Self { server, source, schema })
+    }
+
+    /// The servers and tickets of the partitions of a scan. Queries are planned again for every
+    /// scan, so that they read the rows the server holds then.
+    async fn endpoints(&self) -> FfiResult<Vec<(FlightServer, Vec<u8>)>> {
+        let sql = match &self.source {
+            FlightSource::Ticket(ticket) => return Ok(vec![(self.server.clone(), ticket.clone())]),
+            FlightSource::Sql(sql) => sql,
+        };
+        let info = self.server.get_flight_info(sql).await?;
+        info.endpoint
+            .into_iter()
+            .map(|endpoint| {
+                let ticket = endpoint.ticket.ok_or_else(|| protocol_error("Flight server returned an endpoint without a ticket"))?;
+                Ok((self.server.at(endpoint.location.first())?, ticket.ticket))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TableProvider for FlightTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let endpoints = self.endpoints().await.map_err(DataFusionError::into_datafusion)?;
+        if endpoints.is_empty() {
+            return Ok(Arc::new(EmptyExec::new(project_schema(&self.schema, projection)?)));
+        }
+        let partitions = endpoints
+            .into_iter()
+            .map(|(server, ticket)| {
+                let partition = FlightPartition {
+                    server,
+                    ticket,
+                    schema: self.schema.clone(),
+                };
+                Arc::new(partition) as Arc<dyn PartitionStream>
+            })
+            .collect();
+        Ok(Arc::new(StreamingTableExec::try_new(self.schema.clone(), partitions, projection, [], false, limit)?))
+    }
+}
+
+/// The batches of one ticket of a Flight table, redeemed when the partition is executed
+#[derive(Debug)]
+struct FlightPartition {
+    server: FlightServer,
+    ticket: Vec<u8>,
+    schema: SchemaRef,
+}
+
+impl PartitionStream for FlightPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let server = self.server.clone();
+        let ticket = self.ticket.clone();
+        let schema = self.schema.clone();
+        let batches = stream::once(async move { server.do_get(&ticket).await })
+            .map_ok(|batches| {
+                stream::try_unfold(batches, |mut batches| async move {
+                    Ok(batches.next().await?.map(|batch| (batch, batches)))
+                })
+            })
+            .try_flatten()
+            // The batches take the table's schema, failing if the server's column types changed
+            .map(move |batch: FfiResult<RecordBatch>| -> FfiResult<RecordBatch> {
+                Ok(RecordBatch::try_new(schema.clone(), batch?.columns().to_vec())?)
+            })
+            .map_err(DataFusionError::into_datafusion);
+        Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), batches))
+    }
+}
@@ -0,0 +1,213 @@
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+use crate::error::{DataFusionError, FfiResult};
+use crate::DATAFUSION_INVALID_HANDLE;
+
+/// Header embedded in every opaque handle.
+///
+/// It holds a type-specific magic value while the handle is alive and is zeroed when the handle is
+/// freed, so a stale or foreign pointer passed back from C is reported as DATAFUSION_INVALID_HANDLE
+/// instead of being dereferenced as the wrong type. Detecting use-after-free this way is best effort:
+/// it relies on the freed memory not having been reused for another handle of the same type.
+#[derive(Clone, Default)]
+pub(crate) struct HandleHeader {
+    magic: u64,
+    /// Whether the handle was counted by handle tracking when it was created
+    tracked: bool,
+}
+
+impl HandleHeader {
+    /// A header that is already valid, for handles not created through `into_handle`
+    pub(crate) fn valid<T: Handle>() -> Self {
+        Self {
+            magic: T::MAGIC,
+            tracked: false,
+        }
+    }
+}
+
+/// Number of live handles of each type, as counted since handle tracking was enabled
+#[repr(C)]
+pub struct DataFusionHandleCounts {
+    pub contexts: i64,
+    pub results: i64,
+    pub schemas: i64,
+    pub cursors: i64,
+    pub streams: i64,
+    pub queries: i64,
+    pub statements: i64,
+    pub scripts: i64,
+    pub dataframes: i64,
+    pub exprs: i64,
+    pub table_builders: i64,
+    pub string_lists: i64,
+    pub errors: i64,
+    pub iceberg_catalogs: i64,
+    pub iceberg_schemas: i64,
+    pub iceberg_partition_specs: i64,
+    pub iceberg_tables: i64,
+}
+
+/// Live handle counters, one per field of DataFusionHandleCounts
+pub(crate) struct LiveHandles {
+    pub(crate) contexts: AtomicI64,
+    pub(crate) results: AtomicI64,
+    pub(crate) schemas: AtomicI64,
+    pub(crate) cursors: AtomicI64,
+    pub(crate) streams: AtomicI64,
+    pub(crate) queries: AtomicI64,
+    pub(crate) statements: AtomicI64,
+    pub(crate) scripts: AtomicI64,
+    pub(crate) dataframes: AtomicI64,
+    pub(crate) exprs: AtomicI64,
+    pub(crate) table_builders: AtomicI64,
+    pub(crate) string_lists: AtomicI64,
+    pub(crate) errors: AtomicI64,
+    pub(crate) iceberg_catalogs: AtomicI64,
+    pub(crate) iceberg_schemas: AtomicI64,
+    pub(crate) iceberg_partition_specs: AtomicI64,
+    pub(crate) iceberg_tables: AtomicI64,
+}
+
+pub(crate) static LIVE_HANDLES: LiveHandles = LiveHandles {
+    contexts: AtomicI64::new(0),
+    results: AtomicI64::new(0),
+    schemas: AtomicI64::new(0),
+    cursors: AtomicI64::new(0),
+    streams: AtomicI64::new(0),
+    queries: AtomicI64::new(0),
+    statements: AtomicI64::new(0),
+    scripts: AtomicI64::new(0),
+    dataframes: AtomicI64::new(0),
+    exprs: AtomicI64::new(0),
+    table_builders: AtomicI64::new(0),
+    string_lists: AtomicI64::new(0),
+    errors: AtomicI64::new(0),
+    iceberg_catalogs: AtomicI64::new(0),
+    iceberg_schemas: AtomicI64::new(0),
+    iceberg_partition_specs: AtomicI64::new(0),
+    iceberg_tables: AtomicI64::new(0),
+};
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+
+/// An opaque type handed out to C callers as a raw pointer
+pub(crate) trait Handle: Sized {
+    const MAGIC: u64;
+    const NAME: &'static str;
+
+    fn header(&self) -> &HandleHeader;
+    fn header_mut(&mut self) -> &mut HandleHeader;
+    fn live_count() -> &'static AtomicI64;
+}
+
+macro_rules! impl_handle {
+    ($ty:ty, $magic:expr, $counter:ident) => {
+        impl $crate::handle::Handle for $ty {
+            const MAGIC: u64 = $magic;
+            const NAME: &'static str = stringify!($ty);
+
+            fn live_count() -> &'static std::sync::atomic::AtomicI64 {
+                &$crate::handle::LIVE_HANDLES.$counter
+            }
+
+            fn header(&self) -> &$crate::handle::HandleHeader {
+                &self.header
+            }
+
+            fn header_mut(&mut self) -> &mut $crate::handle::HandleHeader {
+                &mut self.header
+            }
+        }
+    };
+}
+pub(crate) use impl_handle;
+
+fn check<T: Handle>(handle: &T, name: &str) -> FfiResult<()> {
+    if handle.header().magic == T::MAGIC {
+        Ok(())
+    } else {
+        Err(DataFusionError::new(
+            DATAFUSION_INVALID_HANDLE,
+            format!("{name} is not a valid {} (already freed or wrong type)", T::NAME),
+        ))
+    }
+}
+
+/// Move a value to the heap and stamp its magic, returning the pointer handed to C
+pub(crate) fn into_handle<T: Handle>(value: Box<T>) -> *mut T {
+    let mut value = value;
+    let tracked = TRACKING.load(Ordering::Relaxed);
+    if tracked {
+        T::live_count().fetch_add(1, Ordering::Relaxed);
+    }
+    *value.header_mut() = HandleHeader {
+        magic: T::MAGIC,
+        tracked,
+    };
+    Box::into_raw(value)
+}
+
+/// Borrow a handle argument, rejecting null pointers and invalid handles
+pub(crate) fn handle_ref<'a, T: Handle>(value: *const T, name: &str) -> FfiResult<&'a T> {
+    let handle = unsafe { value.as_ref() }
+        .ok_or_else(|| DataFusionError::invalid_argument(format!("{name} must not be null")))?;
+    check(handle, name)?;
+    Ok(handle)
+}
+
+/// Mutably borrow a handle argument, rejecting null pointers and invalid handles
+pub(crate) fn handle_mut<'a, T: Handle>(value: *mut T, name: &str) -> FfiResult<&'a mut T> {
+    let handle = unsafe { value.as_mut() }
+        .ok_or_else(|| DataFusionError::invalid_argument(format!("{name} must not be null")))?;
+    check(handle, name)?;
+    Ok(handle)
+}
+
+/// Free a handle, zeroing its magic first; freeing null is a no-op
+pub(crate) fn free_handle<T: Handle>(value: *mut T, name: &str) -> FfiResult<()> {
+    if value.is_null() {
+        return Ok(());
+    }
+    let handle = handle_mut(value, name)?;
+    if handle.header().tracked {
+        T::live_count().fetch_sub(1, Ordering::Relaxed);
+    }
+    handle.header_mut().magic = 0;
+    unsafe {
+        let _ = Box::from_raw(value);
+    }
+    Ok(())
+}
+
+/// Start counting live handles per type, for leak tests of binding finalizers.
+/// Only handles created after this call are counted. Tracking stays enabled for the life of the process.
+#[no_mangle]
+pub extern "C" fn datafusion_enable_handle_tracking() {
+    TRACKING.store(true, Ordering::Relaxed);
+}
+
+/// Get the number of live handles of each type created since handle tracking was enabled
+#[no_mangle]
+pub extern "C" fn datafusion_live_handle_counts() -> DataFusionHandleCounts {
+    let live = &LIVE_HANDLES;
+    DataFusionHandleCounts {
+        contexts: live.contexts.load(Ordering::Relaxed),
+        results: live.results.load(Ordering::Relaxed),
+        schemas: live.schemas.load(Ordering::Relaxed),
+        cursors: live.cursors.load(Ordering::Relaxed),
+        streams: live.streams.load(Ordering::Relaxed),
+        queries: live.queries.load(Ordering::Relaxed),
+        statements: live.statements.load(Ordering::Relaxed),
+        scripts: live.scripts.load(Ordering::Relaxed),
+        dataframes: live.dataframes.load(Ordering::Relaxed),
+        exprs: live.exprs.load(Ordering::Relaxed),
+        table_builders: live.table_builders.load(Ordering::Relaxed),
+        string_lists: live.string_lists.load(Ordering::Relaxed),
+        errors: live.errors.load(Ordering::Relaxed),
+        iceberg_catalogs: live.iceberg_catalogs.load(Ordering::Relaxed),
+        iceberg_schemas: live.iceberg_schemas.load(Ordering::Relaxed),
+        iceberg_partition_specs: live.iceberg_partition_specs.load(Ordering::Relaxed),
+        iceberg_tables: live.iceberg_tables.load(Ordering::Relaxed),
+    }
+}
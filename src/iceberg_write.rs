@@ -0,0 +1,176 @@
+use std::any::Any;
+use std::fmt;
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::catalog::{
+    CatalogProvider, CatalogProviderList, MemoryCatalogProvider, MemoryCatalogProviderList, MemorySchemaProvider,
+    SchemaProvider, Session, TableReference,
+};
+use datafusion::common::{not_impl_err, plan_err, Result};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::{SendableRecordBatchStream, SessionStateBuilder, TaskContext};
+use datafusion::logical_expr::dml::InsertOp;
+use datafusion::logical_expr::{cast, Expr, LogicalPlanBuilder};
+use datafusion::physical_plan::insert::{DataSink, DataSinkExec};
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
+use datafusion::prelude::DataFrame;
+use datafusion_iceberg::DataFusionTable;
+use futures::TryStreamExt;
+use iceberg_rust::arrow::write::write_parquet_partitioned;
+use iceberg_rust::catalog::tabular::Tabular;
+
+use crate::error::{DataFusionError, FfiResult};
+
+// Iceberg write modes
+pub const DATAFUSION_WRITE_APPEND: c_int = 0;
+/// Replace all rows of the table
+pub const DATAFUSION_WRITE_OVERWRITE: c_int = 1;
+
+/// Where the target of a write is resolved, in a catalog of its own rather than the context's
+const TARGET_CATALOG: &str = "iceberg_write";
+const TARGET_SCHEMA: &str = "iceberg_write";
+const TARGET_TABLE: &str = "target";
+
+/// Writes the files of an Iceberg table and commits them as a new snapshot, appending to or
+/// replacing the table's data
+#[derive(Debug)]
+struct IcebergSink {
+    table: Arc<DataFusionTable>,
+    overwrite: bool,
+}
+
+impl DisplayAs for IcebergSink {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IcebergSink: overwrite={}", self.overwrite)
+    }
+}
+
+#[async_trait]
+impl DataSink for IcebergSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> &SchemaRef {
+        &self.table.schema
+    }
+
+    async fn write_all(&self, data: SendableRecordBatchStream, _context: &Arc<TaskContext>) -> Result<u64> {
+        let rows = Arc::new(AtomicU64::new(0));
+        let data = {
+            let rows = rows.clone();
+            data.inspect_ok(move |batch| {
+                rows.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+            })
+        };
+
+        let mut tabular = self.table.inner_mut().await;
+        let Tabular::Table(table) = &mut *tabular else {
+            return plan_err!("only Iceberg tables can be written to, not views");
+        };
+        let branch = self.table.branch.as_deref();
+        let files = write_parquet_partitioned(table, data.map_err(Into::into), branch).await?;
+        let transaction = table.new_transaction(branch);
+        // Snapshots must add files, so appending no rows leaves the table as it is, and replacing its
+        // rows with none would need a delete, which iceberg-rust does not support
+        let transaction = match (self.overwrite, files.is_empty()) {
+            (false, true) => return Ok(0),
+            (false, false) => transaction.append_data(files),
+            (true, true) => return not_impl_err!("overwriting an Iceberg table with no rows"),
+            (true, false) => transaction.replace(files),
+        };
+        transaction
+            .commit()
+            .await
+            .map_err(|err| datafusion::common::DataFusionError::External(Box::new(err)))?;
+        Ok(rows.load(Ordering::Relaxed))
+    }
+}
+
+/// An Iceberg table as the target of a write, written through IcebergSink
+#[derive(Debug)]
+struct IcebergTarget(Arc<DataFusionTable>);
+
+#[async_trait]
+impl TableProvider for IcebergTarget {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.0.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        _projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("the target of an Iceberg write cannot be scanned")
+    }
+
+    async fn insert_into(
+        &self,
+        _state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+        insert_op: InsertOp,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let sink = IcebergSink {
+            table: self.0.clone(),
+            overwrite: insert_op == InsertOp::Overwrite,
+        };
+        Ok(Arc::new(DataSinkExec::new(input, Arc::new(sink), None)))
+    }
+}
+
+/// Plan writing the rows of a DataFrame into an Iceberg table with a DATAFUSION_WRITE_* mode. The
+/// columns of the DataFrame are matched to those of the table by name and cast to their types.
+/// Executing the returned DataFrame writes the rows and produces their count.
+pub(crate) fn insert(df: DataFrame, table: &Arc<DataFusionTable>, mode: c_int) -> FfiResult<DataFrame> {
+    let insert_op = match mode {
+        DATAFUSION_WRITE_APPEND => InsertOp::Append,
+        DATAFUSION_WRITE_OVERWRITE => InsertOp::Overwrite,
+        _ => return Err(DataFusionError::invalid_argument(format!("invalid write mode {mode}"))),
+    };
+
+    let table_schema = table.schema.clone();
+    let df_schema = df.schema();
+    if let Some(field) = df_schema.fields().iter().find(|field| table_schema.field_with_name(field.name()).is_err()) {
+        return Err(DataFusionError::invalid_argument(format!("the table has no column {}", field.name())));
+    }
+    let columns = table_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = df_schema
+                .qualified_field_with_unqualified_name(field.name())
+                .map_err(|_| DataFusionError::invalid_argument(format!("the DataFrame has no column {}", field.name())))?;
+            Ok(cast(Expr::Column(column.into()), field.data_type().clone()).alias(field.name()))
+        })
+        .collect::<FfiResult<Vec<_>>>()?;
+
+    let (state, plan) = df.select(columns)?.into_parts();
+    let target = TableReference::full(TARGET_CATALOG, TARGET_SCHEMA, TARGET_TABLE);
+    let plan = LogicalPlanBuilder::insert_into(plan, target, &table_schema, insert_op)?.build()?;
+
+    let schema = MemorySchemaProvider::new();
+    schema.register_table(TARGET_TABLE.to_string(), Arc::new(IcebergTarget(table.clone())))?;
+    let catalog = MemoryCatalogProvider::new();
+    catalog.register_schema(TARGET_SCHEMA, Arc::new(schema))?;
+    let catalogs = MemoryCatalogProviderList::new();
+    catalogs.register_catalog(TARGET_CATALOG.to_string(), Arc::new(catalog));
+    let state = SessionStateBuilder::new_from_existing(state)
+        .with_catalog_list(Arc::new(catalogs))
+        .build();
+    Ok(DataFrame::new(state, plan))
+}
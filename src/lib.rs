@@ -1,13 +1,28 @@
-use std::ffi::CStr;
+// Every exported function validates its pointer arguments before dereferencing them
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::ffi::CString;
+use std::future::Future;
 use std::os::raw::{c_char, c_int};
 use std::ptr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use datafusion::prelude::*;
 use datafusion::execution::context::SessionContext;
 use datafusion_iceberg::DataFusionTable;
+use futures::StreamExt;
 use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::array::{Array, UInt64Array};
+use datafusion::arrow::compute::{concat_batches, interleave_record_batch};
 use datafusion::arrow::util::pretty;
+use datafusion::common::runtime::SpawnedTask;
+use datafusion::execution::runtime_env::RuntimeEnvBuilder;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::insert::DataSinkExec;
+use datafusion::physical_plan::{self, ExecutionPlan};
 use iceberg_rust::{
     catalog::Catalog,
     object_store::ObjectStoreBuilder,
@@ -20,417 +35,1015 @@ use iceberg_rust::{
 };
 use iceberg_sql_catalog::SqlCatalog;
 
+mod buffers;
+mod cancel;
+mod catalog;
+mod cursor;
+mod dataframe;
+mod decimal;
+mod error;
+mod explain;
+mod export;
+mod expr;
+mod flight;
+mod handle;
+mod iceberg_write;
+mod logging;
+mod metrics;
+mod nested;
+mod object_stores;
+mod params;
+mod partition;
+mod progress;
+mod query;
+mod register;
+mod registry;
+mod repartition;
+mod schema;
+mod script;
+mod statement;
+mod stats;
+mod stream;
+mod substrait;
+mod table_builder;
+mod temporal;
+mod validate;
+mod values;
+mod version;
+
+pub use error::DataFusionError;
+use error::{ffi_bool, ffi_code, ffi_ptr, ffi_string, ffi_value, str_arg};
+use handle::{free_handle, handle_mut, handle_ref, impl_handle, HandleHeader};
+use object_stores::ObjectStores;
+use values::write_out;
+
 // Opaque handles for C API
 pub struct DataFusionContext {
+    header: HandleHeader,
     ctx: SessionContext,
-    runtime: tokio::runtime::Runtime,
+    /// Shared with streams so they can keep executing after the context is freed
+    runtime: Arc<tokio::runtime::Runtime>,
+    log_filter: logging::ContextLogFilter,
+    /// Maximum memory of a collected query result in bytes, or 0 for no limit
+    max_result_bytes: usize,
+    /// Maximum number of rows of a collected query result, or 0 for no limit
+    max_result_rows: usize,
+    /// Default timeout of queries, if any
+    query_timeout: Option<Duration>,
+    /// Queries running on the context, shared with its statements
+    queries: Arc<registry::QueryRegistry>,
+    /// The kinds of statements SQL may run, checked before a plan executes
+    sql_options: SQLOptions,
 }
 
 pub struct DataFusionResult {
+    header: HandleHeader,
     batches: Vec<RecordBatch>,
+    schema: SchemaRef,
+    /// The executed physical plan, kept for metrics; None for results not produced by a query
+    plan: Option<Arc<dyn ExecutionPlan>>,
+    /// Number of batches of each output partition, whose batches are stored one partition after
+    /// another; None for results collected as a single partition
+    partitions: Option<Vec<usize>>,
 }
 
 pub struct IcebergCatalog {
+    header: HandleHeader,
     catalog: Arc<dyn Catalog>,
     runtime: tokio::runtime::Runtime,
 }
 
 pub struct IcebergSchema {
+    header: HandleHeader,
     builder: iceberg_rust::spec::schema::SchemaBuilder,
 }
 
 pub struct IcebergPartitionSpec {
+    header: HandleHeader,
     builder: iceberg_rust::spec::partition::PartitionSpecBuilder,
 }
 
 pub struct IcebergTable {
+    header: HandleHeader,
     table: Arc<DataFusionTable>,
 }
 
+impl_handle!(DataFusionContext, 0x4446_4354_5800_0001, contexts);
+impl_handle!(DataFusionResult, 0x4446_5253_4c54_0002, results);
+impl_handle!(IcebergCatalog, 0x4943_4341_5441_0003, iceberg_catalogs);
+impl_handle!(IcebergSchema, 0x4943_5343_484d_0004, iceberg_schemas);
+impl_handle!(IcebergPartitionSpec, 0x4943_5350_4543_0005, iceberg_partition_specs);
+impl_handle!(IcebergTable, 0x4943_5442_4c45_0006, iceberg_tables);
+
 // Error codes
 pub const DATAFUSION_OK: c_int = 0;
 pub const DATAFUSION_ERROR: c_int = -1;
+pub const DATAFUSION_INVALID_ARGUMENT: c_int = -2;
+/// A handle was already freed or is not of the expected type
+pub const DATAFUSION_INVALID_HANDLE: c_int = -3;
+/// The library panicked; the panic message is available as the last error
+pub const DATAFUSION_PANIC: c_int = -4;
+/// The requested value is null
+pub const DATAFUSION_NULL_VALUE: c_int = -5;
+/// The requested value does not have the expected type
+pub const DATAFUSION_TYPE_MISMATCH: c_int = -6;
+/// A configured resource limit, such as the context's maximum result size, was exceeded
+pub const DATAFUSION_RESOURCE_EXHAUSTED: c_int = -7;
+/// The query was cancelled
+pub const DATAFUSION_CANCELLED: c_int = -8;
+/// The query did not finish within its timeout
+pub const DATAFUSION_TIMEOUT: c_int = -9;
+/// A table of the same name is already registered with the context
+pub const DATAFUSION_ALREADY_EXISTS: c_int = -10;
+
+/// How to execute a query and collect its result
+#[derive(Clone, Default)]
+struct QueryOptions {
+    /// Maximum memory of the collected result in bytes, or 0 for no limit
+    max_result_bytes: usize,
+    /// Maximum number of rows of the collected result, or 0 for no limit
+    max_result_rows: usize,
+    timeout: Option<Duration>,
+    /// Lets other handles stop the query
+    cancellation: Option<cancel::Cancellation>,
+    /// Collect the output partitions of the plan in parallel and keep them apart in the result
+    preserve_partitions: bool,
+    /// Reports the query's progress while it executes
+    progress: Option<progress::Progress>,
+}
+
+impl DataFusionContext {
+    /// The options of a query executed with the context's limits
+    fn query_options(&self) -> QueryOptions {
+        QueryOptions {
+            max_result_bytes: self.max_result_bytes,
+            max_result_rows: self.max_result_rows,
+            timeout: self.query_timeout,
+            ..Default::default()
+        }
+    }
+
+    /// Run a future to completion on the context's runtime, applying the context's log filter
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let _log_filter = logging::enter_context_filter(&self.log_filter);
+        self.runtime.block_on(future)
+    }
+
+    /// Plan and execute a SQL query, collecting its batches while enforcing the maximum result sizes
+    /// of `options`, usually the context's query options. The future does not borrow the context, so
+    /// it can also be spawned on the runtime. With a timeout or a cancellation, the query stops with
+    /// an error once it runs out of time or is cancelled.
+    fn sql(
+        &self,
+        sql: &str,
+        options: QueryOptions,
+    ) -> impl Future<Output = error::FfiResult<DataFusionResult>> + Send + 'static {
+        let ctx = self.ctx.clone();
+        let sql_options = self.sql_options;
+        let df = {
+            let sql = sql.to_string();
+            async move { Ok(ctx.sql_with_options(&sql, sql_options).await?) }
+        };
+        self.queries.execute(Some(sql), df, options)
+    }
+}
+
+/// Execute the DataFrame produced by `df` like DataFusionContext::sql, with the given options
+fn execute(
+    df: impl Future<Output = error::FfiResult<DataFrame>> + Send + 'static,
+    options: QueryOptions,
+) -> impl Future<Output = error::FfiResult<DataFusionResult>> + Send + 'static {
+    let cancellation = match options.timeout {
+        Some(timeout) => Some(options.cancellation.clone().unwrap_or_default().with_timeout(timeout)),
+        None => options.cancellation.clone(),
+    };
+    let query = collect(df, QueryOptions { cancellation: cancellation.clone(), ..options });
+    async move {
+        let Some(cancellation) = cancellation else {
+            return query.await;
+        };
+        let outcome = match cancellation.deadline() {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), query)
+                .await
+                .unwrap_or_else(|_| Err(cancellation.error())),
+            None => query.await,
+        };
+        match outcome {
+            Err(_) if cancellation.is_cancelled() => Err(cancellation.error()),
+            outcome => outcome,
+        }
+    }
+}
+
+async fn collect(
+    df: impl Future<Output = error::FfiResult<DataFrame>>,
+    options: QueryOptions,
+) -> error::FfiResult<DataFusionResult> {
+    let QueryOptions {
+        max_result_bytes,
+        max_result_rows,
+        cancellation,
+        preserve_partitions,
+        progress,
+        ..
+    } = options;
+    let df = df.await?;
+    let task_ctx = Arc::new(df.task_ctx());
+    let mut plan = df.create_physical_plan().await?;
+    if let Some(cancellation) = &cancellation {
+        plan = cancel::cancellable(plan, cancellation)?;
+    }
+    // Reports the final progress when dropped, however collecting ends
+    let _progress = progress.map(|progress| progress.start(plan.clone()));
+
+    let collected = Arc::new(CollectedSize::default());
+    let (batches, partitions) = if preserve_partitions {
+        let streams = physical_plan::execute_stream_partitioned(plan.clone(), task_ctx)?;
+        let tasks = streams.into_iter().map(|stream| {
+            let task = SpawnedTask::spawn(collect_stream(stream, max_result_bytes, max_result_rows, collected.clone()));
+            async { task.join_unwind().await.map_err(DataFusionError::from)? }
+        });
+        let partitions = futures::future::try_join_all(tasks).await?;
+        let counts = partitions.iter().map(Vec::len).collect();
+        (partitions.concat(), Some(counts))
+    } else {
+        let stream = physical_plan::execute_stream(plan.clone(), task_ctx)?;
+        (collect_stream(stream, max_result_bytes, max_result_rows, collected).await?, None)
+    };
+
+    Ok(DataFusionResult {
+        header: HandleHeader::default(),
+        batches,
+        schema: plan.schema(),
+        plan: Some(plan),
+        partitions,
+    })
+}
+
+/// The size of a query result collected so far, shared by the streams of its partitions
+#[derive(Default)]
+struct CollectedSize {
+    bytes: AtomicUsize,
+    rows: AtomicUsize,
+}
+
+/// Collect the batches of a stream, adding their memory and rows to `collected`, and failing as soon
+/// as either exceeds its maximum, `max_result_bytes` or `max_result_rows` (0 for no limit)
+async fn collect_stream(
+    mut stream: SendableRecordBatchStream,
+    max_result_bytes: usize,
+    max_result_rows: usize,
+    collected: Arc<CollectedSize>,
+) -> error::FfiResult<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        let size = batch.get_array_memory_size();
+        if max_result_bytes > 0 && collected.bytes.fetch_add(size, Ordering::Relaxed) + size > max_result_bytes {
+            return Err(DataFusionError::new(
+                DATAFUSION_RESOURCE_EXHAUSTED,
+                format!("query result exceeds the maximum result size of {max_result_bytes} bytes"),
+            ));
+        }
+        let rows = batch.num_rows();
+        if max_result_rows > 0 && collected.rows.fetch_add(rows, Ordering::Relaxed) + rows > max_result_rows {
+            return Err(DataFusionError::new(
+                DATAFUSION_RESOURCE_EXHAUSTED,
+                format!("query result exceeds the maximum of {max_result_rows} rows"),
+            ));
+        }
+        batches.push(batch);
+    }
+    Ok(batches)
+}
 
 /// Create a new DataFusion context
 /// Returns a pointer to the context or null on error
 #[no_mangle]
-pub extern "C" fn datafusion_context_new() -> *mut DataFusionContext {
-    let runtime = match tokio::runtime::Runtime::new() {
-        Ok(rt) => rt,
-        Err(_) => return ptr::null_mut(),
-    };
-    
-    let ctx = SessionContext::new();
-    
-    let df_ctx = Box::new(DataFusionContext { ctx, runtime });
-    Box::into_raw(df_ctx)
+pub extern "C" fn datafusion_context_new(
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionContext {
+    ffi_ptr(error_out, || {
+        let log_filter = Arc::new(RwLock::new(None));
+        let runtime = Arc::new(logging::runtime_with_filter(log_filter.clone())?);
+        let runtime_env = RuntimeEnvBuilder::new()
+            .with_object_store_registry(Arc::new(ObjectStores::default()))
+            .build_arc()?;
+        let ctx = SessionContext::new_with_config_rt(SessionConfig::new(), runtime_env);
+
+        Ok(Box::new(DataFusionContext {
+            header: HandleHeader::default(),
+            ctx,
+            runtime,
+            log_filter,
+            max_result_bytes: 0,
+            max_result_rows: 0,
+            query_timeout: None,
+            queries: Default::default(),
+            sql_options: SQLOptions::new(),
+        }))
+    })
 }
 
 /// Free a DataFusion context
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
 #[no_mangle]
-pub extern "C" fn datafusion_context_free(ctx: *mut DataFusionContext) {
-    if !ctx.is_null() {
-        unsafe {
-            let _ = Box::from_raw(ctx);
-        }
-    }
+pub extern "C" fn datafusion_context_free(ctx: *mut DataFusionContext) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(ctx, "ctx"))
+}
+
+/// Convert a timeout in milliseconds to a duration, with 0 meaning no timeout
+fn timeout_arg(timeout_ms: i64) -> error::FfiResult<Option<Duration>> {
+    let timeout_ms = u64::try_from(timeout_ms)
+        .map_err(|_| DataFusionError::invalid_argument(format!("invalid timeout {timeout_ms} ms")))?;
+    Ok((timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)))
 }
 
-/// Register a CSV file with the context
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+/// Fail queries run by datafusion_sql, datafusion_sql_async and datafusion_sql_submit with
+/// DATAFUSION_TIMEOUT when they do not finish within `timeout_ms` milliseconds, or remove the
+/// timeout with 0. The timeout covers planning and execution, including waits on object stores.
+/// Returns DATAFUSION_OK on success or an error code on failure
 #[no_mangle]
-pub extern "C" fn datafusion_register_csv(
+pub extern "C" fn datafusion_context_set_query_timeout_ms(
     ctx: *mut DataFusionContext,
-    table_name: *const c_char,
-    file_path: *const c_char,
+    timeout_ms: i64,
+    error_out: *mut *mut c_char,
 ) -> c_int {
-    if ctx.is_null() || table_name.is_null() || file_path.is_null() {
-        return DATAFUSION_ERROR;
-    }
+    ffi_code(error_out, || {
+        let ctx = handle_mut(ctx, "ctx")?;
+        ctx.query_timeout = timeout_arg(timeout_ms)?;
+        Ok(())
+    })
+}
 
-    let ctx = unsafe { &mut *ctx };
-    
-    let table_name = match unsafe { CStr::from_ptr(table_name) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return DATAFUSION_ERROR,
-    };
-    
-    let file_path = match unsafe { CStr::from_ptr(file_path) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return DATAFUSION_ERROR,
-    };
+/// Limit the memory of results collected by datafusion_sql to `max_bytes` bytes, or remove the limit with 0.
+/// Queries whose result would exceed the limit fail with DATAFUSION_RESOURCE_EXHAUSTED.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_context_set_max_result_bytes(
+    ctx: *mut DataFusionContext,
+    max_bytes: i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_mut(ctx, "ctx")?;
+        ctx.max_result_bytes = usize::try_from(max_bytes)
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid maximum result size {max_bytes}")))?;
+        Ok(())
+    })
+}
 
-    match ctx.runtime.block_on(async {
-        ctx.ctx.register_csv(table_name, file_path, CsvReadOptions::new()).await
-    }) {
-        Ok(_) => DATAFUSION_OK,
-        Err(_) => DATAFUSION_ERROR,
-    }
+/// Limit the number of rows of results collected by datafusion_sql to `max_rows`, or remove the
+/// limit with 0. Queries whose result would exceed the limit fail with DATAFUSION_RESOURCE_EXHAUSTED
+/// as soon as it is exceeded, without collecting the rest of the result.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_context_set_max_result_rows(
+    ctx: *mut DataFusionContext,
+    max_rows: i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_mut(ctx, "ctx")?;
+        ctx.max_result_rows = usize::try_from(max_rows)
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid maximum result rows {max_rows}")))?;
+        Ok(())
+    })
+}
+
+/// Allow or forbid DDL statements in SQL run with the context, such as CREATE EXTERNAL TABLE,
+/// CREATE VIEW and DROP TABLE. DDL is allowed by default; forbidding it keeps SQL from registering
+/// tables over files and object stores, while tables registered through the C API stay usable.
+/// Forbidden statements fail before they execute.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_context_set_allow_ddl(
+    ctx: *mut DataFusionContext,
+    allow: bool,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_mut(ctx, "ctx")?;
+        ctx.sql_options = ctx.sql_options.with_allow_ddl(allow);
+        Ok(())
+    })
+}
+
+/// Allow or forbid DML statements in SQL run with the context, such as INSERT INTO and COPY TO,
+/// which write to tables and files. DML is allowed by default. Forbidden statements fail before
+/// they execute.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_context_set_allow_dml(
+    ctx: *mut DataFusionContext,
+    allow: bool,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_mut(ctx, "ctx")?;
+        ctx.sql_options = ctx.sql_options.with_allow_dml(allow);
+        Ok(())
+    })
+}
+
+/// Register a CSV file with the context, read with the default options of
+/// datafusion_register_csv_with_options
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_csv(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    file_path: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    register::datafusion_register_csv_with_options(ctx, table_name, file_path, ptr::null(), error_out)
 }
 
 /// Execute a SQL query
 /// Returns a pointer to the result or null on error
 #[no_mangle]
 pub extern "C" fn datafusion_sql(
-    ctx: *mut DataFusionContext,
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        Ok(Box::new(ctx.block_on(ctx.sql(sql_str, ctx.query_options()))?))
+    })
+}
+
+/// Execute a SQL query that fails with DATAFUSION_TIMEOUT if it does not finish within `timeout_ms`
+/// milliseconds, overriding the context's query timeout; 0 runs the query without a timeout
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_with_timeout(
+    ctx: *const DataFusionContext,
     sql: *const c_char,
+    timeout_ms: i64,
+    error_out: *mut *mut c_char,
 ) -> *mut DataFusionResult {
-    if ctx.is_null() || sql.is_null() {
-        return ptr::null_mut();
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+        let timeout = timeout_arg(timeout_ms)?;
+
+        Ok(Box::new(ctx.block_on(ctx.sql(sql_str, QueryOptions { timeout, ..ctx.query_options() }))?))
+    })
+}
+
+impl DataFusionResult {
+    /// The row count reported by the DataSinkExec that executed a DML statement (INSERT, COPY), or
+    /// None if the result was not produced by one
+    fn rows_affected(&self) -> Option<u64> {
+        let plan = cancel::unwrap_cancellable(self.plan.as_ref()?);
+        if !plan.as_any().is::<DataSinkExec>() {
+            return None;
+        }
+        let counts = self.batches.iter().filter_map(|batch| batch.column(0).as_any().downcast_ref::<UInt64Array>());
+        Some(counts.flat_map(|counts| counts.iter().flatten()).sum())
     }
+}
 
-    let ctx = unsafe { &mut *ctx };
-    
-    let sql_str = match unsafe { CStr::from_ptr(sql) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
+/// Execute a SQL statement that changes data, such as INSERT INTO, COPY or CREATE TABLE AS, without
+/// returning a result handle. The number of rows written, as reported by DataFusion, is stored in
+/// `rows_affected` if it is not null; statements that report no count, such as DDL statements
+/// including CREATE TABLE AS, store -1.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_execute_update(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    rows_affected: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
 
-    let batches = match ctx.runtime.block_on(async {
-        let df = ctx.ctx.sql(sql_str).await?;
-        df.collect().await
-    }) {
-        Ok(batches) => batches,
-        Err(_) => return ptr::null_mut(),
-    };
+        let result = ctx.block_on(ctx.sql(sql_str, ctx.query_options()))?;
+        if rows_affected.is_null() {
+            return Ok(());
+        }
+        write_out(rows_affected, result.rows_affected().map_or(-1, |count| count as i64))
+    })
+}
 
-    let result = Box::new(DataFusionResult { batches });
-    Box::into_raw(result)
+/// Get the number of rows written by the statement that produced a result, such as the rows
+/// exported by COPY or inserted by INSERT INTO, stored in `rows_affected`. Results that are not
+/// such a count, including those of queries, store -1.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_rows_affected(
+    result: *const DataFusionResult,
+    rows_affected: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        write_out(rows_affected, result.rows_affected().map_or(-1, |count| count as i64))
+    })
+}
+
+/// Summary statistics of a result
+#[repr(C)]
+pub struct DataFusionResultStats {
+    pub batch_count: i64,
+    pub total_rows: i64,
+    /// Approximate memory used by the result's Arrow buffers, in bytes
+    pub memory_bytes: i64,
+}
+
+impl DataFusionResult {
+    fn memory_bytes(&self) -> usize {
+        self.batches.iter().map(|batch| batch.get_array_memory_size()).sum()
+    }
+
+    fn total_rows(&self) -> usize {
+        self.batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    /// Zero-copy slices of the batches covering rows `offset..offset + length`, clamped to the result
+    fn slice_batches(&self, offset: usize, length: usize) -> Vec<RecordBatch> {
+        let mut skip = offset;
+        let mut remaining = length;
+        let mut batches = Vec::new();
+        for batch in &self.batches {
+            if remaining == 0 {
+                break;
+            }
+            if skip >= batch.num_rows() {
+                skip -= batch.num_rows();
+                continue;
+            }
+            let rows = (batch.num_rows() - skip).min(remaining);
+            batches.push(batch.slice(skip, rows));
+            skip = 0;
+            remaining -= rows;
+        }
+        batches
+    }
+
+    fn batch(&self, batch_index: c_int) -> error::FfiResult<&RecordBatch> {
+        usize::try_from(batch_index)
+            .ok()
+            .and_then(|index| self.batches.get(index))
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "batch index {batch_index} out of range for result with {} batches",
+                    self.batches.len()
+                ))
+            })
+    }
 }
 
 /// Get the number of batches in a result
+/// Returns 0 on error
 #[no_mangle]
-pub extern "C" fn datafusion_result_batch_count(result: *const DataFusionResult) -> c_int {
-    if result.is_null() {
-        return 0;
-    }
-    
-    let result = unsafe { &*result };
-    result.batches.len() as c_int
+pub extern "C" fn datafusion_result_batch_count(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, 0, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.batches.len() as c_int)
+    })
+}
+
+/// Get the total number of rows in a result, across all batches
+/// Returns 0 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_total_rows(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> i64 {
+    ffi_value(error_out, 0, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.total_rows() as i64)
+    })
+}
+
+/// Get the approximate memory used by a result's Arrow buffers, in bytes
+/// Returns 0 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_memory_bytes(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> i64 {
+    ffi_value(error_out, 0, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.memory_bytes() as i64)
+    })
+}
+
+/// Get the batch count, row count and approximate memory size of a result
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_stats(
+    result: *const DataFusionResult,
+    out: *mut DataFusionResultStats,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        write_out(
+            out,
+            DataFusionResultStats {
+                batch_count: result.batches.len() as i64,
+                total_rows: result.total_rows() as i64,
+                memory_bytes: result.memory_bytes() as i64,
+            },
+        )
+    })
 }
 
 /// Get the number of rows in a specific batch
+/// Returns 0 on error
 #[no_mangle]
 pub extern "C" fn datafusion_result_batch_num_rows(
     result: *const DataFusionResult,
     batch_index: c_int,
+    error_out: *mut *mut c_char,
 ) -> c_int {
-    if result.is_null() || batch_index < 0 {
-        return 0;
-    }
-    
-    let result = unsafe { &*result };
-    let index = batch_index as usize;
-    
-    if index >= result.batches.len() {
-        return 0;
-    }
-    
-    result.batches[index].num_rows() as c_int
+    ffi_value(error_out, 0, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.batch(batch_index)?.num_rows() as c_int)
+    })
 }
 
 /// Get the number of columns in a specific batch
+/// Returns 0 on error
 #[no_mangle]
 pub extern "C" fn datafusion_result_batch_num_columns(
     result: *const DataFusionResult,
     batch_index: c_int,
+    error_out: *mut *mut c_char,
 ) -> c_int {
-    if result.is_null() || batch_index < 0 {
-        return 0;
-    }
-    
-    let result = unsafe { &*result };
-    let index = batch_index as usize;
-    
-    if index >= result.batches.len() {
-        return 0;
-    }
-    
-    result.batches[index].num_columns() as c_int
+    ffi_value(error_out, 0, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.batch(batch_index)?.num_columns() as c_int)
+    })
 }
 
 /// Print a result as a formatted table (for debugging)
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+/// Returns DATAFUSION_OK on success or an error code on failure
 #[no_mangle]
-pub extern "C" fn datafusion_result_print(result: *const DataFusionResult) -> c_int {
-    if result.is_null() {
-        return DATAFUSION_ERROR;
-    }
-    
-    let result = unsafe { &*result };
-    
-    match pretty::print_batches(&result.batches) {
-        Ok(_) => DATAFUSION_OK,
-        Err(_) => DATAFUSION_ERROR,
-    }
+pub extern "C" fn datafusion_result_print(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+
+        pretty::print_batches(&result.batches)?;
+        Ok(())
+    })
 }
 
-/// Free a DataFusion result
+/// Create a result holding rows `offset..offset + length` of another result, without copying data.
+/// The range is clamped to the rows available, so slicing past the end gives an empty result.
+/// Returns a pointer to the new result or null on error
 #[no_mangle]
-pub extern "C" fn datafusion_result_free(result: *mut DataFusionResult) {
-    if !result.is_null() {
-        unsafe {
-            let _ = Box::from_raw(result);
-        }
-    }
+pub extern "C" fn datafusion_result_slice(
+    result: *const DataFusionResult,
+    offset: i64,
+    length: i64,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let (Ok(offset), Ok(length)) = (usize::try_from(offset), usize::try_from(length)) else {
+            return Err(DataFusionError::invalid_argument(format!(
+                "invalid slice offset {offset} and length {length}"
+            )));
+        };
+
+        Ok(Box::new(DataFusionResult {
+            header: HandleHeader::default(),
+            batches: result.slice_batches(offset, length),
+            schema: result.schema.clone(),
+            plan: None,
+            partitions: None,
+        }))
+    })
 }
 
-/// Get last error message (simplified for this example)
+/// Create a result with the same rows as another, concatenated into batches of `target_rows` rows
+/// (the last batch may be smaller), or into a single batch if `target_rows` is 0
+/// Returns a pointer to the new result or null on error
 #[no_mangle]
-pub extern "C" fn datafusion_get_last_error() -> *const c_char {
-    // In a real implementation, you'd want to store error messages in thread-local storage
-    b"DataFusion error occurred\0".as_ptr() as *const c_char
+pub extern "C" fn datafusion_result_coalesce(
+    result: *const DataFusionResult,
+    target_rows: i64,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let target_rows = usize::try_from(target_rows)
+            .map_err(|_| DataFusionError::invalid_argument(format!("invalid target rows {target_rows}")))?;
+
+        let batches = if target_rows == 0 {
+            vec![concat_batches(&result.schema, &result.batches)?]
+        } else {
+            // Slices of the input batches making up the output batch being filled
+            let mut slices = Vec::new();
+            let mut slice_rows = 0;
+            let mut batches = Vec::new();
+            for batch in &result.batches {
+                let mut offset = 0;
+                while offset < batch.num_rows() {
+                    let rows = (batch.num_rows() - offset).min(target_rows - slice_rows);
+                    slices.push(batch.slice(offset, rows));
+                    offset += rows;
+                    slice_rows += rows;
+                    if slice_rows == target_rows {
+                        batches.push(concat_batches(&result.schema, &slices)?);
+                        slices.clear();
+                        slice_rows = 0;
+                    }
+                }
+            }
+            if slice_rows > 0 {
+                batches.push(concat_batches(&result.schema, &slices)?);
+            }
+            batches
+        };
+
+        Ok(Box::new(DataFusionResult {
+            header: HandleHeader::default(),
+            batches,
+            schema: result.schema.clone(),
+            plan: None,
+            partitions: None,
+        }))
+    })
 }
 
-// Iceberg-related functions
-
-/// Create a new SQL catalog for Iceberg
-/// Returns a pointer to the catalog or null on error
+/// Create a result with the rows at the given indices (counted across all batches), in that order.
+/// Indices may repeat. The rows are copied into a single batch.
+/// Returns a pointer to the new result or null on error
 #[no_mangle]
-pub extern "C" fn iceberg_catalog_new_sql(database_url: *const c_char, name: *const c_char) -> *mut IcebergCatalog {
-    if database_url.is_null() || name.is_null() {
-        return ptr::null_mut();
-    }
+pub extern "C" fn datafusion_result_take(
+    result: *const DataFusionResult,
+    indices: *const i64,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let indices = error::slice_arg(indices, count, "indices")?;
+        let total_rows = result.total_rows();
+        if let Some(index) = indices.iter().find(|&&index| usize::try_from(index).map_or(true, |i| i >= total_rows)) {
+            return Err(DataFusionError::invalid_argument(format!(
+                "row index {index} out of range for result with {total_rows} rows"
+            )));
+        }
 
-    let database_url = match unsafe { CStr::from_ptr(database_url) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
+        // Rows are gathered from the batches they are in, without copying the rest of the result
+        let batches: Vec<&RecordBatch> = result.batches.iter().filter(|batch| batch.num_rows() > 0).collect();
+        let starts: Vec<usize> = batches
+            .iter()
+            .scan(0, |start, batch| {
+                let batch_start = *start;
+                *start += batch.num_rows();
+                Some(batch_start)
+            })
+            .collect();
+        let rows: Vec<(usize, usize)> = indices
+            .iter()
+            .map(|&index| {
+                let index = index as usize;
+                let batch = starts.partition_point(|&start| start <= index) - 1;
+                (batch, index - starts[batch])
+            })
+            .collect();
+        let batch = if rows.is_empty() {
+            RecordBatch::new_empty(result.schema.clone())
+        } else {
+            interleave_record_batch(&batches, &rows)?
+        };
+        Ok(Box::new(DataFusionResult {
+            header: HandleHeader::default(),
+            batches: vec![batch],
+            schema: result.schema.clone(),
+            plan: None,
+            partitions: None,
+        }))
+    })
+}
 
-    let name = match unsafe { CStr::from_ptr(name) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
+/// Format a result as a table, showing at most `max_rows` rows (all rows if `max_rows` <= 0).
+/// A truncated table is followed by a line giving the number of rows shown.
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_format(
+    result: *const DataFusionResult,
+    max_rows: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let total_rows = result.total_rows();
+        let limit = usize::try_from(max_rows).ok().filter(|limit| *limit > 0).unwrap_or(total_rows);
+
+        let mut table = pretty::pretty_format_batches(&result.slice_batches(0, limit))?.to_string();
+        if limit < total_rows {
+            table.push_str(&format!("\n({limit} of {total_rows} rows shown)"));
+        }
+        Ok(table)
+    })
+}
 
-    let runtime = match tokio::runtime::Runtime::new() {
-        Ok(rt) => rt,
-        Err(_) => return ptr::null_mut(),
-    };
+/// Free a DataFusion result
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_result_free(result: *mut DataFusionResult) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(result, "result"))
+}
 
-    let catalog = match runtime.block_on(async {
-        let object_store = ObjectStoreBuilder::memory();
-        SqlCatalog::new(database_url, name, object_store).await
-    }) {
-        Ok(catalog) => Arc::new(catalog) as Arc<dyn Catalog>,
-        Err(_) => return ptr::null_mut(),
-    };
+/// Free a string allocated by the library, such as an `error_out` message
+#[no_mangle]
+pub extern "C" fn datafusion_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        let _ = error::catch_panic(|| {
+            drop(unsafe { CString::from_raw(s) });
+            Ok(())
+        });
+    }
+}
+
+// Iceberg-related functions
 
-    let iceberg_catalog = Box::new(IcebergCatalog { catalog, runtime });
-    Box::into_raw(iceberg_catalog)
+/// Create a new SQL catalog for Iceberg
+/// Returns a pointer to the catalog or null on error
+#[no_mangle]
+pub extern "C" fn iceberg_catalog_new_sql(
+    database_url: *const c_char,
+    name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut IcebergCatalog {
+    ffi_ptr(error_out, || {
+        let database_url = str_arg(database_url, "database_url")?;
+        let name = str_arg(name, "name")?;
+
+        let runtime = tokio::runtime::Runtime::new()?;
+
+        let catalog = runtime.block_on(async {
+            let object_store = ObjectStoreBuilder::memory();
+            SqlCatalog::new(database_url, name, object_store).await
+        })?;
+        let catalog = Arc::new(catalog) as Arc<dyn Catalog>;
+
+        Ok(Box::new(IcebergCatalog {
+            header: HandleHeader::default(),
+            catalog,
+            runtime,
+        }))
+    })
 }
 
-/// Free an Iceberg catalog  
+/// Free an Iceberg catalog
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
 #[no_mangle]
-pub extern "C" fn iceberg_catalog_free(catalog: *mut IcebergCatalog) {
-    if !catalog.is_null() {
-        unsafe {
-            let _ = Box::from_raw(catalog);
-        }
-    }
+pub extern "C" fn iceberg_catalog_free(catalog: *mut IcebergCatalog) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(catalog, "catalog"))
 }
 
 /// Create a new Iceberg schema builder
 /// Returns a pointer to the schema or null on error
 #[no_mangle]
-pub extern "C" fn iceberg_schema_new() -> *mut IcebergSchema {
-    let builder = Schema::builder();
-    let schema = Box::new(IcebergSchema { builder });
-    Box::into_raw(schema)
+pub extern "C" fn iceberg_schema_new(error_out: *mut *mut c_char) -> *mut IcebergSchema {
+    ffi_ptr(error_out, || {
+        let builder = Schema::builder();
+        Ok(Box::new(IcebergSchema {
+            header: HandleHeader::default(),
+            builder,
+        }))
+    })
 }
 
-/// Add a long field to the schema
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
-#[no_mangle]
-pub extern "C" fn iceberg_schema_add_long_field(
+fn add_schema_field(
     schema: *mut IcebergSchema,
     id: u32,
     name: *const c_char,
     required: bool,
-) -> bool {
-    if schema.is_null() || name.is_null() {
-        return false;
-    }
-
-    let schema = unsafe { &mut *schema };
-    let name = unsafe { CStr::from_ptr(name) };
-    let name = match name.to_str() {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
+    field_type: PrimitiveType,
+) -> error::FfiResult<()> {
+    let schema = handle_mut(schema, "schema")?;
+    let name = str_arg(name, "name")?;
 
     schema.builder.with_struct_field(StructField {
         id: id as i32,
         name: name.to_string(),
         required,
-        field_type: Type::Primitive(PrimitiveType::Long),
+        field_type: Type::Primitive(field_type),
         doc: None,
     });
 
-    true
+    Ok(())
+}
+
+/// Add a long field to the schema
+/// Returns true on success, false on failure
+#[no_mangle]
+pub extern "C" fn iceberg_schema_add_long_field(
+    schema: *mut IcebergSchema,
+    id: u32,
+    name: *const c_char,
+    required: bool,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_bool(error_out, || {
+        add_schema_field(schema, id, name, required, PrimitiveType::Long)
+    })
 }
 
 /// Add an int field to the schema
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+/// Returns true on success, false on failure
 #[no_mangle]
 pub extern "C" fn iceberg_schema_add_int_field(
     schema: *mut IcebergSchema,
     id: u32,
     name: *const c_char,
     required: bool,
+    error_out: *mut *mut c_char,
 ) -> bool {
-    if schema.is_null() || name.is_null() {
-        return false;
-    }
-
-    let schema = unsafe { &mut *schema };
-    let name = unsafe { CStr::from_ptr(name) };
-    let name = match name.to_str() {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
-
-    schema.builder.with_struct_field(StructField {
-        id: id as i32,
-        name: name.to_string(),
-        required,
-        field_type: Type::Primitive(PrimitiveType::Int),
-        doc: None,
-    });
-
-    true
+    ffi_bool(error_out, || {
+        add_schema_field(schema, id, name, required, PrimitiveType::Int)
+    })
 }
 
 /// Add a date field to the schema
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+/// Returns true on success, false on failure
 #[no_mangle]
 pub extern "C" fn iceberg_schema_add_date_field(
     schema: *mut IcebergSchema,
     id: u32,
     name: *const c_char,
     required: bool,
+    error_out: *mut *mut c_char,
 ) -> bool {
-    if schema.is_null() || name.is_null() {
-        return false;
-    }
-
-    let schema = unsafe { &mut *schema };
-    let name = unsafe { CStr::from_ptr(name) };
-    let name = match name.to_str() {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
-
-    schema.builder.with_struct_field(StructField {
-        id: id as i32,
-        name: name.to_string(),
-        required,
-        field_type: Type::Primitive(PrimitiveType::Date),
-        doc: None,
-    });
-
-    true
+    ffi_bool(error_out, || {
+        add_schema_field(schema, id, name, required, PrimitiveType::Date)
+    })
 }
 
 /// Free an Iceberg schema
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
 #[no_mangle]
-pub extern "C" fn iceberg_schema_free(schema: *mut IcebergSchema) {
-    if !schema.is_null() {
-        unsafe {
-            let _ = Box::from_raw(schema);
-        }
-    }
+pub extern "C" fn iceberg_schema_free(schema: *mut IcebergSchema) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(schema, "schema"))
 }
 
 /// Create a new Iceberg partition spec
 /// Returns a pointer to the partition spec or null on error
 #[no_mangle]
-pub extern "C" fn iceberg_partition_spec_new() -> *mut IcebergPartitionSpec {
-    let builder = PartitionSpec::builder();
-    let partition_spec = Box::new(IcebergPartitionSpec { builder });
-    Box::into_raw(partition_spec)
+pub extern "C" fn iceberg_partition_spec_new(error_out: *mut *mut c_char) -> *mut IcebergPartitionSpec {
+    ffi_ptr(error_out, || {
+        let builder = PartitionSpec::builder();
+        Ok(Box::new(IcebergPartitionSpec {
+            header: HandleHeader::default(),
+            builder,
+        }))
+    })
 }
 
 /// Add a day partition field to the partition spec
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+/// Returns true on success, false on failure
 #[no_mangle]
 pub extern "C" fn iceberg_partition_spec_add_day_field(
     spec: *mut IcebergPartitionSpec,
     source_id: u32,
     field_id: u32,
     name: *const c_char,
+    error_out: *mut *mut c_char,
 ) -> bool {
-    if spec.is_null() || name.is_null() {
-        return false;
-    }
-
-    let spec = unsafe { &mut *spec };
-    let name = unsafe { CStr::from_ptr(name) };
-    let name = match name.to_str() {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
-
-    spec.builder.with_partition_field(PartitionField::new(
-        source_id as i32,
-        field_id as i32,
-        name,
-        Transform::Day,
-    ));
-
-    true
+    ffi_bool(error_out, || {
+        let spec = handle_mut(spec, "spec")?;
+        let name = str_arg(name, "name")?;
+
+        spec.builder.with_partition_field(PartitionField::new(
+            source_id as i32,
+            field_id as i32,
+            name,
+            Transform::Day,
+        ));
+
+        Ok(())
+    })
 }
 
 /// Free an Iceberg partition spec
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
 #[no_mangle]
-pub extern "C" fn iceberg_partition_spec_free(spec: *mut IcebergPartitionSpec) {
-    if !spec.is_null() {
-        unsafe {
-            let _ = Box::from_raw(spec);
-        }
-    }
+pub extern "C" fn iceberg_partition_spec_free(spec: *mut IcebergPartitionSpec) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(spec, "spec"))
 }
 
 /// Create a new Iceberg table
+/// The schema and partition spec remain owned by the caller and must still be freed
 /// Returns a pointer to the table or null on error
 #[no_mangle]
 pub extern "C" fn iceberg_table_create(
@@ -440,95 +1053,60 @@ pub extern "C" fn iceberg_table_create(
     partition_spec: *mut IcebergPartitionSpec,
     catalog: *mut IcebergCatalog,
     namespace_name: *const c_char,
+    error_out: *mut *mut c_char,
 ) -> *mut IcebergTable {
-    if name.is_null() || location.is_null() || schema.is_null() || 
-       partition_spec.is_null() || catalog.is_null() || namespace_name.is_null() {
-        return ptr::null_mut();
-    }
-
-    let name = match unsafe { CStr::from_ptr(name) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
-
-    let location = match unsafe { CStr::from_ptr(location) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
-
-    let namespace_name = match unsafe { CStr::from_ptr(namespace_name) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
-
-    // Take ownership of the builders (this consumes them)
-    let mut schema_box = unsafe { Box::from_raw(schema) };
-    let partition_spec_box = unsafe { Box::from_raw(partition_spec) };
-    let catalog = unsafe { &mut *catalog };
-
-    // Build the schema and partition spec (this consumes the builders)
-    let built_schema = match schema_box.builder.build() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
-
-    let built_partition_spec = match partition_spec_box.builder.build() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
-
-    // Note: schema_box and partition_spec_box are now consumed and should not be freed by Julia finalizers
-    // This fixes the double-free issue
-
-    let table = match catalog.runtime.block_on(async {
-        Table::builder()
-            .with_name(name)
-            .with_location(location)
-            .with_schema(built_schema)
-            .with_partition_spec(built_partition_spec)
-            .build(&[namespace_name.to_owned()], catalog.catalog.clone())
-            .await
-    }) {
-        Ok(table) => Arc::new(DataFusionTable::from(table)),
-        Err(_) => return ptr::null_mut(),
-    };
-
-    let iceberg_table = Box::new(IcebergTable { table });
-    Box::into_raw(iceberg_table)
+    ffi_ptr(error_out, || {
+        let name = str_arg(name, "name")?;
+        let location = str_arg(location, "location")?;
+        let namespace_name = str_arg(namespace_name, "namespace_name")?;
+        let schema = handle_mut(schema, "schema")?;
+        let partition_spec = handle_mut(partition_spec, "partition_spec")?;
+        let catalog = handle_mut(catalog, "catalog")?;
+
+        // Build from the builders in place; the handles stay valid so that finalizers
+        // on the host side can free them without racing against table creation
+        let built_schema = schema.builder.build()?;
+        let built_partition_spec = partition_spec.builder.build()?;
+
+        let table = catalog.runtime.block_on(async {
+            Table::builder()
+                .with_name(name)
+                .with_location(location)
+                .with_schema(built_schema)
+                .with_partition_spec(built_partition_spec)
+                .build(&[namespace_name.to_owned()], catalog.catalog.clone())
+                .await
+        })?;
+
+        Ok(Box::new(IcebergTable {
+            header: HandleHeader::default(),
+            table: Arc::new(DataFusionTable::from(table)),
+        }))
+    })
 }
 
 /// Free an Iceberg table
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
 #[no_mangle]
-pub extern "C" fn iceberg_table_free(table: *mut IcebergTable) {
-    if !table.is_null() {
-        unsafe {
-            let _ = Box::from_raw(table);
-        }
-    }
+pub extern "C" fn iceberg_table_free(table: *mut IcebergTable) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(table, "table"))
 }
 
 /// Register an Iceberg table with the DataFusion context
-/// Returns DATAFUSION_OK on success, DATAFUSION_ERROR on failure
+/// Returns DATAFUSION_OK on success or an error code on failure
 #[no_mangle]
 pub extern "C" fn datafusion_register_iceberg_table(
-    ctx: *mut DataFusionContext,
+    ctx: *const DataFusionContext,
     table_name: *const c_char,
     table: *mut IcebergTable,
+    error_out: *mut *mut c_char,
 ) -> c_int {
-    if ctx.is_null() || table_name.is_null() || table.is_null() {
-        return DATAFUSION_ERROR;
-    }
-
-    let ctx = unsafe { &mut *ctx };
-    let table = unsafe { &*table };
-    
-    let table_name = match unsafe { CStr::from_ptr(table_name) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return DATAFUSION_ERROR,
-    };
-
-    match ctx.ctx.register_table(table_name, table.table.clone()) {
-        Ok(_) => DATAFUSION_OK,
-        Err(_) => DATAFUSION_ERROR,
-    }
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = register::table_name_arg(ctx, table_name)?;
+        let table = handle_ref(table, "table")?;
+
+        ctx.ctx.register_table(table_name, table.table.clone())?;
+        Ok(())
+    })
 }
@@ -0,0 +1,262 @@
+use std::cell::RefCell;
+use std::ffi::{c_void, CString};
+use std::fmt::Write as _;
+use std::os::raw::{c_char, c_int};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+use crate::error::{ffi_code, str_arg, DataFusionError, FfiResult};
+use crate::handle::handle_mut;
+use crate::{DataFusionContext, DATAFUSION_ERROR};
+
+// Log levels, ordered from least to most verbose
+pub const DATAFUSION_LOG_OFF: c_int = 0;
+pub const DATAFUSION_LOG_ERROR: c_int = 1;
+pub const DATAFUSION_LOG_WARN: c_int = 2;
+pub const DATAFUSION_LOG_INFO: c_int = 3;
+pub const DATAFUSION_LOG_DEBUG: c_int = 4;
+pub const DATAFUSION_LOG_TRACE: c_int = 5;
+
+/// Callback receiving log records: level, target (e.g. `datafusion_optimizer`), message and user data.
+/// The strings are only valid for the duration of the call. The callback may be invoked
+/// concurrently from any thread that runs queries, including tokio worker threads.
+pub type DataFusionLogCallback = Option<
+    extern "C" fn(level: c_int, target: *const c_char, message: *const c_char, user_data: *mut c_void),
+>;
+
+#[derive(Clone, Copy)]
+struct LogSink {
+    callback: extern "C" fn(c_int, *const c_char, *const c_char, *mut c_void),
+    user_data: *mut c_void,
+    level: c_int,
+}
+
+// The host is responsible for making user_data safe to use from any thread
+unsafe impl Send for LogSink {}
+unsafe impl Sync for LogSink {}
+
+static LOG_SINK: RwLock<Option<LogSink>> = RwLock::new(None);
+/// Outcome of installing the subscriber, which can only be attempted once per process
+static INSTALL: OnceLock<Result<(), String>> = OnceLock::new();
+
+fn level_to_c(level: &Level) -> c_int {
+    match *level {
+        Level::ERROR => DATAFUSION_LOG_ERROR,
+        Level::WARN => DATAFUSION_LOG_WARN,
+        Level::INFO => DATAFUSION_LOG_INFO,
+        Level::DEBUG => DATAFUSION_LOG_DEBUG,
+        Level::TRACE => DATAFUSION_LOG_TRACE,
+    }
+}
+
+fn level_filter(level: c_int) -> FfiResult<LevelFilter> {
+    match level {
+        DATAFUSION_LOG_OFF => Ok(LevelFilter::OFF),
+        DATAFUSION_LOG_ERROR => Ok(LevelFilter::ERROR),
+        DATAFUSION_LOG_WARN => Ok(LevelFilter::WARN),
+        DATAFUSION_LOG_INFO => Ok(LevelFilter::INFO),
+        DATAFUSION_LOG_DEBUG => Ok(LevelFilter::DEBUG),
+        DATAFUSION_LOG_TRACE => Ok(LevelFilter::TRACE),
+        _ => Err(DataFusionError::invalid_argument(format!("invalid log level {level}"))),
+    }
+}
+
+/// Per-context override of the callback's log level, shared with the context's runtime threads
+pub(crate) type ContextLogFilter = Arc<RwLock<Option<Targets>>>;
+
+thread_local! {
+    static CONTEXT_FILTER: RefCell<Option<ContextLogFilter>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously active context filter of the thread when dropped
+pub(crate) struct ContextFilterGuard {
+    previous: Option<ContextLogFilter>,
+}
+
+impl Drop for ContextFilterGuard {
+    fn drop(&mut self) {
+        CONTEXT_FILTER.with(|current| *current.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Apply `filter` to events emitted on the calling thread until the guard is dropped
+pub(crate) fn enter_context_filter(filter: &ContextLogFilter) -> ContextFilterGuard {
+    let previous = CONTEXT_FILTER.with(|current| current.borrow_mut().replace(filter.clone()));
+    ContextFilterGuard { previous }
+}
+
+/// Build a context runtime whose worker threads apply the context's log filter
+pub(crate) fn runtime_with_filter(filter: ContextLogFilter) -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .on_thread_start(move || {
+            CONTEXT_FILTER.with(|current| *current.borrow_mut() = Some(filter.clone()));
+        })
+        .build()
+}
+
+fn is_enabled(sink: &LogSink, metadata: &Metadata<'_>) -> bool {
+    let context_filter = CONTEXT_FILTER.with(|current| current.borrow().clone());
+    let context_filter = context_filter
+        .as_ref()
+        .map(|filter| filter.read().unwrap_or_else(|e| e.into_inner()));
+    match context_filter.as_ref().and_then(|filter| filter.as_ref()) {
+        Some(targets) => targets.would_enable(metadata.target(), metadata.level()),
+        None => level_to_c(metadata.level()) <= sink.level,
+    }
+}
+
+/// Collects the message and any structured fields of an event into one line
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => {
+                let _ = write!(self.message, "{value:?}");
+            }
+            // Metadata attached by the `log` bridge is already reflected in the normalized metadata
+            name if name.starts_with("log.") => {}
+            name => {
+                let _ = write!(self.fields, " {name}={value:?}");
+            }
+        }
+    }
+}
+
+/// Layer forwarding every enabled event to the installed C callback
+struct CallbackLayer;
+
+impl<S: Subscriber> Layer<S> for CallbackLayer {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // Whether a callsite is enabled depends on the thread's context, so it cannot be cached
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        let sink = LOG_SINK.read().unwrap_or_else(|e| e.into_inner());
+        sink.as_ref().is_some_and(|sink| is_enabled(sink, metadata))
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+
+        // Copied out so the callback runs without the lock, and may replace itself or log
+        let Some(sink) = *LOG_SINK.read().unwrap_or_else(|e| e.into_inner()) else {
+            return;
+        };
+        if !is_enabled(&sink, metadata) {
+            return;
+        }
+        let level = level_to_c(metadata.level());
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        visitor.message.push_str(&visitor.fields);
+
+        let target = CString::new(metadata.target().replace('\0', " ")).unwrap_or_default();
+        let message = CString::new(visitor.message.replace('\0', " ")).unwrap_or_default();
+        (sink.callback)(level, target.as_ptr(), message.as_ptr(), sink.user_data);
+    }
+}
+
+fn install_subscriber() -> FfiResult<()> {
+    let result = INSTALL.get_or_init(|| {
+        let subscriber = tracing_subscriber::registry().with(CallbackLayer);
+        tracing::subscriber::set_global_default(subscriber).map_err(|err| err.to_string())?;
+        // Forward records from crates using the `log` facade (such as DataFusion) as tracing events
+        tracing_log::LogTracer::init().map_err(|err| err.to_string())
+    });
+    result
+        .clone()
+        .map_err(|message| DataFusionError::new(DATAFUSION_ERROR, format!("cannot install the log subscriber: {message}")))
+}
+
+/// Install a callback receiving DataFusion, object store and Iceberg log records up to `level`
+/// (one of the DATAFUSION_LOG_* constants). Passing a null callback stops forwarding.
+/// Calling this again replaces the previous callback. Contexts with their own log level or
+/// filter override `level` for the work they run.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_set_log_callback(
+    level: c_int,
+    callback: DataFusionLogCallback,
+    user_data: *mut c_void,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        level_filter(level)?;
+        install_subscriber()?;
+
+        let mut sink = LOG_SINK.write().unwrap_or_else(|e| e.into_inner());
+        *sink = callback.map(|callback| LogSink {
+            callback,
+            user_data,
+            level,
+        });
+        // Contexts may be more verbose than the callback level, so the layer does the filtering
+        log::set_max_level(match sink.as_ref() {
+            Some(_) => log::LevelFilter::Trace,
+            None => log::LevelFilter::Off,
+        });
+        Ok(())
+    })
+}
+
+fn set_context_filter(ctx: *mut DataFusionContext, targets: Option<Targets>) -> FfiResult<()> {
+    let ctx = handle_mut(ctx, "ctx")?;
+    *ctx.log_filter.write().unwrap_or_else(|e| e.into_inner()) = targets;
+    Ok(())
+}
+
+/// Set the log level for work run by this context, overriding the level of the log callback
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_context_set_log_level(
+    ctx: *mut DataFusionContext,
+    level: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let level = level_filter(level)?;
+        set_context_filter(ctx, Some(Targets::new().with_default(level)))
+    })
+}
+
+/// Set an env-filter style log filter for work run by this context, overriding the level of the
+/// log callback, e.g. "warn,datafusion::physical_plan=debug,iceberg_rust=trace".
+/// Targets without a matching directive are not logged unless a bare default level is given.
+/// Passing null removes the override so the context follows the callback level again.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_context_set_log_filter(
+    ctx: *mut DataFusionContext,
+    filter: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let targets = if filter.is_null() {
+            None
+        } else {
+            let filter = str_arg(filter, "filter")?;
+            let targets = filter.parse::<Targets>().map_err(|err| {
+                DataFusionError::invalid_argument(format!("invalid log filter '{filter}': {err}"))
+            })?;
+            Some(targets)
+        };
+        set_context_filter(ctx, targets)
+    })
+}
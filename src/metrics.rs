@@ -0,0 +1,92 @@
+use std::fmt::Write;
+use std::os::raw::c_char;
+use std::sync::Arc;
+
+use datafusion::physical_plan::metrics::MetricsSet;
+use datafusion::physical_plan::{displayable, ExecutionPlan};
+use serde_json::{json, Map};
+
+use crate::cancel::unwrap_cancellable;
+use crate::error::ffi_string;
+use crate::handle::handle_ref;
+use crate::DataFusionResult;
+
+/// Visit the operators of a plan depth first with their depth, skipping cancellation wrappers
+fn visit_operators(plan: &Arc<dyn ExecutionPlan>, depth: usize, visit: &mut impl FnMut(&Arc<dyn ExecutionPlan>, usize)) {
+    let plan = unwrap_cancellable(plan);
+    visit(plan, depth);
+    for child in plan.children() {
+        visit_operators(child, depth + 1, visit);
+    }
+}
+
+/// The progress of an executing plan: the rows produced by the operators at its leaves and the
+/// bytes scanned from storage by all operators, as far as they report them
+pub(crate) fn scan_progress(plan: &Arc<dyn ExecutionPlan>) -> (usize, usize) {
+    let (mut rows, mut bytes) = (0, 0);
+    visit_operators(plan, 0, &mut |plan, _| {
+        let Some(metrics) = plan.metrics() else {
+            return;
+        };
+        if plan.children().is_empty() {
+            rows += metrics.output_rows().unwrap_or_default();
+        }
+        bytes += metrics.sum_by_name("bytes_scanned").map_or(0, |value| value.as_usize());
+    });
+    (rows, bytes)
+}
+
+/// The metrics of an operator aggregated across partitions, in display order
+fn aggregated_metrics(plan: &Arc<dyn ExecutionPlan>) -> Option<MetricsSet> {
+    plan.metrics()
+        .map(|set| set.aggregate_by_name().sorted_for_display().timestamps_removed())
+}
+
+/// Render the operator metrics of an executed plan as JSON
+pub(crate) fn metrics_json(plan: Option<&Arc<dyn ExecutionPlan>>) -> String {
+    let mut operators = Vec::new();
+    if let Some(plan) = plan {
+        visit_operators(plan, 0, &mut |plan, depth| {
+            let mut metrics = Map::new();
+            for metric in aggregated_metrics(plan).iter().flat_map(|set| set.iter()) {
+                let value = metric.value();
+                metrics.insert(value.name().to_string(), json!(value.as_usize()));
+            }
+            operators.push(json!({
+                "operator": plan.name(),
+                "depth": depth,
+                "partitions": plan.properties().output_partitioning().partition_count(),
+                "metrics": metrics,
+            }));
+        });
+    }
+    json!({ "operators": operators }).to_string()
+}
+
+/// Render an executed plan like EXPLAIN ANALYZE: one operator per line, indented by depth, followed
+/// by its metrics
+pub(crate) fn metrics_text(plan: &Arc<dyn ExecutionPlan>) -> String {
+    let mut text = String::new();
+    visit_operators(plan, 0, &mut |plan, depth| {
+        let operator = displayable(plan.as_ref()).one_line().to_string();
+        let metrics = aggregated_metrics(plan).map(|set| set.to_string()).unwrap_or_default();
+        let _ = writeln!(text, "{:indent$}{}, metrics=[{metrics}]", "", operator.trim_end(), indent = depth * 2);
+    });
+    text
+}
+
+/// Get operator-level execution metrics of the query that produced a result as JSON:
+/// `{"operators": [{"operator", "depth", "partitions", "metrics": {name: value}}]}` in plan order.
+/// Metric names follow DataFusion, e.g. `output_rows`, `elapsed_compute` (nanoseconds),
+/// `spill_count`, `spilled_bytes` and `bytes_scanned`. Results not produced by a query have no operators.
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_metrics_json(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let result = handle_ref(result, "result")?;
+        Ok(metrics_json(result.plan.as_ref()))
+    })
+}
@@ -0,0 +1,299 @@
+use std::os::raw::{c_char, c_int};
+
+use datafusion::arrow::array::{Array, AsArray, BooleanArray, Float64Array, Int32Array, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+
+use crate::error::{ffi_code, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::values::{write_out, Cell};
+use crate::{DataFusionResult, DATAFUSION_NULL_VALUE, DATAFUSION_TYPE_MISMATCH};
+
+fn reject_null(cell: &Cell<'_>) -> FfiResult<()> {
+    if cell.is_null() {
+        return Err(DataFusionError::new(
+            DATAFUSION_NULL_VALUE,
+            format!("value at row {} is null", cell.row),
+        ));
+    }
+    Ok(())
+}
+
+/// Offset into the child values and number of elements of a List, LargeList, FixedSizeList or Map cell
+fn list_range(cell: &Cell<'_>) -> FfiResult<(usize, usize)> {
+    let row = cell.row;
+    let range = match cell.array.data_type() {
+        DataType::List(_) => {
+            let offsets = cell.array.as_list::<i32>().value_offsets();
+            (offsets[row] as usize, (offsets[row + 1] - offsets[row]) as usize)
+        }
+        DataType::LargeList(_) => {
+            let offsets = cell.array.as_list::<i64>().value_offsets();
+            (offsets[row] as usize, (offsets[row + 1] - offsets[row]) as usize)
+        }
+        DataType::FixedSizeList(_, size) => {
+            let list = cell.array.as_fixed_size_list();
+            (list.value_offset(row) as usize, *size as usize)
+        }
+        DataType::Map(_, _) => {
+            let offsets = cell.array.as_map().value_offsets();
+            (offsets[row] as usize, (offsets[row + 1] - offsets[row]) as usize)
+        }
+        other => {
+            return Err(DataFusionError::new(
+                DATAFUSION_TYPE_MISMATCH,
+                format!("column has type {other}, not a list or map"),
+            ))
+        }
+    };
+    reject_null(cell)?;
+    Ok(range)
+}
+
+/// Number of fields of a struct cell, or of elements of a list or map cell
+fn child_count(cell: &Cell<'_>) -> FfiResult<usize> {
+    if let DataType::Struct(fields) = cell.array.data_type() {
+        reject_null(cell)?;
+        return Ok(fields.len());
+    }
+    Ok(list_range(cell)?.1)
+}
+
+/// The field of a struct cell with the given name
+fn struct_child<'a>(cell: &Cell<'a>, name: &str) -> FfiResult<Cell<'a>> {
+    let DataType::Struct(_) = cell.array.data_type() else {
+        return Err(DataFusionError::new(
+            DATAFUSION_TYPE_MISMATCH,
+            format!("cannot select field '{name}' of a value of type {}", cell.array.data_type()),
+        ));
+    };
+    let child = cell
+        .array
+        .as_struct()
+        .column_by_name(name)
+        .ok_or_else(|| DataFusionError::invalid_argument(format!("struct has no field named '{name}'")))?;
+    Ok(Cell::new(child.as_ref(), cell.row))
+}
+
+/// The element at `index` of a list cell, or the entry (a struct of key and value) of a map cell
+fn list_child<'a>(cell: &Cell<'a>, index: usize) -> FfiResult<Cell<'a>> {
+    let (offset, length) = list_range(cell)?;
+    if index >= length {
+        return Err(DataFusionError::invalid_argument(format!(
+            "index {index} out of range for list with {length} elements"
+        )));
+    }
+    let values: &dyn Array = match cell.array.data_type() {
+        DataType::List(_) => cell.array.as_list::<i32>().values().as_ref(),
+        DataType::LargeList(_) => cell.array.as_list::<i64>().values().as_ref(),
+        DataType::FixedSizeList(_, _) => cell.array.as_fixed_size_list().values().as_ref(),
+        _ => cell.array.as_map().entries(),
+    };
+    Ok(Cell::new(values, offset + index))
+}
+
+impl DataFusionResult {
+    /// Resolve a path such as `a.b[2].c` to a nested cell: a column name followed by `.field`
+    /// selectors for struct fields and `[index]` selectors for list elements and map entries.
+    /// Returns None if any value along the path, including the final one, is null.
+    fn path_cell(&self, batch_index: c_int, row_index: c_int, path: *const c_char) -> FfiResult<Option<Cell<'_>>> {
+        let path = str_arg(path, "path")?;
+        let invalid = || DataFusionError::invalid_argument(format!("invalid path '{path}'"));
+
+        let column_end = path.find(['.', '[']).unwrap_or(path.len());
+        let name = &path[..column_end];
+        let column = self
+            .schema
+            .index_of(name)
+            .map_err(|_| DataFusionError::invalid_argument(format!("result has no column named '{name}'")))?;
+        let mut cell = self.cell(batch_index, row_index, column as c_int)?;
+
+        let mut rest = &path[column_end..];
+        while !rest.is_empty() {
+            if cell.is_null() {
+                return Ok(None);
+            }
+            if let Some(selector) = rest.strip_prefix('.') {
+                let end = selector.find(['.', '[']).unwrap_or(selector.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                cell = struct_child(&cell, &selector[..end])?;
+                rest = &selector[end..];
+            } else if let Some(selector) = rest.strip_prefix('[') {
+                let end = selector.find(']').ok_or_else(invalid)?;
+                let index = selector[..end].parse::<usize>().map_err(|_| invalid())?;
+                cell = list_child(&cell, index)?;
+                rest = &selector[end + 1..];
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok((!cell.is_null()).then_some(cell))
+    }
+
+    /// Resolve a path to a non-null cell
+    fn path_value(&self, batch_index: c_int, row_index: c_int, path: *const c_char) -> FfiResult<Cell<'_>> {
+        self.path_cell(batch_index, row_index, path)?
+            .ok_or_else(|| DataFusionError::new(DATAFUSION_NULL_VALUE, "value at path is null"))
+    }
+}
+
+/// Get the number of fields of a struct cell, or the number of elements of a list or map cell
+/// Returns -1 on error, including when the value is null
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_child_count(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> i64 {
+    ffi_value(error_out, -1, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        Ok(child_count(&cell)? as i64)
+    })
+}
+
+/// Get the range of a List, LargeList, FixedSizeList or Map cell within the column's child values:
+/// `offset` is set to the index of the first element and `length` to the number of elements
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not a list or map, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_list_range(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    offset: *mut i64,
+    length: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        let (start, len) = list_range(&cell)?;
+        write_out(offset, start as i64)?;
+        write_out(length, len as i64)
+    })
+}
+
+/// Check whether a nested value is null. `path` is a column name followed by `.field` selectors
+/// for struct fields and `[index]` selectors for list elements and map entries, e.g. "a.b[2].c";
+/// map entries are structs with "key" and "value" fields. A value is null if any value on its path is.
+/// Returns false on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_is_null_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_value(error_out, false, || {
+        let cell = handle_ref(result, "result")?.path_cell(batch_index, row_index, path)?;
+        Ok(cell.is_none())
+    })
+}
+
+/// Get the number of fields of a nested struct, or of elements of a nested list or map
+/// Returns -1 on error, including when the value is null
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_child_count_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    error_out: *mut *mut c_char,
+) -> i64 {
+    ffi_value(error_out, -1, || {
+        let cell = handle_ref(result, "result")?.path_value(batch_index, row_index, path)?;
+        Ok(child_count(&cell)? as i64)
+    })
+}
+
+/// Get a nested Int64 value
+/// Returns the same codes as datafusion_result_get_int64
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_int64_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    out: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.path_value(batch_index, row_index, path)?;
+        write_out(out, cell.value_array::<Int64Array>("Int64")?.value(cell.row))
+    })
+}
+
+/// Get a nested Int32 value
+/// Returns the same codes as datafusion_result_get_int32
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_int32_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    out: *mut i32,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.path_value(batch_index, row_index, path)?;
+        write_out(out, cell.value_array::<Int32Array>("Int32")?.value(cell.row))
+    })
+}
+
+/// Get a nested Float64 value
+/// Returns the same codes as datafusion_result_get_float64
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_float64_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    out: *mut f64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.path_value(batch_index, row_index, path)?;
+        write_out(out, cell.value_array::<Float64Array>("Float64")?.value(cell.row))
+    })
+}
+
+/// Get a nested Boolean value
+/// Returns the same codes as datafusion_result_get_bool
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_bool_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    out: *mut bool,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.path_value(batch_index, row_index, path)?;
+        write_out(out, cell.value_array::<BooleanArray>("Boolean")?.value(cell.row))
+    })
+}
+
+/// Get a nested string value without copying
+/// Returns the same codes as datafusion_result_get_string
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_string_by_path(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    path: *const c_char,
+    data: *mut *const c_char,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.path_value(batch_index, row_index, path)?;
+        let value = cell.str_value()?;
+        write_out(data, value.as_ptr() as *const c_char)?;
+        write_out(len, value.len())
+    })
+}
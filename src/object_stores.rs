@@ -0,0 +1,123 @@
+use std::os::raw::{c_char, c_int};
+use std::sync::Arc;
+
+use datafusion::common::Result;
+use datafusion::execution::object_store::{DefaultObjectStoreRegistry, ObjectStoreRegistry};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::http::HttpBuilder;
+use object_store::{ClientConfigKey, ObjectStore};
+use url::Url;
+
+use crate::error::{ffi_code, str_arg, str_array_arg, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::DataFusionContext;
+
+/// A builder of the object store for the scheme and authority of a URL
+enum StoreBuilder {
+    S3(AmazonS3Builder),
+    Gcs(GoogleCloudStorageBuilder),
+    Azure(MicrosoftAzureBuilder),
+    Http(HttpBuilder),
+}
+
+impl StoreBuilder {
+    /// A builder configured from the environment variables of its cloud, such as AWS_REGION or
+    /// GOOGLE_SERVICE_ACCOUNT, or None if the scheme is not of a supported store
+    fn from_env(url: &Url) -> Option<Self> {
+        Some(match url.scheme() {
+            "s3" | "s3a" => Self::S3(AmazonS3Builder::from_env().with_url(url.as_str())),
+            "gs" => Self::Gcs(GoogleCloudStorageBuilder::from_env().with_url(url.as_str())),
+            "az" | "azure" | "abfs" | "abfss" | "adl" => {
+                Self::Azure(MicrosoftAzureBuilder::from_env().with_url(url.as_str()))
+            }
+            "http" | "https" => {
+                let base = &url[..url::Position::AfterPort];
+                let builder = HttpBuilder::new().with_url(base);
+                Self::Http(builder.with_config(ClientConfigKey::AllowHttp, (url.scheme() == "http").to_string()))
+            }
+            _ => return None,
+        })
+    }
+
+    /// Set an option named as in the object_store crate, such as "aws_access_key_id"
+    fn with_option(self, key: &str, value: &str) -> FfiResult<Self> {
+        let unknown = |_| DataFusionError::invalid_argument(format!("unknown object store option {key}"));
+        Ok(match self {
+            Self::S3(builder) => Self::S3(builder.with_config(key.parse().map_err(unknown)?, value)),
+            Self::Gcs(builder) => Self::Gcs(builder.with_config(key.parse().map_err(unknown)?, value)),
+            Self::Azure(builder) => Self::Azure(builder.with_config(key.parse().map_err(unknown)?, value)),
+            Self::Http(builder) => Self::Http(builder.with_config(key.parse().map_err(unknown)?, value)),
+        })
+    }
+
+    fn build(self) -> object_store::Result<Arc<dyn ObjectStore>> {
+        Ok(match self {
+            Self::S3(builder) => Arc::new(builder.build()?),
+            Self::Gcs(builder) => Arc::new(builder.build()?),
+            Self::Azure(builder) => Arc::new(builder.build()?),
+            Self::Http(builder) => Arc::new(builder.build()?),
+        })
+    }
+}
+
+/// The object stores of a context. URLs of S3, Google Cloud Storage, Azure and HTTP without a
+/// registered store get one on first use, configured from the environment.
+#[derive(Debug, Default)]
+pub(crate) struct ObjectStores(DefaultObjectStoreRegistry);
+
+impl ObjectStoreRegistry for ObjectStores {
+    fn register_store(&self, url: &Url, store: Arc<dyn ObjectStore>) -> Option<Arc<dyn ObjectStore>> {
+        self.0.register_store(url, store)
+    }
+
+    // DataFusion's error type grows past clippy's limit when the avro feature adds its Avro variant
+    #[allow(clippy::result_large_err)]
+    fn get_store(&self, url: &Url) -> Result<Arc<dyn ObjectStore>> {
+        self.0.get_store(url).or_else(|err| {
+            let Some(builder) = StoreBuilder::from_env(url) else {
+                return Err(err);
+            };
+            let store = builder.build()?;
+            self.0.register_store(url, store.clone());
+            Ok(store)
+        })
+    }
+}
+
+/// Register the object store of the tables and files at the URLs with the scheme and authority of
+/// `url`, such as "s3://bucket", configured with `count` options named as in the object_store crate,
+/// such as "aws_access_key_id", "aws_region", "aws_endpoint", "google_service_account" or
+/// "azure_storage_account_key". Options not given are read from the environment variables of the
+/// store's cloud, such as AWS_SECRET_ACCESS_KEY, as they are for stores created on first use of an
+/// URL that has none registered. Supported schemes are s3 and s3a, gs, az, azure, abfs, abfss and
+/// adl, and http and https. A store registered for the URL before is replaced.
+/// Returns DATAFUSION_OK on success, DATAFUSION_INVALID_ARGUMENT for an unsupported scheme or an
+/// unknown option, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_object_store(
+    ctx: *const DataFusionContext,
+    url: *const c_char,
+    keys: *const *const c_char,
+    values: *const *const c_char,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let url = str_arg(url, "url")?;
+        let keys = str_array_arg(keys, count, "keys")?;
+        let values = str_array_arg(values, count, "values")?;
+
+        let url = Url::parse(url).map_err(|err| DataFusionError::invalid_argument(format!("invalid url {url}: {err}")))?;
+        let mut builder = StoreBuilder::from_env(&url).ok_or_else(|| {
+            DataFusionError::invalid_argument(format!("unsupported object store scheme {}", url.scheme()))
+        })?;
+        for (key, value) in keys.into_iter().zip(values) {
+            builder = builder.with_option(key, value)?;
+        }
+        ctx.ctx.register_object_store(&url, builder.build()?);
+        Ok(())
+    })
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::os::raw::{c_char, c_int};
+
+use datafusion::common::{ParamValues, ScalarValue};
+
+use crate::error::{ffi_ptr, slice_arg, str_arg, str_array_arg, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::schema::{
+    DATAFUSION_TYPE_BOOLEAN, DATAFUSION_TYPE_FLOAT64, DATAFUSION_TYPE_INT64, DATAFUSION_TYPE_NULL, DATAFUSION_TYPE_UTF8,
+};
+use crate::{DataFusionContext, DataFusionResult};
+
+/// The value of a DataFusionScalar, in the field selected by its type
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union DataFusionScalarValue {
+    pub boolean: bool,
+    pub int64: i64,
+    pub float64: f64,
+    /// Null terminated UTF-8, copied when the scalar is used
+    pub utf8: *const c_char,
+}
+
+/// A scalar value passed from C, such as the value of a query parameter. `data_type` is
+/// DATAFUSION_TYPE_NULL, DATAFUSION_TYPE_BOOLEAN, DATAFUSION_TYPE_INT64, DATAFUSION_TYPE_FLOAT64 or
+/// DATAFUSION_TYPE_UTF8 and selects the field of `value` holding the value (none for NULL).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DataFusionScalar {
+    pub data_type: c_int,
+    pub value: DataFusionScalarValue,
+}
+
+impl DataFusionScalar {
+    pub(crate) fn to_scalar_value(self, name: &str) -> FfiResult<ScalarValue> {
+        let value = self.value;
+        Ok(match self.data_type {
+            DATAFUSION_TYPE_NULL => ScalarValue::Null,
+            DATAFUSION_TYPE_BOOLEAN => ScalarValue::Boolean(Some(unsafe { value.boolean })),
+            DATAFUSION_TYPE_INT64 => ScalarValue::Int64(Some(unsafe { value.int64 })),
+            DATAFUSION_TYPE_FLOAT64 => ScalarValue::Float64(Some(unsafe { value.float64 })),
+            DATAFUSION_TYPE_UTF8 => ScalarValue::Utf8(Some(str_arg(unsafe { value.utf8 }, name)?.to_string())),
+            data_type => {
+                return Err(DataFusionError::invalid_argument(format!(
+                    "{name} has unsupported type {data_type}, expected NULL, BOOLEAN, INT64, FLOAT64 or UTF8"
+                )))
+            }
+        })
+    }
+}
+
+/// Execute a SQL query with named `:name` placeholders, substituting the values of `count`
+/// parameters: `names[i]`, given without the colon, names the placeholder that gets `values[i]`.
+/// Values are bound as typed values rather than spliced into the SQL text, so they need no quoting.
+/// Every placeholder of the query must be given a value.
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_with_params(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    names: *const *const c_char,
+    values: *const DataFusionScalar,
+    count: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+        let names = str_array_arg(names, count, "names")?;
+        let values = slice_arg(values, count, "values")?;
+
+        let mut params = HashMap::with_capacity(count);
+        for (index, (name, value)) in names.into_iter().zip(values).enumerate() {
+            let value = value.to_scalar_value(&format!("values[{index}]"))?;
+            if params.insert(name.to_string(), value).is_some() {
+                return Err(DataFusionError::invalid_argument(format!("parameter {name} is given more than once")));
+            }
+        }
+
+        let session = ctx.ctx.clone();
+        let sql = sql_str.to_string();
+        let sql_options = ctx.sql_options;
+        let df = async move { Ok(session.sql_with_options(&sql, sql_options).await?.with_param_values(ParamValues::Map(params))?) };
+        Ok(Box::new(ctx.block_on(ctx.queries.execute(Some(sql_str), df, ctx.query_options()))?))
+    })
+}
@@ -0,0 +1,107 @@
+use std::ops::Range;
+use std::os::raw::{c_char, c_int};
+
+use datafusion::arrow::array::RecordBatch;
+
+use crate::error::{ffi_ptr, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::{DataFusionContext, DataFusionResult, QueryOptions};
+
+impl DataFusionResult {
+    fn partition_count(&self) -> usize {
+        self.partitions.as_ref().map_or(1, Vec::len)
+    }
+
+    /// The indexes of the batches of an output partition
+    fn partition_batches(&self, partition_index: c_int) -> FfiResult<Range<usize>> {
+        let partition = usize::try_from(partition_index)
+            .ok()
+            .filter(|&partition| partition < self.partition_count())
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "partition index {partition_index} out of range for result with {} partitions",
+                    self.partition_count()
+                ))
+            })?;
+        let Some(partitions) = &self.partitions else {
+            return Ok(0..self.batches.len());
+        };
+        let start = partitions[..partition].iter().sum::<usize>();
+        Ok(start..start + partitions[partition])
+    }
+
+    /// The batches of each output partition
+    pub(crate) fn into_partitions(self) -> Vec<Vec<RecordBatch>> {
+        let Some(partitions) = self.partitions else {
+            return vec![self.batches];
+        };
+        let mut batches = self.batches.into_iter();
+        partitions.into_iter().map(|count| batches.by_ref().take(count).collect()).collect()
+    }
+}
+
+/// Execute a SQL query like datafusion_sql, but collect each output partition of the plan in
+/// parallel and keep the partitions apart in the result, so the host can process them in parallel.
+/// The batches of the result are stored one partition after another and can be read with the usual
+/// result functions; datafusion_result_partition_first_batch gives where each partition starts.
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_collect_partitioned(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let session = ctx.ctx.clone();
+        let sql = sql_str.to_string();
+        let sql_options = ctx.sql_options;
+        let df = async move { Ok(session.sql_with_options(&sql, sql_options).await?) };
+        let options = QueryOptions {
+            preserve_partitions: true,
+            ..ctx.query_options()
+        };
+        let query = ctx.queries.execute(Some(sql_str), df, options);
+        Ok(Box::new(ctx.block_on(query)?))
+    })
+}
+
+/// Get the number of output partitions of a result. Results not collected with
+/// datafusion_sql_collect_partitioned have a single partition holding all batches.
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_partition_count(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || Ok(handle_ref(result, "result")?.partition_count() as c_int))
+}
+
+/// Get the number of batches of an output partition of a result, which may be 0
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_partition_batch_count(
+    result: *const DataFusionResult,
+    partition_index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || {
+        Ok(handle_ref(result, "result")?.partition_batches(partition_index)?.len() as c_int)
+    })
+}
+
+/// Get the index of the first batch of an output partition of a result, so batch `i` of the
+/// partition is batch `first + i` of the result
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_partition_first_batch(
+    result: *const DataFusionResult,
+    partition_index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || {
+        Ok(handle_ref(result, "result")?.partition_batches(partition_index)?.start as c_int)
+    })
+}
@@ -0,0 +1,117 @@
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use datafusion::physical_plan::ExecutionPlan;
+use tokio::task::AbortHandle;
+
+use crate::error::{ffi_ptr, str_arg, DataFusionError};
+use crate::handle::handle_ref;
+use crate::{metrics, DataFusionContext, DataFusionResult, QueryOptions};
+
+/// Callback receiving the progress of a query: the rows produced so far by the scans at the leaves
+/// of its plan, the bytes they read from storage (reported by Parquet scans, 0 for other sources),
+/// and the user data
+pub type DataFusionProgressCallback =
+    Option<extern "C" fn(rows_produced: i64, bytes_scanned: i64, user_data: *mut c_void)>;
+
+/// Progress callback of a query together with its user data and reporting interval
+#[derive(Clone)]
+pub(crate) struct Progress {
+    callback: extern "C" fn(i64, i64, *mut c_void),
+    user_data: *mut c_void,
+    interval: Duration,
+    /// Serializes the reports and records whether the final one was made, after which the periodic
+    /// reports stop
+    finished: Arc<Mutex<bool>>,
+}
+
+// The host is responsible for making user_data safe to use from any thread
+unsafe impl Send for Progress {}
+unsafe impl Sync for Progress {}
+
+impl Progress {
+    fn report(&self, plan: &Arc<dyn ExecutionPlan>, last: bool) {
+        let mut finished = self.finished.lock().unwrap_or_else(|e| e.into_inner());
+        if *finished {
+            return;
+        }
+        *finished = last;
+        let (rows, bytes) = metrics::scan_progress(plan);
+        (self.callback)(rows as i64, bytes as i64, self.user_data);
+    }
+
+    /// Report the progress of an executing plan every interval on the runtime, and a last time when
+    /// the returned reporter is dropped
+    pub(crate) fn start(self, plan: Arc<dyn ExecutionPlan>) -> ProgressReporter {
+        let ticker = tokio::spawn({
+            let progress = self.clone();
+            let plan = plan.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(progress.interval).await;
+                    progress.report(&plan, false);
+                }
+            }
+        });
+        ProgressReporter {
+            progress: self,
+            plan,
+            ticker: ticker.abort_handle(),
+        }
+    }
+}
+
+/// Reports the progress of a query while it executes
+pub(crate) struct ProgressReporter {
+    progress: Progress,
+    plan: Arc<dyn ExecutionPlan>,
+    ticker: AbortHandle,
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        self.ticker.abort();
+        self.progress.report(&self.plan, true);
+    }
+}
+
+/// Execute a SQL query like datafusion_sql, invoking `callback` with the query's progress every
+/// `interval_ms` milliseconds while it executes and once more when execution ends, successfully or
+/// not, so hosts can show progress of long scans. Queries that fail before executing, such as with
+/// a syntax error, report no progress. The callback runs on a runtime worker thread or the calling
+/// thread, never concurrently with itself, and is not invoked after this function returns.
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_with_progress(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    callback: DataFusionProgressCallback,
+    interval_ms: i64,
+    user_data: *mut c_void,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+        let callback = callback.ok_or_else(|| DataFusionError::invalid_argument("callback must not be null"))?;
+        let interval = u64::try_from(interval_ms)
+            .ok()
+            .filter(|&interval_ms| interval_ms > 0)
+            .map(Duration::from_millis)
+            .ok_or_else(|| DataFusionError::invalid_argument("interval_ms must be positive"))?;
+
+        let progress = Progress {
+            callback,
+            user_data,
+            interval,
+            finished: Default::default(),
+        };
+        let options = QueryOptions {
+            progress: Some(progress),
+            ..ctx.query_options()
+        };
+        Ok(Box::new(ctx.block_on(ctx.sql(sql_str, options))?))
+    })
+}
@@ -0,0 +1,173 @@
+use std::ffi::c_void;
+use std::os::raw::{c_char, c_int};
+use std::panic::AssertUnwindSafe;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use futures::FutureExt;
+use tokio::task::{AbortHandle, JoinHandle};
+
+use crate::cancel::Cancellation;
+use crate::error::{ffi_code, ffi_ptr, ffi_value, panic_error, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_ref, impl_handle, into_handle, HandleHeader};
+use crate::{DataFusionContext, DataFusionResult, QueryOptions};
+
+/// Callback receiving the outcome of an asynchronous query: either a result or an error, the other
+/// being null, and the user data. Both are owned by the callback, which must free them with
+/// datafusion_result_free or datafusion_error_free. The callback runs on a runtime worker thread.
+pub type DataFusionQueryCallback =
+    Option<extern "C" fn(result: *mut DataFusionResult, error: *mut DataFusionError, user_data: *mut c_void)>;
+
+/// Completion callback together with its user data, moved to the thread that runs the query
+struct Completion {
+    callback: extern "C" fn(*mut DataFusionResult, *mut DataFusionError, *mut c_void),
+    user_data: *mut c_void,
+}
+
+// The host is responsible for making user_data safe to use from any thread
+unsafe impl Send for Completion {}
+
+impl Completion {
+    fn complete(self, outcome: Result<DataFusionResult, DataFusionError>) {
+        match outcome {
+            Ok(result) => (self.callback)(into_handle(Box::new(result)), ptr::null_mut(), self.user_data),
+            Err(err) => (self.callback)(ptr::null_mut(), into_handle(Box::new(err)), self.user_data),
+        }
+    }
+}
+
+/// Execute a SQL query in the background on the context's runtime and invoke `callback` with its
+/// result or error when it finishes, without blocking the calling thread. The callback is invoked
+/// exactly once for every query that was submitted successfully, unless the context is freed while
+/// the query is still running, in which case the query is abandoned. The callback must not free
+/// the context.
+/// Returns DATAFUSION_OK if the query was submitted or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_sql_async(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    callback: DataFusionQueryCallback,
+    user_data: *mut c_void,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+        let callback = callback.ok_or_else(|| DataFusionError::invalid_argument("callback must not be null"))?;
+        let completion = Completion { callback, user_data };
+
+        let query = AssertUnwindSafe(ctx.sql(sql_str, ctx.query_options())).catch_unwind();
+        ctx.runtime.spawn(async move {
+            let outcome = query.await.unwrap_or_else(|payload| Err(panic_error(payload)));
+            completion.complete(outcome);
+        });
+        Ok(())
+    })
+}
+
+/// A query running in the background that can be waited for or cancelled
+pub struct DataFusionQuery {
+    header: HandleHeader,
+    /// The running query, taken by the first wait
+    task: Mutex<Option<JoinHandle<FfiResult<DataFusionResult>>>>,
+    /// Aborts the query without waiting for it, from any thread
+    abort: AbortHandle,
+    /// Stops the query at its next batch, for queries busy computing when they are aborted
+    cancellation: Cancellation,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl_handle!(DataFusionQuery, 0x4446_5155_4552_000a, queries);
+
+impl Drop for DataFusionQuery {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl DataFusionQuery {
+    fn wait(&self) -> FfiResult<DataFusionResult> {
+        let task = self
+            .task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .ok_or_else(|| DataFusionError::invalid_argument("the query's result was already taken"))?;
+        match self.runtime.block_on(task) {
+            Ok(outcome) => outcome,
+            Err(err) if err.is_cancelled() => Err(self.cancellation.error()),
+            Err(err) => Err(panic_error(err.into_panic())),
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancellation.cancel();
+        self.abort.abort();
+    }
+}
+
+/// Start executing a SQL query in the background on the context's runtime, returning a handle to
+/// wait for its result or cancel it. The query keeps running after the context is freed.
+/// Returns a pointer to the query or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_submit(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionQuery {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let cancellation = Cancellation::default();
+        let options = QueryOptions {
+            cancellation: Some(cancellation.clone()),
+            ..ctx.query_options()
+        };
+        let task = ctx.runtime.spawn(ctx.sql(sql_str, options));
+        Ok(Box::new(DataFusionQuery {
+            header: HandleHeader::default(),
+            abort: task.abort_handle(),
+            cancellation,
+            task: Mutex::new(Some(task)),
+            runtime: ctx.runtime.clone(),
+        }))
+    })
+}
+
+/// Cancel a query, stopping its execution as soon as possible. Waiting for a cancelled query fails
+/// with DATAFUSION_CANCELLED unless it had already finished. May be called from any thread,
+/// including while another thread waits for the query.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_query_cancel(query: *const DataFusionQuery, error_out: *mut *mut c_char) -> c_int {
+    ffi_code(error_out, || {
+        handle_ref(query, "query")?.cancel();
+        Ok(())
+    })
+}
+
+/// Check whether a query has finished, successfully or not, so waiting for it will not block
+/// Returns false on error
+#[no_mangle]
+pub extern "C" fn datafusion_query_is_finished(query: *const DataFusionQuery, error_out: *mut *mut c_char) -> bool {
+    ffi_value(error_out, false, || Ok(handle_ref(query, "query")?.abort.is_finished()))
+}
+
+/// Block until a query finishes and take its result. The result can only be taken once.
+/// Returns a pointer to the result, or null on error with DATAFUSION_CANCELLED if the query was
+/// cancelled
+#[no_mangle]
+pub extern "C" fn datafusion_query_wait(
+    query: *const DataFusionQuery,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || Ok(Box::new(handle_ref(query, "query")?.wait()?)))
+}
+
+/// Free a query, cancelling it if it is still running
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_query_free(query: *mut DataFusionQuery) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(query, "query"))
+}
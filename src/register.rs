@@ -0,0 +1,880 @@
+use std::io::{Cursor, Read};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::Arc;
+
+use arrow::ffi::{from_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use datafusion::arrow::csv::{self, reader::Format};
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef};
+use datafusion::arrow::json;
+use datafusion::arrow::array::{Array, StructArray};
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchReader};
+use datafusion::common::{Column, GetExt};
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use datafusion::datasource::file_format::options::{ArrowReadOptions, ReadOptions};
+use datafusion::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::datasource::listing::{ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl};
+use datafusion::logical_expr::LogicalPlan;
+use datafusion::prelude::{AvroReadOptions, CsvReadOptions, Expr, NdJsonReadOptions, ParquetReadOptions, SessionContext};
+
+use crate::error::{ffi_code, slice_arg, str_arg, str_array_arg, DataFusionError, FfiResult};
+use crate::export::compression_arg;
+use crate::flight::{FlightServer, FlightSource, FlightTable};
+use crate::handle::handle_ref;
+use crate::schema::{self, import_schema};
+use crate::{DataFusionContext, DATAFUSION_ALREADY_EXISTS};
+
+/// The name of a table to register, which must not be registered already: tables are replaced by
+/// deregistering them first
+pub(crate) fn table_name_arg<'a>(ctx: &DataFusionContext, table_name: *const c_char) -> FfiResult<&'a str> {
+    let table_name = str_arg(table_name, "table_name")?;
+    if ctx.ctx.table_exist(table_name)? {
+        return Err(DataFusionError::new(
+            DATAFUSION_ALREADY_EXISTS,
+            format!("a table named {table_name} is already registered"),
+        ));
+    }
+    Ok(table_name)
+}
+
+/// Options for registering CSV files. Pass null to use the defaults of
+/// datafusion_csv_read_options_default.
+#[repr(C)]
+pub struct DataFusionCsvReadOptions {
+    /// Field delimiter, ',' by default
+    pub delimiter: u8,
+    /// Quote character, '"' by default
+    pub quote: u8,
+    /// Escape character of quotes within quoted fields, or 0 for none (quotes are then escaped by
+    /// doubling them), 0 by default
+    pub escape: u8,
+    /// Whether the first line of each file is a header with the column names, true by default.
+    /// Without a header the columns are named column_1, column_2, ...
+    pub has_header: bool,
+    /// Number of rows read to infer the column types, 1000 by default, or SIZE_MAX to read every
+    /// row. Rows after them holding values of another type fail the queries reading them.
+    pub schema_infer_max_records: usize,
+    /// Whether to infer the column types, true by default. Otherwise every column is read as Utf8,
+    /// keeping values such as zip codes with leading zeros as written, to be cast in queries.
+    pub infer_types: bool,
+    /// Compression of the files: "gzip", "bzip2", "xz", "zstd" or "uncompressed", or null to infer
+    /// it from the extension of a single file, such as ".gz", and read a directory uncompressed
+    pub compression: *const c_char,
+    /// Extension of the files read, or null for ".csv" followed by the extension of the
+    /// compression, such as ".csv.gz"
+    pub file_extension: *const c_char,
+    /// Schema of the files, exported through the Arrow C data interface, or null to infer the
+    /// column types from the first rows. Only read: the caller still releases it.
+    pub schema: *const FFI_ArrowSchema,
+}
+
+impl Default for DataFusionCsvReadOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: 0,
+            has_header: true,
+            schema_infer_max_records: DEFAULT_SCHEMA_INFER_MAX_RECORD,
+            infer_types: true,
+            compression: ptr::null(),
+            file_extension: ptr::null(),
+            schema: ptr::null(),
+        }
+    }
+}
+
+/// Get the default CSV read options
+#[no_mangle]
+pub extern "C" fn datafusion_csv_read_options_default() -> DataFusionCsvReadOptions {
+    DataFusionCsvReadOptions::default()
+}
+
+/// The compression of the read options, or if they name none, the compression of a single file
+/// named by its extension, such as ".gz" for gzip
+fn read_compression(compression: *const c_char, path: &str) -> FfiResult<FileCompressionType> {
+    if !compression.is_null() || ListingTableUrl::parse(path)?.is_collection() {
+        return Ok(FileCompressionType::from(compression_arg(compression, "options.compression")?));
+    }
+    let compressions = [
+        FileCompressionType::GZIP,
+        FileCompressionType::BZIP2,
+        FileCompressionType::XZ,
+        FileCompressionType::ZSTD,
+    ];
+    let extension = compressions.into_iter().find(|compression| path.ends_with(&compression.get_ext()));
+    Ok(extension.unwrap_or(FileCompressionType::UNCOMPRESSED))
+}
+
+/// The extension of files of `format`, such as ".csv", compressed with `compression`
+fn file_extension(extension: *const c_char, format: &str, compression: &FileCompressionType) -> FfiResult<String> {
+    if extension.is_null() {
+        return Ok(format!(".{format}{}", compression.get_ext()));
+    }
+    Ok(str_arg(extension, "options.file_extension")?.to_string())
+}
+
+/// The number of rows read to infer a schema, which must be positive
+fn schema_infer_max_records(records: usize) -> FfiResult<usize> {
+    if records == 0 {
+        return Err(DataFusionError::invalid_argument("options.schema_infer_max_records must be positive"));
+    }
+    Ok(records)
+}
+
+/// The columns of the CSV files at `path`, named as they are read with `read_options`, all typed
+/// Utf8
+async fn string_schema(ctx: &SessionContext, path: &str, read_options: &CsvReadOptions<'_>) -> FfiResult<Schema> {
+    let listing_options = read_options.to_listing_options(&ctx.copied_config(), ctx.copied_table_options());
+    let inferred = listing_options.infer_schema(&ctx.state(), &ListingTableUrl::parse(path)?).await?;
+    let fields = inferred.fields().iter().map(|field| field.as_ref().clone().with_data_type(DataType::Utf8));
+    Ok(Schema::new(fields.collect::<Vec<_>>()))
+}
+
+/// Register a CSV file, or a directory of CSV files read as one table, with the context. Unless
+/// given a schema, the column types are inferred from the first rows of the files. Empty fields are
+/// read as null.
+/// `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+/// (see datafusion_register_object_store).
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_csv_with_options(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    path: *const c_char,
+    options: *const DataFusionCsvReadOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let path = str_arg(path, "path")?;
+        let defaults = DataFusionCsvReadOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let schema_infer_max_records = schema_infer_max_records(options.schema_infer_max_records)?;
+        let compression = read_compression(options.compression, path)?;
+        let file_extension = file_extension(options.file_extension, "csv", &compression)?;
+        let mut read_options = CsvReadOptions::new()
+            .delimiter(options.delimiter)
+            .quote(options.quote)
+            .has_header(options.has_header)
+            .schema_infer_max_records(schema_infer_max_records)
+            .file_extension(&file_extension)
+            .file_compression_type(compression);
+        if options.escape != 0 {
+            read_options = read_options.escape(options.escape);
+        }
+        let schema = match unsafe { options.schema.as_ref() } {
+            Some(schema) => Some(import_schema(schema)?),
+            None if !options.infer_types => Some(ctx.block_on(string_schema(&ctx.ctx, path, &read_options))?),
+            None => None,
+        };
+        if let Some(schema) = &schema {
+            read_options = read_options.schema(schema);
+        }
+
+        ctx.block_on(ctx.ctx.register_csv(table_name, path, read_options))?;
+        Ok(())
+    })
+}
+
+/// Options for registering Parquet files. Pass null to use the defaults of
+/// datafusion_parquet_read_options_default.
+#[repr(C)]
+pub struct DataFusionParquetReadOptions {
+    /// Extension of the files read from a directory, or null for ".parquet"
+    pub file_extension: *const c_char,
+    /// Whether to skip row groups whose statistics show they cannot match a query's filters, true by
+    /// default
+    pub pruning: bool,
+    /// Whether to evaluate a query's filters while decoding rows, to skip decoding the other columns
+    /// of rows that do not match, false by default. Requires `pruning`, since DataFusion only hands
+    /// the filters to Parquet scans that prune.
+    pub pushdown_filters: bool,
+    /// Whether to read the page index of files that have one, to skip the pages within row groups
+    /// that cannot match a query's filters, true by default. Used only with `pruning`.
+    pub page_index: bool,
+    /// Whether to read the bloom filters of files that have them, to skip the row groups that cannot
+    /// hold the values of equality filters, true by default. Used only with `pruning`.
+    pub bloom_filters: bool,
+    /// Whether to read binary columns as strings, for files of writers that store strings without
+    /// marking them as UTF-8, false by default
+    pub binary_as_string: bool,
+    /// Number of bytes read from the end of each file in the first request, to fetch its metadata
+    /// with the footer in one request instead of two when large enough, such as 524288 for object
+    /// stores, or 0 to read the footer first, 0 by default
+    pub metadata_size_hint: usize,
+    /// Names of the partition columns of a directory whose files are in hive-style `column=value`
+    /// directories, in the order of the directory levels, or null for none. Filters on them skip
+    /// the directories of other values.
+    pub partition_columns: *const *const c_char,
+    /// DATAFUSION_TYPE_* types without parameters of the partition columns, such as
+    /// DATAFUSION_TYPE_INT32 or DATAFUSION_TYPE_DATE32, to which the directory values are cast
+    pub partition_types: *const c_int,
+    pub partition_column_count: usize,
+}
+
+impl Default for DataFusionParquetReadOptions {
+    fn default() -> Self {
+        Self {
+            file_extension: ptr::null(),
+            pruning: true,
+            pushdown_filters: false,
+            page_index: true,
+            bloom_filters: true,
+            binary_as_string: false,
+            metadata_size_hint: 0,
+            partition_columns: ptr::null(),
+            partition_types: ptr::null(),
+            partition_column_count: 0,
+        }
+    }
+}
+
+/// Get the default Parquet read options
+#[no_mangle]
+pub extern "C" fn datafusion_parquet_read_options_default() -> DataFusionParquetReadOptions {
+    DataFusionParquetReadOptions::default()
+}
+
+/// Register a Parquet file, or a directory of Parquet files read as one table, with the context.
+/// `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+/// (see datafusion_register_object_store).
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_parquet(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    path: *const c_char,
+    options: *const DataFusionParquetReadOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let path = str_arg(path, "path")?;
+        let defaults = DataFusionParquetReadOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        if options.pushdown_filters && !options.pruning {
+            return Err(DataFusionError::invalid_argument("options.pushdown_filters requires options.pruning"));
+        }
+
+        let names = str_array_arg(options.partition_columns, options.partition_column_count, "options.partition_columns")?;
+        let types = slice_arg(options.partition_types, options.partition_column_count, "options.partition_types")?;
+        let partition_columns = names
+            .into_iter()
+            .zip(types)
+            .map(|(name, &type_id)| Ok((name.to_string(), schema::data_type(type_id)?)))
+            .collect::<FfiResult<Vec<_>>>()?;
+
+        let mut read_options =
+            ParquetReadOptions::new().parquet_pruning(options.pruning).table_partition_cols(partition_columns);
+        if !options.file_extension.is_null() {
+            read_options = read_options.file_extension(str_arg(options.file_extension, "options.file_extension")?);
+        }
+        let mut table_options = ctx.ctx.copied_table_options();
+        let parquet_options = &mut table_options.parquet.global;
+        parquet_options.pushdown_filters = options.pushdown_filters;
+        parquet_options.enable_page_index = options.page_index;
+        parquet_options.bloom_filter_on_read = options.bloom_filters;
+        parquet_options.binary_as_string = options.binary_as_string;
+        parquet_options.metadata_size_hint = (options.metadata_size_hint > 0).then_some(options.metadata_size_hint);
+        let listing_options = read_options.to_listing_options(&ctx.ctx.copied_config(), table_options);
+
+        ctx.block_on(ctx.ctx.register_listing_table(table_name, path, listing_options, None, None))?;
+        Ok(())
+    })
+}
+
+/// Options for registering newline delimited JSON files. Pass null to use the defaults of
+/// datafusion_json_read_options_default.
+#[repr(C)]
+pub struct DataFusionJsonReadOptions {
+    /// Extension of the files read from a directory, or null for ".json" followed by the extension
+    /// of the compression, such as ".json.gz"
+    pub file_extension: *const c_char,
+    /// Number of rows read to infer the schema of the files, 1000 by default, or SIZE_MAX to read
+    /// every row. Rows after them holding values of another type fail the queries reading them.
+    pub schema_infer_max_records: usize,
+    /// Compression of the files: "gzip", "bzip2", "xz", "zstd" or "uncompressed", or null to infer
+    /// it from the extension of a single file, such as ".gz", and read a directory uncompressed
+    pub compression: *const c_char,
+    /// Schema of the files, exported through the Arrow C data interface, or null to infer it from
+    /// the first rows. Only read: the caller still releases it.
+    pub schema: *const FFI_ArrowSchema,
+}
+
+impl Default for DataFusionJsonReadOptions {
+    fn default() -> Self {
+        Self {
+            file_extension: ptr::null(),
+            schema_infer_max_records: DEFAULT_SCHEMA_INFER_MAX_RECORD,
+            compression: ptr::null(),
+            schema: ptr::null(),
+        }
+    }
+}
+
+/// Get the default JSON read options
+#[no_mangle]
+pub extern "C" fn datafusion_json_read_options_default() -> DataFusionJsonReadOptions {
+    DataFusionJsonReadOptions::default()
+}
+
+/// Register a newline delimited JSON file, with one object per line, or a directory of such files
+/// read as one table, with the context. Unless given, the schema is inferred from the first rows of
+/// the files.
+/// `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+/// (see datafusion_register_object_store).
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_json(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    path: *const c_char,
+    options: *const DataFusionJsonReadOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let path = str_arg(path, "path")?;
+        let defaults = DataFusionJsonReadOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let schema_infer_max_records = schema_infer_max_records(options.schema_infer_max_records)?;
+        let compression = read_compression(options.compression, path)?;
+        let file_extension = file_extension(options.file_extension, "json", &compression)?;
+        let schema = unsafe { options.schema.as_ref() }.map(import_schema).transpose()?;
+        let read_options = NdJsonReadOptions {
+            schema: schema.as_ref(),
+            schema_infer_max_records,
+            file_extension: &file_extension,
+            file_compression_type: compression,
+            ..Default::default()
+        };
+
+        ctx.block_on(ctx.ctx.register_json(table_name, path, read_options))?;
+        Ok(())
+    })
+}
+
+/// The bytes at `data`, decompressed with the DataFusion `compression` of the read options
+fn decompressed(data: *const u8, len: usize, compression: *const c_char) -> FfiResult<Vec<u8>> {
+    let data = slice_arg(data, len, "data")?.to_vec();
+    let compression = FileCompressionType::from(compression_arg(compression, "options.compression")?);
+    if !compression.is_compressed() {
+        return Ok(data);
+    }
+    let mut decompressed = Vec::new();
+    compression.convert_read(Cursor::new(data))?.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Register record batches read from memory as an in-memory table
+fn register_batches(
+    ctx: &DataFusionContext,
+    table_name: &str,
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+) -> FfiResult<()> {
+    ctx.ctx.register_table(table_name, Arc::new(MemTable::try_new(schema, vec![batches])?))?;
+    Ok(())
+}
+
+/// Register `len` bytes of CSV held in memory, such as data received over a socket, as an in-memory
+/// table. The bytes are parsed before returning and the caller keeps ownership of them. The read
+/// options apply as to files, except that `file_extension` is ignored and no `compression` means
+/// uncompressed bytes.
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_csv_bytes(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    data: *const u8,
+    len: usize,
+    options: *const DataFusionCsvReadOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let defaults = DataFusionCsvReadOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let schema_infer_max_records = schema_infer_max_records(options.schema_infer_max_records)?;
+        let data = decompressed(data, len, options.compression)?;
+        let mut format = Format::default()
+            .with_delimiter(options.delimiter)
+            .with_quote(options.quote)
+            .with_header(options.has_header);
+        if options.escape != 0 {
+            format = format.with_escape(options.escape);
+        }
+        let schema = match unsafe { options.schema.as_ref() } {
+            Some(schema) => import_schema(schema)?,
+            None => {
+                let (inferred, _) = format.infer_schema(data.as_slice(), Some(schema_infer_max_records))?;
+                if options.infer_types {
+                    inferred
+                } else {
+                    let fields = inferred.fields().iter().map(|field| field.as_ref().clone().with_data_type(DataType::Utf8));
+                    Schema::new(fields.collect::<Vec<_>>())
+                }
+            }
+        };
+        let schema = Arc::new(schema);
+
+        let reader = csv::ReaderBuilder::new(schema.clone()).with_format(format).build(data.as_slice())?;
+        register_batches(ctx, table_name, schema, reader.collect::<Result<_, _>>()?)
+    })
+}
+
+/// Register `len` bytes of newline delimited JSON held in memory as an in-memory table. The bytes
+/// are parsed before returning and the caller keeps ownership of them. The read options apply as to
+/// files, except that `file_extension` is ignored and no `compression` means uncompressed bytes.
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_json_bytes(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    data: *const u8,
+    len: usize,
+    options: *const DataFusionJsonReadOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let defaults = DataFusionJsonReadOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let schema_infer_max_records = schema_infer_max_records(options.schema_infer_max_records)?;
+        let data = decompressed(data, len, options.compression)?;
+        let schema = match unsafe { options.schema.as_ref() } {
+            Some(schema) => import_schema(schema)?,
+            None => json::reader::infer_json_schema(data.as_slice(), Some(schema_infer_max_records))?.0,
+        };
+        let schema = Arc::new(schema);
+
+        let reader = json::ReaderBuilder::new(schema.clone()).build(data.as_slice())?;
+        register_batches(ctx, table_name, schema, reader.collect::<Result<_, _>>()?)
+    })
+}
+
+/// Register an Arrow IPC file (Feather v2), or a directory of ".arrow" files read as one table, with
+/// the context. A single file is read whatever its extension, such as ".arrow", ".feather" or
+/// ".ipc". The schema is read from the files, and their record batches are scanned without parsing.
+/// `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+/// (see datafusion_register_object_store).
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_arrow(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    path: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let path = str_arg(path, "path")?;
+
+        // Files listed from a directory are selected by extension, which would leave out a single
+        // file named otherwise
+        let read_options = ArrowReadOptions {
+            file_extension: if ListingTableUrl::parse(path)?.is_collection() { ".arrow" } else { "" },
+            ..Default::default()
+        };
+
+        ctx.block_on(ctx.ctx.register_arrow(table_name, path, read_options))?;
+        Ok(())
+    })
+}
+
+/// Register an Avro object container file, or a directory of ".avro" files read as one table, with
+/// the context. The schema is read from the files.
+/// `path` is a local path or an object store URL, such as "s3://bucket/key" or "https://host/file"
+/// (see datafusion_register_object_store).
+/// Only available when the library is built with the `avro` feature, which defines
+/// DATAFUSION_C_API_AVRO in the header.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[cfg(feature = "avro")]
+#[no_mangle]
+pub extern "C" fn datafusion_register_avro(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    path: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let path = str_arg(path, "path")?;
+
+        ctx.block_on(ctx.ctx.register_avro(table_name, path, AvroReadOptions::default()))?;
+        Ok(())
+    })
+}
+
+// File formats of listing tables
+pub const DATAFUSION_FORMAT_CSV: c_int = 0;
+pub const DATAFUSION_FORMAT_PARQUET: c_int = 1;
+pub const DATAFUSION_FORMAT_JSON: c_int = 2;
+pub const DATAFUSION_FORMAT_ARROW: c_int = 3;
+pub const DATAFUSION_FORMAT_AVRO: c_int = 4;
+
+/// Options for registering listing tables. Pass null to use the defaults of
+/// datafusion_listing_table_options_default.
+#[repr(C)]
+pub struct DataFusionListingTableOptions {
+    /// Extension of the files read, or null for the extension of the format, such as ".parquet"
+    pub file_extension: *const c_char,
+    /// Whether to add a partition column for each `key=value` directory level of the files' paths,
+    /// true by default. Partition columns hold strings, and filters on them skip the directories of
+    /// other values.
+    pub infer_partitions: bool,
+    /// Names of the columns the rows of every file are sorted by, most significant first, as declared
+    /// by `WITH ORDER` in CREATE EXTERNAL TABLE, or null if the files are not sorted. Queries then
+    /// skip sorts the files already satisfy and can merge sorted files instead of sorting them. The
+    /// order is trusted: files not sorted this way give wrong results.
+    pub sort_columns: *const *const c_char,
+    /// Whether each sort column is in ascending order
+    pub sort_ascending: *const bool,
+    /// Whether the nulls of each sort column come before its other values
+    pub sort_nulls_first: *const bool,
+    pub sort_column_count: usize,
+}
+
+impl Default for DataFusionListingTableOptions {
+    fn default() -> Self {
+        Self {
+            file_extension: ptr::null(),
+            infer_partitions: true,
+            sort_columns: ptr::null(),
+            sort_ascending: ptr::null(),
+            sort_nulls_first: ptr::null(),
+            sort_column_count: 0,
+        }
+    }
+}
+
+/// Get the default listing table options
+#[no_mangle]
+pub extern "C" fn datafusion_listing_table_options_default() -> DataFusionListingTableOptions {
+    DataFusionListingTableOptions::default()
+}
+
+/// The listing options of the files of a DATAFUSION_FORMAT_* format, read with the defaults of
+/// the format
+fn format_listing_options(ctx: &SessionContext, format: c_int) -> FfiResult<ListingOptions> {
+    let (config, table_options) = (ctx.copied_config(), ctx.copied_table_options());
+    Ok(match format {
+        DATAFUSION_FORMAT_CSV => CsvReadOptions::new().to_listing_options(&config, table_options),
+        DATAFUSION_FORMAT_PARQUET => ParquetReadOptions::new().to_listing_options(&config, table_options),
+        DATAFUSION_FORMAT_JSON => NdJsonReadOptions::default().to_listing_options(&config, table_options),
+        DATAFUSION_FORMAT_ARROW => ArrowReadOptions::default().to_listing_options(&config, table_options),
+        DATAFUSION_FORMAT_AVRO => AvroReadOptions::default().to_listing_options(&config, table_options),
+        _ => return Err(DataFusionError::invalid_argument(format!("invalid format {format}"))),
+    })
+}
+
+/// Register the files matching a URL or path as one table, read as a DATAFUSION_FORMAT_* format
+/// with its default options. `url` is a directory, a file, or a glob pattern such as
+/// "s3://bucket/data/part-*.parquet", matching files of an object store (see
+/// datafusion_register_object_store) or local files. Files in `key=value` directories, such as
+/// "data/year=2024/month=01/", get partition columns of their keys unless `options` disables it; a
+/// glob is matched against the paths without these directories, which are selected by filtering on
+/// their columns instead. Files in other subdirectories are left out unless the context's
+/// `datafusion.execution.listing_table_ignore_subdirectory` setting is false. The schema is
+/// inferred from the files, and `options` may declare the order of their rows.
+/// DATAFUSION_FORMAT_AVRO requires the library to be built with the `avro` feature.
+/// `options` may be null to use the defaults.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_listing_table(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    url: *const c_char,
+    format: c_int,
+    options: *const DataFusionListingTableOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let url = ListingTableUrl::parse(str_arg(url, "url")?)?;
+        let defaults = DataFusionListingTableOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let mut listing_options = format_listing_options(&ctx.ctx, format)?;
+        if !options.file_extension.is_null() {
+            listing_options = listing_options.with_file_extension(str_arg(options.file_extension, "options.file_extension")?);
+        }
+        let count = options.sort_column_count;
+        let sort_columns = str_array_arg(options.sort_columns, count, "options.sort_columns")?;
+        let ascending = slice_arg(options.sort_ascending, count, "options.sort_ascending")?;
+        let nulls_first = slice_arg(options.sort_nulls_first, count, "options.sort_nulls_first")?;
+        if count > 0 {
+            let sort_order = sort_columns
+                .iter()
+                .zip(ascending.iter().zip(nulls_first))
+                .map(|(&name, (&ascending, &nulls_first))| {
+                    Expr::Column(Column::new_unqualified(name)).sort(ascending, nulls_first)
+                })
+                .collect();
+            listing_options = listing_options.with_file_sort_order(vec![sort_order]);
+        }
+
+        let table = ctx.block_on(async {
+            let state = ctx.ctx.state();
+            let mut config = ListingTableConfig::new(url).with_listing_options(listing_options);
+            if options.infer_partitions {
+                config = config.infer_partitions_from_path(&state).await?;
+            }
+            ListingTable::try_new(config.infer_schema(&state).await?)
+        })?;
+        if let Some(name) = sort_columns.iter().find(|&&name| table.schema().field_with_name(name).is_err()) {
+            return Err(DataFusionError::invalid_argument(format!("sort column {name} is not a column of the table")));
+        }
+        ctx.ctx.register_table(table_name, Arc::new(table))?;
+        Ok(())
+    })
+}
+
+/// Register a view: a table defined by a SQL query, such as a commonly used join or filter, that
+/// later queries refer to by name, like one created by `CREATE VIEW name AS query` with
+/// datafusion_sql. The query is planned when the view is registered, so the tables it reads must be
+/// registered already, and it runs each time the view is read. It must be a query: DDL, DML and
+/// other statements are invalid arguments.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_view(
+    ctx: *const DataFusionContext,
+    view_name: *const c_char,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let view_name = table_name_arg(ctx, view_name)?;
+        let sql = str_arg(sql, "sql")?;
+
+        let plan = ctx.block_on(ctx.ctx.state().create_logical_plan(sql))?;
+        let statement = match &plan {
+            LogicalPlan::Ddl(ddl) => Some(ddl.name().to_string()),
+            LogicalPlan::Dml(dml) => Some(dml.op.to_string()),
+            LogicalPlan::Copy(_) => Some("COPY".to_string()),
+            LogicalPlan::Statement(statement) => Some(statement.name().to_string()),
+            _ => None,
+        };
+        if let Some(statement) = statement {
+            return Err(DataFusionError::invalid_argument(format!("a view is defined by a query, not a {statement} statement")));
+        }
+        let df = ctx.block_on(ctx.ctx.execute_logical_plan(plan))?;
+        ctx.ctx.register_table(view_name, df.into_view())?;
+        Ok(())
+    })
+}
+
+/// Register the record batches of an Arrow C stream as an in-memory table. The stream is read to
+/// its end and released before returning, and the table is a copy of its batches: the caller's
+/// struct is moved from and marked released whether or not the call succeeds, and must not be
+/// released again.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_arrow_stream(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    stream: *mut FFI_ArrowArrayStream,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        if stream.is_null() {
+            return Err(DataFusionError::invalid_argument("stream must not be null"));
+        }
+        // Taken first, so the stream is released even when another argument is invalid
+        let reader = unsafe { ArrowArrayStreamReader::from_raw(stream) };
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let reader = reader?;
+
+        let schema = reader.schema();
+        register_batches(ctx, table_name, schema, reader.collect::<Result<_, _>>()?)
+    })
+}
+
+/// Register one record batch, passed through the Arrow C data interface as a struct array with one
+/// child per column and its schema, as an in-memory table. The array is moved: the table takes
+/// ownership of its buffers, and the caller's struct is marked released whether or not the call
+/// succeeds, and must not be released again. The schema is only read: the caller still releases
+/// it.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_record_batch(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    array: *mut FFI_ArrowArray,
+    schema: *const FFI_ArrowSchema,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        if array.is_null() {
+            return Err(DataFusionError::invalid_argument("array must not be null"));
+        }
+        // Taken first, so the array is released even when another argument is invalid
+        let array = unsafe { FFI_ArrowArray::from_raw(array) };
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let schema = unsafe { schema.as_ref() }.ok_or_else(|| DataFusionError::invalid_argument("schema must not be null"))?;
+        if array.is_released() {
+            return Err(DataFusionError::invalid_argument("array was already released"));
+        }
+
+        let data = unsafe { from_ffi(array, schema) }?;
+        if !matches!(data.data_type(), DataType::Struct(_)) {
+            return Err(DataFusionError::invalid_argument(format!(
+                "array must be a struct array with one child per column, not {}",
+                data.data_type()
+            )));
+        }
+        let columns = StructArray::from(data);
+        if columns.null_count() > 0 {
+            return Err(DataFusionError::invalid_argument("array must not have null rows"));
+        }
+        let batch = RecordBatch::from(columns);
+        ctx.ctx.register_table(table_name, Arc::new(MemTable::try_new(batch.schema(), vec![vec![batch]])?))?;
+        Ok(())
+    })
+}
+
+/// Credentials and headers sent to an Arrow Flight server. Pass null to use the defaults of
+/// datafusion_flight_options_default, which send none.
+#[repr(C)]
+pub struct DataFusionFlightOptions {
+    /// Bearer token sent as "authorization: Bearer <token>" with every call, or null
+    pub token: *const c_char,
+    /// User name of Flight's basic authentication, or null. The user name and password are sent to
+    /// the server's Handshake method when the table is registered, and the bearer token the server
+    /// returns, if any, authenticates the later calls instead of them.
+    pub username: *const c_char,
+    /// Password of `username`
+    pub password: *const c_char,
+    /// Names of more headers sent with every call, such as "x-api-key", or null for none
+    pub header_names: *const *const c_char,
+    /// Values of the headers, in the order of their names
+    pub header_values: *const *const c_char,
+    pub header_count: usize,
+}
+
+impl Default for DataFusionFlightOptions {
+    fn default() -> Self {
+        Self {
+            token: ptr::null(),
+            username: ptr::null(),
+            password: ptr::null(),
+            header_names: ptr::null(),
+            header_values: ptr::null(),
+            header_count: 0,
+        }
+    }
+}
+
+/// Get the default Flight options
+#[no_mangle]
+pub extern "C" fn datafusion_flight_options_default() -> DataFusionFlightOptions {
+    DataFusionFlightOptions::default()
+}
+
+/// Register a table whose record batches are streamed from an Arrow Flight server whenever it is
+/// scanned, to query them together with the context's other tables. `uri` is the location of the
+/// server, such as "grpc://host:port", or "grpc+tls://host:port" to connect with TLS, verifying the
+/// server's certificate with the web's root authorities.
+/// Exactly one of `ticket`, of `ticket_len` bytes, and `sql` is given. A ticket is redeemed with
+/// DoGet by every scan, so it must stay valid as long as the table is registered. A Flight SQL
+/// query is planned with GetFlightInfo by every scan, which reads the endpoints returned as its
+/// partitions.
+/// The schema of the table is read when it is registered, from the query's FlightInfo or the first
+/// message streamed for the ticket. Scans that read other column types fail.
+/// `options` may be null to send no credentials.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_register_flight_table(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    uri: *const c_char,
+    ticket: *const u8,
+    ticket_len: usize,
+    sql: *const c_char,
+    options: *const DataFusionFlightOptions,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        let mut server = FlightServer::parse(str_arg(uri, "uri")?)?;
+        let source = match (ticket.is_null(), sql.is_null()) {
+            (false, true) => FlightSource::Ticket(slice_arg(ticket, ticket_len, "ticket")?.to_vec()),
+            (true, false) => FlightSource::Sql(str_arg(sql, "sql")?.to_string()),
+            _ => return Err(DataFusionError::invalid_argument("exactly one of ticket and sql must be given")),
+        };
+        let defaults = DataFusionFlightOptions::default();
+        let options = unsafe { options.as_ref() }.unwrap_or(&defaults);
+
+        let names = str_array_arg(options.header_names, options.header_count, "options.header_names")?;
+        let values = str_array_arg(options.header_values, options.header_count, "options.header_values")?;
+        for (name, value) in names.into_iter().zip(values) {
+            server = server.with_header(name, value)?;
+        }
+        let credentials = match (options.token.is_null(), options.username.is_null()) {
+            (false, false) => {
+                return Err(DataFusionError::invalid_argument("options.token and options.username are exclusive"));
+            }
+            (false, true) => {
+                let token = str_arg(options.token, "options.token")?;
+                server = server.with_header("authorization", &format!("Bearer {token}"))?;
+                None
+            }
+            (true, false) => Some((str_arg(options.username, "options.username")?, str_arg(options.password, "options.password")?)),
+            (true, true) => None,
+        };
+
+        let table = ctx.block_on(async move {
+            if let Some((username, password)) = credentials {
+                server = server.authenticate(username, password).await?;
+            }
+            FlightTable::try_new(server, source).await
+        })?;
+        ctx.ctx.register_table(table_name, Arc::new(table))?;
+        Ok(())
+    })
+}
+
+/// Deregister a table, such as one to register again after its files changed. Queries, DataFrames
+/// and statements already planned keep reading it.
+/// Returns DATAFUSION_OK on success, DATAFUSION_INVALID_ARGUMENT if no table of that name is
+/// registered, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_deregister_table(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = str_arg(table_name, "table_name")?;
+        if ctx.ctx.deregister_table(table_name)?.is_none() {
+            return Err(DataFusionError::invalid_argument(format!("no table named {table_name} is registered")));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::os::raw::{c_char, c_int};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use datafusion::arrow::array::{Int64Array, RecordBatch, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::prelude::DataFrame;
+
+use crate::cancel::Cancellation;
+use crate::error::{ffi_code, ffi_ptr, DataFusionError, FfiResult};
+use crate::handle::{handle_ref, HandleHeader};
+use crate::{DataFusionContext, DataFusionResult, QueryOptions};
+
+/// A query listed by datafusion_queries_list
+struct RunningQuery {
+    /// The SQL text of the query, None for plans not given as SQL
+    sql: Option<String>,
+    started: Instant,
+    cancellation: Cancellation,
+}
+
+/// The queries running on a context, shared with the statements prepared with it
+#[derive(Default)]
+pub(crate) struct QueryRegistry {
+    last_id: AtomicI64,
+    running: Mutex<BTreeMap<i64, RunningQuery>>,
+}
+
+/// Lists a query as running until it is dropped
+pub(crate) struct Registration {
+    registry: Arc<QueryRegistry>,
+    id: i64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.registry.running().remove(&self.id);
+    }
+}
+
+impl QueryRegistry {
+    fn running(&self) -> std::sync::MutexGuard<'_, BTreeMap<i64, RunningQuery>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// List a query as running under a new ID, so it can be killed through `cancellation`
+    pub(crate) fn register(self: &Arc<Self>, sql: Option<&str>, cancellation: &Cancellation) -> Registration {
+        let id = self.last_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let query = RunningQuery {
+            sql: sql.map(str::to_string),
+            started: Instant::now(),
+            cancellation: cancellation.clone(),
+        };
+        self.running().insert(id, query);
+        Registration {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    /// Execute the DataFrame produced by `df` like crate::execute, listed as running until it
+    /// finishes
+    pub(crate) fn execute(
+        self: &Arc<Self>,
+        sql: Option<&str>,
+        df: impl Future<Output = FfiResult<DataFrame>> + Send + 'static,
+        options: QueryOptions,
+    ) -> impl Future<Output = FfiResult<DataFusionResult>> + Send + 'static {
+        let cancellation = options.cancellation.clone().unwrap_or_default();
+        let registration = self.register(sql, &cancellation);
+        let options = QueryOptions {
+            cancellation: Some(cancellation),
+            ..options
+        };
+        let query = crate::execute(df, options);
+        async move {
+            let _registration = registration;
+            query.await
+        }
+    }
+
+    /// The running queries as a result with `query_id`, `sql` and `elapsed_ms` columns
+    fn list(&self) -> FfiResult<DataFusionResult> {
+        let running = self.running();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("query_id", DataType::Int64, false),
+            Field::new("sql", DataType::Utf8, true),
+            Field::new("elapsed_ms", DataType::Int64, false),
+        ]));
+        let ids = Int64Array::from_iter_values(running.keys().copied());
+        let sql = StringArray::from_iter(running.values().map(|query| query.sql.as_deref()));
+        let elapsed = Int64Array::from_iter_values(
+            running.values().map(|query| query.started.elapsed().as_millis() as i64),
+        );
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(ids), Arc::new(sql), Arc::new(elapsed)])?;
+        Ok(DataFusionResult {
+            header: HandleHeader::default(),
+            batches: vec![batch],
+            schema,
+            plan: None,
+            partitions: None,
+        })
+    }
+
+    fn kill(&self, id: i64) -> FfiResult<()> {
+        let running = self.running();
+        let query = running
+            .get(&id)
+            .ok_or_else(|| DataFusionError::invalid_argument(format!("no running query with ID {id}")))?;
+        query.cancellation.cancel();
+        Ok(())
+    }
+}
+
+/// List the queries running on a context: collected queries, whether synchronous, asynchronous,
+/// submitted or prepared statements, until they finish, and streams until they are freed. Each
+/// query is assigned an ID when it starts, unique within the context. May be called from any
+/// thread, including while other threads run queries on the context.
+/// Returns a result with one row per query, ordered by ID, with the columns `query_id` (Int64),
+/// `sql` (Utf8, null for plans not given as SQL, such as Substrait plans and DataFrames) and `elapsed_ms`
+/// (Int64, the time since the query started), or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_queries_list(
+    ctx: *const DataFusionContext,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || Ok(Box::new(handle_ref(ctx, "ctx")?.queries.list()?)))
+}
+
+/// Kill a running query of a context by the ID listed by datafusion_queries_list. The query stops
+/// at its next batch and fails with DATAFUSION_CANCELLED. May be called from any thread.
+/// Returns DATAFUSION_OK on success, DATAFUSION_INVALID_ARGUMENT if no query with the ID is
+/// running, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_query_kill(
+    ctx: *const DataFusionContext,
+    query_id: i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || handle_ref(ctx, "ctx")?.queries.kill(query_id))
+}
@@ -0,0 +1,126 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{Result, Statistics};
+use datafusion::config::ConfigOptions;
+use datafusion::execution::{SendableRecordBatchStream, SessionStateBuilder, TaskContext};
+use datafusion::logical_expr::Partitioning;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::metrics::MetricsSet;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use datafusion::prelude::DataFrame;
+
+use crate::error::FfiResult;
+
+/// A RepartitionExec planned for a repartitioning the caller asked for.
+///
+/// DataFusion's EnforceDistribution rule removes every RepartitionExec from the plan and adds back
+/// the ones its operators require, partitioned by the session's target partitions, so a
+/// repartitioning asked for through the DataFrame API would never take effect. Wrapped, the
+/// RepartitionExec is left alone, and operators after it that need the same partitioning use it.
+#[derive(Debug)]
+struct KeptRepartitionExec(RepartitionExec);
+
+impl KeptRepartitionExec {
+    /// Repartition `input` like `repartition` does its input
+    // DataFusion's error type grows past clippy's limit when the avro feature adds its Avro variant
+    #[allow(clippy::result_large_err)]
+    fn new(input: Arc<dyn ExecutionPlan>, repartition: &RepartitionExec) -> Result<Self> {
+        let kept = RepartitionExec::try_new(input, repartition.partitioning().clone())?;
+        Ok(Self(if repartition.preserve_order() { kept.with_preserve_order() } else { kept }))
+    }
+}
+
+impl DisplayAs for KeptRepartitionExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for KeptRepartitionExec {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        self.0.properties()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![self.0.input()]
+    }
+
+    fn with_new_children(self: Arc<Self>, children: Vec<Arc<dyn ExecutionPlan>>) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self::new(children[0].clone(), &self.0)?))
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        self.0.benefits_from_input_partitioning()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        self.0.maintains_input_order()
+    }
+
+    fn execute(&self, partition: usize, context: Arc<TaskContext>) -> Result<SendableRecordBatchStream> {
+        self.0.execute(partition, context)
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.0.metrics()
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.0.statistics()
+    }
+}
+
+/// Wraps the RepartitionExecs of a plan in KeptRepartitionExec. Runs before the other rules, when
+/// the only RepartitionExecs are those planned for the Repartition nodes of the logical plan.
+#[derive(Debug)]
+struct KeepRepartitions;
+
+impl PhysicalOptimizerRule for KeepRepartitions {
+    // DataFusion's error type grows past clippy's limit when the avro feature adds its Avro variant
+    #[allow(clippy::result_large_err)]
+    fn optimize(&self, plan: Arc<dyn ExecutionPlan>, _config: &ConfigOptions) -> Result<Arc<dyn ExecutionPlan>> {
+        let plan = plan.transform_up(|node| {
+            let Some(repartition) = node.as_any().downcast_ref::<RepartitionExec>() else {
+                return Ok(Transformed::no(node));
+            };
+            let kept = KeptRepartitionExec::new(repartition.input().clone(), repartition)?;
+            Ok(Transformed::yes(Arc::new(kept) as Arc<dyn ExecutionPlan>))
+        })?;
+        Ok(plan.data)
+    }
+
+    fn name(&self) -> &str {
+        "keep_repartitions"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Repartition the rows of a DataFrame, keeping the partitioning in its physical plan
+pub(crate) fn repartition(df: DataFrame, partitioning: Partitioning) -> FfiResult<DataFrame> {
+    let (state, plan) = df.into_parts();
+    let rules = state.physical_optimizers();
+    let state = if rules.iter().any(|rule| rule.name() == KeepRepartitions.name()) {
+        state
+    } else {
+        let rules = std::iter::once(Arc::new(KeepRepartitions) as _).chain(rules.iter().cloned()).collect();
+        SessionStateBuilder::new_from_existing(state)
+            .with_physical_optimizer_rules(rules)
+            .build()
+    };
+    Ok(DataFrame::new(state, plan).repartition(partitioning)?)
+}
@@ -0,0 +1,393 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::Arc;
+
+use arrow::ffi::FFI_ArrowSchema;
+use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
+
+use crate::error::{ffi_code, ffi_ptr, ffi_string, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_ref, impl_handle, HandleHeader};
+use crate::values::write_out;
+use crate::{DataFusionContext, DataFusionResult};
+
+/// A standalone Arrow schema, or the children of a nested field
+pub struct DataFusionSchema {
+    header: HandleHeader,
+    schema: SchemaRef,
+}
+
+impl_handle!(DataFusionSchema, 0x4446_5343_484d_0007, schemas);
+
+impl DataFusionSchema {
+    pub(crate) fn new(schema: SchemaRef) -> Box<Self> {
+        Box::new(Self {
+            header: HandleHeader::default(),
+            schema,
+        })
+    }
+
+    fn field(&self, field_index: c_int) -> FfiResult<&Field> {
+        field_at(self.schema.fields(), field_index, "schema")
+    }
+}
+
+fn field_at<'a>(fields: &'a Fields, index: c_int, owner: &str) -> FfiResult<&'a Field> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| fields.get(i))
+        .map(|field| field.as_ref())
+        .ok_or_else(|| {
+            DataFusionError::invalid_argument(format!(
+                "field index {index} out of range for {owner} with {} fields",
+                fields.len()
+            ))
+        })
+}
+
+/// The child fields of a nested type: struct members, or the single item field of lists and maps
+fn child_fields(data_type: &DataType) -> Fields {
+    match data_type {
+        DataType::Struct(fields) => fields.clone(),
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _)
+        | DataType::ListView(field)
+        | DataType::LargeListView(field)
+        | DataType::Map(field, _) => Fields::from(vec![field.clone()]),
+        DataType::Dictionary(_, value_type) => child_fields(value_type),
+        _ => Fields::empty(),
+    }
+}
+
+// Arrow data types, as reported by datafusion_result_column_type
+pub const DATAFUSION_TYPE_NULL: c_int = 0;
+pub const DATAFUSION_TYPE_BOOLEAN: c_int = 1;
+pub const DATAFUSION_TYPE_INT8: c_int = 2;
+pub const DATAFUSION_TYPE_INT16: c_int = 3;
+pub const DATAFUSION_TYPE_INT32: c_int = 4;
+pub const DATAFUSION_TYPE_INT64: c_int = 5;
+pub const DATAFUSION_TYPE_UINT8: c_int = 6;
+pub const DATAFUSION_TYPE_UINT16: c_int = 7;
+pub const DATAFUSION_TYPE_UINT32: c_int = 8;
+pub const DATAFUSION_TYPE_UINT64: c_int = 9;
+pub const DATAFUSION_TYPE_FLOAT16: c_int = 10;
+pub const DATAFUSION_TYPE_FLOAT32: c_int = 11;
+pub const DATAFUSION_TYPE_FLOAT64: c_int = 12;
+pub const DATAFUSION_TYPE_UTF8: c_int = 13;
+pub const DATAFUSION_TYPE_LARGE_UTF8: c_int = 14;
+pub const DATAFUSION_TYPE_BINARY: c_int = 15;
+pub const DATAFUSION_TYPE_LARGE_BINARY: c_int = 16;
+pub const DATAFUSION_TYPE_FIXED_SIZE_BINARY: c_int = 17;
+pub const DATAFUSION_TYPE_DATE32: c_int = 18;
+pub const DATAFUSION_TYPE_DATE64: c_int = 19;
+pub const DATAFUSION_TYPE_TIMESTAMP: c_int = 20;
+pub const DATAFUSION_TYPE_TIME32: c_int = 21;
+pub const DATAFUSION_TYPE_TIME64: c_int = 22;
+pub const DATAFUSION_TYPE_DURATION: c_int = 23;
+pub const DATAFUSION_TYPE_INTERVAL: c_int = 24;
+pub const DATAFUSION_TYPE_DECIMAL128: c_int = 25;
+pub const DATAFUSION_TYPE_DECIMAL256: c_int = 26;
+pub const DATAFUSION_TYPE_LIST: c_int = 27;
+pub const DATAFUSION_TYPE_LARGE_LIST: c_int = 28;
+pub const DATAFUSION_TYPE_FIXED_SIZE_LIST: c_int = 29;
+pub const DATAFUSION_TYPE_STRUCT: c_int = 30;
+pub const DATAFUSION_TYPE_MAP: c_int = 31;
+pub const DATAFUSION_TYPE_DICTIONARY: c_int = 32;
+/// Any other type (views, unions, run-end encoded); see datafusion_result_column_type_name
+pub const DATAFUSION_TYPE_OTHER: c_int = 99;
+
+/// The data type of a DATAFUSION_TYPE_* constant, for types without parameters
+pub(crate) fn data_type(type_id: c_int) -> FfiResult<DataType> {
+    Ok(match type_id {
+        DATAFUSION_TYPE_NULL => DataType::Null,
+        DATAFUSION_TYPE_BOOLEAN => DataType::Boolean,
+        DATAFUSION_TYPE_INT8 => DataType::Int8,
+        DATAFUSION_TYPE_INT16 => DataType::Int16,
+        DATAFUSION_TYPE_INT32 => DataType::Int32,
+        DATAFUSION_TYPE_INT64 => DataType::Int64,
+        DATAFUSION_TYPE_UINT8 => DataType::UInt8,
+        DATAFUSION_TYPE_UINT16 => DataType::UInt16,
+        DATAFUSION_TYPE_UINT32 => DataType::UInt32,
+        DATAFUSION_TYPE_UINT64 => DataType::UInt64,
+        DATAFUSION_TYPE_FLOAT16 => DataType::Float16,
+        DATAFUSION_TYPE_FLOAT32 => DataType::Float32,
+        DATAFUSION_TYPE_FLOAT64 => DataType::Float64,
+        DATAFUSION_TYPE_UTF8 => DataType::Utf8,
+        DATAFUSION_TYPE_LARGE_UTF8 => DataType::LargeUtf8,
+        DATAFUSION_TYPE_BINARY => DataType::Binary,
+        DATAFUSION_TYPE_LARGE_BINARY => DataType::LargeBinary,
+        DATAFUSION_TYPE_DATE32 => DataType::Date32,
+        DATAFUSION_TYPE_DATE64 => DataType::Date64,
+        _ => {
+            return Err(DataFusionError::invalid_argument(format!(
+                "type {type_id} is not a type without parameters"
+            )))
+        }
+    })
+}
+
+pub(crate) fn type_id(data_type: &DataType) -> c_int {
+    match data_type {
+        DataType::Null => DATAFUSION_TYPE_NULL,
+        DataType::Boolean => DATAFUSION_TYPE_BOOLEAN,
+        DataType::Int8 => DATAFUSION_TYPE_INT8,
+        DataType::Int16 => DATAFUSION_TYPE_INT16,
+        DataType::Int32 => DATAFUSION_TYPE_INT32,
+        DataType::Int64 => DATAFUSION_TYPE_INT64,
+        DataType::UInt8 => DATAFUSION_TYPE_UINT8,
+        DataType::UInt16 => DATAFUSION_TYPE_UINT16,
+        DataType::UInt32 => DATAFUSION_TYPE_UINT32,
+        DataType::UInt64 => DATAFUSION_TYPE_UINT64,
+        DataType::Float16 => DATAFUSION_TYPE_FLOAT16,
+        DataType::Float32 => DATAFUSION_TYPE_FLOAT32,
+        DataType::Float64 => DATAFUSION_TYPE_FLOAT64,
+        DataType::Utf8 => DATAFUSION_TYPE_UTF8,
+        DataType::LargeUtf8 => DATAFUSION_TYPE_LARGE_UTF8,
+        DataType::Binary => DATAFUSION_TYPE_BINARY,
+        DataType::LargeBinary => DATAFUSION_TYPE_LARGE_BINARY,
+        DataType::FixedSizeBinary(_) => DATAFUSION_TYPE_FIXED_SIZE_BINARY,
+        DataType::Date32 => DATAFUSION_TYPE_DATE32,
+        DataType::Date64 => DATAFUSION_TYPE_DATE64,
+        DataType::Timestamp(_, _) => DATAFUSION_TYPE_TIMESTAMP,
+        DataType::Time32(_) => DATAFUSION_TYPE_TIME32,
+        DataType::Time64(_) => DATAFUSION_TYPE_TIME64,
+        DataType::Duration(_) => DATAFUSION_TYPE_DURATION,
+        DataType::Interval(_) => DATAFUSION_TYPE_INTERVAL,
+        DataType::Decimal128(_, _) => DATAFUSION_TYPE_DECIMAL128,
+        DataType::Decimal256(_, _) => DATAFUSION_TYPE_DECIMAL256,
+        DataType::List(_) => DATAFUSION_TYPE_LIST,
+        DataType::LargeList(_) => DATAFUSION_TYPE_LARGE_LIST,
+        DataType::FixedSizeList(_, _) => DATAFUSION_TYPE_FIXED_SIZE_LIST,
+        DataType::Struct(_) => DATAFUSION_TYPE_STRUCT,
+        DataType::Map(_, _) => DATAFUSION_TYPE_MAP,
+        DataType::Dictionary(_, _) => DATAFUSION_TYPE_DICTIONARY,
+        _ => DATAFUSION_TYPE_OTHER,
+    }
+}
+
+impl DataFusionResult {
+    pub(crate) fn field(&self, column_index: c_int) -> FfiResult<&Field> {
+        let fields = self.schema.fields();
+        usize::try_from(column_index)
+            .ok()
+            .and_then(|index| fields.get(index))
+            .map(|field| field.as_ref())
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "column index {column_index} out of range for result with {} columns",
+                    fields.len()
+                ))
+            })
+    }
+}
+
+/// Get the number of columns in a result, which is known even if it has no batches
+/// Returns 0 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_count(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, 0, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.schema.fields().len() as c_int)
+    })
+}
+
+/// Get the name of a column
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_name(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.field(column_index)?.name().clone())
+    })
+}
+
+/// Get the Arrow type of a column as one of the DATAFUSION_TYPE_* constants
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_type(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || {
+        let result = handle_ref(result, "result")?;
+        Ok(type_id(result.field(column_index)?.data_type()))
+    })
+}
+
+/// Get the full Arrow type of a column as a string, e.g. "Int64" or "Timestamp(Nanosecond, None)"
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_type_name(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.field(column_index)?.data_type().to_string())
+    })
+}
+
+/// Check whether a column may contain nulls
+/// Returns false on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_nullable(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_value(error_out, false, || {
+        let result = handle_ref(result, "result")?;
+        Ok(result.field(column_index)?.is_nullable())
+    })
+}
+
+/// Get the schema of a result
+/// Returns a pointer to the schema or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_schema(
+    result: *const DataFusionResult,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionSchema {
+    ffi_ptr(error_out, || {
+        let result = handle_ref(result, "result")?;
+        Ok(DataFusionSchema::new(result.schema.clone()))
+    })
+}
+
+/// Get the schema of a table registered with the context
+/// Returns a pointer to the schema or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_table_schema(
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionSchema {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = str_arg(table_name, "table_name")?;
+        let provider = ctx.block_on(ctx.ctx.table_provider(table_name))?;
+        Ok(DataFusionSchema::new(provider.schema()))
+    })
+}
+
+/// Get the number of fields in a schema
+/// Returns 0 on error
+#[no_mangle]
+pub extern "C" fn datafusion_schema_field_count(
+    schema: *const DataFusionSchema,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, 0, || {
+        let schema = handle_ref(schema, "schema")?;
+        Ok(schema.schema.fields().len() as c_int)
+    })
+}
+
+/// Get the name of a field
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_schema_field_name(
+    schema: *const DataFusionSchema,
+    field_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let schema = handle_ref(schema, "schema")?;
+        Ok(schema.field(field_index)?.name().clone())
+    })
+}
+
+/// Get the Arrow type of a field as one of the DATAFUSION_TYPE_* constants
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_schema_field_type(
+    schema: *const DataFusionSchema,
+    field_index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || {
+        let schema = handle_ref(schema, "schema")?;
+        Ok(type_id(schema.field(field_index)?.data_type()))
+    })
+}
+
+/// Get the full Arrow type of a field as a string, e.g. "Int64" or "Timestamp(Nanosecond, None)"
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_schema_field_type_name(
+    schema: *const DataFusionSchema,
+    field_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let schema = handle_ref(schema, "schema")?;
+        Ok(schema.field(field_index)?.data_type().to_string())
+    })
+}
+
+/// Check whether a field may contain nulls
+/// Returns false on error
+#[no_mangle]
+pub extern "C" fn datafusion_schema_field_nullable(
+    schema: *const DataFusionSchema,
+    field_index: c_int,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_value(error_out, false, || {
+        let schema = handle_ref(schema, "schema")?;
+        Ok(schema.field(field_index)?.is_nullable())
+    })
+}
+
+/// Get the child fields of a nested field as a schema: the members of a struct, or the single
+/// item field of a list or map. Fields of other types have no children.
+/// Returns a pointer to the schema or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_schema_field_children(
+    schema: *const DataFusionSchema,
+    field_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionSchema {
+    ffi_ptr(error_out, || {
+        let schema = handle_ref(schema, "schema")?;
+        let children = child_fields(schema.field(field_index)?.data_type());
+        Ok(DataFusionSchema::new(Arc::new(Schema::new(children))))
+    })
+}
+
+/// Export a schema through the Arrow C data interface, as a struct type with one child per field.
+/// The caller must call the `release` callback when done; `out` may point to uninitialized memory.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_schema_export(
+    schema: *const DataFusionSchema,
+    out: *mut FFI_ArrowSchema,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let schema = handle_ref(schema, "schema")?;
+        write_out(out, FFI_ArrowSchema::try_from(schema.schema.as_ref())?)
+    })
+}
+
+/// Read a schema passed through the Arrow C data interface, as a struct type with one child per
+/// field. The ArrowSchema is only read: it stays owned by the caller, who releases it.
+pub(crate) fn import_schema(schema: &FFI_ArrowSchema) -> FfiResult<Schema> {
+    Ok(Schema::try_from(schema)?)
+}
+
+/// Free a schema
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_schema_free(schema: *mut DataFusionSchema) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(schema, "schema"))
+}
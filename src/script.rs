@@ -0,0 +1,146 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use datafusion::common::plan_datafusion_err;
+use datafusion::sql::parser::DFParser;
+use datafusion::sql::sqlparser::dialect::dialect_from_str;
+
+use crate::error::{ffi_code, ffi_ptr, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_ref, impl_handle, HandleHeader};
+use crate::{DataFusionContext, DataFusionResult, DATAFUSION_CANCELLED};
+
+/// The outcomes of the statements of a SQL script, in script order
+pub struct DataFusionScript {
+    header: HandleHeader,
+    outcomes: Vec<FfiResult<DataFusionResult>>,
+}
+
+impl_handle!(DataFusionScript, 0x4446_5343_5250_000c, scripts);
+
+impl DataFusionScript {
+    fn outcome(&self, statement_index: c_int) -> FfiResult<&FfiResult<DataFusionResult>> {
+        usize::try_from(statement_index)
+            .ok()
+            .and_then(|index| self.outcomes.get(index))
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "statement index {statement_index} out of range (script has {} statements)",
+                    self.outcomes.len()
+                ))
+            })
+    }
+}
+
+/// Parse, plan and execute the statements of a script one after another, so each statement sees the
+/// tables created by the ones before it. Statements after a failed one are not executed.
+fn run_script(ctx: &DataFusionContext, sql: &str) -> FfiResult<Vec<FfiResult<DataFusionResult>>> {
+    let dialect_name = ctx.ctx.state().config().options().sql_parser.dialect.clone();
+    let dialect = dialect_from_str(&dialect_name)
+        .ok_or_else(|| plan_datafusion_err!("Unsupported SQL dialect: {dialect_name}"))?;
+    let statements = DFParser::parse_sql_with_dialect(sql, dialect.as_ref())?;
+
+    let mut outcomes = Vec::with_capacity(statements.len());
+    let mut failed = None;
+    for statement in statements {
+        if let Some(failed) = failed {
+            outcomes.push(Err(DataFusionError::new(
+                DATAFUSION_CANCELLED,
+                format!("statement was not executed because the statement at index {failed} failed"),
+            )));
+            continue;
+        }
+        let sql = statement.to_string();
+        let session = ctx.ctx.clone();
+        let sql_options = ctx.sql_options;
+        let df = async move {
+            let plan = session.state().statement_to_plan(statement).await?;
+            sql_options.verify_plan(&plan)?;
+            Ok(session.execute_logical_plan(plan).await?)
+        };
+        let query = ctx.queries.execute(Some(&sql), df, ctx.query_options());
+        let outcome = ctx.block_on(query);
+        if outcome.is_err() {
+            failed = Some(outcomes.len());
+        }
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+/// Execute a script of `;`-separated SQL statements, such as DDL followed by queries, in order.
+/// Execution stops at the first failing statement; the statements after it are not executed. Each
+/// statement uses the context's maximum result size and query timeout.
+/// Returns a pointer to the script's outcomes, or null on error if the script could not be parsed
+/// (in which case no statement was executed)
+#[no_mangle]
+pub extern "C" fn datafusion_sql_script(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionScript {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        Ok(Box::new(DataFusionScript {
+            header: HandleHeader::default(),
+            outcomes: run_script(ctx, sql_str)?,
+        }))
+    })
+}
+
+/// Get the number of statements in a script
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_script_statement_count(
+    script: *const DataFusionScript,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || Ok(handle_ref(script, "script")?.outcomes.len() as c_int))
+}
+
+/// Get the status of a statement of a script. A failed statement's error is reported like the
+/// error of any failed call, through error_out and datafusion_last_error.
+/// Returns DATAFUSION_OK if the statement succeeded, the error code it failed with,
+/// DATAFUSION_CANCELLED if it was not executed because an earlier statement failed, or another
+/// error code for an invalid script or index
+#[no_mangle]
+pub extern "C" fn datafusion_script_status(
+    script: *const DataFusionScript,
+    statement_index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || match handle_ref(script, "script")?.outcome(statement_index)? {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.clone()),
+    })
+}
+
+/// Get the result of a statement of a script, as a new result to be freed with
+/// datafusion_result_free. DDL statements have empty results.
+/// Returns a pointer to the result, or null on error, which for a failed statement is the
+/// statement's error
+#[no_mangle]
+pub extern "C" fn datafusion_script_result(
+    script: *const DataFusionScript,
+    statement_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let result = handle_ref(script, "script")?.outcome(statement_index)?.as_ref().map_err(Clone::clone)?;
+        Ok(Box::new(DataFusionResult {
+            header: HandleHeader::default(),
+            batches: result.batches.clone(),
+            schema: result.schema.clone(),
+            plan: result.plan.clone(),
+            partitions: result.partitions.clone(),
+        }))
+    })
+}
+
+/// Free a script and the results of its statements
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_script_free(script: *mut DataFusionScript) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(script, "script"))
+}
@@ -0,0 +1,259 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::Arc;
+
+use datafusion::common::ScalarValue;
+use datafusion::logical_expr::LogicalPlan;
+use datafusion::prelude::DataFrame;
+use datafusion_proto::bytes::{logical_plan_from_bytes, logical_plan_to_bytes};
+
+use crate::error::{ffi_code, ffi_ptr, ffi_value, slice_arg, str_arg, DataFusionError, FfiResult};
+use crate::export::write_buffer;
+use crate::handle::{free_handle, handle_mut, handle_ref, impl_handle, HandleHeader};
+use crate::logging::{self, ContextLogFilter};
+use crate::registry::QueryRegistry;
+use crate::{DataFusionContext, DataFusionResult, QueryOptions};
+
+/// A query planned once with `$1`, `$2`, ... placeholders, executed with the values bound to them
+pub struct DataFusionStatement {
+    header: HandleHeader,
+    /// The SQL text of the query, None for deserialized plans
+    sql: Option<String>,
+    /// The planned query, still containing its placeholders
+    df: DataFrame,
+    /// Values bound to the parameters, None for parameters not bound yet
+    params: Vec<Option<ScalarValue>>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    log_filter: ContextLogFilter,
+    /// Limits of the context the statement was prepared with
+    options: QueryOptions,
+    /// Where executions are listed as running queries of the context
+    queries: Arc<QueryRegistry>,
+}
+
+impl_handle!(DataFusionStatement, 0x4446_5354_4d54_000b, statements);
+
+impl DataFusionStatement {
+    /// A statement for a planned query, with the limits of the context it is prepared with
+    fn new(ctx: &DataFusionContext, sql: Option<&str>, df: DataFrame) -> FfiResult<Self> {
+        Ok(Self {
+            header: HandleHeader::default(),
+            sql: sql.map(str::to_string),
+            params: vec![None; parameter_count(&df)?],
+            df,
+            runtime: ctx.runtime.clone(),
+            log_filter: ctx.log_filter.clone(),
+            options: ctx.query_options(),
+            queries: ctx.queries.clone(),
+        })
+    }
+
+    fn bind(&mut self, index: c_int, value: ScalarValue) -> FfiResult<()> {
+        let count = self.params.len();
+        let param = usize::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|index| self.params.get_mut(index))
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "parameter index {index} out of range (statement has {count} parameters)"
+                ))
+            })?;
+        *param = Some(value);
+        Ok(())
+    }
+
+    fn execute(&self) -> FfiResult<DataFusionResult> {
+        let values = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                value
+                    .clone()
+                    .ok_or_else(|| DataFusionError::invalid_argument(format!("parameter ${} is not bound", index + 1)))
+            })
+            .collect::<FfiResult<Vec<_>>>()?;
+        let df = self.df.clone().with_param_values(values)?;
+
+        let _log_filter = logging::enter_context_filter(&self.log_filter);
+        let query = self.queries.execute(self.sql.as_deref(), async { Ok(df) }, self.options.clone());
+        self.runtime.block_on(query)
+    }
+}
+
+/// A statement executing `plan`, a query or a statement writing data such as INSERT INTO or COPY.
+/// DDL and other statements are rejected: DataFusion would run them as they are planned, and run
+/// them only once.
+fn prepared(ctx: &DataFusionContext, sql: Option<&str>, plan: LogicalPlan) -> FfiResult<DataFusionStatement> {
+    ctx.sql_options.verify_plan(&plan)?;
+    let statement = match &plan {
+        LogicalPlan::Ddl(ddl) => Some(ddl.name()),
+        LogicalPlan::Statement(statement) => Some(statement.name()),
+        _ => None,
+    };
+    if let Some(statement) = statement {
+        return Err(DataFusionError::invalid_argument(format!(
+            "only queries, INSERT INTO and COPY can be prepared, not a {statement} statement"
+        )));
+    }
+    DataFusionStatement::new(ctx, sql, DataFrame::new(ctx.ctx.state(), plan))
+}
+
+/// The number of positional parameters of a planned query: the highest `$n` placeholder it uses
+fn parameter_count(df: &DataFrame) -> FfiResult<usize> {
+    let mut count = 0;
+    for name in df.logical_plan().get_parameter_names()? {
+        let position = name[1..]
+            .parse::<usize>()
+            .ok()
+            .filter(|&position| position > 0)
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "unsupported placeholder {name}, only positional placeholders like $1 are supported"
+                ))
+            })?;
+        count = count.max(position);
+    }
+    Ok(count)
+}
+
+/// Parse and plan a SQL query with positional placeholders (`$1`, `$2`, ...) for values bound later
+/// with the datafusion_stmt_bind_* functions, so values never have to be spliced into the SQL text.
+/// The statement can be executed any number of times, with bindings kept between executions. It
+/// uses the context's maximum result size and query timeout as they are when it is prepared, and
+/// stays valid after the context is freed. INSERT INTO and COPY statements can be prepared too, and
+/// write their rows each time they are executed; DDL statements such as CREATE TABLE cannot.
+/// Returns a pointer to the statement or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_prepare(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionStatement {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let plan = ctx.block_on(ctx.ctx.state().create_logical_plan(sql_str))?;
+        Ok(Box::new(prepared(ctx, Some(sql_str), plan)?))
+    })
+}
+
+/// Get the number of parameters of a statement, so the valid indexes are 1 to the count
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_parameter_count(
+    stmt: *const DataFusionStatement,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || Ok(handle_ref(stmt, "stmt")?.params.len() as c_int))
+}
+
+/// Bind an integer to the parameter at `index`, counting from 1 like `$1`
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_bind_int64(
+    stmt: *mut DataFusionStatement,
+    index: c_int,
+    value: i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || handle_mut(stmt, "stmt")?.bind(index, ScalarValue::Int64(Some(value))))
+}
+
+/// Bind a double to the parameter at `index`, counting from 1 like `$1`
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_bind_double(
+    stmt: *mut DataFusionStatement,
+    index: c_int,
+    value: f64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || handle_mut(stmt, "stmt")?.bind(index, ScalarValue::Float64(Some(value))))
+}
+
+/// Bind a copy of a UTF-8 string to the parameter at `index`, counting from 1 like `$1`
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_bind_string(
+    stmt: *mut DataFusionStatement,
+    index: c_int,
+    value: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let stmt = handle_mut(stmt, "stmt")?;
+        let value = str_arg(value, "value")?;
+        stmt.bind(index, ScalarValue::Utf8(Some(value.to_string())))
+    })
+}
+
+/// Bind SQL NULL to the parameter at `index`, counting from 1 like `$1`
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_bind_null(
+    stmt: *mut DataFusionStatement,
+    index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || handle_mut(stmt, "stmt")?.bind(index, ScalarValue::Null))
+}
+
+/// Execute a statement with its current bindings. Every parameter must be bound.
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_execute(
+    stmt: *const DataFusionStatement,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || Ok(Box::new(handle_ref(stmt, "stmt")?.execute()?)))
+}
+
+/// Serialize the logical plan of a statement, including its placeholders but not its bindings, with
+/// DataFusion's protobuf format, to be cached or sent to another process linking this library and
+/// loaded with datafusion_plan_deserialize. Plans reading tables created in memory, such as with
+/// CREATE TABLE AS, cannot be serialized; plans reading files registered as tables can.
+/// `out` is set to a buffer of `len` bytes to be freed with datafusion_buffer_free.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_plan_serialize(
+    stmt: *const DataFusionStatement,
+    out: *mut *mut u8,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let stmt = handle_ref(stmt, "stmt")?;
+        let bytes = logical_plan_to_bytes(stmt.df.logical_plan())?;
+        write_buffer(bytes.to_vec(), out, len)
+    })
+}
+
+/// Load a logical plan serialized by datafusion_plan_serialize as a statement of the context, ready
+/// to be bound and executed like a prepared statement. Tables read by the plan are restored from
+/// their serialized definitions rather than looked up in the context.
+/// Returns a pointer to the statement or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_plan_deserialize(
+    ctx: *const DataFusionContext,
+    plan: *const u8,
+    len: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionStatement {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let bytes = slice_arg(plan, len, "plan")?;
+
+        let plan = logical_plan_from_bytes(bytes, &ctx.ctx)?;
+        Ok(Box::new(prepared(ctx, None, plan)?))
+    })
+}
+
+/// Free a prepared statement
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_stmt_free(stmt: *mut DataFusionStatement) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(stmt, "stmt"))
+}
@@ -0,0 +1,156 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::util::display::{ArrayFormatter, FormatOptions};
+use datafusion::common::ScalarValue;
+use datafusion::functions_aggregate::min_max::{MaxAccumulator, MinAccumulator};
+use datafusion::logical_expr::Accumulator;
+
+use crate::error::{ffi_code, ffi_value, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::values::write_out;
+use crate::{DataFusionResult, DATAFUSION_NULL_VALUE, DATAFUSION_TYPE_MISMATCH};
+
+impl DataFusionResult {
+    /// The arrays of a column, one per batch
+    fn column_arrays(&self, column_index: c_int) -> FfiResult<Vec<ArrayRef>> {
+        self.field(column_index)?;
+        let column = column_index as usize;
+        Ok(self.batches.iter().map(|batch| batch.column(column).clone()).collect())
+    }
+
+    /// The smallest and largest non-null values of a column, which are null if it has none
+    fn min_max(&self, column_index: c_int) -> FfiResult<(ScalarValue, ScalarValue)> {
+        let arrays = self.column_arrays(column_index)?;
+        let data_type = self.field(column_index)?.data_type();
+        let mut min = MinAccumulator::try_new(data_type)?;
+        let mut max = MaxAccumulator::try_new(data_type)?;
+        for array in &arrays {
+            min.update_batch(std::slice::from_ref(array))?;
+            max.update_batch(std::slice::from_ref(array))?;
+        }
+        Ok((min.evaluate()?, max.evaluate()?))
+    }
+}
+
+/// Cast a min or max value to `target`, if the column type is accepted
+fn cast_bound(
+    value: ScalarValue,
+    accepts: fn(&DataType) -> bool,
+    target: &DataType,
+    expected: &str,
+) -> FfiResult<ScalarValue> {
+    if !accepts(&value.data_type()) {
+        return Err(DataFusionError::new(
+            DATAFUSION_TYPE_MISMATCH,
+            format!("column has type {}, not {expected}", value.data_type()),
+        ));
+    }
+    if value.is_null() {
+        return Err(DataFusionError::new(DATAFUSION_NULL_VALUE, "column has no non-null values"));
+    }
+    Ok(value.cast_to(target)?)
+}
+
+/// Format a min or max value the way datafusion_result_get_display formats cells, or return
+/// null if the column has no non-null values
+fn bound_string(value: ScalarValue) -> FfiResult<*mut c_char> {
+    if value.is_null() {
+        return Ok(ptr::null_mut());
+    }
+    let array = value.to_array()?;
+    let formatted = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?.value(0).to_string();
+    Ok(CString::new(formatted)?.into_raw())
+}
+
+/// Get the number of null values in a column across all batches
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_null_count(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> i64 {
+    ffi_value(error_out, -1, || {
+        let arrays = handle_ref(result, "result")?.column_arrays(column_index)?;
+        Ok(arrays.iter().map(|array| array.logical_null_count()).sum::<usize>() as i64)
+    })
+}
+
+/// Get the smallest and largest values of an integer column across all batches
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the column has no non-null values,
+/// DATAFUSION_TYPE_MISMATCH if the column is not an integer column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_min_max_int64(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    min: *mut i64,
+    max: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let (lo, hi) = handle_ref(result, "result")?.min_max(column_index)?;
+        let bound = |value| match cast_bound(value, DataType::is_integer, &DataType::Int64, "an integer")? {
+            ScalarValue::Int64(Some(value)) => Ok(value),
+            other => Err(DataFusionError::new(DATAFUSION_TYPE_MISMATCH, format!("unexpected value {other}"))),
+        };
+        write_out(min, bound(lo)?)?;
+        write_out(max, bound(hi)?)
+    })
+}
+
+/// Get the smallest and largest values of a numeric column (integer, floating point or decimal)
+/// across all batches, converted to double
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the column has no non-null values,
+/// DATAFUSION_TYPE_MISMATCH if the column is not numeric, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_min_max_float64(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    min: *mut f64,
+    max: *mut f64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let (lo, hi) = handle_ref(result, "result")?.min_max(column_index)?;
+        let bound = |value| match cast_bound(value, DataType::is_numeric, &DataType::Float64, "numeric")? {
+            ScalarValue::Float64(Some(value)) => Ok(value),
+            other => Err(DataFusionError::new(DATAFUSION_TYPE_MISMATCH, format!("unexpected value {other}"))),
+        };
+        write_out(min, bound(lo)?)?;
+        write_out(max, bound(hi)?)
+    })
+}
+
+/// Get the smallest value of a column of any orderable type across all batches, formatted like
+/// datafusion_result_get_display
+/// Returns a string to be freed with datafusion_string_free, or null if the column has no
+/// non-null values or on error (check error_out to tell them apart)
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_min(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_value(error_out, ptr::null_mut(), || {
+        bound_string(handle_ref(result, "result")?.min_max(column_index)?.0)
+    })
+}
+
+/// Get the largest value of a column of any orderable type across all batches, formatted like
+/// datafusion_result_get_display
+/// Returns a string to be freed with datafusion_string_free, or null if the column has no
+/// non-null values or on error (check error_out to tell them apart)
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_max(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_value(error_out, ptr::null_mut(), || {
+        bound_string(handle_ref(result, "result")?.min_max(column_index)?.1)
+    })
+}
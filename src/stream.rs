@@ -0,0 +1,146 @@
+use std::future::Future;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::{self, ExecutionPlan};
+use datafusion::prelude::DataFrame;
+use futures::StreamExt;
+
+use crate::cancel::{self, Cancellation};
+use crate::error::{ffi_code, ffi_ptr, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_mut, handle_ref, impl_handle, into_handle, HandleHeader};
+use crate::logging::{self, ContextLogFilter};
+use crate::registry::{QueryRegistry, Registration};
+use crate::schema::DataFusionSchema;
+use crate::{DataFusionContext, DataFusionResult};
+
+/// A query whose batches are produced one at a time as the caller asks for them
+pub struct DataFusionStream {
+    header: HandleHeader,
+    stream: SendableRecordBatchStream,
+    plan: Arc<dyn ExecutionPlan>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    log_filter: ContextLogFilter,
+    /// Stops the stream when it is killed with datafusion_query_kill
+    cancellation: Cancellation,
+    /// Lists the stream as a running query of its context until it is freed
+    _registration: Registration,
+}
+
+impl_handle!(DataFusionStream, 0x4446_5354_524d_0009, streams);
+
+impl DataFusionStream {
+    /// Start executing the DataFrame produced by `df` on `runtime`, listed as a running query in
+    /// `queries` until the stream is freed
+    pub(crate) fn start(
+        sql: Option<&str>,
+        df: impl Future<Output = FfiResult<DataFrame>>,
+        runtime: &Arc<tokio::runtime::Runtime>,
+        log_filter: &ContextLogFilter,
+        queries: &Arc<QueryRegistry>,
+    ) -> FfiResult<Self> {
+        let cancellation = Cancellation::default();
+        let registration = queries.register(sql, &cancellation);
+        let (stream, plan) = {
+            let _log_filter = logging::enter_context_filter(log_filter);
+            runtime.block_on(async {
+                let df = df.await?;
+                let task_ctx = Arc::new(df.task_ctx());
+                let plan = cancel::cancellable(df.create_physical_plan().await?, &cancellation)?;
+                let stream = physical_plan::execute_stream(plan.clone(), task_ctx)?;
+                Ok::<_, DataFusionError>((stream, plan))
+            })?
+        };
+
+        Ok(Self {
+            header: HandleHeader::default(),
+            stream,
+            plan,
+            runtime: runtime.clone(),
+            log_filter: log_filter.clone(),
+            cancellation,
+            _registration: registration,
+        })
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+}
+
+/// Plan a SQL query and start executing it, without collecting its results. Batches are computed
+/// as they are fetched with datafusion_stream_next, so results of any size can be consumed in
+/// bounded memory; the context's maximum result size does not apply. The stream keeps running
+/// on the context's runtime and stays valid after the context is freed. It is listed as a running
+/// query of the context until it is freed; once killed, fetching fails with DATAFUSION_CANCELLED.
+/// Returns a pointer to the stream or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_stream(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionStream {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let df = async { Ok(ctx.ctx.sql_with_options(sql_str, ctx.sql_options).await?) };
+        Ok(Box::new(DataFusionStream::start(
+            Some(sql_str),
+            df,
+            &ctx.runtime,
+            &ctx.log_filter,
+            &ctx.queries,
+        )?))
+    })
+}
+
+/// Fetch the next batch of a stream as a result with a single batch, to be freed with
+/// datafusion_result_free. Metrics of the result cover the stream's execution so far.
+/// Returns a pointer to the result, or null when the stream is exhausted or on error
+/// (check error_out to tell them apart)
+#[no_mangle]
+pub extern "C" fn datafusion_stream_next(
+    stream: *mut DataFusionStream,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_value(error_out, ptr::null_mut(), || {
+        let stream = handle_mut(stream, "stream")?;
+        let next = {
+            let _log_filter = logging::enter_context_filter(&stream.log_filter);
+            stream.runtime.block_on(stream.stream.next())
+        };
+        let batch = match next {
+            None => return Ok(ptr::null_mut()),
+            Some(Err(_)) if stream.cancellation.is_cancelled() => return Err(stream.cancellation.error()),
+            Some(batch) => batch?,
+        };
+        Ok(into_handle(Box::new(DataFusionResult {
+            header: HandleHeader::default(),
+            batches: vec![batch],
+            schema: stream.schema(),
+            plan: Some(stream.plan.clone()),
+            partitions: None,
+        })))
+    })
+}
+
+/// Get the schema of the batches produced by a stream
+/// Returns a pointer to the schema or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_stream_schema(
+    stream: *const DataFusionStream,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionSchema {
+    ffi_ptr(error_out, || Ok(DataFusionSchema::new(handle_ref(stream, "stream")?.schema())))
+}
+
+/// Free a stream, stopping the query if it has not finished
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_stream_free(stream: *mut DataFusionStream) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(stream, "stream"))
+}
@@ -0,0 +1,67 @@
+use std::os::raw::{c_char, c_int};
+
+use datafusion_substrait::logical_plan::consumer::from_substrait_plan;
+use datafusion_substrait::logical_plan::producer::to_substrait_plan;
+use datafusion_substrait::serializer::deserialize_bytes;
+use prost::Message;
+
+use crate::error::{ffi_code, ffi_ptr, slice_arg, str_arg, FfiResult};
+use crate::export::write_buffer;
+use crate::handle::handle_ref;
+use crate::{DataFusionContext, DataFusionResult};
+
+/// Execute a Substrait plan, serialized as `len` bytes of protobuf, against the tables registered
+/// with the context. Table references in the plan are resolved by name like in SQL queries. The
+/// plan is executed like datafusion_sql, with the context's maximum result size and query timeout.
+/// Returns a pointer to the result or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_execute_substrait(
+    ctx: *const DataFusionContext,
+    plan: *const u8,
+    len: usize,
+    error_out: *mut *mut c_char,
+) -> *mut DataFusionResult {
+    ffi_ptr(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let bytes = slice_arg(plan, len, "plan")?.to_vec();
+
+        let session = ctx.ctx.clone();
+        let sql_options = ctx.sql_options;
+        let df = async move {
+            let plan = deserialize_bytes(bytes).await?;
+            let plan = from_substrait_plan(&session.state(), &plan).await?;
+            sql_options.verify_plan(&plan)?;
+            Ok(session.execute_logical_plan(plan).await?)
+        };
+        let query = ctx.queries.execute(None, df, ctx.query_options());
+        Ok(Box::new(ctx.block_on(query)?))
+    })
+}
+
+/// Plan a SQL query without executing it and serialize its optimized logical plan as a Substrait
+/// plan in protobuf form, for execution by another engine or with datafusion_execute_substrait.
+/// `out` is set to a buffer of `len` bytes to be freed with datafusion_buffer_free.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_sql_to_substrait(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    out: *mut *mut u8,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        // Planned rather than run with SessionContext::sql, which would run DDL
+        let state = ctx.ctx.state();
+        let plan = ctx.block_on(async {
+            let plan = state.create_logical_plan(sql_str).await?;
+            ctx.sql_options.verify_plan(&plan)?;
+            FfiResult::Ok(state.optimize(&plan)?)
+        })?;
+        let bytes = to_substrait_plan(&plan, &state)?.encode_to_vec();
+        write_buffer(bytes, out, len)
+    })
+}
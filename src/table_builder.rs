@@ -0,0 +1,250 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, UInt64Array};
+use datafusion::arrow::buffer::{BooleanBuffer, Buffer, NullBuffer, OffsetBuffer, ScalarBuffer};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::dataframe::DataFrameWriteOptions;
+use datafusion::datasource::MemTable;
+
+use crate::error::{ffi_code, ffi_ptr, slice_arg, str_arg, DataFusionError, FfiResult};
+use crate::handle::{free_handle, handle_mut, handle_ref, impl_handle, HandleHeader};
+use crate::register::table_name_arg;
+use crate::values::write_out;
+use crate::DataFusionContext;
+
+/// Columns copied from C arrays, to register as an in-memory table without an Arrow library
+pub struct DataFusionTableBuilder {
+    header: HandleHeader,
+    fields: Vec<Field>,
+    columns: Vec<ArrayRef>,
+}
+
+impl_handle!(DataFusionTableBuilder, 0x4446_5442_4c44_000f, table_builders);
+
+impl DataFusionTableBuilder {
+    fn add(&mut self, name: &str, column: ArrayRef) -> FfiResult<()> {
+        if let Some(first) = self.columns.first() {
+            if column.len() != first.len() {
+                return Err(DataFusionError::invalid_argument(format!(
+                    "column {name} has {} rows, but the columns before it have {}",
+                    column.len(),
+                    first.len()
+                )));
+            }
+        }
+        if self.fields.iter().any(|field| field.name() == name) {
+            return Err(DataFusionError::invalid_argument(format!("duplicate column {name}")));
+        }
+        self.fields.push(Field::new(name, column.data_type().clone(), true));
+        self.columns.push(column);
+        Ok(())
+    }
+}
+
+/// Copy a validity bitmap of `len` values, one bit per value from the least significant bit of the
+/// first byte, set for valid values. Null means all values are valid.
+fn validity_arg(validity: *const u8, len: usize) -> FfiResult<Option<NullBuffer>> {
+    if validity.is_null() {
+        return Ok(None);
+    }
+    let bitmap = Buffer::from_slice_ref(slice_arg(validity, len.div_ceil(8), "validity")?);
+    Ok(Some(NullBuffer::new(BooleanBuffer::new(bitmap, 0, len))))
+}
+
+/// Add a column copied from `len` values of a C array to a builder, with the nulls of `validity`
+fn add_column(
+    builder: *mut DataFusionTableBuilder,
+    name: *const c_char,
+    len: usize,
+    validity: *const u8,
+    error_out: *mut *mut c_char,
+    column: impl FnOnce(Option<NullBuffer>) -> FfiResult<ArrayRef>,
+) -> c_int {
+    ffi_code(error_out, || {
+        let builder = handle_mut(builder, "builder")?;
+        let name = str_arg(name, "name")?;
+        let column = column(validity_arg(validity, len)?)?;
+        builder.add(name, column)
+    })
+}
+
+/// Create a builder of an in-memory table, to add columns to from C arrays
+/// Returns a pointer to the builder or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_new(error_out: *mut *mut c_char) -> *mut DataFusionTableBuilder {
+    ffi_ptr(error_out, || {
+        Ok(Box::new(DataFusionTableBuilder {
+            header: HandleHeader::default(),
+            fields: Vec::new(),
+            columns: Vec::new(),
+        }))
+    })
+}
+
+/// Add an Int64 column of `len` values, copied from `values`. `validity` is a bitmap of `len`
+/// bits, set for the valid values from the least significant bit of its first byte as in Arrow, or
+/// null if all values are valid. Every column of a table has the same number of values.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_add_int64(
+    builder: *mut DataFusionTableBuilder,
+    name: *const c_char,
+    values: *const i64,
+    validity: *const u8,
+    len: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    add_column(builder, name, len, validity, error_out, |nulls| {
+        let values = ScalarBuffer::from(slice_arg(values, len, "values")?.to_vec());
+        Ok(Arc::new(Int64Array::new(values, nulls)))
+    })
+}
+
+/// Add a Float64 column of `len` values, copied from `values`, with the nulls of `validity` as in
+/// datafusion_table_builder_add_int64
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_add_float64(
+    builder: *mut DataFusionTableBuilder,
+    name: *const c_char,
+    values: *const f64,
+    validity: *const u8,
+    len: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    add_column(builder, name, len, validity, error_out, |nulls| {
+        let values = ScalarBuffer::from(slice_arg(values, len, "values")?.to_vec());
+        Ok(Arc::new(Float64Array::new(values, nulls)))
+    })
+}
+
+/// Add a Boolean column of `len` values, copied from an array of `bool`, with the nulls of
+/// `validity` as in datafusion_table_builder_add_int64
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_add_bool(
+    builder: *mut DataFusionTableBuilder,
+    name: *const c_char,
+    values: *const bool,
+    validity: *const u8,
+    len: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    add_column(builder, name, len, validity, error_out, |nulls| {
+        let values = BooleanBuffer::from_iter(slice_arg(values, len, "values")?.iter().copied());
+        Ok(Arc::new(BooleanArray::new(values, nulls)))
+    })
+}
+
+/// Add a Utf8 column of `len` strings, copied from Arrow's layout: the bytes of string `i` are
+/// `data[offsets[i]..offsets[i + 1]]`, so `offsets` has `len + 1` non-decreasing entries and `data`
+/// holds `offsets[len]` bytes of UTF-8 (it may be null if there are none). Nulls are given by
+/// `validity` as in datafusion_table_builder_add_int64.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_add_string(
+    builder: *mut DataFusionTableBuilder,
+    name: *const c_char,
+    offsets: *const i32,
+    data: *const u8,
+    validity: *const u8,
+    len: usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    add_column(builder, name, len, validity, error_out, |nulls| {
+        let offsets = slice_arg(offsets, len + 1, "offsets")?;
+        if offsets[0] < 0 || offsets.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(DataFusionError::invalid_argument("offsets must be non-negative and non-decreasing"));
+        }
+        let data = slice_arg(data, offsets[len] as usize, "data")?;
+        let offsets = OffsetBuffer::new(ScalarBuffer::from(offsets.to_vec()));
+        Ok(Arc::new(StringArray::try_new(offsets, Buffer::from_slice_ref(data), nulls)?))
+    })
+}
+
+/// Register the columns added to a builder as an in-memory table with the context. The builder
+/// keeps its columns: more can be added to register another table.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_register(
+    builder: *const DataFusionTableBuilder,
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let builder = handle_ref(builder, "builder")?;
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = table_name_arg(ctx, table_name)?;
+        if builder.columns.is_empty() {
+            return Err(DataFusionError::invalid_argument("builder has no columns"));
+        }
+
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(builder.fields.clone())), builder.columns.clone())?;
+        ctx.ctx.register_table(table_name, Arc::new(MemTable::try_new(batch.schema(), vec![vec![batch]])?))?;
+        Ok(())
+    })
+}
+
+/// Append the rows of the columns added to a builder to a registered table that accepts inserts,
+/// such as the in-memory tables registered by datafusion_table_builder_register, like INSERT INTO
+/// does. Columns are matched to those of the table by name and cast to their types; every column
+/// of the table needs a column in the builder. The number of rows inserted is stored in
+/// `rows_inserted` if it is not null. The builder keeps its columns.
+/// Returns DATAFUSION_OK on success or an error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_insert(
+    builder: *const DataFusionTableBuilder,
+    ctx: *const DataFusionContext,
+    table_name: *const c_char,
+    rows_inserted: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let builder = handle_ref(builder, "builder")?;
+        let ctx = handle_ref(ctx, "ctx")?;
+        let table_name = str_arg(table_name, "table_name")?;
+        if !ctx.ctx.table_exist(table_name)? {
+            return Err(DataFusionError::invalid_argument(format!("no table named {table_name} is registered")));
+        }
+
+        let schema = ctx.block_on(ctx.ctx.table_provider(table_name))?.schema();
+        if let Some(field) = builder.fields.iter().find(|field| schema.field_with_name(field.name()).is_err()) {
+            return Err(DataFusionError::invalid_argument(format!("table {table_name} has no column {}", field.name())));
+        }
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let index = builder.fields.iter().position(|column| column.name() == field.name()).ok_or_else(|| {
+                    DataFusionError::invalid_argument(format!("builder has no column {} of table {table_name}", field.name()))
+                })?;
+                Ok(cast(&builder.columns[index], field.data_type())?)
+            })
+            .collect::<FfiResult<Vec<_>>>()?;
+        let batch = RecordBatch::try_new(schema, columns)?;
+
+        let df = ctx.ctx.read_batch(batch)?;
+        let counts = ctx.block_on(df.write_table(table_name, DataFrameWriteOptions::new()))?;
+        let count = counts
+            .iter()
+            .filter_map(|batch| batch.column(0).as_any().downcast_ref::<UInt64Array>())
+            .flat_map(|counts| counts.iter().flatten())
+            .sum::<u64>();
+        if rows_inserted.is_null() {
+            return Ok(());
+        }
+        write_out(rows_inserted, count as i64)
+    })
+}
+
+/// Free a table builder. Tables registered from it stay valid.
+/// Returns DATAFUSION_OK on success or DATAFUSION_INVALID_HANDLE if it was already freed
+#[no_mangle]
+pub extern "C" fn datafusion_table_builder_free(builder: *mut DataFusionTableBuilder) -> c_int {
+    ffi_code(ptr::null_mut(), || free_handle(builder, "builder"))
+}
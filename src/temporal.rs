@@ -0,0 +1,258 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use datafusion::arrow::array::{
+    Date32Array, Date64Array, IntervalDayTimeArray, IntervalMonthDayNanoArray, IntervalYearMonthArray,
+    Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray, Time64NanosecondArray,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
+};
+use datafusion::arrow::datatypes::{DataType, IntervalUnit, TimeUnit};
+
+use crate::error::{ffi_code, ffi_value, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::values::{write_out, Cell};
+use crate::{DataFusionResult, DATAFUSION_TYPE_MISMATCH};
+
+// Units of temporal values
+pub const DATAFUSION_TIME_UNIT_SECOND: c_int = 0;
+pub const DATAFUSION_TIME_UNIT_MILLISECOND: c_int = 1;
+pub const DATAFUSION_TIME_UNIT_MICROSECOND: c_int = 2;
+pub const DATAFUSION_TIME_UNIT_NANOSECOND: c_int = 3;
+pub const DATAFUSION_TIME_UNIT_DAY: c_int = 4;
+
+/// An interval value. Year-month intervals only set `months`; day-time intervals set `days` and
+/// `nanoseconds`, with their millisecond part converted to nanoseconds.
+#[repr(C)]
+#[derive(Default)]
+pub struct DataFusionInterval {
+    pub months: i32,
+    pub days: i32,
+    pub nanoseconds: i64,
+}
+
+fn time_unit(unit: &TimeUnit) -> c_int {
+    match unit {
+        TimeUnit::Second => DATAFUSION_TIME_UNIT_SECOND,
+        TimeUnit::Millisecond => DATAFUSION_TIME_UNIT_MILLISECOND,
+        TimeUnit::Microsecond => DATAFUSION_TIME_UNIT_MICROSECOND,
+        TimeUnit::Nanosecond => DATAFUSION_TIME_UNIT_NANOSECOND,
+    }
+}
+
+fn type_mismatch(cell: &Cell<'_>, expected: &str) -> DataFusionError {
+    DataFusionError::new(
+        DATAFUSION_TYPE_MISMATCH,
+        format!("column has type {}, not {expected}", cell.array.data_type()),
+    )
+}
+
+/// Read a temporal cell as an integer and its unit
+fn temporal_value(cell: &Cell<'_>, expected: &str) -> FfiResult<(i64, c_int)> {
+    let row = cell.row;
+    let value = match cell.array.data_type() {
+        DataType::Date32 => (
+            cell.value_array::<Date32Array>(expected)?.value(row) as i64,
+            DATAFUSION_TIME_UNIT_DAY,
+        ),
+        DataType::Date64 => (
+            cell.value_array::<Date64Array>(expected)?.value(row),
+            DATAFUSION_TIME_UNIT_MILLISECOND,
+        ),
+        DataType::Timestamp(unit, _) => {
+            let value = match unit {
+                TimeUnit::Second => cell.value_array::<TimestampSecondArray>(expected)?.value(row),
+                TimeUnit::Millisecond => cell.value_array::<TimestampMillisecondArray>(expected)?.value(row),
+                TimeUnit::Microsecond => cell.value_array::<TimestampMicrosecondArray>(expected)?.value(row),
+                TimeUnit::Nanosecond => cell.value_array::<TimestampNanosecondArray>(expected)?.value(row),
+            };
+            (value, time_unit(unit))
+        }
+        DataType::Time32(unit) => {
+            let value = match unit {
+                TimeUnit::Second => cell.value_array::<Time32SecondArray>(expected)?.value(row),
+                _ => cell.value_array::<Time32MillisecondArray>(expected)?.value(row),
+            };
+            (value as i64, time_unit(unit))
+        }
+        DataType::Time64(unit) => {
+            let value = match unit {
+                TimeUnit::Microsecond => cell.value_array::<Time64MicrosecondArray>(expected)?.value(row),
+                _ => cell.value_array::<Time64NanosecondArray>(expected)?.value(row),
+            };
+            (value, time_unit(unit))
+        }
+        _ => return Err(type_mismatch(cell, expected)),
+    };
+    Ok(value)
+}
+
+/// Read a temporal cell, checking that its column is of one of the accepted kinds
+fn get_temporal(
+    cell: Cell<'_>,
+    accepts: fn(&DataType) -> bool,
+    expected: &str,
+    value: *mut i64,
+    unit: *mut c_int,
+) -> FfiResult<()> {
+    if !accepts(cell.array.data_type()) {
+        return Err(type_mismatch(&cell, expected));
+    }
+    let (v, u) = temporal_value(&cell, expected)?;
+    write_out(value, v)?;
+    write_out(unit, u)
+}
+
+/// Get a date value from a Date32 or Date64 result cell. `value` is set to the number of days
+/// (Date32, DATAFUSION_TIME_UNIT_DAY) or milliseconds (Date64, DATAFUSION_TIME_UNIT_MILLISECOND)
+/// since the Unix epoch and `unit` to its unit.
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not a date column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_date(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    value: *mut i64,
+    unit: *mut c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let accepts = |t: &DataType| matches!(t, DataType::Date32 | DataType::Date64);
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        get_temporal(cell, accepts, "Date", value, unit)
+    })
+}
+
+/// Get a timestamp value from a result cell. `value` is set to the time since the Unix epoch in UTC
+/// and `unit` to its unit (one of DATAFUSION_TIME_UNIT_SECOND through _NANOSECOND). The column's
+/// timezone is available from datafusion_result_column_timezone.
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not a timestamp column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_timestamp(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    value: *mut i64,
+    unit: *mut c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let accepts = |t: &DataType| matches!(t, DataType::Timestamp(_, _));
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        get_temporal(cell, accepts, "Timestamp", value, unit)
+    })
+}
+
+/// Get a time of day value from a Time32 or Time64 result cell. `value` is set to the time since
+/// midnight and `unit` to its unit.
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not a time column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_time(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    value: *mut i64,
+    unit: *mut c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let accepts = |t: &DataType| matches!(t, DataType::Time32(_) | DataType::Time64(_));
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        get_temporal(cell, accepts, "Time", value, unit)
+    })
+}
+
+/// Get an interval value from a result cell of any interval unit
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not an interval column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_interval(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    out: *mut DataFusionInterval,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        const EXPECTED: &str = "Interval";
+        let interval = match cell.array.data_type() {
+            DataType::Interval(IntervalUnit::YearMonth) => DataFusionInterval {
+                months: cell.value_array::<IntervalYearMonthArray>(EXPECTED)?.value(cell.row),
+                ..Default::default()
+            },
+            DataType::Interval(IntervalUnit::DayTime) => {
+                let value = cell.value_array::<IntervalDayTimeArray>(EXPECTED)?.value(cell.row);
+                DataFusionInterval {
+                    months: 0,
+                    days: value.days,
+                    nanoseconds: value.milliseconds as i64 * 1_000_000,
+                }
+            }
+            DataType::Interval(IntervalUnit::MonthDayNano) => {
+                let value = cell.value_array::<IntervalMonthDayNanoArray>(EXPECTED)?.value(cell.row);
+                DataFusionInterval {
+                    months: value.months,
+                    days: value.days,
+                    nanoseconds: value.nanoseconds,
+                }
+            }
+            _ => return Err(type_mismatch(&cell, EXPECTED)),
+        };
+        write_out(out, interval)
+    })
+}
+
+/// Get the unit of a temporal column (date, timestamp, time or duration) as a DATAFUSION_TIME_UNIT_* constant
+/// Returns -1 on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_time_unit(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || {
+        let field = handle_ref(result, "result")?.field(column_index)?;
+        match field.data_type() {
+            DataType::Date32 => Ok(DATAFUSION_TIME_UNIT_DAY),
+            DataType::Date64 => Ok(DATAFUSION_TIME_UNIT_MILLISECOND),
+            DataType::Timestamp(unit, _)
+            | DataType::Time32(unit)
+            | DataType::Time64(unit)
+            | DataType::Duration(unit) => Ok(time_unit(unit)),
+            other => Err(DataFusionError::new(
+                DATAFUSION_TYPE_MISMATCH,
+                format!("column has type {other}, which has no time unit"),
+            )),
+        }
+    })
+}
+
+/// Get the timezone of a timestamp column, e.g. "UTC" or "+02:00"
+/// Returns a string to be freed with datafusion_string_free, or null if the column has no timezone
+/// or on error (check error_out to tell them apart)
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_timezone(
+    result: *const DataFusionResult,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_value(error_out, ptr::null_mut(), || {
+        let field = handle_ref(result, "result")?.field(column_index)?;
+        match field.data_type() {
+            DataType::Timestamp(_, Some(tz)) => Ok(CString::new(tz.as_ref())?.into_raw()),
+            DataType::Timestamp(_, None) => Ok(ptr::null_mut()),
+            other => Err(DataFusionError::new(
+                DATAFUSION_TYPE_MISMATCH,
+                format!("column has type {other}, not Timestamp"),
+            )),
+        }
+    })
+}
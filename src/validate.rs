@@ -0,0 +1,104 @@
+use std::os::raw::c_char;
+
+use datafusion::common::{DataFusionError as DFError, SchemaError};
+use serde_json::{json, Value};
+
+use crate::error::{ffi_string, str_arg};
+use crate::handle::handle_ref;
+use crate::DataFusionContext;
+
+/// The 1-based line and column of a parser error, which sqlparser appends to its messages as
+/// `at Line: 1, Column: 8`
+fn parser_position(message: &str) -> Option<(usize, usize)> {
+    let (_, position) = message.rsplit_once("Line: ")?;
+    let (line, column) = position.split_once(", Column: ")?;
+    let column = column.split(|c: char| !c.is_ascii_digit()).next()?;
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+/// The 1-based line and column of the first occurrence of `name` as a whole word in `sql`, ignoring
+/// case, to point at an unresolved identifier
+fn name_position(sql: &str, name: &str) -> Option<(usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let lower = sql.to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+    let offset = lower.match_indices(&name).map(|(offset, _)| offset).find(|&offset| {
+        !lower[..offset].ends_with(is_word) && !lower[offset + name.len()..].starts_with(is_word)
+    })?;
+    Some(end_position(&sql[..offset]))
+}
+
+/// The 1-based line and column just past the end of `sql`, where sqlparser reports no position for
+/// unexpected ends of input
+fn end_position(sql: &str) -> (usize, usize) {
+    let line = sql.matches('\n').count() + 1;
+    let column = sql.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+    (line, column)
+}
+
+/// Describe why a statement failed to plan, in the format of datafusion_sql_validate
+fn diagnostic(sql: &str, err: &DFError) -> Value {
+    let message = err.to_string();
+    let (kind, name, candidates) = match err.find_root() {
+        DFError::SQL(..) => ("syntax", None, Vec::new()),
+        DFError::SchemaError(SchemaError::FieldNotFound { field, valid_fields }, _) => (
+            "unknown_column",
+            Some(field.name.clone()),
+            valid_fields.iter().map(|column| column.name.clone()).collect(),
+        ),
+        DFError::Plan(plan) if plan.starts_with("table '") && plan.ends_with("' not found") => {
+            let table = &plan["table '".len()..plan.len() - "' not found".len()];
+            let name = table.rsplit('.').next().unwrap_or(table);
+            ("unknown_table", Some(name.to_string()), Vec::new())
+        }
+        _ => ("plan", None, Vec::new()),
+    };
+    let position = match &name {
+        Some(name) => name_position(sql, name),
+        None if kind == "syntax" && message.contains("found: EOF") => {
+            parser_position(&message).or(Some(end_position(sql)))
+        }
+        None => parser_position(&message),
+    };
+    json!({
+        "valid": false,
+        "kind": kind,
+        "message": message,
+        "line": position.map(|(line, _)| line),
+        "column": position.map(|(_, column)| column),
+        "name": name,
+        "candidates": candidates,
+    })
+}
+
+/// Parse, plan and analyze a SQL statement without executing it, not even DDL, and describe any
+/// problem as JSON: `{"valid": true}` for a valid statement, otherwise
+/// `{"valid": false, "kind", "message", "line", "column", "name", "candidates"}` where `kind` is
+/// `syntax`, `unknown_table`, `unknown_column` or `plan` (any other planning error, such as a type
+/// error). `line` and `column` are 1-based and point at the syntax error or the first occurrence
+/// of the unresolved `name`; they and `name` are null when unknown. `candidates` lists the columns
+/// available where an unknown column was referenced.
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_sql_validate(
+    ctx: *const DataFusionContext,
+    sql: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let ctx = handle_ref(ctx, "ctx")?;
+        let sql_str = str_arg(sql, "sql")?;
+
+        let state = ctx.ctx.state();
+        let planned = ctx.block_on(async {
+            let plan = state.create_logical_plan(sql_str).await?;
+            ctx.sql_options.verify_plan(&plan)?;
+            state.optimize(&plan)
+        });
+        Ok(match planned {
+            Ok(_) => json!({ "valid": true }),
+            Err(err) => diagnostic(sql_str, &err),
+        }
+        .to_string())
+    })
+}
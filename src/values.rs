@@ -0,0 +1,396 @@
+use std::os::raw::{c_char, c_int};
+
+use datafusion::arrow::array::{
+    downcast_dictionary_array, Array, BinaryArray, BinaryViewArray, BooleanArray,
+    FixedSizeBinaryArray, Float64Array, Int32Array, Int64Array, LargeBinaryArray, LargeStringArray,
+    StringArray, StringViewArray,
+};
+use datafusion::arrow::datatypes::{ArrowNativeType, DataType};
+use datafusion::arrow::util::display::{ArrayFormatter, FormatOptions};
+
+use crate::error::{ffi_code, ffi_string, ffi_value, str_arg, DataFusionError, FfiResult};
+use crate::handle::handle_ref;
+use crate::{DataFusionResult, DATAFUSION_NULL_VALUE, DATAFUSION_TYPE_MISMATCH};
+
+/// A single cell of a result, validated to be in range. Cells of dictionary encoded columns refer
+/// to the dictionary values, so accessors see the decoded value type.
+pub(crate) struct Cell<'a> {
+    pub(crate) array: &'a dyn Array,
+    pub(crate) row: usize,
+    null: bool,
+}
+
+/// Follow dictionary keys to the array and row holding the value. The key of a null slot may
+/// point anywhere, so the value must not be read for null cells.
+fn decode_dictionary(array: &dyn Array, row: usize) -> (&dyn Array, usize) {
+    downcast_dictionary_array!(
+        array => decode_dictionary(array.values().as_ref(), array.keys().value(row).as_usize()),
+        _ => (array, row)
+    )
+}
+
+impl DataFusionResult {
+    pub(crate) fn cell(&self, batch_index: c_int, row_index: c_int, column_index: c_int) -> FfiResult<Cell<'_>> {
+        let batch = self.batch(batch_index)?;
+        self.field(column_index)?;
+        let array = batch.column(column_index as usize);
+        let row = usize::try_from(row_index)
+            .ok()
+            .filter(|row| *row < batch.num_rows())
+            .ok_or_else(|| {
+                DataFusionError::invalid_argument(format!(
+                    "row index {row_index} out of range for batch with {} rows",
+                    batch.num_rows()
+                ))
+            })?;
+        Ok(Cell::new(array.as_ref(), row))
+    }
+
+    /// Look up the index of a column by name
+    pub(crate) fn column_index(&self, column_name: *const c_char) -> FfiResult<c_int> {
+        let name = str_arg(column_name, "column_name")?;
+        let index = self
+            .schema
+            .index_of(name)
+            .map_err(|_| DataFusionError::invalid_argument(format!("result has no column named '{name}'")))?;
+        Ok(index as c_int)
+    }
+}
+
+impl<'a> Cell<'a> {
+    /// The cell at `row` of `array`, which must be in range
+    pub(crate) fn new(array: &'a dyn Array, row: usize) -> Self {
+        // Logical nulls account for both null keys and null values of dictionaries
+        let null = array.logical_nulls().is_some_and(|nulls| nulls.is_null(row));
+        let (array, row) = decode_dictionary(array, row);
+        Cell { array, row, null }
+    }
+
+    /// Whether the value is logically null, including in Null, dictionary and run-end encoded columns
+    pub(crate) fn is_null(&self) -> bool {
+        self.null
+    }
+
+    /// Downcast the column to the expected array type, then reject null values
+    pub(crate) fn value_array<A: Array + 'static>(&self, expected: &str) -> FfiResult<&A> {
+        let array = self.array.as_any().downcast_ref::<A>().ok_or_else(|| {
+            DataFusionError::new(
+                DATAFUSION_TYPE_MISMATCH,
+                format!("column has type {}, not {expected}", self.array.data_type()),
+            )
+        })?;
+        if self.is_null() {
+            return Err(DataFusionError::new(
+                DATAFUSION_NULL_VALUE,
+                format!("value at row {} is null", self.row),
+            ));
+        }
+        Ok(array)
+    }
+
+    /// Borrow a string value from any of the Arrow string column types
+    pub(crate) fn str_value(&self) -> FfiResult<&str> {
+        const EXPECTED: &str = "Utf8";
+        match self.array.data_type() {
+            DataType::LargeUtf8 => Ok(self.value_array::<LargeStringArray>(EXPECTED)?.value(self.row)),
+            DataType::Utf8View => Ok(self.value_array::<StringViewArray>(EXPECTED)?.value(self.row)),
+            _ => Ok(self.value_array::<StringArray>(EXPECTED)?.value(self.row)),
+        }
+    }
+
+    /// Borrow a binary value from any of the Arrow binary column types
+    pub(crate) fn binary_value(&self) -> FfiResult<&[u8]> {
+        const EXPECTED: &str = "Binary";
+        match self.array.data_type() {
+            DataType::LargeBinary => Ok(self.value_array::<LargeBinaryArray>(EXPECTED)?.value(self.row)),
+            DataType::FixedSizeBinary(_) => Ok(self.value_array::<FixedSizeBinaryArray>(EXPECTED)?.value(self.row)),
+            DataType::BinaryView => Ok(self.value_array::<BinaryViewArray>(EXPECTED)?.value(self.row)),
+            _ => Ok(self.value_array::<BinaryArray>(EXPECTED)?.value(self.row)),
+        }
+    }
+}
+
+/// Write a value to an output pointer, rejecting null pointers. The previous contents are
+/// overwritten without being dropped, so `out` may point to uninitialized memory.
+pub(crate) fn write_out<T>(out: *mut T, value: T) -> FfiResult<()> {
+    if out.is_null() {
+        return Err(DataFusionError::invalid_argument("out must not be null"));
+    }
+    unsafe { out.write(value) };
+    Ok(())
+}
+
+/// Get the index of the column with the given name
+/// Returns -1 on error, including when there is no such column
+#[no_mangle]
+pub extern "C" fn datafusion_result_column_index(
+    result: *const DataFusionResult,
+    column_name: *const c_char,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_value(error_out, -1, || handle_ref(result, "result")?.column_index(column_name))
+}
+
+/// Check whether a result cell is null, for columns of any type
+/// Returns false on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_is_null(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> bool {
+    ffi_value(error_out, false, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        Ok(cell.is_null())
+    })
+}
+
+/// Get an Int64 value from a result cell
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not Int64, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_int64(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    out: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        write_out(out, cell.value_array::<Int64Array>("Int64")?.value(cell.row))
+    })
+}
+
+/// Get an Int32 value from a result cell
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not Int32, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_int32(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    out: *mut i32,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        write_out(out, cell.value_array::<Int32Array>("Int32")?.value(cell.row))
+    })
+}
+
+/// Get a Float64 value from a result cell
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not Float64, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_float64(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    out: *mut f64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        write_out(out, cell.value_array::<Float64Array>("Float64")?.value(cell.row))
+    })
+}
+
+/// Get a Boolean value from a result cell
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not Boolean, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_bool(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    out: *mut bool,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        write_out(out, cell.value_array::<BooleanArray>("Boolean")?.value(cell.row))
+    })
+}
+
+/// Get a string value from a result cell without copying. `data` is set to the UTF-8 bytes, which are
+/// not null terminated and stay valid until the result is freed, and `len` to their length in bytes.
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not a string column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_string(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    data: *mut *const c_char,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        let value = cell.str_value()?;
+        write_out(data, value.as_ptr() as *const c_char)?;
+        write_out(len, value.len())
+    })
+}
+
+/// Get a copy of a string value from a result cell as a null terminated string
+/// Returns a string to be freed with datafusion_string_free, or null on error (including null values)
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_string_copy(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        Ok(cell.str_value()?.to_string())
+    })
+}
+
+/// Format a result cell of any type as a string, the way it appears in datafusion_result_format:
+/// temporal values in ISO 8601, decimals with their scale and nested values in brackets.
+/// Null values are formatted as empty strings.
+/// Returns a string to be freed with datafusion_string_free, or null on error
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_display(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    error_out: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_string(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        if cell.is_null() {
+            return Ok(String::new());
+        }
+        let formatter = ArrayFormatter::try_new(cell.array, &FormatOptions::default())?;
+        Ok(formatter.value(cell.row).to_string())
+    })
+}
+
+/// Get a binary value from a result cell without copying. `data` is set to the bytes, which stay
+/// valid until the result is freed, and `len` to their length. Works for Binary, LargeBinary,
+/// FixedSizeBinary and BinaryView columns.
+/// Returns DATAFUSION_OK on success, DATAFUSION_NULL_VALUE if the value is null,
+/// DATAFUSION_TYPE_MISMATCH if the column is not a binary column, or another error code on failure
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_binary(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_index: c_int,
+    data: *mut *const u8,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let cell = handle_ref(result, "result")?.cell(batch_index, row_index, column_index)?;
+        let value = cell.binary_value()?;
+        write_out(data, value.as_ptr())?;
+        write_out(len, value.len())
+    })
+}
+
+/// Get an Int64 value from a result cell, selecting the column by name
+/// Returns the same codes as datafusion_result_get_int64
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_int64_by_name(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_name: *const c_char,
+    out: *mut i64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let cell = result.cell(batch_index, row_index, result.column_index(column_name)?)?;
+        write_out(out, cell.value_array::<Int64Array>("Int64")?.value(cell.row))
+    })
+}
+
+/// Get an Int32 value from a result cell, selecting the column by name
+/// Returns the same codes as datafusion_result_get_int32
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_int32_by_name(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_name: *const c_char,
+    out: *mut i32,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let cell = result.cell(batch_index, row_index, result.column_index(column_name)?)?;
+        write_out(out, cell.value_array::<Int32Array>("Int32")?.value(cell.row))
+    })
+}
+
+/// Get a Float64 value from a result cell, selecting the column by name
+/// Returns the same codes as datafusion_result_get_float64
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_float64_by_name(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_name: *const c_char,
+    out: *mut f64,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let cell = result.cell(batch_index, row_index, result.column_index(column_name)?)?;
+        write_out(out, cell.value_array::<Float64Array>("Float64")?.value(cell.row))
+    })
+}
+
+/// Get a Boolean value from a result cell, selecting the column by name
+/// Returns the same codes as datafusion_result_get_bool
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_bool_by_name(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_name: *const c_char,
+    out: *mut bool,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let cell = result.cell(batch_index, row_index, result.column_index(column_name)?)?;
+        write_out(out, cell.value_array::<BooleanArray>("Boolean")?.value(cell.row))
+    })
+}
+
+/// Get a string value from a result cell without copying, selecting the column by name
+/// Returns the same codes as datafusion_result_get_string
+#[no_mangle]
+pub extern "C" fn datafusion_result_get_string_by_name(
+    result: *const DataFusionResult,
+    batch_index: c_int,
+    row_index: c_int,
+    column_name: *const c_char,
+    data: *mut *const c_char,
+    len: *mut usize,
+    error_out: *mut *mut c_char,
+) -> c_int {
+    ffi_code(error_out, || {
+        let result = handle_ref(result, "result")?;
+        let cell = result.cell(batch_index, row_index, result.column_index(column_name)?)?;
+        let value = cell.str_value()?;
+        write_out(data, value.as_ptr() as *const c_char)?;
+        write_out(len, value.len())
+    })
+}
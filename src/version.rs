@@ -0,0 +1,62 @@
+use std::os::raw::c_char;
+
+/// Versions and build settings of the library, for checking ABI compatibility at load time.
+/// All strings are static and must not be freed.
+#[repr(C)]
+pub struct DataFusionBuildInfo {
+    /// Version of this C API library
+    pub c_api_version: *const c_char,
+    /// Version of DataFusion the library was built against
+    pub datafusion_version: *const c_char,
+    /// Version of arrow-rs the library was built against
+    pub arrow_version: *const c_char,
+    /// Version of iceberg-rust the library was built against
+    pub iceberg_rust_version: *const c_char,
+    /// Git commit the library was built from, or "unknown"
+    pub git_hash: *const c_char,
+    /// Comma separated list of enabled cargo features
+    pub features: *const c_char,
+    /// Target triple, e.g. "x86_64-unknown-linux-gnu"
+    pub target: *const c_char,
+    /// Cargo profile, "debug" or "release"
+    pub profile: *const c_char,
+}
+
+macro_rules! static_c_str {
+    ($value:expr) => {
+        concat!($value, "\0").as_ptr() as *const c_char
+    };
+}
+
+/// Get the version of DataFusion the library was built against
+#[no_mangle]
+pub extern "C" fn datafusion_version() -> *const c_char {
+    static_c_str!(env!("DATAFUSION_C_API_DATAFUSION_VERSION"))
+}
+
+/// Get the version of arrow-rs the library was built against
+#[no_mangle]
+pub extern "C" fn datafusion_arrow_version() -> *const c_char {
+    static_c_str!(env!("DATAFUSION_C_API_ARROW_VERSION"))
+}
+
+/// Get the version of iceberg-rust the library was built against
+#[no_mangle]
+pub extern "C" fn iceberg_rust_version() -> *const c_char {
+    static_c_str!(env!("DATAFUSION_C_API_ICEBERG_RUST_VERSION"))
+}
+
+/// Get the versions and build settings of the library
+#[no_mangle]
+pub extern "C" fn datafusion_build_info() -> DataFusionBuildInfo {
+    DataFusionBuildInfo {
+        c_api_version: static_c_str!(env!("CARGO_PKG_VERSION")),
+        datafusion_version: datafusion_version(),
+        arrow_version: datafusion_arrow_version(),
+        iceberg_rust_version: iceberg_rust_version(),
+        git_hash: static_c_str!(env!("DATAFUSION_C_API_GIT_HASH")),
+        features: static_c_str!(env!("DATAFUSION_C_API_FEATURES")),
+        target: static_c_str!(env!("DATAFUSION_C_API_TARGET")),
+        profile: static_c_str!(env!("DATAFUSION_C_API_PROFILE")),
+    }
+}
@@ -3,6 +3,10 @@
 #include <string.h>
 #include <assert.h>
 #include <unistd.h>
+#include <pthread.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
 #include "../include/datafusion.h"
 
 // Test data
@@ -40,7 +44,7 @@ void cleanup_test_files() {
 int test_context_creation() {
     printf("Test 1: Context creation and destruction\n");
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         printf("FAILED: Could not create DataFusion context\n");
         return -1;
@@ -59,13 +63,13 @@ int test_csv_registration() {
         return -1;
     }
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         cleanup_test_files();
         return -1;
     }
     
-    int result = datafusion_register_csv(ctx, "employees", test_csv_path);
+    int result = datafusion_register_csv(ctx, "employees", test_csv_path, NULL);
     if (result != DATAFUSION_OK) {
         printf("FAILED: Could not register CSV file: %s\n", datafusion_get_last_error());
         datafusion_context_free(ctx);
@@ -87,20 +91,20 @@ int test_basic_query() {
         return -1;
     }
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         cleanup_test_files();
         return -1;
     }
     
-    if (datafusion_register_csv(ctx, "employees", test_csv_path) != DATAFUSION_OK) {
+    if (datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
         printf("FAILED: Could not register CSV file\n");
         datafusion_context_free(ctx);
         cleanup_test_files();
         return -1;
     }
     
-    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM employees");
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM employees", NULL);
     if (!result) {
         printf("FAILED: Could not execute SQL query: %s\n", datafusion_get_last_error());
         datafusion_context_free(ctx);
@@ -109,7 +113,7 @@ int test_basic_query() {
     }
     
     // Check result metadata
-    int batch_count = datafusion_result_batch_count(result);
+    int batch_count = datafusion_result_batch_count(result, NULL);
     if (batch_count < 1) {
         printf("FAILED: Expected at least 1 batch, got %d\n", batch_count);
         datafusion_result_free(result);
@@ -118,7 +122,7 @@ int test_basic_query() {
         return -1;
     }
     
-    int row_count = datafusion_result_batch_num_rows(result, 0);
+    int row_count = datafusion_result_batch_num_rows(result, 0, NULL);
     if (row_count != 5) {
         printf("FAILED: Expected 5 rows, got %d\n", row_count);
         datafusion_result_free(result);
@@ -127,7 +131,7 @@ int test_basic_query() {
         return -1;
     }
     
-    int col_count = datafusion_result_batch_num_columns(result, 0);
+    int col_count = datafusion_result_batch_num_columns(result, 0, NULL);
     if (col_count != 5) {
         printf("FAILED: Expected 5 columns, got %d\n", col_count);
         datafusion_result_free(result);
@@ -151,20 +155,20 @@ int test_filtered_query() {
         return -1;
     }
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         cleanup_test_files();
         return -1;
     }
     
-    if (datafusion_register_csv(ctx, "employees", test_csv_path) != DATAFUSION_OK) {
+    if (datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
         printf("FAILED: Could not register CSV file\n");
         datafusion_context_free(ctx);
         cleanup_test_files();
         return -1;
     }
     
-    DataFusionResult* result = datafusion_sql(ctx, "SELECT name FROM employees WHERE age > 30");
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name FROM employees WHERE age > 30", NULL);
     if (!result) {
         printf("FAILED: Could not execute filtered query: %s\n", datafusion_get_last_error());
         datafusion_context_free(ctx);
@@ -172,7 +176,7 @@ int test_filtered_query() {
         return -1;
     }
     
-    int row_count = datafusion_result_batch_num_rows(result, 0);
+    int row_count = datafusion_result_batch_num_rows(result, 0, NULL);
     if (row_count != 2) {  // Bob and Carol
         printf("FAILED: Expected 2 rows for age > 30, got %d\n", row_count);
         datafusion_result_free(result);
@@ -196,20 +200,20 @@ int test_aggregation_query() {
         return -1;
     }
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         cleanup_test_files();
         return -1;
     }
     
-    if (datafusion_register_csv(ctx, "employees", test_csv_path) != DATAFUSION_OK) {
+    if (datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
         printf("FAILED: Could not register CSV file\n");
         datafusion_context_free(ctx);
         cleanup_test_files();
         return -1;
     }
     
-    DataFusionResult* result = datafusion_sql(ctx, "SELECT COUNT(*) as total FROM employees");
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT COUNT(*) as total FROM employees", NULL);
     if (!result) {
         printf("FAILED: Could not execute aggregation query: %s\n", datafusion_get_last_error());
         datafusion_context_free(ctx);
@@ -217,7 +221,7 @@ int test_aggregation_query() {
         return -1;
     }
     
-    int row_count = datafusion_result_batch_num_rows(result, 0);
+    int row_count = datafusion_result_batch_num_rows(result, 0, NULL);
     if (row_count != 1) {
         printf("FAILED: Expected 1 row for COUNT query, got %d\n", row_count);
         datafusion_result_free(result);
@@ -226,7 +230,7 @@ int test_aggregation_query() {
         return -1;
     }
     
-    int col_count = datafusion_result_batch_num_columns(result, 0);
+    int col_count = datafusion_result_batch_num_columns(result, 0, NULL);
     if (col_count != 1) {
         printf("FAILED: Expected 1 column for COUNT query, got %d\n", col_count);
         datafusion_result_free(result);
@@ -246,13 +250,13 @@ int test_aggregation_query() {
 int test_error_handling() {
     printf("Test 6: Error handling\n");
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         return -1;
     }
     
     // Test invalid CSV file
-    int result = datafusion_register_csv(ctx, "invalid", "nonexistent.csv");
+    int result = datafusion_register_csv(ctx, "invalid", "nonexistent.csv", NULL);
     if (result == DATAFUSION_OK) {
         printf("FAILED: Expected error for nonexistent CSV file\n");
         datafusion_context_free(ctx);
@@ -265,14 +269,14 @@ int test_error_handling() {
         return -1;
     }
     
-    if (datafusion_register_csv(ctx, "employees", test_csv_path) != DATAFUSION_OK) {
+    if (datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
         printf("FAILED: Could not register valid CSV file\n");
         datafusion_context_free(ctx);
         cleanup_test_files();
         return -1;
     }
     
-    DataFusionResult* result_ptr = datafusion_sql(ctx, "SELECT * FROM nonexistent_table");
+    DataFusionResult* result_ptr = datafusion_sql(ctx, "SELECT * FROM nonexistent_table", NULL);
     if (result_ptr != NULL) {
         printf("FAILED: Expected error for invalid table reference\n");
         datafusion_result_free(result_ptr);
@@ -295,20 +299,20 @@ int test_print_result() {
         return -1;
     }
     
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     if (!ctx) {
         cleanup_test_files();
         return -1;
     }
     
-    if (datafusion_register_csv(ctx, "employees", test_csv_path) != DATAFUSION_OK) {
+    if (datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
         printf("FAILED: Could not register CSV file\n");
         datafusion_context_free(ctx);
         cleanup_test_files();
         return -1;
     }
     
-    DataFusionResult* result = datafusion_sql(ctx, "SELECT name, age FROM employees ORDER BY age");
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name, age FROM employees ORDER BY age", NULL);
     if (!result) {
         printf("FAILED: Could not execute query for print test\n");
         datafusion_context_free(ctx);
@@ -317,7 +321,7 @@ int test_print_result() {
     }
     
     printf("Printing query result:\n");
-    int print_result = datafusion_result_print(result);
+    int print_result = datafusion_result_print(result, NULL);
     if (print_result != DATAFUSION_OK) {
         printf("FAILED: Could not print result\n");
         datafusion_result_free(result);
@@ -333,6 +337,6625 @@ int test_print_result() {
     return 0;
 }
 
+// Test 8: Error messages and structured errors
+int test_error_object() {
+    printf("Test 8: Error messages and structured errors\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx) {
+        return -1;
+    }
+    
+    char* message = NULL;
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM nonexistent_table", &message);
+    if (result != NULL || message == NULL) {
+        printf("FAILED: Expected an error message for invalid table reference\n");
+        datafusion_result_free(result);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    if (!strstr(message, "nonexistent_table")) {
+        printf("FAILED: Error message does not mention the table: %s\n", message);
+        datafusion_string_free(message);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    DataFusionError* error = datafusion_last_error();
+    if (!error || datafusion_error_code(error) != DATAFUSION_ERROR ||
+        strcmp(datafusion_error_message(error), message) != 0) {
+        printf("FAILED: Last error does not match the reported message\n");
+        datafusion_error_free(error);
+        datafusion_string_free(message);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    for (const DataFusionError* source = datafusion_error_source(error); source; source = datafusion_error_source(source)) {
+        printf("  caused by: %s\n", datafusion_error_message(source));
+    }
+    datafusion_error_free(error);
+    datafusion_string_free(message);
+    
+    message = NULL;
+    if (datafusion_register_csv(ctx, NULL, test_csv_path, &message) != DATAFUSION_INVALID_ARGUMENT || message == NULL) {
+        printf("FAILED: Expected DATAFUSION_INVALID_ARGUMENT for null table name\n");
+        datafusion_string_free(message);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    datafusion_string_free(message);
+    
+    message = NULL;
+    if (datafusion_result_batch_num_rows(NULL, 0, &message) != 0 || message == NULL) {
+        printf("FAILED: Expected an error message for null result\n");
+        datafusion_string_free(message);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    datafusion_string_free(message);
+    
+    datafusion_context_free(ctx);
+    printf("PASSED: Error reporting works correctly\n");
+    return 0;
+}
+
+// Log callback used by test 9
+static void count_log_records(int level, const char* target, const char* message, void* user_data) {
+    (void)target;
+    (void)message;
+    if (level >= DATAFUSION_LOG_ERROR && level <= DATAFUSION_LOG_TRACE) {
+        (*(int*)user_data)++;
+    }
+}
+
+// Log callback used by test 9 that removes itself, which must not deadlock
+static void remove_log_callback(int level, const char* target, const char* message, void* user_data) {
+    count_log_records(level, target, message, user_data);
+    datafusion_set_log_callback(DATAFUSION_LOG_OFF, NULL, NULL, NULL);
+}
+
+// Test 9: Log callback
+int test_log_callback() {
+    printf("Test 9: Log callback\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    int records = 0;
+    if (datafusion_set_log_callback(DATAFUSION_LOG_TRACE, count_log_records, &records, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not install log callback: %s\n", datafusion_get_last_error());
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context\n");
+        datafusion_set_log_callback(DATAFUSION_LOG_OFF, NULL, NULL, NULL);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name FROM employees WHERE age > 30", NULL);
+    datafusion_result_free(result);
+    datafusion_set_log_callback(DATAFUSION_LOG_OFF, NULL, NULL, NULL);
+    
+    // A callback may call back into the library, here to remove itself
+    int removed_records = 0;
+    datafusion_set_log_callback(DATAFUSION_LOG_TRACE, remove_log_callback, &removed_records, NULL);
+    result = datafusion_sql(ctx, "SELECT name FROM employees WHERE age > 30", NULL);
+    datafusion_result_free(result);
+    int after_removal = removed_records;
+    result = datafusion_sql(ctx, "SELECT name FROM employees WHERE age > 30", NULL);
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (records == 0) {
+        printf("FAILED: Expected log records while planning a query\n");
+        return -1;
+    }
+    if (removed_records == 0 || removed_records != after_removal) {
+        printf("FAILED: Expected a callback to remove itself\n");
+        return -1;
+    }
+    
+    if (datafusion_set_log_callback(42, NULL, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected DATAFUSION_INVALID_ARGUMENT for invalid level\n");
+        return -1;
+    }
+    
+    printf("PASSED: Log callback received %d records\n", records);
+    return 0;
+}
+
+// Test 10: Per-context log filters
+int test_context_log_filter() {
+    printf("Test 10: Per-context log filters\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    int records = 0;
+    if (datafusion_set_log_callback(DATAFUSION_LOG_OFF, count_log_records, &records, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not install log callback: %s\n", datafusion_get_last_error());
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionContext* quiet = datafusion_context_new(NULL);
+    DataFusionContext* verbose = datafusion_context_new(NULL);
+    int failed = !quiet || !verbose ||
+        datafusion_register_csv(quiet, "employees", test_csv_path, NULL) != DATAFUSION_OK ||
+        datafusion_register_csv(verbose, "employees", test_csv_path, NULL) != DATAFUSION_OK;
+    
+    if (!failed) {
+        datafusion_result_free(datafusion_sql(quiet, "SELECT name FROM employees WHERE age > 30", NULL));
+        if (records != 0) {
+            printf("FAILED: Expected no records from a context without a filter, got %d\n", records);
+            failed = 1;
+        }
+    }
+    
+    if (!failed && datafusion_context_set_log_filter(verbose, "warn,datafusion=trace", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set log filter: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (!failed) {
+        datafusion_result_free(datafusion_sql(verbose, "SELECT name FROM employees WHERE age > 30", NULL));
+        if (records == 0) {
+            printf("FAILED: Expected records from a context with a trace filter\n");
+            failed = 1;
+        }
+    }
+    
+    if (!failed && datafusion_context_set_log_filter(verbose, "datafusion=loud", NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected DATAFUSION_INVALID_ARGUMENT for malformed filter\n");
+        failed = 1;
+    }
+    
+    if (!failed && datafusion_context_set_log_level(verbose, DATAFUSION_LOG_OFF, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set log level: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    datafusion_set_log_callback(DATAFUSION_LOG_OFF, NULL, NULL, NULL);
+    datafusion_context_free(quiet);
+    datafusion_context_free(verbose);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Per-context log filters work correctly\n");
+    return 0;
+}
+
+// Test 11: Version and build information
+int test_build_info() {
+    printf("Test 11: Version and build information\n");
+    
+    DataFusionBuildInfo info = datafusion_build_info();
+    if (!info.c_api_version || !info.git_hash || !info.features || !info.target || !info.profile) {
+        printf("FAILED: Build info contains null strings\n");
+        return -1;
+    }
+    
+    if (strcmp(info.datafusion_version, datafusion_version()) != 0 ||
+        strcmp(info.arrow_version, datafusion_arrow_version()) != 0 ||
+        strcmp(info.iceberg_rust_version, iceberg_rust_version()) != 0) {
+        printf("FAILED: Build info versions do not match the version getters\n");
+        return -1;
+    }
+    
+    if (strcmp(datafusion_version(), "unknown") == 0 || strlen(info.target) == 0) {
+        printf("FAILED: Expected a resolved DataFusion version and target\n");
+        return -1;
+    }
+    
+    printf("  c-api %s, datafusion %s, arrow %s, iceberg-rust %s (%s, %s)\n",
+           info.c_api_version, info.datafusion_version, info.arrow_version,
+           info.iceberg_rust_version, info.target, info.profile);
+    printf("PASSED: Build information is available\n");
+    return 0;
+}
+
+// Test 12: Handle validation
+int test_handle_validation() {
+    printf("Test 12: Handle validation\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx) {
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT 1 AS one", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    // A context passed where a result is expected must be rejected
+    char* message = NULL;
+    datafusion_result_batch_count((const DataFusionResult*)ctx, &message);
+    if (!message) {
+        printf("FAILED: Expected an error for a handle of the wrong type\n");
+        datafusion_result_free(result);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    datafusion_string_free(message);
+    
+    if (datafusion_result_free(result) != DATAFUSION_OK) {
+        printf("FAILED: Could not free result\n");
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    if (datafusion_result_free(result) != DATAFUSION_INVALID_HANDLE) {
+        printf("FAILED: Expected DATAFUSION_INVALID_HANDLE for a double free\n");
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    if (datafusion_context_free(ctx) != DATAFUSION_OK || datafusion_context_free(NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not free context\n");
+        return -1;
+    }
+    
+    printf("PASSED: Invalid handles are rejected\n");
+    return 0;
+}
+
+// Test 13: Handle tracking
+int test_handle_tracking() {
+    printf("Test 13: Handle tracking\n");
+    
+    datafusion_enable_handle_tracking();
+    DataFusionHandleCounts before = datafusion_live_handle_counts();
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx, "SELECT 1 AS one", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    DataFusionHandleCounts during = datafusion_live_handle_counts();
+    if (during.contexts != before.contexts + 1 || during.results != before.results + 1) {
+        printf("FAILED: Expected one more live context and result, got %lld and %lld\n",
+               (long long)(during.contexts - before.contexts), (long long)(during.results - before.results));
+        datafusion_result_free(result);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    DataFusionHandleCounts after = datafusion_live_handle_counts();
+    if (after.contexts != before.contexts || after.results != before.results) {
+        printf("FAILED: Handles leaked after free\n");
+        return -1;
+    }
+    
+    printf("PASSED: Live handles are counted\n");
+    return 0;
+}
+
+int test_result_metrics() {
+    printf("Test 14: Result metrics\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT department, COUNT(*) FROM employees GROUP BY department", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    char* error = NULL;
+    char* json = datafusion_result_metrics_json(result, &error);
+    if (!json) {
+        printf("FAILED: Could not get metrics: %s\n", error ? error : "unknown error");
+        datafusion_string_free(error);
+        datafusion_result_free(result);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int ok = strstr(json, "\"operators\"") != NULL && strstr(json, "\"output_rows\"") != NULL
+        && strstr(json, "\"elapsed_compute\"") != NULL;
+    printf("Metrics: %.80s...\n", json);
+    datafusion_string_free(json);
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (!ok) {
+        printf("FAILED: Metrics JSON is missing operator metrics\n");
+        return -1;
+    }
+    
+    printf("PASSED: Operator metrics reported\n");
+    return 0;
+}
+
+int test_result_schema() {
+    printf("Test 15: Result schema introspection\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    // A query without rows still reports its columns
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name, age, salary FROM employees WHERE age > 100", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    int columns = datafusion_result_column_count(result, NULL);
+    if (columns != 3) {
+        printf("FAILED: Expected 3 columns, got %d\n", columns);
+        failed = 1;
+    }
+    
+    char* name = datafusion_result_column_name(result, 1, NULL);
+    char* type_name = datafusion_result_column_type_name(result, 1, NULL);
+    if (!name || strcmp(name, "age") != 0 || datafusion_result_column_type(result, 1, NULL) != DATAFUSION_TYPE_INT64) {
+        printf("FAILED: Unexpected column 1: %s (%s)\n", name ? name : "null", type_name ? type_name : "null");
+        failed = 1;
+    } else {
+        printf("Column 1: %s %s nullable=%d\n", name, type_name, datafusion_result_column_nullable(result, 1, NULL));
+    }
+    datafusion_string_free(name);
+    datafusion_string_free(type_name);
+    
+    if (datafusion_result_column_type(result, 0, NULL) != DATAFUSION_TYPE_UTF8) {
+        printf("FAILED: Expected column 0 to be Utf8\n");
+        failed = 1;
+    }
+    
+    char* error = NULL;
+    if (datafusion_result_column_name(result, 3, &error) != NULL || !error) {
+        printf("FAILED: Out of range column index should fail\n");
+        failed = 1;
+    }
+    datafusion_string_free(error);
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Column names, types and nullability reported\n");
+    return 0;
+}
+
+int test_typed_getters() {
+    printf("Test 16: Typed cell accessors\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT CAST(42 AS BIGINT) AS a, CAST(7 AS INT) AS b, 1.5 AS c, true AS d, CAST(NULL AS BIGINT) AS e", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t a = 0;
+    int32_t b = 0;
+    double c = 0;
+    bool d = false;
+    if (datafusion_result_get_int64(result, 0, 0, 0, &a, NULL) != DATAFUSION_OK || a != 42
+        || datafusion_result_get_int32(result, 0, 0, 1, &b, NULL) != DATAFUSION_OK || b != 7
+        || datafusion_result_get_float64(result, 0, 0, 2, &c, NULL) != DATAFUSION_OK || c != 1.5
+        || datafusion_result_get_bool(result, 0, 0, 3, &d, NULL) != DATAFUSION_OK || !d) {
+        printf("FAILED: Unexpected values: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_int64(result, 0, 0, 4, &a, NULL) != DATAFUSION_NULL_VALUE) {
+        printf("FAILED: Expected DATAFUSION_NULL_VALUE for a null cell\n");
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_float64(result, 0, 0, 0, &c, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected DATAFUSION_TYPE_MISMATCH for an Int64 column read as Float64\n");
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_int64(result, 0, 1, 0, &a, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected DATAFUSION_INVALID_ARGUMENT for an out of range row\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Typed values, nulls and type mismatches reported\n");
+    return 0;
+}
+
+int test_string_getters() {
+    printf("Test 17: String cell accessors\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name, CAST(NULL AS VARCHAR) AS missing FROM employees ORDER BY id LIMIT 1", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* data = NULL;
+    size_t len = 0;
+    if (datafusion_result_get_string(result, 0, 0, 0, &data, &len, NULL) != DATAFUSION_OK
+        || len != 5 || strncmp(data, "Alice", len) != 0) {
+        printf("FAILED: Unexpected borrowed string: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    char* copy = datafusion_result_get_string_copy(result, 0, 0, 0, NULL);
+    if (!copy || strcmp(copy, "Alice") != 0) {
+        printf("FAILED: Unexpected copied string\n");
+        failed = 1;
+    }
+    datafusion_string_free(copy);
+    
+    if (datafusion_result_get_string(result, 0, 0, 1, &data, &len, NULL) != DATAFUSION_NULL_VALUE
+        || datafusion_result_get_string_copy(result, 0, 0, 1, NULL) != NULL) {
+        printf("FAILED: Expected null string values to be reported\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Borrowed and copied strings read\n");
+    return 0;
+}
+
+int test_is_null() {
+    printf("Test 18: Null checks\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT 0 AS zero, CAST(NULL AS BIGINT) AS missing, NULL AS untyped, "
+        "[1, 2] AS list, CAST(NULL AS INT[]) AS missing_list", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_result_is_null(result, 0, 0, 0, NULL) || datafusion_result_is_null(result, 0, 0, 3, NULL)) {
+        printf("FAILED: Non-null values reported as null\n");
+        failed = 1;
+    }
+    if (!datafusion_result_is_null(result, 0, 0, 1, NULL) || !datafusion_result_is_null(result, 0, 0, 2, NULL)
+        || !datafusion_result_is_null(result, 0, 0, 4, NULL)) {
+        printf("FAILED: Null values not reported as null\n");
+        failed = 1;
+    }
+    
+    char* error = NULL;
+    datafusion_result_is_null(result, 0, 0, 5, &error);
+    if (!error) {
+        printf("FAILED: Out of range column index should fail\n");
+        failed = 1;
+    }
+    datafusion_string_free(error);
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Null values detected for all column types\n");
+    return 0;
+}
+
+int test_binary_getter() {
+    printf("Test 19: Binary cell accessor\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT X'DEADBEEF' AS blob, arrow_cast(X'0102', 'FixedSizeBinary(2)') AS fixed, "
+        "CAST(NULL AS BYTEA) AS missing, 'text' AS not_binary", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    const uint8_t* data = NULL;
+    size_t len = 0;
+    if (datafusion_result_get_binary(result, 0, 0, 0, &data, &len, NULL) != DATAFUSION_OK
+        || len != 4 || data[0] != 0xDE || data[3] != 0xEF) {
+        printf("FAILED: Unexpected binary value: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_get_binary(result, 0, 0, 1, &data, &len, NULL) != DATAFUSION_OK
+        || len != 2 || data[1] != 0x02) {
+        printf("FAILED: Unexpected fixed size binary value: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_get_binary(result, 0, 0, 2, &data, &len, NULL) != DATAFUSION_NULL_VALUE) {
+        printf("FAILED: Expected DATAFUSION_NULL_VALUE for a null blob\n");
+        failed = 1;
+    }
+    if (datafusion_result_get_binary(result, 0, 0, 3, &data, &len, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected DATAFUSION_TYPE_MISMATCH for a string column\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Binary values read\n");
+    return 0;
+}
+
+int test_temporal_getters() {
+    printf("Test 20: Temporal cell accessors\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT DATE '2024-01-02' AS d, "
+        "arrow_cast(TIMESTAMP '2024-01-02 03:04:05', 'Timestamp(Millisecond, Some(\"UTC\"))') AS ts, "
+        "TIME '01:00:00' AS t, INTERVAL '1 month 2 days 3 seconds' AS i", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t value = 0;
+    int unit = -1;
+    if (datafusion_result_get_date(result, 0, 0, 0, &value, &unit, NULL) != DATAFUSION_OK
+        || value != 19724 || unit != DATAFUSION_TIME_UNIT_DAY) {
+        printf("FAILED: Unexpected date %lld (unit %d): %s\n", (long long)value, unit, datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_timestamp(result, 0, 0, 1, &value, &unit, NULL) != DATAFUSION_OK
+        || value != 1704164645000LL || unit != DATAFUSION_TIME_UNIT_MILLISECOND) {
+        printf("FAILED: Unexpected timestamp %lld (unit %d): %s\n", (long long)value, unit, datafusion_get_last_error());
+        failed = 1;
+    }
+    char* tz = datafusion_result_column_timezone(result, 1, NULL);
+    if (!tz || strcmp(tz, "UTC") != 0) {
+        printf("FAILED: Expected UTC timezone\n");
+        failed = 1;
+    }
+    datafusion_string_free(tz);
+    
+    if (datafusion_result_get_time(result, 0, 0, 2, &value, &unit, NULL) != DATAFUSION_OK
+        || datafusion_result_column_time_unit(result, 2, NULL) != unit
+        || value != 3600LL * 1000000000LL || unit != DATAFUSION_TIME_UNIT_NANOSECOND) {
+        printf("FAILED: Unexpected time %lld (unit %d): %s\n", (long long)value, unit, datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionInterval interval;
+    if (datafusion_result_get_interval(result, 0, 0, 3, &interval, NULL) != DATAFUSION_OK
+        || interval.months != 1 || interval.days != 2 || interval.nanoseconds != 3000000000LL) {
+        printf("FAILED: Unexpected interval: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_timestamp(result, 0, 0, 0, &value, &unit, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected DATAFUSION_TYPE_MISMATCH for a date read as a timestamp\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Dates, timestamps, times and intervals read\n");
+    return 0;
+}
+
+int test_decimal_getters() {
+    printf("Test 21: Decimal cell accessors\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT CAST(-1234.5 AS DECIMAL(10, 2)) AS small, "
+        "arrow_cast(CAST(98765.4321 AS DECIMAL(20, 4)), 'Decimal256(40, 4)') AS big", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionDecimal128 value;
+    uint8_t precision = 0;
+    int8_t scale = 0;
+    if (datafusion_result_get_decimal128(result, 0, 0, 0, &value, NULL) != DATAFUSION_OK
+        || (int64_t)value.low != -123450 || value.high != UINT64_MAX) {
+        printf("FAILED: Unexpected decimal128 value: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_column_decimal(result, 0, &precision, &scale, NULL) != DATAFUSION_OK
+        || precision != 10 || scale != 2) {
+        printf("FAILED: Unexpected precision %d and scale %d\n", precision, scale);
+        failed = 1;
+    }
+    
+    char* small = datafusion_result_get_decimal_string(result, 0, 0, 0, NULL);
+    char* big = datafusion_result_get_decimal_string(result, 0, 0, 1, NULL);
+    if (!small || strcmp(small, "-1234.50") != 0 || !big || strcmp(big, "98765.4321") != 0) {
+        printf("FAILED: Unexpected decimal strings %s and %s\n", small ? small : "null", big ? big : "null");
+        failed = 1;
+    }
+    datafusion_string_free(small);
+    datafusion_string_free(big);
+    
+    if (datafusion_result_get_decimal128(result, 0, 0, 1, &value, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected DATAFUSION_TYPE_MISMATCH for a Decimal256 column\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Decimal values, precision and scale read\n");
+    return 0;
+}
+
+static int buffer_bit(const uint8_t* validity, size_t offset, size_t i) {
+    return validity == NULL || (validity[(offset + i) / 8] >> ((offset + i) % 8)) & 1;
+}
+
+int test_column_buffers() {
+    printf("Test 22: Raw column buffers\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT column1 AS n, column2 AS s FROM (VALUES (1, 'a'), (NULL, 'bb'), (3, NULL), (4, 'dddd')) "
+        "ORDER BY column1 NULLS FIRST OFFSET 1", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    const uint8_t* validity = NULL;
+    size_t validity_offset = 0;
+    const void* offsets = NULL;
+    const void* data = NULL;
+    size_t len = 0;
+    
+    // Rows after the offset: (1, 'a'), (3, NULL), (4, 'dddd')
+    if (datafusion_result_column_buffers(result, 0, 0, &validity, &validity_offset, &offsets, &data, &len, NULL) != DATAFUSION_OK
+        || len != 3 || offsets != NULL) {
+        printf("FAILED: Could not get numeric buffers: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else {
+        const int64_t* values = (const int64_t*)data;
+        if (values[0] != 1 || values[1] != 3 || values[2] != 4) {
+            printf("FAILED: Unexpected numeric values\n");
+            failed = 1;
+        }
+    }
+    
+    if (datafusion_result_column_buffers(result, 0, 1, &validity, &validity_offset, &offsets, &data, &len, NULL) != DATAFUSION_OK
+        || len != 3 || offsets == NULL) {
+        printf("FAILED: Could not get string buffers: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else {
+        const int32_t* string_offsets = (const int32_t*)offsets;
+        const char* bytes = (const char*)data;
+        if (!buffer_bit(validity, validity_offset, 0) || buffer_bit(validity, validity_offset, 1)
+            || !buffer_bit(validity, validity_offset, 2)
+            || string_offsets[3] - string_offsets[2] != 4 || strncmp(bytes + string_offsets[2], "dddd", 4) != 0
+            || string_offsets[1] - string_offsets[0] != 1 || bytes[string_offsets[0]] != 'a') {
+            printf("FAILED: Unexpected string buffers\n");
+            failed = 1;
+        }
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Validity, offset and data buffers read\n");
+    return 0;
+}
+
+int test_export_batch() {
+    printf("Test 23: Arrow C data interface export\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT id, name FROM employees ORDER BY id", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    struct ArrowArray array;
+    struct ArrowSchema schema;
+    int rc = datafusion_result_export_batch(result, 0, &array, &schema, NULL);
+    int rows = datafusion_result_batch_num_rows(result, 0, NULL);
+    // The exported data must stay valid after the result is freed
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (rc != DATAFUSION_OK) {
+        printf("FAILED: Could not export batch: %s\n", datafusion_get_last_error());
+        return -1;
+    }
+    
+    int failed = 0;
+    if (strcmp(schema.format, "+s") != 0 || schema.n_children != 2 || strcmp(schema.children[1]->name, "name") != 0
+        || strcmp(schema.children[0]->format, "l") != 0 || strcmp(schema.children[1]->format, "u") != 0) {
+        printf("FAILED: Unexpected exported schema\n");
+        failed = 1;
+    }
+    if (array.length != rows || array.n_children != 2) {
+        printf("FAILED: Unexpected exported array\n");
+        failed = 1;
+    } else {
+        const int64_t* ids = (const int64_t*)array.children[0]->buffers[1];
+        if (ids[array.children[0]->offset] != 1) {
+            printf("FAILED: Unexpected exported values\n");
+            failed = 1;
+        }
+    }
+    
+    array.release(&array);
+    schema.release(&schema);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Batch exported as ArrowArray and ArrowSchema\n");
+    return 0;
+}
+
+int test_export_stream() {
+    printf("Test 24: Arrow C stream interface export\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT id, name FROM employees", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    struct ArrowArrayStream stream;
+    int rc = datafusion_result_export_stream(result, &stream, NULL);
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (rc != DATAFUSION_OK) {
+        printf("FAILED: Could not export stream: %s\n", datafusion_get_last_error());
+        return -1;
+    }
+    
+    int failed = 0;
+    struct ArrowSchema schema;
+    if (stream.get_schema(&stream, &schema) != 0 || schema.n_children != 2) {
+        printf("FAILED: Unexpected stream schema\n");
+        failed = 1;
+    } else {
+        schema.release(&schema);
+    }
+    
+    int64_t total_rows = 0;
+    while (!failed) {
+        struct ArrowArray array;
+        if (stream.get_next(&stream, &array) != 0) {
+            printf("FAILED: get_next failed: %s\n", stream.get_last_error(&stream));
+            failed = 1;
+            break;
+        }
+        if (array.release == NULL) {
+            break;
+        }
+        total_rows += array.length;
+        array.release(&array);
+    }
+    stream.release(&stream);
+    
+    if (!failed && total_rows != 5) {
+        printf("FAILED: Expected 5 streamed rows, got %lld\n", (long long)total_rows);
+        failed = 1;
+    }
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result exported as ArrowArrayStream\n");
+    return 0;
+}
+
+int test_result_to_ipc() {
+    printf("Test 25: Arrow IPC serialization\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx, "SELECT 1 AS a, 'x' AS b", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    uint8_t* buf = NULL;
+    size_t len = 0;
+    if (datafusion_result_to_ipc(result, DATAFUSION_IPC_STREAM, &buf, &len, NULL) != DATAFUSION_OK
+        || len < 8 || buf[0] != 0xFF || buf[3] != 0xFF) {
+        printf("FAILED: Unexpected IPC stream: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_buffer_free(buf, len);
+    
+    buf = NULL;
+    if (datafusion_result_to_ipc(result, DATAFUSION_IPC_FILE, &buf, &len, NULL) != DATAFUSION_OK
+        || len < 12 || memcmp(buf, "ARROW1", 6) != 0 || memcmp(buf + len - 6, "ARROW1", 6) != 0) {
+        printf("FAILED: Unexpected IPC file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_buffer_free(buf, len);
+    
+    if (datafusion_result_to_ipc(result, 42, &buf, &len, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an invalid format to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result serialized as IPC stream and file\n");
+    return 0;
+}
+
+int test_result_to_csv() {
+    printf("Test 26: CSV rendering\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx, "SELECT 1 AS id, 'a;b' AS name", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    char* csv = datafusion_result_to_csv(result, NULL, NULL);
+    if (!csv || strcmp(csv, "id,name\n1,a;b\n") != 0) {
+        printf("FAILED: Unexpected default CSV: %s\n", csv ? csv : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(csv);
+    
+    DataFusionCsvOptions options = datafusion_csv_options_default();
+    options.delimiter = ';';
+    options.header = false;
+    options.quote = '\'';
+    csv = datafusion_result_to_csv(result, &options, NULL);
+    if (!csv || strcmp(csv, "1;'a;b'\n") != 0) {
+        printf("FAILED: Unexpected CSV with options: %s\n", csv ? csv : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(csv);
+    
+    const char* output_path = "test_output.csv";
+    if (datafusion_result_write_csv(result, output_path, NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write CSV file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else {
+        char contents[64] = {0};
+        FILE* file = fopen(output_path, "r");
+        size_t read = file ? fread(contents, 1, sizeof(contents) - 1, file) : 0;
+        if (file) fclose(file);
+        if (read == 0 || strcmp(contents, "id,name\n1,a;b\n") != 0) {
+            printf("FAILED: Unexpected CSV file contents: %s\n", contents);
+            failed = 1;
+        }
+        unlink(output_path);
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result rendered as CSV string and file\n");
+    return 0;
+}
+
+int test_result_to_json() {
+    printf("Test 27: JSON rendering\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT column1 AS id, column2 AS name FROM (VALUES (1, 'a'), (2, 'b')) ORDER BY id", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    char* json = datafusion_result_to_json(result, false, NULL);
+    if (!json || strcmp(json, "[{\"id\":1,\"name\":\"a\"},{\"id\":2,\"name\":\"b\"}]") != 0) {
+        printf("FAILED: Unexpected JSON array: %s\n", json ? json : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(json);
+    
+    json = datafusion_result_to_json(result, true, NULL);
+    if (!json || strcmp(json, "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}\n") != 0) {
+        printf("FAILED: Unexpected NDJSON: %s\n", json ? json : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(json);
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result rendered as JSON array and NDJSON\n");
+    return 0;
+}
+
+int test_result_format() {
+    printf("Test 28: Formatting results as strings\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT id, name FROM employees ORDER BY id", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    char* table = datafusion_result_format(result, 0, NULL);
+    if (!table || !strstr(table, "| Alice |") || !strstr(table, "| Eve   |") || strstr(table, "rows shown")) {
+        printf("FAILED: Unexpected full table:\n%s\n", table ? table : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(table);
+    
+    table = datafusion_result_format(result, 2, NULL);
+    if (!table || !strstr(table, "| Bob   |") || strstr(table, "Carol") || !strstr(table, "(2 of 5 rows shown)")) {
+        printf("FAILED: Unexpected truncated table:\n%s\n", table ? table : datafusion_get_last_error());
+        failed = 1;
+    } else {
+        printf("%s\n", table);
+    }
+    datafusion_string_free(table);
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result formatted into a string\n");
+    return 0;
+}
+
+int test_result_stats() {
+    printf("Test 29: Result statistics\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM employees", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionResultStats stats;
+    int64_t total_rows = datafusion_result_total_rows(result, NULL);
+    if (datafusion_result_stats(result, &stats, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not get statistics: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else if (total_rows != 5 || stats.total_rows != 5 || stats.memory_bytes <= 0
+               || stats.batch_count != datafusion_result_batch_count(result, NULL)) {
+        printf("FAILED: Unexpected statistics: %lld rows, %lld batches, %lld bytes\n",
+               (long long)stats.total_rows, (long long)stats.batch_count, (long long)stats.memory_bytes);
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Row count, batch count and memory size reported\n");
+    return 0;
+}
+
+int test_result_slice() {
+    printf("Test 30: Result slicing\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT id, name FROM employees ORDER BY id", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionResult* page = datafusion_result_slice(result, 1, 2, NULL);
+    int64_t first_id = 0;
+    if (!page || datafusion_result_total_rows(page, NULL) != 2
+        || datafusion_result_get_int64(page, 0, 0, 0, &first_id, NULL) != DATAFUSION_OK || first_id != 2) {
+        printf("FAILED: Unexpected slice: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(page);
+    
+    // Slicing past the end gives an empty result that still has the columns
+    page = datafusion_result_slice(result, 10, 5, NULL);
+    if (!page || datafusion_result_total_rows(page, NULL) != 0 || datafusion_result_column_count(page, NULL) != 2) {
+        printf("FAILED: Unexpected slice past the end\n");
+        failed = 1;
+    }
+    datafusion_result_free(page);
+    
+    if (datafusion_result_slice(result, -1, 2, NULL) != NULL) {
+        printf("FAILED: Negative offset should be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Row windows sliced from a result\n");
+    return 0;
+}
+
+int test_result_coalesce() {
+    printf("Test 31: Coalescing result batches\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT * FROM (VALUES (1), (2), (3)) UNION ALL SELECT * FROM (VALUES (4), (5))", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionResult* single = datafusion_result_coalesce(result, 0, NULL);
+    if (!single || datafusion_result_batch_count(single, NULL) != 1 || datafusion_result_batch_num_rows(single, 0, NULL) != 5) {
+        printf("FAILED: Expected a single batch of 5 rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(single);
+    
+    DataFusionResult* pairs = datafusion_result_coalesce(result, 2, NULL);
+    if (!pairs || datafusion_result_batch_count(pairs, NULL) != 3
+        || datafusion_result_batch_num_rows(pairs, 0, NULL) != 2 || datafusion_result_batch_num_rows(pairs, 2, NULL) != 1) {
+        printf("FAILED: Expected batches of 2, 2 and 1 rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(pairs);
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Batches coalesced to the target size\n");
+    return 0;
+}
+
+int test_schema_handle() {
+    printf("Test 32: Schema handles\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionSchema* table_schema = datafusion_table_schema(ctx, "employees", NULL);
+    char* name = table_schema ? datafusion_schema_field_name(table_schema, 4, NULL) : NULL;
+    if (!table_schema || datafusion_schema_field_count(table_schema, NULL) != 5 || !name || strcmp(name, "salary") != 0) {
+        printf("FAILED: Unexpected table schema: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_schema_free(table_schema);
+    
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT named_struct('a', 1, 'b', 'x') AS s, [1, 2] AS l, 1 AS n", NULL);
+    DataFusionSchema* schema = result ? datafusion_result_schema(result, NULL) : NULL;
+    if (!schema) {
+        printf("FAILED: Could not get result schema: %s\n", datafusion_get_last_error());
+        datafusion_result_free(result);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    if (datafusion_schema_field_type(schema, 0, NULL) != DATAFUSION_TYPE_STRUCT
+        || datafusion_schema_field_type(schema, 1, NULL) != DATAFUSION_TYPE_LIST) {
+        printf("FAILED: Unexpected field types\n");
+        failed = 1;
+    }
+    
+    DataFusionSchema* members = datafusion_schema_field_children(schema, 0, NULL);
+    char* member = members ? datafusion_schema_field_name(members, 1, NULL) : NULL;
+    if (!members || datafusion_schema_field_count(members, NULL) != 2 || !member || strcmp(member, "b") != 0
+        || datafusion_schema_field_type(members, 1, NULL) != DATAFUSION_TYPE_UTF8) {
+        printf("FAILED: Unexpected struct children\n");
+        failed = 1;
+    }
+    datafusion_string_free(member);
+    datafusion_schema_free(members);
+    
+    DataFusionSchema* item = datafusion_schema_field_children(schema, 1, NULL);
+    DataFusionSchema* none = datafusion_schema_field_children(schema, 2, NULL);
+    if (!item || datafusion_schema_field_count(item, NULL) != 1 || datafusion_schema_field_type(item, 0, NULL) != DATAFUSION_TYPE_INT64
+        || !none || datafusion_schema_field_count(none, NULL) != 0) {
+        printf("FAILED: Unexpected list children\n");
+        failed = 1;
+    }
+    datafusion_schema_free(item);
+    datafusion_schema_free(none);
+    
+    struct ArrowSchema exported;
+    if (datafusion_schema_export(schema, &exported, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not export schema: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else {
+        if (strcmp(exported.format, "+s") != 0 || exported.n_children != 3 || strcmp(exported.children[0]->format, "+s") != 0) {
+            printf("FAILED: Unexpected exported schema\n");
+            failed = 1;
+        }
+        exported.release(&exported);
+    }
+    
+    datafusion_schema_free(schema);
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Schemas of results and tables inspected and exported\n");
+    return 0;
+}
+
+int test_dictionary_columns() {
+    printf("Test 33: Dictionary encoded columns\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT arrow_cast(column1, 'Dictionary(Int32, Utf8)') AS city, "
+        "arrow_cast(column2, 'Dictionary(Int8, Int64)') AS code "
+        "FROM (VALUES ('Paris', 1), (NULL, 2), ('Paris', NULL), ('Oslo', 1))", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_result_column_type(result, 0, NULL) != DATAFUSION_TYPE_DICTIONARY) {
+        printf("FAILED: Expected a dictionary column\n");
+        failed = 1;
+    }
+    
+    char* city = datafusion_result_get_string_copy(result, 0, 3, 0, NULL);
+    int64_t code = 0;
+    if (!city || strcmp(city, "Oslo") != 0
+        || datafusion_result_get_int64(result, 0, 1, 1, &code, NULL) != DATAFUSION_OK || code != 2) {
+        printf("FAILED: Unexpected decoded values: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(city);
+    
+    const char* data = NULL;
+    size_t len = 0;
+    if (datafusion_result_get_string(result, 0, 1, 0, &data, &len, NULL) != DATAFUSION_NULL_VALUE
+        || !datafusion_result_is_null(result, 0, 2, 1, NULL)
+        || datafusion_result_get_int64(result, 0, 2, 1, &code, NULL) != DATAFUSION_NULL_VALUE) {
+        printf("FAILED: Expected null dictionary values to be reported\n");
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_float64(result, 0, 0, 1, NULL, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected DATAFUSION_TYPE_MISMATCH for the decoded value type\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Dictionary values decoded by cell accessors\n");
+    return 0;
+}
+
+static int last_error_code() {
+    DataFusionError* error = datafusion_last_error();
+    int code = error ? datafusion_error_code(error) : DATAFUSION_OK;
+    datafusion_error_free(error);
+    return code;
+}
+
+int test_max_result_bytes() {
+    printf("Test 34: Result memory limit\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx, "SELECT * FROM generate_series(1, 100000)", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t bytes = datafusion_result_memory_bytes(result, NULL);
+    if (bytes < 100000 * 8) {
+        printf("FAILED: Unexpected result size of %lld bytes\n", (long long)bytes);
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    char* error = NULL;
+    datafusion_context_set_max_result_bytes(ctx, 64 * 1024, NULL);
+    result = datafusion_sql(ctx, "SELECT * FROM generate_series(1, 100000)", &error);
+    if (result || last_error_code() != DATAFUSION_RESOURCE_EXHAUSTED) {
+        printf("FAILED: Expected DATAFUSION_RESOURCE_EXHAUSTED for an oversized result\n");
+        failed = 1;
+    } else {
+        printf("Error: %s\n", error);
+    }
+    datafusion_string_free(error);
+    datafusion_result_free(result);
+    
+    result = datafusion_sql(ctx, "SELECT 1", NULL);
+    if (!result) {
+        printf("FAILED: Small results should stay within the limit: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    datafusion_context_set_max_result_bytes(ctx, 0, NULL);
+    result = datafusion_sql(ctx, "SELECT * FROM generate_series(1, 100000)", NULL);
+    if (!result) {
+        printf("FAILED: Removing the limit should allow large results: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Oversized results rejected\n");
+    return 0;
+}
+
+int test_write_parquet() {
+    printf("Test 35: Writing results to Parquet\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM employees", NULL);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* output_path = "test_output.parquet";
+    if (datafusion_result_write_parquet(result, output_path, "snappy", 2, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write Parquet file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_write_parquet(result, output_path, "bogus", 0, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown codec to be rejected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // Read the file back
+    result = failed ? NULL : datafusion_sql(ctx,
+        "CREATE EXTERNAL TABLE written STORED AS PARQUET LOCATION 'test_output.parquet'", NULL);
+    datafusion_result_free(result);
+    result = failed ? NULL : datafusion_sql(ctx, "SELECT name FROM written WHERE id = 3", NULL);
+    char* name = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!failed && (!name || strcmp(name, "Carol") != 0)) {
+        printf("FAILED: Could not read the Parquet file back: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    
+    unlink(output_path);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result written to a Parquet file\n");
+    return 0;
+}
+
+int test_columns_by_name() {
+    printf("Test 36: Column access by name\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT 'x' AS label, CAST(7 AS INT) AS small, CAST(42 AS BIGINT) AS big, 2.5 AS ratio, false AS flag", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_result_column_index(result, "ratio", NULL) != 3 || datafusion_result_column_index(result, "missing", NULL) != -1) {
+        printf("FAILED: Unexpected column indices\n");
+        failed = 1;
+    }
+    
+    int64_t big = 0;
+    int32_t small = 0;
+    double ratio = 0;
+    bool flag = true;
+    const char* label = NULL;
+    size_t len = 0;
+    if (datafusion_result_get_int64_by_name(result, 0, 0, "big", &big, NULL) != DATAFUSION_OK || big != 42
+        || datafusion_result_get_int32_by_name(result, 0, 0, "small", &small, NULL) != DATAFUSION_OK || small != 7
+        || datafusion_result_get_float64_by_name(result, 0, 0, "ratio", &ratio, NULL) != DATAFUSION_OK || ratio != 2.5
+        || datafusion_result_get_bool_by_name(result, 0, 0, "flag", &flag, NULL) != DATAFUSION_OK || flag
+        || datafusion_result_get_string_by_name(result, 0, 0, "label", &label, &len, NULL) != DATAFUSION_OK
+        || len != 1 || label[0] != 'x') {
+        printf("FAILED: Unexpected values: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (datafusion_result_get_int64_by_name(result, 0, 0, "missing", &big, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown column name to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Columns looked up and read by name\n");
+    return 0;
+}
+
+int test_write_ndjson() {
+    printf("Test 37: Writing results to newline delimited JSON\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT * FROM (VALUES (1, 'a'), (2, 'b')) AS t(id, tag)", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* plain_path = "test_output.ndjson";
+    const char* gzip_path = "test_output.ndjson.gz";
+    if (datafusion_result_write_ndjson(result, plain_path, NULL, NULL) != DATAFUSION_OK
+        || datafusion_result_write_ndjson(result, gzip_path, "gzip", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write NDJSON files: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_write_ndjson(result, plain_path, "bogus", NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown compression to be rejected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    char contents[128] = {0};
+    FILE* file = fopen(plain_path, "rb");
+    if (file) {
+        fread(contents, 1, sizeof(contents) - 1, file);
+        fclose(file);
+    }
+    if (!failed && strcmp(contents, "{\"id\":1,\"tag\":\"a\"}\n{\"id\":2,\"tag\":\"b\"}\n") != 0) {
+        printf("FAILED: Unexpected NDJSON contents: %s\n", contents);
+        failed = 1;
+    }
+    
+    unsigned char magic[2] = {0};
+    file = fopen(gzip_path, "rb");
+    if (file) {
+        fread(magic, 1, sizeof(magic), file);
+        fclose(file);
+    }
+    if (!failed && (magic[0] != 0x1f || magic[1] != 0x8b)) {
+        printf("FAILED: Expected a gzip compressed file\n");
+        failed = 1;
+    }
+    
+    unlink(plain_path);
+    unlink(gzip_path);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Result written as plain and gzip compressed NDJSON\n");
+    return 0;
+}
+
+int test_row_cursor() {
+    printf("Test 38: Row cursor\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT * FROM (VALUES (1, 'a'), (2, NULL), (3, 'c')) AS t(id, tag) ORDER BY id", NULL) : NULL;
+    // Split the rows over several batches, including an empty one
+    DataFusionResult* batched = result ? datafusion_result_coalesce(result, 2, NULL) : NULL;
+    DataFusionRowCursor* cursor = batched ? datafusion_result_cursor(batched, NULL) : NULL;
+    datafusion_result_free(result);
+    datafusion_result_free(batched);
+    if (!cursor) {
+        printf("FAILED: Could not create cursor: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_cursor_get_int64(cursor, 0, &(int64_t){0}, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected reading before the first row to fail\n");
+        failed = 1;
+    }
+    
+    int64_t id_sum = 0;
+    int rows = 0;
+    int nulls = 0;
+    while (datafusion_cursor_next(cursor, NULL)) {
+        int64_t id = 0;
+        if (datafusion_cursor_get_int64(cursor, 0, &id, NULL) != DATAFUSION_OK
+            || datafusion_cursor_row_number(cursor, NULL) != rows) {
+            printf("FAILED: Could not read row %d: %s\n", rows, datafusion_get_last_error());
+            failed = 1;
+            break;
+        }
+        id_sum += id;
+        if (datafusion_cursor_is_null(cursor, 1, NULL)) {
+            nulls++;
+        } else {
+            const char* tag = NULL;
+            size_t len = 0;
+            if (datafusion_cursor_get_string(cursor, 1, &tag, &len, NULL) != DATAFUSION_OK || len != 1 || tag[0] != 'a' + id - 1) {
+                printf("FAILED: Unexpected tag in row %d\n", rows);
+                failed = 1;
+            }
+        }
+        rows++;
+    }
+    if (!failed && (rows != 3 || id_sum != 6 || nulls != 1)) {
+        printf("FAILED: Expected 3 rows with ids summing to 6 and one null tag, got %d rows\n", rows);
+        failed = 1;
+    }
+    if (datafusion_cursor_next(cursor, NULL)) {
+        printf("FAILED: Expected the cursor to stay at the end\n");
+        failed = 1;
+    }
+    
+    datafusion_cursor_free(cursor);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Iterated 3 rows with a cursor\n");
+    return 0;
+}
+
+int test_nested_values() {
+    printf("Test 39: Nested struct, list and map values\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT named_struct('name', 'widget', 'dims', [CAST(3 AS BIGINT), 4, 5], "
+        "'owner', named_struct('id', CAST(7 AS INT), 'nick', CAST(NULL AS VARCHAR)))  AS item, "
+        "MAP(['a', 'b'], [1.5, 2.5]) AS weights, CAST(NULL AS BIGINT[]) AS missing", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t offset = -1, length = -1;
+    if (datafusion_result_get_child_count(result, 0, 0, 0, NULL) != 3
+        || datafusion_result_get_child_count(result, 0, 0, 1, NULL) != 2
+        || datafusion_result_get_list_range(result, 0, 0, 1, &offset, &length, NULL) != DATAFUSION_OK
+        || offset != 0 || length != 2) {
+        printf("FAILED: Unexpected child counts or list range: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_get_list_range(result, 0, 0, 2, &offset, &length, NULL) != DATAFUSION_NULL_VALUE
+        || datafusion_result_get_list_range(result, 0, 0, 0, &offset, &length, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected null and non-list columns to be rejected\n");
+        failed = 1;
+    }
+    
+    int64_t dim = 0;
+    int32_t owner = 0;
+    double weight = 0;
+    const char* name = NULL;
+    size_t len = 0;
+    if (datafusion_result_get_child_count_by_path(result, 0, 0, "item.dims", NULL) != 3
+        || datafusion_result_get_int64_by_path(result, 0, 0, "item.dims[2]", &dim, NULL) != DATAFUSION_OK || dim != 5
+        || datafusion_result_get_int32_by_path(result, 0, 0, "item.owner.id", &owner, NULL) != DATAFUSION_OK || owner != 7
+        || datafusion_result_get_float64_by_path(result, 0, 0, "weights[1].value", &weight, NULL) != DATAFUSION_OK || weight != 2.5
+        || datafusion_result_get_string_by_path(result, 0, 0, "weights[0].key", &name, &len, NULL) != DATAFUSION_OK
+        || len != 1 || name[0] != 'a'
+        || datafusion_result_get_string_by_path(result, 0, 0, "item.name", &name, &len, NULL) != DATAFUSION_OK
+        || len != 6 || strncmp(name, "widget", len) != 0) {
+        printf("FAILED: Unexpected nested values: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (!datafusion_result_is_null_by_path(result, 0, 0, "item.owner.nick", NULL)
+        || !datafusion_result_is_null_by_path(result, 0, 0, "missing[0]", NULL)
+        || datafusion_result_is_null_by_path(result, 0, 0, "item.dims[0]", NULL)) {
+        printf("FAILED: Unexpected null checks\n");
+        failed = 1;
+    }
+    if (datafusion_result_get_int64_by_path(result, 0, 0, "item.dims[3]", &dim, NULL) != DATAFUSION_INVALID_ARGUMENT
+        || datafusion_result_get_int64_by_path(result, 0, 0, "item.nope", &dim, NULL) != DATAFUSION_INVALID_ARGUMENT
+        || datafusion_result_get_int64_by_path(result, 0, 0, "item.dims[x]", &dim, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected invalid paths to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Nested values read by path\n");
+    return 0;
+}
+
+int test_display_values() {
+    printf("Test 40: Formatting cells of any type\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql(ctx,
+        "SELECT TIMESTAMP '2024-01-02 03:04:05' AS ts, CAST(12.5 AS DECIMAL(6, 2)) AS amount, "
+        "[1, 2, 3] AS nums, named_struct('a', 1, 'b', 'x') AS rec, CAST(NULL AS INT) AS nothing", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    const char* expected[] = {"2024-01-02T03:04:05", "12.50", "[1, 2, 3]", "{a: 1, b: x}", ""};
+    int failed = 0;
+    for (int col = 0; col < 5; col++) {
+        char* display = datafusion_result_get_display(result, 0, 0, col, NULL);
+        if (!display || strcmp(display, expected[col]) != 0) {
+            printf("FAILED: Column %d formatted as '%s', expected '%s'\n", col, display ? display : "(error)", expected[col]);
+            failed = 1;
+        }
+        datafusion_string_free(display);
+    }
+    if (datafusion_result_get_display(result, 0, 1, 0, NULL) != NULL) {
+        printf("FAILED: Expected an out of range row to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Timestamps, decimals, lists, structs and nulls formatted\n");
+    return 0;
+}
+
+int test_column_statistics() {
+    printf("Test 41: Column null counts and min/max\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* query = ctx ? datafusion_sql(ctx,
+        "SELECT n, x, s, CAST(NULL AS INT) AS nothing FROM (VALUES (3, 2.5, 'b'), (NULL, -1.0, 'a'), (7, NULL, 'c')) AS t(n, x, s)", NULL) : NULL;
+    // Spread the rows over one batch per row
+    DataFusionResult* result = query ? datafusion_result_coalesce(query, 1, NULL) : NULL;
+    datafusion_result_free(query);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_result_column_null_count(result, 0, NULL) != 1
+        || datafusion_result_column_null_count(result, 2, NULL) != 0
+        || datafusion_result_column_null_count(result, 3, NULL) != 3
+        || datafusion_result_column_null_count(result, 4, NULL) != -1) {
+        printf("FAILED: Unexpected null counts\n");
+        failed = 1;
+    }
+    
+    int64_t min = 0, max = 0;
+    double fmin = 0, fmax = 0;
+    if (datafusion_result_column_min_max_int64(result, 0, &min, &max, NULL) != DATAFUSION_OK || min != 3 || max != 7
+        || datafusion_result_column_min_max_float64(result, 1, &fmin, &fmax, NULL) != DATAFUSION_OK || fmin != -1.0 || fmax != 2.5) {
+        printf("FAILED: Unexpected numeric min/max: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_column_min_max_int64(result, 2, &min, &max, NULL) != DATAFUSION_TYPE_MISMATCH
+        || datafusion_result_column_min_max_int64(result, 3, &min, &max, NULL) != DATAFUSION_NULL_VALUE) {
+        printf("FAILED: Expected string and all-null columns to be rejected\n");
+        failed = 1;
+    }
+    
+    char* smin = datafusion_result_column_min(result, 2, NULL);
+    char* smax = datafusion_result_column_max(result, 2, NULL);
+    char* error = NULL;
+    char* none = datafusion_result_column_min(result, 3, &error);
+    if (!smin || !smax || strcmp(smin, "a") != 0 || strcmp(smax, "c") != 0 || none || error) {
+        printf("FAILED: Unexpected string min/max\n");
+        failed = 1;
+    }
+    datafusion_string_free(smin);
+    datafusion_string_free(smax);
+    datafusion_string_free(error);
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Null counts and min/max computed across batches\n");
+    return 0;
+}
+
+int test_result_take() {
+    printf("Test 42: Taking rows by index\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* query = ctx ? datafusion_sql(ctx,
+        "SELECT * FROM (VALUES (10), (20), (30), (40)) AS t(n) ORDER BY n", NULL) : NULL;
+    DataFusionResult* result = query ? datafusion_result_coalesce(query, 3, NULL) : NULL;
+    datafusion_result_free(query);
+    if (!result) {
+        printf("FAILED: Could not execute query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    const int64_t indices[] = {3, 0, 3, 1};
+    const int64_t expected[] = {40, 10, 40, 20};
+    DataFusionResult* taken = datafusion_result_take(result, indices, 4, NULL);
+    if (!taken || datafusion_result_total_rows(taken, NULL) != 4) {
+        printf("FAILED: Could not take rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    for (int row = 0; taken && !failed && row < 4; row++) {
+        int64_t value = 0;
+        if (datafusion_result_get_int64(taken, 0, row, 0, &value, NULL) != DATAFUSION_OK || value != expected[row]) {
+            printf("FAILED: Row %d is %lld, expected %lld\n", row, (long long)value, (long long)expected[row]);
+            failed = 1;
+        }
+    }
+    datafusion_result_free(taken);
+    
+    DataFusionResult* none = datafusion_result_take(result, NULL, 0, NULL);
+    if (!none || datafusion_result_total_rows(none, NULL) != 0 || datafusion_result_column_count(none, NULL) != 1) {
+        printf("FAILED: Expected taking no rows to keep the schema: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(none);
+    
+    const int64_t out_of_range[] = {4};
+    if (datafusion_result_take(result, out_of_range, 1, NULL) != NULL
+        || datafusion_result_take(result, NULL, 1, NULL) != NULL) {
+        printf("FAILED: Expected invalid indices to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Rows gathered across batches in index order\n");
+    return 0;
+}
+
+int test_streaming_query() {
+    printf("Test 43: Streaming query results\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* set = ctx ? datafusion_sql(ctx, "SET datafusion.execution.batch_size = 3", NULL) : NULL;
+    datafusion_result_free(set);
+    DataFusionStream* stream = ctx ? datafusion_sql_stream(ctx, "SELECT value FROM generate_series(1, 10)", NULL) : NULL;
+    // The stream keeps working after its context is freed
+    datafusion_context_free(ctx);
+    if (!stream) {
+        printf("FAILED: Could not start stream: %s\n", datafusion_get_last_error());
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionSchema* schema = datafusion_stream_schema(stream, NULL);
+    if (!schema || datafusion_schema_field_count(schema, NULL) != 1) {
+        printf("FAILED: Unexpected stream schema: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_schema_free(schema);
+    
+    int batches = 0;
+    int64_t sum = 0;
+    DataFusionResult* batch;
+    while ((batch = datafusion_stream_next(stream, NULL)) != NULL) {
+        int64_t rows = datafusion_result_total_rows(batch, NULL);
+        for (int row = 0; row < rows; row++) {
+            int64_t value = 0;
+            datafusion_result_get_int64(batch, 0, row, 0, &value, NULL);
+            sum += value;
+        }
+        batches++;
+        datafusion_result_free(batch);
+    }
+    char* error = NULL;
+    if (datafusion_stream_next(stream, &error) != NULL || error) {
+        printf("FAILED: Expected an exhausted stream to stay exhausted without an error\n");
+        failed = 1;
+    }
+    datafusion_string_free(error);
+    if (!failed && (sum != 55 || batches < 2)) {
+        printf("FAILED: Expected values summing to 55 in several batches, got %lld in %d\n", (long long)sum, batches);
+        failed = 1;
+    }
+    
+    datafusion_stream_free(stream);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Streamed 10 rows batch by batch\n");
+    return 0;
+}
+
+// Completion state shared with the asynchronous query callback
+typedef struct {
+    pthread_mutex_t mutex;
+    pthread_cond_t done;
+    int completed;
+    int64_t rows;
+    int error_code;
+} AsyncQueryState;
+
+static void on_query_done(DataFusionResult* result, DataFusionError* error, void* user_data) {
+    AsyncQueryState* state = user_data;
+    pthread_mutex_lock(&state->mutex);
+    if (result) {
+        state->rows = datafusion_result_total_rows(result, NULL);
+        datafusion_result_free(result);
+    }
+    if (error) {
+        state->error_code = datafusion_error_code(error);
+        datafusion_error_free(error);
+    }
+    state->completed++;
+    pthread_cond_signal(&state->done);
+    pthread_mutex_unlock(&state->mutex);
+}
+
+static void wait_for_query(AsyncQueryState* state, int count) {
+    pthread_mutex_lock(&state->mutex);
+    while (state->completed < count) {
+        pthread_cond_wait(&state->done, &state->mutex);
+    }
+    pthread_mutex_unlock(&state->mutex);
+}
+
+int test_async_query() {
+    printf("Test 44: Asynchronous queries\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx) {
+        printf("FAILED: Could not create context: %s\n", datafusion_get_last_error());
+        return -1;
+    }
+    
+    AsyncQueryState state = {PTHREAD_MUTEX_INITIALIZER, PTHREAD_COND_INITIALIZER, 0, -1, DATAFUSION_OK};
+    int failed = 0;
+    if (datafusion_sql_async(ctx, "SELECT * FROM generate_series(1, 5)", on_query_done, &state, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not submit query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    wait_for_query(&state, 1);
+    if (state.rows != 5 || state.error_code != DATAFUSION_OK) {
+        printf("FAILED: Expected 5 rows, got %lld\n", (long long)state.rows);
+        failed = 1;
+    }
+    
+    if (datafusion_sql_async(ctx, "SELECT * FROM missing_table", on_query_done, &state, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not submit failing query: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else {
+        wait_for_query(&state, 2);
+        if (state.error_code == DATAFUSION_OK) {
+            printf("FAILED: Expected the callback to receive an error\n");
+            failed = 1;
+        }
+    }
+    
+    if (datafusion_sql_async(ctx, "SELECT 1", NULL, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null callback to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Query results and errors delivered to the callback\n");
+    return 0;
+}
+
+int test_query_cancellation() {
+    printf("Test 45: Query cancellation\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionQuery* quick = ctx ? datafusion_sql_submit(ctx, "SELECT 42 AS answer", NULL) : NULL;
+    if (!quick) {
+        printf("FAILED: Could not submit query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionResult* result = datafusion_query_wait(quick, NULL);
+    int64_t answer = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &answer, NULL) != DATAFUSION_OK || answer != 42
+        || !datafusion_query_is_finished(quick, NULL)) {
+        printf("FAILED: Unexpected result of the quick query: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    if (datafusion_query_wait(quick, NULL) != NULL) {
+        printf("FAILED: Expected the result to be taken only once\n");
+        failed = 1;
+    }
+    datafusion_query_free(quick);
+    
+    DataFusionQuery* slow = datafusion_sql_submit(ctx,
+        "SELECT count(*) FROM generate_series(1, 100000000000) WHERE value % 7 = 3", NULL);
+    // The query keeps running after its context is freed
+    datafusion_context_free(ctx);
+    if (!slow || datafusion_query_is_finished(slow, NULL) || datafusion_query_cancel(slow, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not cancel the slow query: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = slow ? datafusion_query_wait(slow, NULL) : NULL;
+    if (slow && (result || last_error_code() != DATAFUSION_CANCELLED)) {
+        printf("FAILED: Expected the cancelled query to fail with DATAFUSION_CANCELLED\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_query_free(slow);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Query result waited for and long query cancelled\n");
+    return 0;
+}
+
+int test_query_timeouts() {
+    printf("Test 46: Query timeouts\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_context_set_query_timeout_ms(ctx, 200, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set query timeout: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    const char* slow_sql = "SELECT count(*) FROM generate_series(1, 100000000000) WHERE value % 7 = 3";
+    int failed = 0;
+    DataFusionResult* result = datafusion_sql(ctx, slow_sql, NULL);
+    if (result || last_error_code() != DATAFUSION_TIMEOUT) {
+        printf("FAILED: Expected the context timeout to stop the query\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    result = datafusion_sql_with_timeout(ctx, slow_sql, 100, NULL);
+    if (result || last_error_code() != DATAFUSION_TIMEOUT) {
+        printf("FAILED: Expected the per-call timeout to stop the query\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    result = datafusion_sql_with_timeout(ctx, "SELECT 1", 0, NULL);
+    if (!result) {
+        printf("FAILED: Could not run a query without a timeout: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_context_set_query_timeout_ms(ctx, -1, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a negative timeout to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Slow queries stopped with DATAFUSION_TIMEOUT\n");
+    return 0;
+}
+
+// Run a prepared statement and return its row count, or -1 on error
+static int64_t execute_count(DataFusionStatement* stmt) {
+    DataFusionResult* result = datafusion_stmt_execute(stmt, NULL);
+    int64_t rows = result ? datafusion_result_total_rows(result, NULL) : -1;
+    datafusion_result_free(result);
+    return rows;
+}
+
+int test_prepared_statements() {
+    printf("Test 47: Prepared statements\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* created = ctx ? datafusion_sql(ctx,
+        "CREATE TABLE people AS SELECT * FROM (VALUES (1, 'Alice', 25.5), (2, 'Bob', 30.0), (3, 'Carol', NULL)) AS t(id, name, score)", NULL) : NULL;
+    datafusion_result_free(created);
+    DataFusionStatement* stmt = created ? datafusion_prepare(ctx,
+        "SELECT id FROM people WHERE id >= $1 AND name <> $2 AND (score > $3 OR score IS NULL)", NULL) : NULL;
+    if (!stmt) {
+        printf("FAILED: Could not prepare statement: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_stmt_parameter_count(stmt, NULL) != 3) {
+        printf("FAILED: Expected 3 parameters\n");
+        failed = 1;
+    }
+    
+    datafusion_stmt_bind_int64(stmt, 1, 1, NULL);
+    datafusion_stmt_bind_string(stmt, 2, "Bob", NULL);
+    if (datafusion_stmt_execute(stmt, NULL) || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected executing with an unbound parameter to fail\n");
+        failed = 1;
+    }
+    
+    datafusion_stmt_bind_double(stmt, 3, 20.0, NULL);
+    int64_t rows = execute_count(stmt);
+    if (rows != 2) {
+        printf("FAILED: Expected 2 rows, got %lld: %s\n", (long long)rows, datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    // Rebinding reuses the plan, and bound strings are values rather than SQL
+    datafusion_stmt_bind_string(stmt, 2, "x' OR '1'='1", NULL);
+    datafusion_stmt_bind_double(stmt, 3, 26.0, NULL);
+    rows = execute_count(stmt);
+    if (rows != 2) {
+        printf("FAILED: Expected 2 rows after rebinding, got %lld\n", (long long)rows);
+        failed = 1;
+    }
+    
+    datafusion_stmt_bind_null(stmt, 1, NULL);
+    rows = execute_count(stmt);
+    if (rows != 0) {
+        printf("FAILED: Expected no rows when comparing with NULL, got %lld\n", (long long)rows);
+        failed = 1;
+    }
+    
+    if (datafusion_stmt_bind_int64(stmt, 4, 0, NULL) != DATAFUSION_INVALID_ARGUMENT
+        || datafusion_stmt_bind_int64(stmt, 0, 0, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected out of range parameter indexes to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_stmt_free(stmt);
+    
+    // Inserts write their rows on every execution, while DDL is rejected without running
+    stmt = datafusion_prepare(ctx, "INSERT INTO people VALUES ($1, $2, NULL)", NULL);
+    datafusion_stmt_bind_int64(stmt, 1, 4, NULL);
+    datafusion_stmt_bind_string(stmt, 2, "Dave", NULL);
+    DataFusionResult* inserted = stmt ? datafusion_stmt_execute(stmt, NULL) : NULL;
+    datafusion_result_free(inserted);
+    datafusion_stmt_bind_int64(stmt, 1, 5, NULL);
+    inserted = inserted ? datafusion_stmt_execute(stmt, NULL) : NULL;
+    datafusion_result_free(inserted);
+    datafusion_stmt_free(stmt);
+    DataFusionResult* count = datafusion_sql(ctx, "SELECT count(*) FROM people WHERE name = 'Dave'", NULL);
+    int64_t daves = 0;
+    if (!inserted || !count || datafusion_result_get_int64(count, 0, 0, 0, &daves, NULL) != DATAFUSION_OK || daves != 2) {
+        printf("FAILED: Expected a prepared insert to insert a row per execution: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(count);
+    
+    if (datafusion_prepare(ctx, "CREATE TABLE prepared AS SELECT 1", NULL) || last_error_code() != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_prepare(ctx, "DROP TABLE people", NULL) || !datafusion_table_exists(ctx, "people", NULL) ||
+        datafusion_table_exists(ctx, "prepared", NULL)) {
+        printf("FAILED: Expected DDL to be rejected without running\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Statement executed with rebound parameters\n");
+    return 0;
+}
+
+int test_sql_script() {
+    printf("Test 48: Multi-statement scripts\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionScript* script = ctx ? datafusion_sql_script(ctx,
+        "CREATE TABLE nums AS VALUES (1), (2), (3);\n"
+        "INSERT INTO nums VALUES (4);\n"
+        "SELECT sum(column1) AS total FROM nums;\n"
+        "SELECT * FROM missing_table;\n"
+        "SELECT 1;", NULL) : NULL;
+    if (!script) {
+        printf("FAILED: Could not run script: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_script_statement_count(script, NULL) != 5) {
+        printf("FAILED: Expected 5 statements\n");
+        failed = 1;
+    }
+    for (int i = 0; i < 3; i++) {
+        if (datafusion_script_status(script, i, NULL) != DATAFUSION_OK) {
+            printf("FAILED: Statement %d failed: %s\n", i, datafusion_get_last_error());
+            failed = 1;
+        }
+    }
+    
+    DataFusionResult* result = datafusion_script_result(script, 2, NULL);
+    int64_t total = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &total, NULL) != DATAFUSION_OK || total != 10) {
+        printf("FAILED: Expected the query to see the created and inserted rows\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_script_status(script, 3, NULL) != DATAFUSION_ERROR
+        || !strstr(datafusion_get_last_error(), "missing_table")) {
+        printf("FAILED: Expected the statement reading a missing table to fail\n");
+        failed = 1;
+    }
+    if (datafusion_script_result(script, 3, NULL) || last_error_code() != DATAFUSION_ERROR) {
+        printf("FAILED: Expected the failed statement to have no result\n");
+        failed = 1;
+    }
+    if (datafusion_script_status(script, 4, NULL) != DATAFUSION_CANCELLED) {
+        printf("FAILED: Expected the statement after the failure to be skipped\n");
+        failed = 1;
+    }
+    if (datafusion_script_status(script, 5, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an out of range statement index to be rejected\n");
+        failed = 1;
+    }
+    datafusion_script_free(script);
+    
+    if (datafusion_sql_script(ctx, "SELECT 1; SELEC 2", NULL)) {
+        printf("FAILED: Expected a script with a syntax error to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Script statements executed in order until the first failure\n");
+    return 0;
+}
+
+int test_collect_partitioned() {
+    printf("Test 49: Partition-preserving collection\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = ctx ? datafusion_sql_collect_partitioned(ctx,
+        "SELECT * FROM generate_series(1, 3) UNION ALL SELECT * FROM generate_series(4, 10)", NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not collect partitioned result: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    int partitions = datafusion_result_partition_count(result, NULL);
+    if (partitions < 2) {
+        printf("FAILED: Expected the union's inputs in separate partitions, got %d\n", partitions);
+        failed = 1;
+    }
+    
+    // Every partition covers its own range of batches, together covering all rows
+    int next_batch = 0;
+    int64_t rows = 0;
+    for (int p = 0; p < partitions; p++) {
+        int first = datafusion_result_partition_first_batch(result, p, NULL);
+        int count = datafusion_result_partition_batch_count(result, p, NULL);
+        if (first != next_batch || count < 0) {
+            printf("FAILED: Unexpected batches for partition %d\n", p);
+            failed = 1;
+            break;
+        }
+        for (int b = first; b < first + count; b++) {
+            rows += datafusion_result_batch_num_rows(result, b, NULL);
+        }
+        next_batch = first + count;
+    }
+    if (!failed && (next_batch != datafusion_result_batch_count(result, NULL) || rows != 10)) {
+        printf("FAILED: Expected the partitions to cover all 10 rows, got %lld\n", (long long)rows);
+        failed = 1;
+    }
+    if (datafusion_result_partition_batch_count(result, partitions, NULL) != -1
+        || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an out of range partition to be rejected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    result = datafusion_sql(ctx, "SELECT 1", NULL);
+    if (!result || datafusion_result_partition_count(result, NULL) != 1
+        || datafusion_result_partition_batch_count(result, 0, NULL) != datafusion_result_batch_count(result, NULL)) {
+        printf("FAILED: Expected a collected result to have a single partition\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: %d output partitions collected separately\n", partitions);
+    return 0;
+}
+
+int test_execute_update() {
+    printf("Test 50: Executing updates\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int64_t rows_affected = 0;
+    if (!ctx || datafusion_execute_update(ctx, "CREATE TABLE events (id BIGINT, kind VARCHAR)", &rows_affected, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not create table: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (rows_affected != -1) {
+        printf("FAILED: Expected DDL to report no row count, got %lld\n", (long long)rows_affected);
+        failed = 1;
+    }
+    
+    if (datafusion_execute_update(ctx, "INSERT INTO events VALUES (1, 'a'), (2, 'b'), (3, 'a')", &rows_affected, NULL) != DATAFUSION_OK
+        || rows_affected != 3) {
+        printf("FAILED: Expected 3 inserted rows, got %lld: %s\n", (long long)rows_affected, datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    if (datafusion_execute_update(ctx, "INSERT INTO events SELECT id + 10, kind FROM events WHERE kind = 'a'", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not insert without a row count: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM events", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 rows after the inserts, got %lld\n", (long long)count);
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // A query whose result looks like a row count is still a query
+    if (datafusion_execute_update(ctx, "SELECT arrow_cast(3, 'UInt64') AS count", &rows_affected, NULL) != DATAFUSION_OK
+        || rows_affected != -1) {
+        printf("FAILED: Expected a query to report no row count, got %lld\n", (long long)rows_affected);
+        failed = 1;
+    }
+    
+    if (datafusion_execute_update(ctx, "INSERT INTO missing VALUES (1)", &rows_affected, NULL) != DATAFUSION_ERROR) {
+        printf("FAILED: Expected inserting into a missing table to fail\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Inserted row counts reported\n");
+    return 0;
+}
+
+int test_explain() {
+    printf("Test 51: Explaining query plans\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    const char* sql = "SELECT department, count(*) FROM employees WHERE age > 26 GROUP BY department";
+    char* logical = NULL;
+    char* physical = NULL;
+    int failed = 0;
+    if (datafusion_explain(ctx, sql, false, &logical, &physical, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not explain query: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else if (!strstr(logical, "Aggregate") || !strstr(logical, "employees")
+        || !strstr(physical, "AggregateExec") || !strstr(physical, "CsvExec")) {
+        printf("FAILED: Unexpected plans:\n%s\n%s\n", logical, physical);
+        failed = 1;
+    }
+    datafusion_string_free(logical);
+    datafusion_string_free(physical);
+    
+    // Verbose logical plans include operator schemas; outputs may be skipped
+    logical = NULL;
+    if (datafusion_explain(ctx, sql, true, &logical, NULL, NULL) != DATAFUSION_OK || !strstr(logical, "department:Utf8")) {
+        printf("FAILED: Expected a verbose logical plan with schemas\n");
+        failed = 1;
+    }
+    datafusion_string_free(logical);
+    
+    if (datafusion_explain(ctx, "SELECT * FROM missing", false, &logical, &physical, NULL) != DATAFUSION_ERROR) {
+        printf("FAILED: Expected explaining an invalid query to fail\n");
+        failed = 1;
+    }
+    
+    // Explaining plans a statement without running it
+    datafusion_explain(ctx, "CREATE TABLE explained AS VALUES (1)", false, NULL, NULL, NULL);
+    if (datafusion_table_exists(ctx, "explained", NULL)) {
+        printf("FAILED: Expected explaining DDL not to run it\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Logical and physical plans described\n");
+    return 0;
+}
+
+int test_explain_analyze() {
+    printf("Test 52: Explain analyze\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    const char* sql = "SELECT value % 3 AS k, count(*) FROM generate_series(1, 1000) GROUP BY value % 3";
+    char* text = ctx ? datafusion_explain_analyze(ctx, sql, false, NULL) : NULL;
+    if (!text) {
+        printf("FAILED: Could not analyze query: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (!strstr(text, "AggregateExec") || !strstr(text, "metrics=[output_rows=")) {
+        printf("FAILED: Expected operators annotated with metrics:\n%s\n", text);
+        failed = 1;
+    }
+    datafusion_string_free(text);
+    
+    // Timeouts wrap the plan internally; the analyzed plan must not show it
+    datafusion_context_set_query_timeout_ms(ctx, 60000, NULL);
+    char* json = datafusion_explain_analyze(ctx, sql, true, NULL);
+    if (!json || !strstr(json, "\"operators\"") || !strstr(json, "\"output_rows\"") || strstr(json, "Cancellable")) {
+        printf("FAILED: Expected operator metrics as JSON\n");
+        failed = 1;
+    }
+    datafusion_string_free(json);
+    
+    if (datafusion_explain_analyze(ctx, "SELECT * FROM missing", false, NULL)) {
+        printf("FAILED: Expected analyzing an invalid query to fail\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Executed plan annotated with runtime metrics\n");
+    return 0;
+}
+
+int test_plan_graphviz() {
+    printf("Test 53: Graphviz plan export\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    char* dot = NULL;
+    if (!ctx || datafusion_plan_graphviz(ctx,
+            "SELECT value % 3, count(*) FROM generate_series(1, 100) GROUP BY value % 3", &dot, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not render plan: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    if (!strstr(dot, "digraph {") || !strstr(dot, "AggregateExec") || !strstr(dot, "->")) {
+        printf("FAILED: Unexpected DOT output:\n%s\n", dot);
+        failed = 1;
+    }
+    datafusion_string_free(dot);
+    
+    if (datafusion_plan_graphviz(ctx, "SELECT 1", NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null output to be rejected\n");
+        failed = 1;
+    }
+    
+    dot = NULL;
+    datafusion_plan_graphviz(ctx, "CREATE TABLE rendered AS VALUES (1)", &dot, NULL);
+    datafusion_string_free(dot);
+    if (datafusion_table_exists(ctx, "rendered", NULL)) {
+        printf("FAILED: Expected rendering DDL not to run it\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Physical plan rendered as a DOT graph\n");
+    return 0;
+}
+
+// Substrait plan of "SELECT name FROM people WHERE id > 1" for a table people (id BIGINT, name VARCHAR),
+// serialized by DataFusion 45
+static const uint8_t people_substrait_plan[] = {
+    0x12, 0x0c, 0x1a, 0x0a, 0x08, 0xff, 0xff, 0xff, 0xff, 0x0f, 0x1a, 0x02, 0x67, 0x74, 0x1a, 0x6d,
+    0x12, 0x6b, 0x0a, 0x63, 0x3a, 0x61, 0x0a, 0x05, 0x12, 0x03, 0x0a, 0x01, 0x02, 0x12, 0x4c, 0x12,
+    0x4a, 0x12, 0x30, 0x0a, 0x2e, 0x12, 0x18, 0x0a, 0x02, 0x69, 0x64, 0x0a, 0x04, 0x6e, 0x61, 0x6d,
+    0x65, 0x12, 0x0c, 0x0a, 0x04, 0x3a, 0x02, 0x10, 0x01, 0x0a, 0x04, 0x62, 0x02, 0x10, 0x01, 0x22,
+    0x08, 0x0a, 0x06, 0x0a, 0x00, 0x0a, 0x02, 0x08, 0x01, 0x3a, 0x08, 0x0a, 0x06, 0x70, 0x65, 0x6f,
+    0x70, 0x6c, 0x65, 0x1a, 0x16, 0x1a, 0x14, 0x22, 0x0a, 0x1a, 0x08, 0x12, 0x06, 0x0a, 0x02, 0x12,
+    0x00, 0x22, 0x00, 0x22, 0x06, 0x1a, 0x04, 0x0a, 0x02, 0x38, 0x01, 0x1a, 0x0a, 0x12, 0x08, 0x0a,
+    0x04, 0x12, 0x02, 0x08, 0x01, 0x22, 0x00, 0x12, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x32, 0x0e, 0x10,
+    0x40, 0x2a, 0x0a, 0x64, 0x61, 0x74, 0x61, 0x66, 0x75, 0x73, 0x69, 0x6f, 0x6e,
+};
+
+int test_execute_substrait() {
+    printf("Test 54: Executing Substrait plans\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* created = ctx ? datafusion_sql(ctx,
+        "CREATE TABLE people AS SELECT * FROM (VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol')) AS t(id, name)", NULL) : NULL;
+    datafusion_result_free(created);
+    DataFusionResult* result = created ? datafusion_execute_substrait(ctx, people_substrait_plan, sizeof(people_substrait_plan), NULL) : NULL;
+    if (!result) {
+        printf("FAILED: Could not execute Substrait plan: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* name = NULL;
+    size_t len = 0;
+    if (datafusion_result_total_rows(result, NULL) != 2
+        || datafusion_result_get_string(result, 0, 0, 0, &name, &len, NULL) != DATAFUSION_OK
+        || len != 3 || strncmp(name, "Bob", 3) != 0) {
+        printf("FAILED: Expected Bob and Carol from the plan\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    const uint8_t garbage[] = {0xff, 0xff, 0xff};
+    if (datafusion_execute_substrait(ctx, garbage, sizeof(garbage), NULL) || last_error_code() != DATAFUSION_ERROR) {
+        printf("FAILED: Expected a malformed plan to be rejected\n");
+        failed = 1;
+    }
+    if (datafusion_execute_substrait(ctx, NULL, 10, NULL) || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null plan to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Substrait plan executed against a registered table\n");
+    return 0;
+}
+
+int test_sql_to_substrait() {
+    printf("Test 55: Producing Substrait plans\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* created = ctx ? datafusion_sql(ctx,
+        "CREATE TABLE scores AS SELECT * FROM (VALUES ('a', 1), ('b', 5), ('a', 7)) AS t(team, points)", NULL) : NULL;
+    datafusion_result_free(created);
+    uint8_t* plan = NULL;
+    size_t len = 0;
+    if (!created || datafusion_sql_to_substrait(ctx,
+            "SELECT team, sum(points) AS total FROM scores GROUP BY team ORDER BY team", &plan, &len, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not produce Substrait plan: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    // The plan round-trips through the consumer
+    int failed = 0;
+    DataFusionResult* result = len > 0 ? datafusion_execute_substrait(ctx, plan, len, NULL) : NULL;
+    int64_t total = 0;
+    if (!result || datafusion_result_total_rows(result, NULL) != 2
+        || datafusion_result_get_int64(result, 0, 0, 1, &total, NULL) != DATAFUSION_OK || total != 8) {
+        printf("FAILED: Expected the round-tripped plan to sum team a to 8: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_buffer_free(plan, len);
+    
+    if (datafusion_sql_to_substrait(ctx, "SELECT * FROM missing", &plan, &len, NULL) != DATAFUSION_ERROR) {
+        printf("FAILED: Expected an invalid query to fail\n");
+        failed = 1;
+    }
+    if (datafusion_sql_to_substrait(ctx, "SELECT * FROM scores", NULL, &len, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null output buffer to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Substrait plan produced and executed\n");
+    return 0;
+}
+
+int test_plan_serialization() {
+    printf("Test 56: Plan serialization\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionStatement* stmt = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        stmt = datafusion_prepare(ctx, "SELECT name FROM employees WHERE salary > $1 ORDER BY name", NULL);
+    }
+    uint8_t* plan = NULL;
+    size_t len = 0;
+    if (!stmt || datafusion_plan_serialize(stmt, &plan, &len, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not serialize plan: %s\n", datafusion_get_last_error());
+        datafusion_stmt_free(stmt);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    datafusion_stmt_free(stmt);
+    datafusion_context_free(ctx);
+    
+    // The plan is loaded by a context that never registered the table
+    int failed = 0;
+    ctx = datafusion_context_new(NULL);
+    stmt = datafusion_plan_deserialize(ctx, plan, len, NULL);
+    datafusion_buffer_free(plan, len);
+    DataFusionResult* result = NULL;
+    if (!stmt || datafusion_stmt_parameter_count(stmt, NULL) != 1
+        || datafusion_stmt_bind_int64(stmt, 1, 70000, NULL) != DATAFUSION_OK
+        || !(result = datafusion_stmt_execute(stmt, NULL))) {
+        printf("FAILED: Could not execute deserialized plan: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else if (datafusion_result_total_rows(result, NULL) != 3) {
+        printf("FAILED: Expected 3 employees earning over 70000\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_stmt_free(stmt);
+    
+    const uint8_t garbage[] = {0x0a, 0xff, 0x01};
+    if (datafusion_plan_deserialize(ctx, garbage, sizeof(garbage), NULL) || last_error_code() != DATAFUSION_ERROR) {
+        printf("FAILED: Expected a malformed plan to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Plan serialized, loaded and executed in another context\n");
+    return 0;
+}
+
+int test_sql_validate() {
+    printf("Test 57: SQL validation\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* created = ctx ? datafusion_sql(ctx,
+        "CREATE TABLE orders AS SELECT * FROM (VALUES (1, 9.5)) AS t(id, amount)", NULL) : NULL;
+    datafusion_result_free(created);
+    if (!created) {
+        printf("FAILED: Could not create table: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    const char* cases[][2] = {
+        {"SELECT id, amount FROM orders", "\"valid\":true"},
+        {"SELECT id\nFROM orders WHERE id >", "\"kind\":\"syntax\""},
+        {"SELECT id FROM orderz", "\"kind\":\"unknown_table\""},
+        {"SELECT id,\n  amout FROM orders", "\"kind\":\"unknown_column\""},
+        {"SELECT id + 'x' * true FROM orders", "\"kind\":\"plan\""},
+    };
+    int failed = 0;
+    for (int i = 0; i < 5; i++) {
+        char* diagnostics = datafusion_sql_validate(ctx, cases[i][0], NULL);
+        if (!diagnostics || !strstr(diagnostics, cases[i][1])) {
+            printf("FAILED: Expected %s for \"%s\", got %s\n", cases[i][1], cases[i][0], diagnostics ? diagnostics : datafusion_get_last_error());
+            failed = 1;
+        } else if (i == 3 && (!strstr(diagnostics, "\"name\":\"amout\"") || !strstr(diagnostics, "\"line\":2,")
+            || !strstr(diagnostics, "\"column\":3,") || !strstr(diagnostics, "\"amount\""))) {
+            printf("FAILED: Expected the position of the unknown column and candidates, got %s\n", diagnostics);
+            failed = 1;
+        } else if (i == 1 && !strstr(diagnostics, "\"line\":2,")) {
+            printf("FAILED: Expected the position of the syntax error, got %s\n", diagnostics);
+            failed = 1;
+        }
+        datafusion_string_free(diagnostics);
+    }
+    
+    // Validation never executes statements
+    char* diagnostics = datafusion_sql_validate(ctx, "CREATE TABLE created AS SELECT 1", NULL);
+    datafusion_string_free(diagnostics);
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM created", NULL);
+    if (!diagnostics || result) {
+        printf("FAILED: Expected validating DDL not to create the table\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Syntax errors and unresolved names diagnosed\n");
+    return 0;
+}
+
+int test_query_registry() {
+    printf("Test 58: Query registry\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* running = ctx ? datafusion_queries_list(ctx, NULL) : NULL;
+    if (!running || datafusion_result_total_rows(running, NULL) != 0 || datafusion_result_column_count(running, NULL) != 3) {
+        printf("FAILED: Expected no running queries: %s\n", datafusion_get_last_error());
+        datafusion_result_free(running);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    datafusion_result_free(running);
+    
+    int failed = 0;
+    const char* slow_sql = "SELECT count(*) FROM generate_series(1, 100000000000) WHERE value % 7 = 3";
+    DataFusionQuery* slow = datafusion_sql_submit(ctx, slow_sql, NULL);
+    DataFusionStream* stream = datafusion_sql_stream(ctx, "SELECT value FROM generate_series(1, 10)", NULL);
+    running = datafusion_queries_list(ctx, NULL);
+    int64_t slow_id = 0;
+    int64_t stream_id = 0;
+    const char* sql = NULL;
+    size_t sql_len = 0;
+    if (!slow || !stream || !running || datafusion_result_total_rows(running, NULL) != 2
+        || datafusion_result_get_int64(running, 0, 0, 0, &slow_id, NULL) != DATAFUSION_OK
+        || datafusion_result_get_int64(running, 0, 1, 0, &stream_id, NULL) != DATAFUSION_OK
+        || datafusion_result_get_string(running, 0, 0, 1, &sql, &sql_len, NULL) != DATAFUSION_OK
+        || sql_len != strlen(slow_sql) || strncmp(sql, slow_sql, sql_len) != 0) {
+        printf("FAILED: Expected the submitted query and the stream to be listed: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(running);
+    
+    if (datafusion_query_kill(ctx, slow_id, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not kill the slow query: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = slow ? datafusion_query_wait(slow, NULL) : NULL;
+    if (slow && (result || last_error_code() != DATAFUSION_CANCELLED)) {
+        printf("FAILED: Expected the killed query to fail with DATAFUSION_CANCELLED\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_query_free(slow);
+    
+    if (datafusion_query_kill(ctx, stream_id, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not kill the stream: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = stream ? datafusion_stream_next(stream, NULL) : NULL;
+    if (stream && (result || last_error_code() != DATAFUSION_CANCELLED)) {
+        printf("FAILED: Expected the killed stream to fail with DATAFUSION_CANCELLED\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_stream_free(stream);
+    
+    // Finished queries are no longer listed and cannot be killed
+    running = datafusion_queries_list(ctx, NULL);
+    if (!running || datafusion_result_total_rows(running, NULL) != 0
+        || datafusion_query_kill(ctx, slow_id, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected finished queries to be removed from the registry\n");
+        failed = 1;
+    }
+    datafusion_result_free(running);
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Running queries listed and killed\n");
+    return 0;
+}
+
+// Progress reported to the progress callback
+typedef struct {
+    int reports;
+    int64_t rows;
+    int64_t bytes;
+    int decreased;
+} ProgressState;
+
+static void on_progress(int64_t rows_produced, int64_t bytes_scanned, void* user_data) {
+    ProgressState* state = (ProgressState*)user_data;
+    if (rows_produced < state->rows || bytes_scanned < state->bytes) {
+        state->decreased = 1;
+    }
+    state->reports++;
+    state->rows = rows_produced;
+    state->bytes = bytes_scanned;
+}
+
+int test_query_progress() {
+    printf("Test 59: Query progress\n");
+    
+    const char* parquet_path = "test_progress.parquet";
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    DataFusionResult* written = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        datafusion_result_free(datafusion_sql(ctx, "COPY employees TO 'test_progress.parquet' STORED AS PARQUET", NULL));
+        written = datafusion_sql(ctx,
+            "CREATE EXTERNAL TABLE progress_src STORED AS PARQUET LOCATION 'test_progress.parquet'", NULL);
+    }
+    if (!written) {
+        printf("FAILED: Could not create Parquet table: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        unlink(parquet_path);
+        return -1;
+    }
+    datafusion_result_free(written);
+    
+    int failed = 0;
+    ProgressState state = {0};
+    DataFusionResult* result = datafusion_sql_with_progress(ctx,
+        "SELECT department, sum(salary) FROM progress_src GROUP BY department", on_progress, 1, &state, NULL);
+    if (!result || state.reports < 1 || state.rows != 5 || state.bytes <= 0 || state.decreased) {
+        printf("FAILED: Expected the scan of 5 rows to be reported: %s\n", result ? "" : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    ProgressState csv_state = {0};
+    result = datafusion_sql_with_progress(ctx, "SELECT * FROM employees", on_progress, 1000, &csv_state, NULL);
+    if (!result || csv_state.reports != 1 || csv_state.rows != 5) {
+        printf("FAILED: Expected a final progress report for the CSV scan\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    result = datafusion_sql_with_progress(ctx, "SELECT 1", on_progress, 0, &state, NULL);
+    if (result || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a zero interval to be rejected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    unlink(parquet_path);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Rows and bytes scanned reported to the callback\n");
+    return 0;
+}
+
+int test_max_result_rows() {
+    printf("Test 60: Result row limit\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_context_set_max_result_rows(ctx, 1000, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set the row limit: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM generate_series(1, 1000)", NULL);
+    if (!result || datafusion_result_total_rows(result, NULL) != 1000) {
+        printf("FAILED: Results at the limit should be collected: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // Fails as soon as the limit is exceeded instead of collecting the whole result
+    result = datafusion_sql(ctx, "SELECT * FROM generate_series(1, 100000000000)", NULL);
+    if (result || last_error_code() != DATAFUSION_RESOURCE_EXHAUSTED) {
+        printf("FAILED: Expected DATAFUSION_RESOURCE_EXHAUSTED for a result over the row limit\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_context_set_max_result_rows(ctx, -1, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a negative row limit to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_set_max_result_rows(ctx, 0, NULL);
+    result = datafusion_sql(ctx, "SELECT * FROM generate_series(1, 100000)", NULL);
+    if (!result) {
+        printf("FAILED: Removing the limit should allow large results: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Results over the row limit rejected\n");
+    return 0;
+}
+
+int test_sql_to_dataframe() {
+    printf("Test 61: DataFrames from SQL\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx) {
+        datafusion_result_free(datafusion_sql(ctx, "CREATE TABLE events AS VALUES (1), (2)", NULL));
+        df = datafusion_sql_to_dataframe(ctx, "SELECT count(*) AS n FROM events", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    // The DataFrame is executed when collected, so it sees rows inserted after it was created
+    int failed = 0;
+    int64_t rows_affected = 0;
+    if (datafusion_execute_update(ctx, "INSERT INTO events VALUES (3)", &rows_affected, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not insert: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    for (int i = 0; i < 2; i++) {
+        DataFusionResult* result = datafusion_dataframe_collect(df, NULL);
+        int64_t n = 0;
+        if (!result || datafusion_result_get_int64(result, 0, 0, 0, &n, NULL) != DATAFUSION_OK || n != 3) {
+            printf("FAILED: Expected the DataFrame to count 3 rows, got %lld: %s\n", (long long)n, datafusion_get_last_error());
+            failed = 1;
+        }
+        datafusion_result_free(result);
+    }
+    datafusion_dataframe_free(df);
+    
+    df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM missing_table", NULL);
+    if (df || last_error_code() == DATAFUSION_OK) {
+        printf("FAILED: Expected planning errors when creating the DataFrame\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(df);
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame planned once and executed on collect\n");
+    return 0;
+}
+
+int test_sql_with_params() {
+    printf("Test 62: Named query parameters\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not set up context: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* names[] = {"dept", "min_salary"};
+    DataFusionScalar values[2];
+    values[0].data_type = DATAFUSION_TYPE_UTF8;
+    values[0].value.utf8 = "Engineering";
+    values[1].data_type = DATAFUSION_TYPE_INT64;
+    values[1].value.int64 = 78000;
+    DataFusionResult* result = datafusion_sql_with_params(ctx,
+        "SELECT name FROM employees WHERE department = :dept AND salary > :min_salary ORDER BY name",
+        names, values, 2, NULL);
+    char* name = result ? datafusion_result_get_string_copy(result, 0, 1, 0, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 2 || !name || strcmp(name, "Eve") != 0) {
+        printf("FAILED: Expected Charlie and Eve: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    
+    // Values are not spliced into the SQL, so quotes need no escaping
+    const char* quote_name[] = {"s"};
+    DataFusionScalar quote_value;
+    quote_value.data_type = DATAFUSION_TYPE_UTF8;
+    quote_value.value.utf8 = "O'Brien";
+    result = datafusion_sql_with_params(ctx, "SELECT :s", quote_name, &quote_value, 1, NULL);
+    name = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!name || strcmp(name, "O'Brien") != 0) {
+        printf("FAILED: Expected the string to round trip: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    
+    result = datafusion_sql_with_params(ctx, "SELECT :missing", quote_name, &quote_value, 1, NULL);
+    if (result) {
+        printf("FAILED: Expected an error for a placeholder without a value\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    const char* duplicate_names[] = {"dept", "dept"};
+    result = datafusion_sql_with_params(ctx, "SELECT :dept", duplicate_names, values, 2, NULL);
+    if (result || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected duplicate parameters to be rejected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    values[0].data_type = DATAFUSION_TYPE_DATE32;
+    result = datafusion_sql_with_params(ctx, "SELECT :dept", names, values, 1, NULL);
+    if (result || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected unsupported value types to be rejected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Named parameters bound without quoting\n");
+    return 0;
+}
+
+int test_dataframe_filter() {
+    printf("Test 63: DataFrame filters\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionDataFrame* engineers = datafusion_dataframe_filter(df,
+        "salary > 70000 AND department = 'Engineering'", NULL);
+    DataFusionDataFrame* young = engineers ? datafusion_dataframe_filter(engineers, "age < 33", NULL) : NULL;
+    // Derived DataFrames do not depend on the handles they were built from
+    datafusion_dataframe_free(engineers);
+    DataFusionResult* result = young ? datafusion_dataframe_collect(young, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 2) {
+        printf("FAILED: Expected 2 young engineers: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(young);
+    
+    result = datafusion_dataframe_collect(df, NULL);
+    if (!result || datafusion_result_total_rows(result, NULL) != 5) {
+        printf("FAILED: Expected filtering to leave the original DataFrame unchanged\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    const char* invalid[] = {"salary >", "missing_column = 1", "salary + 1"};
+    for (int i = 0; i < 3; i++) {
+        DataFusionDataFrame* filtered = datafusion_dataframe_filter(df, invalid[i], NULL);
+        if (filtered) {
+            printf("FAILED: Expected an error for the predicate \"%s\"\n", invalid[i]);
+            failed = 1;
+        }
+        datafusion_dataframe_free(filtered);
+    }
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrames filtered with SQL predicates\n");
+    return 0;
+}
+
+int test_dataframe_select() {
+    printf("Test 64: DataFrame projections\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    create_test_csv();
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* columns[] = {"salary", "name"};
+    DataFusionDataFrame* selected = datafusion_dataframe_select(df, columns, 2, NULL);
+    DataFusionResult* result = selected ? datafusion_dataframe_collect(selected, NULL) : NULL;
+    char* first = result ? datafusion_result_column_name(result, 0, NULL) : NULL;
+    if (!result || datafusion_result_column_count(result, NULL) != 2 || !first || strcmp(first, "salary") != 0) {
+        printf("FAILED: Expected the salary and name columns: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(first);
+    datafusion_result_free(result);
+    datafusion_dataframe_free(selected);
+    
+    const char* exprs[] = {"upper(name) AS upper_name", "salary / 1000 AS salary_k"};
+    selected = datafusion_dataframe_select_exprs(df, exprs, 2, NULL);
+    DataFusionDataFrame* filtered = selected ? datafusion_dataframe_filter(selected, "salary_k > 80", NULL) : NULL;
+    result = filtered ? datafusion_dataframe_collect(filtered, NULL) : NULL;
+    char* name = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 1 || !name || strcmp(name, "CAROL") != 0) {
+        printf("FAILED: Expected CAROL from the computed columns: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    datafusion_dataframe_free(filtered);
+    datafusion_dataframe_free(selected);
+    
+    const char* missing[] = {"name", "missing_column"};
+    selected = datafusion_dataframe_select(df, missing, 2, NULL);
+    if (selected) {
+        printf("FAILED: Expected an error for an unknown column\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(selected);
+    
+    selected = datafusion_dataframe_select(df, NULL, 2, NULL);
+    if (selected || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null column array to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(selected);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame columns selected and computed\n");
+    return 0;
+}
+
+int test_dataframe_limit() {
+    printf("Test 65: DataFrame limit and offset\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT value FROM generate_series(1, 10) ORDER BY value", NULL) : NULL;
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    // Pages of 4 rows: 1-4, 5-8, 9-10
+    int failed = 0;
+    int64_t expected_rows[] = {4, 4, 2};
+    for (int page = 0; page < 3; page++) {
+        DataFusionDataFrame* limited = datafusion_dataframe_limit(df, page * 4, 4, NULL);
+        DataFusionResult* result = limited ? datafusion_dataframe_collect(limited, NULL) : NULL;
+        int64_t first = 0;
+        if (!result || datafusion_result_total_rows(result, NULL) != expected_rows[page]
+            || datafusion_result_get_int64(result, 0, 0, 0, &first, NULL) != DATAFUSION_OK || first != page * 4 + 1) {
+            printf("FAILED: Unexpected page %d: %s\n", page, datafusion_get_last_error());
+            failed = 1;
+        }
+        datafusion_result_free(result);
+        datafusion_dataframe_free(limited);
+    }
+    
+    DataFusionDataFrame* rest = datafusion_dataframe_limit(df, 7, -1, NULL);
+    DataFusionResult* result = rest ? datafusion_dataframe_collect(rest, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 3) {
+        printf("FAILED: Expected a negative limit to keep all remaining rows\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(rest);
+    
+    rest = datafusion_dataframe_limit(df, -1, 4, NULL);
+    if (rest || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a negative offset to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(rest);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame paged with limit and offset\n");
+    return 0;
+}
+
+int test_dataframe_join() {
+    printf("Test 66: DataFrame joins\n");
+    
+    // Orders and customers come from different contexts
+    DataFusionContext* orders_ctx = datafusion_context_new(NULL);
+    DataFusionContext* customers_ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* orders = orders_ctx ? datafusion_sql_to_dataframe(orders_ctx,
+        "SELECT * FROM (VALUES (1, 10, 100), (2, 10, 200), (3, 20, 300), (4, 40, 400)) AS t(order_id, customer, amount)",
+        NULL) : NULL;
+    DataFusionDataFrame* customers = customers_ctx ? datafusion_sql_to_dataframe(customers_ctx,
+        "SELECT * FROM (VALUES (10, 'Alice'), (20, 'Bob'), (30, 'Carol')) AS t(id, name)", NULL) : NULL;
+    if (!orders || !customers) {
+        printf("FAILED: Could not create DataFrames: %s\n", datafusion_get_last_error());
+        datafusion_dataframe_free(orders);
+        datafusion_dataframe_free(customers);
+        datafusion_context_free(orders_ctx);
+        datafusion_context_free(customers_ctx);
+        return -1;
+    }
+    
+    const char* left_columns[] = {"customer"};
+    const char* right_columns[] = {"id"};
+    struct {
+        int join_type;
+        int64_t rows;
+        int columns;
+    } cases[] = {
+        {DATAFUSION_JOIN_INNER, 3, 5},
+        {DATAFUSION_JOIN_LEFT, 4, 5},
+        {DATAFUSION_JOIN_FULL, 5, 5},
+        {DATAFUSION_JOIN_LEFT_SEMI, 3, 3},
+        {DATAFUSION_JOIN_LEFT_ANTI, 1, 3},
+        {DATAFUSION_JOIN_RIGHT_ANTI, 1, 2},
+    };
+    int failed = 0;
+    for (size_t i = 0; i < sizeof(cases) / sizeof(cases[0]); i++) {
+        DataFusionDataFrame* joined = datafusion_dataframe_join(orders, customers, cases[i].join_type,
+            left_columns, right_columns, 1, NULL);
+        DataFusionResult* result = joined ? datafusion_dataframe_collect(joined, NULL) : NULL;
+        if (!result || datafusion_result_total_rows(result, NULL) != cases[i].rows
+            || datafusion_result_column_count(result, NULL) != cases[i].columns) {
+            printf("FAILED: Unexpected result of join type %d: %s\n", cases[i].join_type, datafusion_get_last_error());
+            failed = 1;
+        }
+        datafusion_result_free(result);
+        datafusion_dataframe_free(joined);
+    }
+    
+    // The joined DataFrame can be transformed further
+    DataFusionDataFrame* joined = datafusion_dataframe_join(orders, customers, DATAFUSION_JOIN_INNER,
+        left_columns, right_columns, 1, NULL);
+    DataFusionDataFrame* alice = joined ? datafusion_dataframe_filter(joined, "name = 'Alice'", NULL) : NULL;
+    DataFusionResult* result = alice ? datafusion_dataframe_collect(alice, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 2) {
+        printf("FAILED: Expected 2 orders of Alice: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(alice);
+    datafusion_dataframe_free(joined);
+    
+    joined = datafusion_dataframe_join(orders, customers, 42, left_columns, right_columns, 1, NULL);
+    if (joined || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an invalid join type to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(joined);
+    
+    const char* unknown_columns[] = {"missing"};
+    joined = datafusion_dataframe_join(orders, customers, DATAFUSION_JOIN_INNER,
+        left_columns, unknown_columns, 1, NULL);
+    if (joined) {
+        printf("FAILED: Expected a join on an unknown column to fail\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(joined);
+    
+    datafusion_dataframe_free(orders);
+    datafusion_dataframe_free(customers);
+    datafusion_context_free(orders_ctx);
+    datafusion_context_free(customers_ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrames joined with each join type\n");
+    return 0;
+}
+
+int test_dataframe_aggregate() {
+    printf("Test 67: DataFrame aggregation\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* group_exprs[] = {"department"};
+    const char* aggr_exprs[] = {"count(*) AS employees", "sum(salary) AS total"};
+    DataFusionDataFrame* by_department = datafusion_dataframe_aggregate(df, group_exprs, 1, aggr_exprs, 2, NULL);
+    DataFusionDataFrame* engineering = by_department
+        ? datafusion_dataframe_filter(by_department, "department = 'Engineering'", NULL) : NULL;
+    DataFusionResult* result = engineering ? datafusion_dataframe_collect(engineering, NULL) : NULL;
+    int64_t employees = 0, total = 0;
+    if (!result || datafusion_result_total_rows(result, NULL) != 1
+        || datafusion_result_get_int64(result, 0, 0, 1, &employees, NULL) != DATAFUSION_OK || employees != 3
+        || datafusion_result_get_int64(result, 0, 0, 2, &total, NULL) != DATAFUSION_OK || total != 240000) {
+        printf("FAILED: Unexpected aggregates of Engineering: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(engineering);
+    
+    result = by_department ? datafusion_dataframe_collect(by_department, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 3) {
+        printf("FAILED: Expected one row per department\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(by_department);
+    
+    // Without group expressions, the aggregates cover all rows
+    const char* max_age[] = {"max(age)"};
+    DataFusionDataFrame* oldest = datafusion_dataframe_aggregate(df, NULL, 0, max_age, 1, NULL);
+    result = oldest ? datafusion_dataframe_collect(oldest, NULL) : NULL;
+    int64_t age = 0;
+    if (!result || datafusion_result_total_rows(result, NULL) != 1
+        || datafusion_result_get_int64(result, 0, 0, 0, &age, NULL) != DATAFUSION_OK || age != 35) {
+        printf("FAILED: Expected a maximum age of 35: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(oldest);
+    
+    const char* invalid[] = {"sum(salary"};
+    DataFusionDataFrame* aggregated = datafusion_dataframe_aggregate(df, group_exprs, 1, invalid, 1, NULL);
+    if (aggregated) {
+        printf("FAILED: Expected an invalid aggregate expression to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(aggregated);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame grouped and aggregated\n");
+    return 0;
+}
+
+int test_dataframe_sort() {
+    printf("Test 68: DataFrame sort\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (1, 2, 10), (2, 1, NULL), (3, 2, 30), (4, 1, 20), (5, 2, NULL)) AS t(id, grp, value)",
+        NULL) : NULL;
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    // By group ascending, then by value descending with nulls first
+    const char* exprs[] = {"grp", "value"};
+    bool ascending[] = {true, false};
+    bool nulls_first[] = {false, true};
+    int64_t expected_ids[] = {2, 4, 5, 3, 1};
+    DataFusionDataFrame* sorted = datafusion_dataframe_sort(df, exprs, ascending, nulls_first, 2, NULL);
+    DataFusionResult* result = sorted ? datafusion_dataframe_collect(sorted, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 5) {
+        printf("FAILED: Could not sort DataFrame: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    for (int row = 0; result && !failed && row < 5; row++) {
+        int64_t id = 0;
+        if (datafusion_result_get_int64(result, 0, row, 0, &id, NULL) != DATAFUSION_OK || id != expected_ids[row]) {
+            printf("FAILED: Expected id %lld at row %d, got %lld\n", (long long)expected_ids[row], row, (long long)id);
+            failed = 1;
+        }
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(sorted);
+    
+    // Top 2 values, with nulls last
+    const char* value_expr[] = {"value * 2"};
+    bool descending[] = {false};
+    bool nulls_last[] = {false};
+    sorted = datafusion_dataframe_sort(df, value_expr, descending, nulls_last, 1, NULL);
+    DataFusionDataFrame* top = sorted ? datafusion_dataframe_limit(sorted, 0, 2, NULL) : NULL;
+    result = top ? datafusion_dataframe_collect(top, NULL) : NULL;
+    int64_t first = 0, second = 0;
+    if (!result || datafusion_result_total_rows(result, NULL) != 2
+        || datafusion_result_get_int64(result, 0, 0, 0, &first, NULL) != DATAFUSION_OK || first != 3
+        || datafusion_result_get_int64(result, 0, 1, 0, &second, NULL) != DATAFUSION_OK || second != 4) {
+        printf("FAILED: Unexpected top 2 rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(top);
+    datafusion_dataframe_free(sorted);
+    
+    sorted = datafusion_dataframe_sort(df, exprs, NULL, nulls_first, 2, NULL);
+    if (sorted || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected null sort orders to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(sorted);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame sorted by multiple keys\n");
+    return 0;
+}
+
+int test_dataframe_union() {
+    printf("Test 69: DataFrame union\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* monday = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (1, 'a'), (2, 'b')) AS t(id, name)", NULL) : NULL;
+    DataFusionDataFrame* tuesday = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (2, 'b'), (3, 'c'), (4, 'd')) AS t(id, name)", NULL) : NULL;
+    DataFusionDataFrame* swapped = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES ('e', 5)) AS t(name, id)", NULL) : NULL;
+    DataFusionDataFrame* mistyped = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (true, 'f')) AS t(id, name)", NULL) : NULL;
+    if (!monday || !tuesday || !swapped || !mistyped) {
+        printf("FAILED: Could not create DataFrames: %s\n", datafusion_get_last_error());
+        datafusion_dataframe_free(monday);
+        datafusion_dataframe_free(tuesday);
+        datafusion_dataframe_free(swapped);
+        datafusion_dataframe_free(mistyped);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionDataFrame* all = datafusion_dataframe_union(monday, tuesday, NULL);
+    DataFusionResult* result = all ? datafusion_dataframe_collect(all, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 5) {
+        printf("FAILED: Expected 5 rows in the union: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(all);
+    
+    DataFusionDataFrame* distinct = datafusion_dataframe_union_distinct(monday, tuesday, NULL);
+    result = distinct ? datafusion_dataframe_collect(distinct, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 4) {
+        printf("FAILED: Expected 4 rows in the distinct union: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(distinct);
+    
+    // Columns are matched by position, so their names and types must match
+    DataFusionDataFrame* incompatible[] = {swapped, mistyped};
+    const char* expected_errors[] = {"column 0 is id in a but name in b", "column id is Int64 in a but Boolean in b"};
+    for (int i = 0; i < 2; i++) {
+        char* error = NULL;
+        DataFusionDataFrame* combined = datafusion_dataframe_union(monday, incompatible[i], &error);
+        if (combined || last_error_code() != DATAFUSION_INVALID_ARGUMENT || !error || !strstr(error, expected_errors[i])) {
+            printf("FAILED: Expected error \"%s\", got \"%s\"\n", expected_errors[i], error ? error : "");
+            failed = 1;
+        }
+        datafusion_string_free(error);
+        datafusion_dataframe_free(combined);
+    }
+    
+    datafusion_dataframe_free(monday);
+    datafusion_dataframe_free(tuesday);
+    datafusion_dataframe_free(swapped);
+    datafusion_dataframe_free(mistyped);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrames combined with union and union distinct\n");
+    return 0;
+}
+
+int test_dataframe_distinct() {
+    printf("Test 70: DataFrame distinct\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (1, 'a', 10), (1, 'a', 10), (1, 'a', 20), (2, 'b', 30), (2, 'c', 30)) AS t(id, name, value)",
+        NULL) : NULL;
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionDataFrame* distinct = datafusion_dataframe_distinct(df, NULL);
+    DataFusionResult* result = distinct ? datafusion_dataframe_collect(distinct, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 4) {
+        printf("FAILED: Expected 4 distinct rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(distinct);
+    
+    // One row per id, with all columns
+    const char* key[] = {"id"};
+    distinct = datafusion_dataframe_distinct_on(df, key, 1, NULL);
+    result = distinct ? datafusion_dataframe_collect(distinct, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 2 || datafusion_result_column_count(result, NULL) != 3) {
+        printf("FAILED: Expected 2 rows of 3 columns distinct on id: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(distinct);
+    
+    const char* keys[] = {"id", "name"};
+    distinct = datafusion_dataframe_distinct_on(df, keys, 2, NULL);
+    result = distinct ? datafusion_dataframe_collect(distinct, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 3) {
+        printf("FAILED: Expected 3 rows distinct on id and name: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(distinct);
+    
+    const char* unknown[] = {"missing"};
+    distinct = datafusion_dataframe_distinct_on(df, unknown, 1, NULL);
+    if (distinct) {
+        printf("FAILED: Expected an unknown column to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(distinct);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame deduplicated with distinct and distinct on\n");
+    return 0;
+}
+
+int test_dataframe_with_column() {
+    printf("Test 71: DataFrame computed and renamed columns\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (1, 3, 25), (2, 4, 50)) AS t(id, quantity, price)", NULL) : NULL;
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionDataFrame* with_total = datafusion_dataframe_with_column(df, "total", "quantity * price", NULL);
+    // Replacing an existing column keeps its position
+    DataFusionDataFrame* with_price = with_total
+        ? datafusion_dataframe_with_column(with_total, "price", "CAST(price AS DOUBLE) / 10", NULL) : NULL;
+    DataFusionDataFrame* renamed = with_price
+        ? datafusion_dataframe_with_column_renamed(with_price, "quantity", "qty", NULL) : NULL;
+    DataFusionResult* result = renamed ? datafusion_dataframe_collect(renamed, NULL) : NULL;
+    int64_t total = 0;
+    double price = 0;
+    char* name = result ? datafusion_result_column_name(result, 1, NULL) : NULL;
+    if (!result || datafusion_result_column_count(result, NULL) != 4
+        || datafusion_result_get_int64(result, 0, 1, 3, &total, NULL) != DATAFUSION_OK || total != 200
+        || datafusion_result_get_float64(result, 0, 1, 2, &price, NULL) != DATAFUSION_OK || price != 5.0
+        || !name || strcmp(name, "qty") != 0) {
+        printf("FAILED: Unexpected computed columns: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    datafusion_dataframe_free(renamed);
+    datafusion_dataframe_free(with_price);
+    datafusion_dataframe_free(with_total);
+    
+    renamed = datafusion_dataframe_with_column_renamed(df, "missing", "other", NULL);
+    if (renamed) {
+        printf("FAILED: Expected renaming an unknown column to fail\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(renamed);
+    
+    with_total = datafusion_dataframe_with_column(df, "total", "quantity *", NULL);
+    if (with_total) {
+        printf("FAILED: Expected an invalid expression to be rejected\n");
+        failed = 1;
+    }
+    datafusion_dataframe_free(with_total);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame columns computed and renamed\n");
+    return 0;
+}
+
+int test_dataframe_stream() {
+    printf("Test 72: DataFrame stream\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT value FROM generate_series(1, 100000)", NULL) : NULL;
+    DataFusionDataFrame* even = df ? datafusion_dataframe_filter(df, "value % 2 = 0", NULL) : NULL;
+    if (!even) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_dataframe_free(df);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionStream* stream = datafusion_dataframe_execute_stream(even, NULL);
+    // The stream is listed as a running query and outlives its DataFrame
+    DataFusionResult* running = ctx ? datafusion_queries_list(ctx, NULL) : NULL;
+    if (!stream || !running || datafusion_result_total_rows(running, NULL) != 1) {
+        printf("FAILED: Could not start stream: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(running);
+    datafusion_dataframe_free(even);
+    
+    int64_t rows = 0;
+    int batches = 0;
+    DataFusionResult* batch;
+    while (stream && (batch = datafusion_stream_next(stream, NULL)) != NULL) {
+        rows += datafusion_result_total_rows(batch, NULL);
+        batches++;
+        datafusion_result_free(batch);
+    }
+    if (rows != 50000 || batches < 2) {
+        printf("FAILED: Expected 50000 rows in several batches, got %lld in %d\n", (long long)rows, batches);
+        failed = 1;
+    }
+    datafusion_stream_free(stream);
+    
+    // The original DataFrame can still be streamed
+    stream = datafusion_dataframe_execute_stream(df, NULL);
+    batch = stream ? datafusion_stream_next(stream, NULL) : NULL;
+    if (!batch) {
+        printf("FAILED: Could not stream the original DataFrame: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(batch);
+    datafusion_stream_free(stream);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame executed as a stream\n");
+    return 0;
+}
+
+int test_dataframe_schema() {
+    printf("Test 73: DataFrame schema\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = ctx ? datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (1, 'a', 2.5)) AS t(id, name, price)", NULL) : NULL;
+    const char* exprs[] = {"id", "upper(name) AS name", "price * 2 AS doubled"};
+    DataFusionDataFrame* projected = df ? datafusion_dataframe_select_exprs(df, exprs, 3, NULL) : NULL;
+    if (!projected) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_dataframe_free(df);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionSchema* schema = datafusion_dataframe_schema(projected, NULL);
+    const char* expected_names[] = {"id", "name", "doubled"};
+    int expected_types[] = {DATAFUSION_TYPE_INT64, DATAFUSION_TYPE_UTF8, DATAFUSION_TYPE_FLOAT64};
+    if (!schema || datafusion_schema_field_count(schema, NULL) != 3) {
+        printf("FAILED: Expected a schema with 3 fields: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    for (int i = 0; schema && !failed && i < 3; i++) {
+        char* name = datafusion_schema_field_name(schema, i, NULL);
+        if (!name || strcmp(name, expected_names[i]) != 0
+            || datafusion_schema_field_type(schema, i, NULL) != expected_types[i]) {
+            printf("FAILED: Unexpected field %d: %s\n", i, name ? name : "");
+            failed = 1;
+        }
+        datafusion_string_free(name);
+    }
+    datafusion_schema_free(schema);
+    
+    schema = datafusion_dataframe_schema(NULL, NULL);
+    if (schema || last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null DataFrame to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_dataframe_free(projected);
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame schema inspected before execution\n");
+    return 0;
+}
+
+int test_dataframe_write_parquet() {
+    printf("Test 74: DataFrame Parquet writes\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* file_path = "test_dataframe.parquet";
+    if (datafusion_dataframe_write_parquet(df, file_path, NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write Parquet file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(datafusion_sql(ctx,
+        "CREATE EXTERNAL TABLE single_file STORED AS PARQUET LOCATION 'test_dataframe.parquet'", NULL));
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM single_file", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 rows in the Parquet file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    unlink(file_path);
+    
+    // Hive-style partitions by department, compressed with snappy in small row groups
+    const char* partition_columns[] = {"department"};
+    DataFusionParquetWriteOptions options = datafusion_parquet_write_options_default();
+    options.compression = "snappy";
+    options.max_row_group_size = 2;
+    options.partition_columns = partition_columns;
+    options.partition_column_count = 1;
+    if (datafusion_dataframe_write_parquet(df, "test_dataframe_parquet/", &options, NULL) != DATAFUSION_OK
+        || access("test_dataframe_parquet/department=Engineering", F_OK) != 0) {
+        printf("FAILED: Could not write partitioned Parquet files: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(datafusion_sql(ctx,
+        "CREATE EXTERNAL TABLE partitioned (id BIGINT, name VARCHAR, age BIGINT, salary BIGINT, department VARCHAR) "
+        "STORED AS PARQUET PARTITIONED BY (department) LOCATION 'test_dataframe_parquet/'", NULL));
+    result = datafusion_sql(ctx, "SELECT count(*) FROM partitioned WHERE department = 'Engineering'", NULL);
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 rows in the Engineering partition: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    system("rm -rf test_dataframe_parquet");
+    
+    options = datafusion_parquet_write_options_default();
+    options.compression = "bogus";
+    if (datafusion_dataframe_write_parquet(df, file_path, &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an invalid compression to be rejected\n");
+        failed = 1;
+    }
+    unlink(file_path);
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame written to Parquet files\n");
+    return 0;
+}
+
+int test_copy_to() {
+    printf("Test 75: COPY TO files\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t rows_affected = 0;
+    DataFusionResult* result = datafusion_sql(ctx,
+        "COPY (SELECT * FROM employees) TO 'test_copy.parquet' STORED AS PARQUET "
+        "OPTIONS ('format.compression' 'zstd(3)', 'format.max_row_group_size' 2)", NULL);
+    if (!result || datafusion_result_rows_affected(result, &rows_affected, NULL) != DATAFUSION_OK
+        || rows_affected != 5) {
+        printf("FAILED: Expected COPY to Parquet to write 5 rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_result_free(datafusion_sql(ctx,
+        "CREATE EXTERNAL TABLE copied STORED AS PARQUET LOCATION 'test_copy.parquet'", NULL));
+    result = datafusion_sql(ctx, "SELECT count(*) FROM copied", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 rows in the copied Parquet file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    // A query is not a count of written rows
+    if (!result || datafusion_result_rows_affected(result, &rows_affected, NULL) != DATAFUSION_OK
+        || rows_affected != -1) {
+        printf("FAILED: Expected a query to report no rows affected\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    unlink("test_copy.parquet");
+    
+    if (datafusion_execute_update(ctx,
+            "COPY (SELECT id, name FROM employees WHERE age > 30) TO 'test_copy.csv' STORED AS CSV "
+            "OPTIONS ('format.has_header' 'true', 'format.delimiter' ';')", &rows_affected, NULL) != DATAFUSION_OK
+        || rows_affected != 2 || access("test_copy.csv", F_OK) != 0) {
+        printf("FAILED: Expected COPY to CSV to write 2 rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    unlink("test_copy.csv");
+    
+    if (datafusion_execute_update(ctx,
+            "COPY (SELECT * FROM employees) TO 'test_copy_json/' STORED AS JSON PARTITIONED BY (department)",
+            &rows_affected, NULL) != DATAFUSION_OK
+        || rows_affected != 5 || access("test_copy_json/department=Engineering", F_OK) != 0) {
+        printf("FAILED: Expected partitioned COPY to JSON to write 5 rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    system("rm -rf test_copy_json");
+    
+    if (datafusion_execute_update(ctx,
+            "COPY (SELECT * FROM employees) TO 'test_copy.parquet' STORED AS PARQUET "
+            "OPTIONS ('format.compression' 'bogus')", NULL, NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected an invalid compression to be rejected\n");
+        failed = 1;
+    }
+    unlink("test_copy.parquet");
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Query results copied to Parquet, CSV and JSON files\n");
+    return 0;
+}
+
+int test_dataframe_count() {
+    printf("Test 76: DataFrame row counts\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t count = 0;
+    if (datafusion_dataframe_count(df, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 rows, got %lld: %s\n", (long long)count, datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionDataFrame* filtered = datafusion_dataframe_filter(df, "department = 'Engineering'", NULL);
+    if (!filtered || datafusion_dataframe_count(filtered, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 Engineering rows, got %lld: %s\n", (long long)count, datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_dataframe_free(filtered);
+    
+    DataFusionDataFrame* empty = datafusion_dataframe_filter(df, "age > 100", NULL);
+    if (!empty || datafusion_dataframe_count(empty, &count, NULL) != DATAFUSION_OK || count != 0) {
+        printf("FAILED: Expected no rows, got %lld: %s\n", (long long)count, datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_dataframe_free(empty);
+    
+    if (datafusion_dataframe_count(df, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null out to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame rows counted without collecting them\n");
+    return 0;
+}
+
+int test_dataframe_explain() {
+    printf("Test 77: DataFrame explain\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        DataFusionDataFrame* all = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+        df = datafusion_dataframe_filter(all, "age > 30", NULL);
+        datafusion_dataframe_free(all);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    char* explain = NULL;
+    if (datafusion_dataframe_explain(df, false, false, &explain, NULL) != DATAFUSION_OK || !explain
+        || !strstr(explain, "logical_plan:") || !strstr(explain, "physical_plan:") || !strstr(explain, "Filter")) {
+        printf("FAILED: Expected logical and physical plans: %s\n", explain ? explain : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(explain);
+    explain = NULL;
+    
+    if (datafusion_dataframe_explain(df, true, false, &explain, NULL) != DATAFUSION_OK || !explain
+        || !strstr(explain, "initial_logical_plan:")) {
+        printf("FAILED: Expected the verbose plan to show the initial logical plan\n");
+        failed = 1;
+    }
+    datafusion_string_free(explain);
+    explain = NULL;
+    
+    if (datafusion_dataframe_explain(df, false, true, &explain, NULL) != DATAFUSION_OK || !explain
+        || !strstr(explain, "output_rows=")) {
+        printf("FAILED: Expected the analyzed plan to show metrics: %s\n", explain ? explain : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(explain);
+    
+    if (datafusion_dataframe_explain(df, false, false, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null out to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame plans explained\n");
+    return 0;
+}
+
+int test_dataframe_cache() {
+    printf("Test 78: DataFrame caching\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    DataFusionDataFrame* cached = df ? datafusion_dataframe_cache(df, NULL) : NULL;
+    if (!cached) {
+        printf("FAILED: Could not cache DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_dataframe_free(df);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    // The cached rows are read from memory, so they outlive the file they were scanned from
+    cleanup_test_files();
+    int failed = 0;
+    DataFusionResult* result = datafusion_dataframe_collect(df, NULL);
+    if (result) {
+        printf("FAILED: Expected the original DataFrame to fail without its file\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    int64_t count = 0;
+    if (datafusion_dataframe_count(cached, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 cached rows, got %lld: %s\n", (long long)count, datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionDataFrame* filtered = datafusion_dataframe_filter(cached, "department = 'Engineering'", NULL);
+    const char* columns[] = {"name", "salary"};
+    DataFusionDataFrame* selected = filtered ? datafusion_dataframe_select(filtered, columns, 2, NULL) : NULL;
+    result = selected ? datafusion_dataframe_collect(selected, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 3 || datafusion_result_column_count(result, NULL) != 2) {
+        printf("FAILED: Expected 3 Engineering rows from the cache: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(selected);
+    datafusion_dataframe_free(filtered);
+    
+    datafusion_dataframe_free(cached);
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrame cached in memory\n");
+    return 0;
+}
+
+int test_dataframe_repartition() {
+    printf("Test 79: DataFrame repartitioning\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    int64_t count = 0;
+    char* explain = NULL;
+    DataFusionDataFrame* round_robin = datafusion_dataframe_repartition(df, 3, NULL);
+    if (!round_robin || datafusion_dataframe_explain(round_robin, false, false, &explain, NULL) != DATAFUSION_OK
+        || !strstr(explain, "RoundRobinBatch(3)")
+        || datafusion_dataframe_count(round_robin, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 rows in 3 round robin partitions: %s\n", explain ? explain : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(explain);
+    explain = NULL;
+    
+    // Caching keeps the partitions
+    DataFusionDataFrame* cached = round_robin ? datafusion_dataframe_cache(round_robin, NULL) : NULL;
+    if (!cached || datafusion_dataframe_explain(cached, false, false, &explain, NULL) != DATAFUSION_OK
+        || !strstr(explain, "partitions=3")) {
+        printf("FAILED: Expected 3 cached partitions: %s\n", explain ? explain : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(explain);
+    explain = NULL;
+    datafusion_dataframe_free(cached);
+    datafusion_dataframe_free(round_robin);
+    
+    const char* columns[] = {"department"};
+    DataFusionDataFrame* hashed = datafusion_dataframe_repartition_by_hash(df, columns, 1, 4, NULL);
+    if (!hashed || datafusion_dataframe_explain(hashed, false, false, &explain, NULL) != DATAFUSION_OK
+        || !strstr(explain, "Hash([department@3], 4)")
+        || datafusion_dataframe_count(hashed, &count, NULL) != DATAFUSION_OK || count != 5) {
+        printf("FAILED: Expected 5 rows in 4 hash partitions: %s\n", explain ? explain : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(explain);
+    datafusion_dataframe_free(hashed);
+    
+    if (datafusion_dataframe_repartition(df, 0, NULL)) {
+        printf("FAILED: Expected 0 partitions to be rejected\n");
+        failed = 1;
+    }
+    const char* missing[] = {"missing"};
+    if (datafusion_dataframe_repartition_by_hash(df, missing, 1, 4, NULL)) {
+        printf("FAILED: Expected an unknown column to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: DataFrames repartitioned round robin and by hash\n");
+    return 0;
+}
+
+int test_expr_builder() {
+    printf("Test 80: Expression builder\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    // department = 'Engineering' AND age > 30
+    DataFusionExpr* department = datafusion_expr_col("department", NULL);
+    DataFusionExpr* engineering = datafusion_expr_lit_string("Engineering", NULL);
+    DataFusionExpr* age = datafusion_expr_col("age", NULL);
+    DataFusionExpr* thirty = datafusion_expr_lit_int64(30, NULL);
+    DataFusionExpr* is_engineering = datafusion_expr_binary(DATAFUSION_OP_EQ, department, engineering, NULL);
+    DataFusionExpr* over_thirty = datafusion_expr_binary(DATAFUSION_OP_GT, age, thirty, NULL);
+    DataFusionExpr* predicate = datafusion_expr_binary(DATAFUSION_OP_AND, is_engineering, over_thirty, NULL);
+    DataFusionDataFrame* filtered = datafusion_dataframe_filter_with_expr(df, predicate, NULL);
+    int64_t count = 0;
+    if (!filtered || datafusion_dataframe_count(filtered, &count, NULL) != DATAFUSION_OK || count != 2) {
+        printf("FAILED: Expected 2 Engineering rows over 30, got %lld: %s\n", (long long)count, datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_dataframe_free(filtered);
+    
+    // A literal containing a quote needs no escaping
+    DataFusionExpr* quoted = datafusion_expr_lit_string("O'Brien", NULL);
+    DataFusionExpr* name = datafusion_expr_col("name", NULL);
+    DataFusionExpr* is_quoted = datafusion_expr_binary(DATAFUSION_OP_EQ, name, quoted, NULL);
+    filtered = datafusion_dataframe_filter_with_expr(df, is_quoted, NULL);
+    if (!filtered || datafusion_dataframe_count(filtered, &count, NULL) != DATAFUSION_OK || count != 0) {
+        printf("FAILED: Expected no rows named O'Brien: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_dataframe_free(filtered);
+    
+    // SELECT name, CAST(salary AS DOUBLE) * 1.5 AS raised
+    DataFusionExpr* salary = datafusion_expr_col("salary", NULL);
+    DataFusionExpr* salary_double = datafusion_expr_cast(salary, DATAFUSION_TYPE_FLOAT64, NULL);
+    DataFusionExpr* factor = datafusion_expr_lit_float64(1.5, NULL);
+    DataFusionExpr* product = datafusion_expr_binary(DATAFUSION_OP_MULTIPLY, salary_double, factor, NULL);
+    DataFusionExpr* raised = datafusion_expr_alias(product, "raised", NULL);
+    char* description = datafusion_expr_to_string(raised, NULL);
+    if (!description || strcmp(description, "CAST(salary AS Float64) * Float64(1.5) AS raised") != 0) {
+        printf("FAILED: Unexpected expression description: %s\n", description ? description : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(description);
+    const DataFusionExpr* select[] = {name, raised};
+    DataFusionDataFrame* selected = datafusion_dataframe_select_with_exprs(df, select, 2, NULL);
+    DataFusionResult* result = selected ? datafusion_dataframe_collect(selected, NULL) : NULL;
+    char* column_name = result ? datafusion_result_column_name(result, 1, NULL) : NULL;
+    double value = 0;
+    if (!result || !column_name || strcmp(column_name, "raised") != 0
+        || datafusion_result_get_float64(result, 0, 0, 1, &value, NULL) != DATAFUSION_OK || value != 112500.0) {
+        printf("FAILED: Expected a raised salary of 112500, got %f: %s\n", value, datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(column_name);
+    datafusion_result_free(result);
+    datafusion_dataframe_free(selected);
+    
+    // SELECT department, sum(salary) AS total GROUP BY department
+    DataFusionExpr* sum = datafusion_expr_function(ctx, "sum", (const DataFusionExpr* const[]){salary}, 1, NULL);
+    DataFusionExpr* total = sum ? datafusion_expr_alias(sum, "total", NULL) : NULL;
+    const DataFusionExpr* group[] = {department};
+    const DataFusionExpr* aggregates[] = {total};
+    DataFusionDataFrame* aggregated = total ? datafusion_dataframe_aggregate_with_exprs(df, group, 1, aggregates, 1, NULL) : NULL;
+    DataFusionDataFrame* engineering_total = aggregated ? datafusion_dataframe_filter_with_expr(aggregated, is_engineering, NULL) : NULL;
+    result = engineering_total ? datafusion_dataframe_collect(engineering_total, NULL) : NULL;
+    int64_t sum_value = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 1, &sum_value, NULL) != DATAFUSION_OK || sum_value != 240000) {
+        printf("FAILED: Expected an Engineering total of 240000, got %lld: %s\n", (long long)sum_value, datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(engineering_total);
+    datafusion_dataframe_free(aggregated);
+    
+    if (datafusion_expr_binary(42, age, thirty, NULL) || datafusion_expr_cast(age, DATAFUSION_TYPE_TIMESTAMP, NULL)
+        || datafusion_expr_function(ctx, "no_such_function", NULL, 0, NULL)) {
+        printf("FAILED: Expected an invalid operator, type and function to be rejected\n");
+        failed = 1;
+    }
+    
+    DataFusionExpr* exprs[] = {department, engineering, age, thirty, is_engineering, over_thirty, predicate, quoted, name,
+                               is_quoted, salary, salary_double, factor, product, raised, sum, total};
+    for (size_t i = 0; i < sizeof(exprs) / sizeof(exprs[0]); i++) {
+        datafusion_expr_free(exprs[i]);
+    }
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Expressions built without SQL and used in filter, select and aggregate\n");
+    return 0;
+}
+
+int test_dataframe_window() {
+    printf("Test 81: DataFrame window functions\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionDataFrame* df = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!df) {
+        printf("FAILED: Could not create DataFrame: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionExpr* id = datafusion_expr_col("id", NULL);
+    DataFusionExpr* department = datafusion_expr_col("department", NULL);
+    DataFusionExpr* salary = datafusion_expr_col("salary", NULL);
+    DataFusionExpr* one = datafusion_expr_lit_int64(1, NULL);
+    
+    // row_number() OVER (PARTITION BY department ORDER BY salary DESC) AS salary_rank
+    DataFusionExpr* row_number = datafusion_expr_function(ctx, "row_number", NULL, 0, NULL);
+    DataFusionExpr* salary_rank = datafusion_expr_alias(row_number, "salary_rank", NULL);
+    const DataFusionExpr* partition_by[] = {department};
+    const DataFusionExpr* by_salary[] = {salary};
+    bool descending[] = {false};
+    bool nulls_last[] = {false};
+    DataFusionDataFrame* ranked = datafusion_dataframe_window(df, (const DataFusionExpr* const[]){salary_rank}, 1,
+                                                              partition_by, 1, by_salary, descending, nulls_last, 1, NULL);
+    
+    // sum(salary) OVER (ORDER BY id) AS running_total, lag(salary, 1) OVER (ORDER BY id) AS previous
+    DataFusionExpr* sum = datafusion_expr_function(ctx, "sum", (const DataFusionExpr* const[]){salary}, 1, NULL);
+    DataFusionExpr* running_total = datafusion_expr_alias(sum, "running_total", NULL);
+    DataFusionExpr* lag = datafusion_expr_function(ctx, "lag", (const DataFusionExpr* const[]){salary, one}, 2, NULL);
+    DataFusionExpr* previous = datafusion_expr_alias(lag, "previous", NULL);
+    const DataFusionExpr* by_id[] = {id};
+    bool ascending[] = {true};
+    DataFusionDataFrame* windowed = ranked ? datafusion_dataframe_window(ranked, (const DataFusionExpr* const[]){running_total, previous}, 2,
+                                                                         NULL, 0, by_id, ascending, nulls_last, 1, NULL) : NULL;
+    
+    const char* sort_exprs[] = {"id"};
+    DataFusionDataFrame* sorted = windowed ? datafusion_dataframe_sort(windowed, sort_exprs, ascending, nulls_last, 1, NULL) : NULL;
+    DataFusionResult* collected = sorted ? datafusion_dataframe_collect(sorted, NULL) : NULL;
+    DataFusionResult* result = collected ? datafusion_result_coalesce(collected, 0, NULL) : NULL;
+    datafusion_result_free(collected);
+    if (!result || datafusion_result_column_count(result, NULL) != 8) {
+        printf("FAILED: Expected 8 columns: %s\n", datafusion_get_last_error());
+        failed = 1;
+    } else {
+        const char* expected[5][3] = {
+            {"3", "75000", ""}, {"1", "140000", "75000"}, {"1", "225000", "65000"},
+            {"1", "280000", "85000"}, {"2", "360000", "55000"},
+        };
+        for (int row = 0; row < 5; row++) {
+            for (int col = 0; col < 3; col++) {
+                char* value = datafusion_result_get_display(result, 0, row, 5 + col, NULL);
+                if (!value || strcmp(value, expected[row][col]) != 0) {
+                    printf("FAILED: Expected %s at row %d, column %d, got %s\n", expected[row][col], row, 5 + col,
+                           value ? value : datafusion_get_last_error());
+                    failed = 1;
+                }
+                datafusion_string_free(value);
+            }
+        }
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(sorted);
+    datafusion_dataframe_free(windowed);
+    datafusion_dataframe_free(ranked);
+    
+    if (datafusion_dataframe_window(df, (const DataFusionExpr* const[]){salary}, 1, NULL, 0, NULL, NULL, NULL, 0, NULL)) {
+        printf("FAILED: Expected a column to be rejected as a window expression\n");
+        failed = 1;
+    }
+    
+    DataFusionExpr* exprs[] = {id, department, salary, one, row_number, salary_rank, sum, running_total, lag, previous};
+    for (size_t i = 0; i < sizeof(exprs) / sizeof(exprs[0]); i++) {
+        datafusion_expr_free(exprs[i]);
+    }
+    datafusion_dataframe_free(df);
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Ranks, running totals and lags computed over windows\n");
+    return 0;
+}
+
+int test_register_parquet() {
+    printf("Test 82: Parquet registration\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK
+        || datafusion_execute_update(ctx, "COPY employees TO 'test_register.parquet' STORED AS PARQUET", NULL, NULL) != DATAFUSION_OK
+        || datafusion_execute_update(ctx, "COPY employees TO 'test_register_dir/part.pq' STORED AS PARQUET", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write Parquet files: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_register_parquet(ctx, "parquet_file", "test_register.parquet", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register Parquet file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM parquet_file WHERE department = 'Engineering'", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 Engineering rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // A directory of files with another extension, filtered while decoding
+    DataFusionParquetReadOptions options = datafusion_parquet_read_options_default();
+    options.file_extension = ".pq";
+    options.pushdown_filters = true;
+    if (datafusion_register_parquet(ctx, "parquet_dir", "test_register_dir/", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register Parquet directory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(*) FROM parquet_dir WHERE salary > 70000", NULL);
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 rows with salaries over 70000: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    options.pruning = false;
+    if (datafusion_register_parquet(ctx, "unpruned", "test_register_dir/", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected filter pushdown without pruning to be rejected\n");
+        failed = 1;
+    }
+    if (datafusion_register_parquet(ctx, "missing", "no_such_file.parquet", NULL, NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected a missing file to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    unlink("test_register.parquet");
+    system("rm -rf test_register_dir");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Parquet files and directories registered\n");
+    return 0;
+}
+
+int test_register_json() {
+    printf("Test 83: JSON registration\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        result = datafusion_sql(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!result || datafusion_result_write_ndjson(result, "test_register.json", NULL, NULL) != DATAFUSION_OK
+        || system("mkdir -p test_register_json_dir") != 0
+        || datafusion_result_write_ndjson(result, "test_register_json_dir/part.json.gz", "gzip", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write JSON files: %s\n", datafusion_get_last_error());
+        datafusion_result_free(result);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    datafusion_result_free(result);
+    
+    int failed = 0;
+    if (datafusion_register_json(ctx, "json_file", "test_register.json", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register JSON file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(*) FROM json_file WHERE department = 'Engineering'", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 Engineering rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // A directory of gzip compressed files, found by the default ".json.gz" extension
+    DataFusionJsonReadOptions options = datafusion_json_read_options_default();
+    options.compression = "gzip";
+    options.schema_infer_max_records = 2;
+    if (datafusion_register_json(ctx, "json_dir", "test_register_json_dir/", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register compressed JSON directory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT sum(salary) FROM json_dir", NULL);
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 360000) {
+        printf("FAILED: Expected a salary total of 360000: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    options.compression = "lz4";
+    if (datafusion_register_json(ctx, "bad_compression", "test_register_json_dir/", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown compression to be rejected\n");
+        failed = 1;
+    }
+    options.compression = NULL;
+    options.schema_infer_max_records = 0;
+    if (datafusion_register_json(ctx, "no_inference", "test_register.json", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected schema inference from no rows to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    unlink("test_register.json");
+    system("rm -rf test_register_json_dir");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: JSON files and compressed directories registered\n");
+    return 0;
+}
+
+int test_register_avro() {
+    printf("Test 84: Avro registration\n");
+    
+#ifndef DATAFUSION_C_API_AVRO
+    printf("SKIPPED: Library built without the avro feature\n");
+    return 0;
+#else
+    // An Avro object container file of records {id: long, name: string}, for Alice, Bob and Carol
+    static const unsigned char avro_file[] = {
+        0x4f, 0x62, 0x6a, 0x01, 0x02, 0x16, 0x61, 0x76, 0x72, 0x6f, 0x2e, 0x73, 0x63, 0x68, 0x65, 0x6d,
+        0x61, 0xd4, 0x01, 0x7b, 0x22, 0x74, 0x79, 0x70, 0x65, 0x22, 0x3a, 0x22, 0x72, 0x65, 0x63, 0x6f,
+        0x72, 0x64, 0x22, 0x2c, 0x22, 0x6e, 0x61, 0x6d, 0x65, 0x22, 0x3a, 0x22, 0x65, 0x6d, 0x70, 0x6c,
+        0x6f, 0x79, 0x65, 0x65, 0x22, 0x2c, 0x22, 0x66, 0x69, 0x65, 0x6c, 0x64, 0x73, 0x22, 0x3a, 0x5b,
+        0x7b, 0x22, 0x6e, 0x61, 0x6d, 0x65, 0x22, 0x3a, 0x22, 0x69, 0x64, 0x22, 0x2c, 0x22, 0x74, 0x79,
+        0x70, 0x65, 0x22, 0x3a, 0x22, 0x6c, 0x6f, 0x6e, 0x67, 0x22, 0x7d, 0x2c, 0x7b, 0x22, 0x6e, 0x61,
+        0x6d, 0x65, 0x22, 0x3a, 0x22, 0x6e, 0x61, 0x6d, 0x65, 0x22, 0x2c, 0x22, 0x74, 0x79, 0x70, 0x65,
+        0x22, 0x3a, 0x22, 0x73, 0x74, 0x72, 0x69, 0x6e, 0x67, 0x22, 0x7d, 0x5d, 0x7d, 0x00, 0x00, 0x01,
+        0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x06, 0x26,
+        0x02, 0x0a, 0x41, 0x6c, 0x69, 0x63, 0x65, 0x04, 0x06, 0x42, 0x6f, 0x62, 0x06, 0x0a, 0x43, 0x61,
+        0x72, 0x6f, 0x6c, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        0x0d, 0x0e, 0x0f,
+    };
+    FILE* file = fopen("test_register.avro", "wb");
+    if (!file || fwrite(avro_file, 1, sizeof(avro_file), file) != sizeof(avro_file)) {
+        printf("FAILED: Could not write Avro file\n");
+        if (file) fclose(file);
+        return -1;
+    }
+    fclose(file);
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx || datafusion_register_avro(ctx, "avro_file", "test_register.avro", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register Avro file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name FROM avro_file WHERE id = 2", NULL);
+    const char* name = NULL;
+    size_t length = 0;
+    if (!result || datafusion_result_get_string(result, 0, 0, 0, &name, &length, NULL) != DATAFUSION_OK
+        || length != 3 || strncmp(name, "Bob", length) != 0) {
+        printf("FAILED: Expected Bob with id 2: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_register_avro(ctx, "missing", "no_such_file.avro", NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected a missing file to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    unlink("test_register.avro");
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Avro file registered\n");
+    return 0;
+#endif
+}
+
+int test_register_arrow() {
+    printf("Test 85: Arrow IPC registration\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK
+        || datafusion_execute_update(ctx, "COPY employees TO 'test_register.feather' STORED AS ARROW", NULL, NULL) != DATAFUSION_OK
+        || datafusion_execute_update(ctx, "COPY (SELECT * FROM employees WHERE age > 30) TO 'test_register_arrow_dir/part.arrow' STORED AS ARROW", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write Arrow files: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_register_arrow(ctx, "feather_file", "test_register.feather", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register Feather file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM feather_file WHERE department = 'Engineering'", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 Engineering rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_register_arrow(ctx, "arrow_dir", "test_register_arrow_dir/", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register Arrow directory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(*) FROM arrow_dir", NULL);
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 2) {
+        printf("FAILED: Expected 2 rows over 30: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_register_arrow(ctx, "not_arrow", test_csv_path, NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected a CSV file to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    unlink("test_register.feather");
+    system("rm -rf test_register_arrow_dir");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Feather file and Arrow directory registered\n");
+    return 0;
+}
+
+int test_register_csv_with_options() {
+    printf("Test 86: CSV read options\n");
+    
+    // Semicolon delimited, without a header, with quotes escaped by a backslash
+    FILE* file = fopen("test_register_options.txt", "w");
+    if (!file) {
+        printf("FAILED: Could not write CSV file\n");
+        return -1;
+    }
+    fprintf(file, "1;'Alice';25\n");
+    fprintf(file, "2;'Bob \\'B\\' Jones';\n");
+    fprintf(file, "3;'Carol; Jr';35\n");
+    fclose(file);
+    if (system("mkdir -p test_register_csv_dir && gzip -c test_register_options.txt > test_register_csv_dir/part.csv.gz") != 0) {
+        printf("FAILED: Could not compress CSV file\n");
+        unlink("test_register_options.txt");
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    DataFusionCsvReadOptions options = datafusion_csv_read_options_default();
+    options.delimiter = ';';
+    options.quote = '\'';
+    options.escape = '\\';
+    options.has_header = false;
+    options.file_extension = ".txt";
+    if (!ctx || datafusion_register_csv_with_options(ctx, "plain", "test_register_options.txt", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* sorted = datafusion_sql(ctx, "SELECT column_2, column_3 FROM plain ORDER BY column_1", NULL);
+    DataFusionResult* result = sorted ? datafusion_result_coalesce(sorted, 0, NULL) : NULL;
+    char* name = result ? datafusion_result_get_string_copy(result, 0, 1, 0, NULL) : NULL;
+    if (!name || strcmp(name, "Bob 'B' Jones") != 0 || !datafusion_result_is_null(result, 0, 1, 1, NULL)) {
+        printf("FAILED: Expected an escaped name and a null age: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    datafusion_result_free(sorted);
+    
+    // A directory of gzip compressed files, found by the default ".csv.gz" extension
+    options.file_extension = NULL;
+    options.compression = "gzip";
+    if (datafusion_register_csv_with_options(ctx, "compressed", "test_register_csv_dir/", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register compressed CSV directory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(column_3) FROM compressed WHERE column_2 LIKE '%;%'", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 1) {
+        printf("FAILED: Expected 1 quoted name with a delimiter: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    options.compression = "lz4";
+    if (datafusion_register_csv_with_options(ctx, "bad_compression", "test_register_csv_dir/", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown compression to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    unlink("test_register_options.txt");
+    system("rm -rf test_register_csv_dir");
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Delimiter, quote, escape, header and compression applied\n");
+    return 0;
+}
+
+/* Export the schema of a query's result through the Arrow C data interface */
+int export_query_schema(DataFusionContext* ctx, const char* sql, struct ArrowSchema* out) {
+    DataFusionResult* result = datafusion_sql(ctx, sql, NULL);
+    DataFusionSchema* schema = result ? datafusion_result_schema(result, NULL) : NULL;
+    int code = schema ? datafusion_schema_export(schema, out, NULL) : DATAFUSION_ERROR;
+    datafusion_schema_free(schema);
+    datafusion_result_free(result);
+    return code;
+}
+
+int test_register_with_schema() {
+    printf("Test 87: Explicit schemas for CSV and JSON\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    FILE* file = fopen("test_register_schema.json", "w");
+    if (!file) {
+        printf("FAILED: Could not write JSON file\n");
+        cleanup_test_files();
+        return -1;
+    }
+    fprintf(file, "{\"id\": 1, \"hired\": \"2019-03-01T09:00:00\"}\n");
+    fprintf(file, "{\"id\": 2, \"hired\": \"2021-07-15T09:00:00\"}\n");
+    fclose(file);
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    struct ArrowSchema csv_schema;
+    struct ArrowSchema json_schema;
+    if (!ctx
+        || export_query_schema(ctx, "SELECT CAST(1 AS INT) AS id, 'a' AS name, CAST(1 AS INT) AS age, 'a' AS department, "
+                                    "CAST(1 AS DECIMAL(10, 2)) AS salary", &csv_schema) != DATAFUSION_OK) {
+        printf("FAILED: Could not export CSV schema: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        unlink("test_register_schema.json");
+        return -1;
+    }
+    if (export_query_schema(ctx, "SELECT CAST(1 AS INT) AS id, CAST('2020-01-01' AS TIMESTAMP) AS hired", &json_schema) != DATAFUSION_OK) {
+        printf("FAILED: Could not export JSON schema: %s\n", datafusion_get_last_error());
+        csv_schema.release(&csv_schema);
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        unlink("test_register_schema.json");
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionCsvReadOptions csv_options = datafusion_csv_read_options_default();
+    csv_options.schema = &csv_schema;
+    if (datafusion_register_csv_with_options(ctx, "employees", test_csv_path, &csv_options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV file with a schema: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionSchema* schema = datafusion_table_schema(ctx, "employees", NULL);
+    if (!schema || datafusion_schema_field_type(schema, 2, NULL) != DATAFUSION_TYPE_INT32
+        || datafusion_schema_field_type(schema, 4, NULL) != DATAFUSION_TYPE_DECIMAL128) {
+        printf("FAILED: Expected Int32 ages and Decimal128 salaries\n");
+        failed = 1;
+    }
+    datafusion_schema_free(schema);
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT CAST(sum(salary) AS BIGINT) FROM employees", NULL);
+    int64_t value = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &value, NULL) != DATAFUSION_OK || value != 360000) {
+        printf("FAILED: Expected a salary total of 360000: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    DataFusionJsonReadOptions json_options = datafusion_json_read_options_default();
+    json_options.schema = &json_schema;
+    if (datafusion_register_json(ctx, "hires", "test_register_schema.json", &json_options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register JSON file with a schema: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT id FROM hires WHERE hired > TIMESTAMP '2020-01-01 00:00:00'", NULL);
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &value, NULL) != DATAFUSION_TYPE_MISMATCH) {
+        printf("FAILED: Expected Int32 ids: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_result_total_rows(result, NULL) != 1) {
+        printf("FAILED: Expected 1 hire after 2020\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // The schemas stay owned by the caller
+    csv_schema.release(&csv_schema);
+    json_schema.release(&json_schema);
+    datafusion_context_free(ctx);
+    unlink("test_register_schema.json");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Declared column types used instead of inference\n");
+    return 0;
+}
+
+int test_register_schema_inference() {
+    printf("Test 88: Schema inference tuning\n");
+    
+    // Codes that look like integers until the fourth row
+    FILE* file = fopen("test_register_inference.csv", "w");
+    if (!file) {
+        printf("FAILED: Could not write CSV file\n");
+        return -1;
+    }
+    fprintf(file, "city,zip\n");
+    fprintf(file, "Boston,02108\n");
+    fprintf(file, "Chicago,60601\n");
+    fprintf(file, "Denver,80202\n");
+    fprintf(file, "London,SW1A\n");
+    fclose(file);
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    DataFusionCsvReadOptions options = datafusion_csv_read_options_default();
+    options.schema_infer_max_records = 3;
+    if (!ctx || datafusion_register_csv_with_options(ctx, "sampled", "test_register_inference.csv", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT * FROM sampled", NULL);
+    if (result) {
+        printf("FAILED: Expected rows after the inferred ones to fail to parse\n");
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    options.schema_infer_max_records = SIZE_MAX;
+    if (datafusion_register_csv_with_options(ctx, "all_rows", "test_register_inference.csv", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV file inferred from every row: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT zip FROM all_rows WHERE city = 'London'", NULL);
+    char* zip = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!zip || strcmp(zip, "SW1A") != 0) {
+        printf("FAILED: Expected the London zip code: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(zip);
+    datafusion_result_free(result);
+    
+    // Without type inference the leading zero is kept
+    options.schema_infer_max_records = 1;
+    options.infer_types = false;
+    if (datafusion_register_csv_with_options(ctx, "strings", "test_register_inference.csv", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV file as strings: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT zip FROM strings WHERE city = 'Boston'", NULL);
+    zip = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!zip || strcmp(zip, "02108") != 0) {
+        printf("FAILED: Expected the Boston zip code with its leading zero: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(zip);
+    datafusion_result_free(result);
+    
+    options.schema_infer_max_records = 0;
+    if (datafusion_register_csv_with_options(ctx, "no_rows", "test_register_inference.csv", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected inference from no rows to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    unlink("test_register_inference.csv");
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Inference bounded, extended to every row and disabled\n");
+    return 0;
+}
+
+int test_register_listing_table() {
+    printf("Test 89: Listing tables with globs and partitions\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK
+        || datafusion_execute_update(ctx, "COPY employees TO 'test_listing_dir/' STORED AS PARQUET PARTITIONED BY (department)", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write partitioned Parquet files: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    if (datafusion_register_listing_table(ctx, "partitioned", "test_listing_dir/", DATAFUSION_FORMAT_PARQUET, NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register partitioned directory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM partitioned WHERE department = 'Engineering'", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 rows in the Engineering partition: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // A glob selecting two of three CSV files
+    if (system("mkdir -p test_listing_logs && cp test_employees.csv test_listing_logs/app-1.csv && cp test_employees.csv test_listing_logs/app-2.csv && cp test_employees.csv test_listing_logs/other.csv") != 0) {
+        printf("FAILED: Could not copy CSV files\n");
+        failed = 1;
+    }
+    DataFusionListingTableOptions options = datafusion_listing_table_options_default();
+    options.infer_partitions = false;
+    if (datafusion_register_listing_table(ctx, "globbed", "test_listing_logs/app-*.csv", DATAFUSION_FORMAT_CSV, &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register glob pattern: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(*) FROM globbed", NULL);
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 10) {
+        printf("FAILED: Expected the 10 rows of two files: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // The partition directories are read without their column
+    if (datafusion_register_listing_table(ctx, "unpartitioned", "test_listing_dir/", DATAFUSION_FORMAT_PARQUET, &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register directory without partitions: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionSchema* schema = datafusion_table_schema(ctx, "unpartitioned", NULL);
+    if (!schema || datafusion_schema_field_count(schema, NULL) != 4) {
+        printf("FAILED: Expected no partition column\n");
+        failed = 1;
+    }
+    datafusion_schema_free(schema);
+    
+    if (datafusion_register_listing_table(ctx, "bad_format", "test_listing_dir/", 42, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown format to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    system("rm -rf test_listing_dir test_listing_logs");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Partition columns discovered and glob patterns matched\n");
+    return 0;
+}
+
+int test_register_parquet_partitions() {
+    printf("Test 90: Declared Parquet partition columns\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK
+        || datafusion_execute_update(ctx, "COPY employees TO 'test_partitions_dir/' STORED AS PARQUET PARTITIONED BY (age)", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write partitioned Parquet files: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* partition_columns[] = {"age"};
+    int partition_types[] = {DATAFUSION_TYPE_INT32};
+    DataFusionParquetReadOptions options = datafusion_parquet_read_options_default();
+    options.partition_columns = partition_columns;
+    options.partition_types = partition_types;
+    options.partition_column_count = 1;
+    if (datafusion_register_parquet(ctx, "by_age", "test_partitions_dir/", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register partitioned directory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionSchema* schema = datafusion_table_schema(ctx, "by_age", NULL);
+    if (!schema || datafusion_schema_field_count(schema, NULL) != 5 || datafusion_schema_field_type(schema, 4, NULL) != DATAFUSION_TYPE_INT32) {
+        printf("FAILED: Expected an Int32 age partition column\n");
+        failed = 1;
+    }
+    datafusion_schema_free(schema);
+    
+    // Compared as numbers: as strings, no age would sort before "100"
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT CAST(sum(age) AS BIGINT) FROM by_age WHERE age > 30 AND age < 100", NULL);
+    int64_t sum = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &sum, NULL) != DATAFUSION_OK || sum != 67) {
+        printf("FAILED: Expected the ages 32 and 35: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    partition_types[0] = DATAFUSION_TYPE_TIMESTAMP;
+    if (datafusion_register_parquet(ctx, "bad_type", "test_partitions_dir/", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a partition type with parameters to be rejected\n");
+        failed = 1;
+    }
+    options.partition_types = NULL;
+    if (datafusion_register_parquet(ctx, "no_types", "test_partitions_dir/", &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected missing partition types to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    system("rm -rf test_partitions_dir");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Typed partition columns read from directory names\n");
+    return 0;
+}
+
+int test_register_arrow_stream() {
+    printf("Test 91: Registering an Arrow C stream\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    // A stream exported by one context, standing in for one produced by another Arrow library
+    DataFusionContext* source = datafusion_context_new(NULL);
+    DataFusionResult* result = NULL;
+    if (source && datafusion_register_csv(source, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        result = datafusion_sql(source, "SELECT * FROM employees", NULL);
+    }
+    struct ArrowArrayStream stream;
+    int rc = result ? datafusion_result_export_stream(result, &stream, NULL) : DATAFUSION_ERROR;
+    datafusion_result_free(result);
+    datafusion_context_free(source);
+    cleanup_test_files();
+    if (rc != DATAFUSION_OK) {
+        printf("FAILED: Could not export stream: %s\n", datafusion_get_last_error());
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx || datafusion_register_arrow_stream(ctx, "streamed", &stream, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register stream: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (stream.release != NULL) {
+        printf("FAILED: Expected the stream to be moved from\n");
+        stream.release(&stream);
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(*) FROM streamed WHERE department = 'Engineering'", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected 3 Engineering rows: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    if (datafusion_register_arrow_stream(ctx, "released", &stream, NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected a released stream to be rejected\n");
+        failed = 1;
+    }
+    if (datafusion_register_arrow_stream(ctx, "missing", NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null stream to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Stream batches registered as an in-memory table\n");
+    return 0;
+}
+
+int test_register_record_batch() {
+    printf("Test 92: Registering a record batch\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    // Batches exported by one context, standing in for ones produced by another Arrow library
+    DataFusionContext* source = datafusion_context_new(NULL);
+    DataFusionResult* result = NULL;
+    if (source && datafusion_register_csv(source, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        result = datafusion_sql(source, "SELECT id, name, salary FROM employees", NULL);
+    }
+    struct ArrowArray array;
+    struct ArrowSchema schema;
+    struct ArrowArray rejected;
+    struct ArrowSchema rejected_schema;
+    int rc = result ? datafusion_result_export_batch(result, 0, &array, &schema, NULL) : DATAFUSION_ERROR;
+    if (rc == DATAFUSION_OK) {
+        rc = datafusion_result_export_batch(result, 0, &rejected, &rejected_schema, NULL);
+        if (rc != DATAFUSION_OK) {
+            array.release(&array);
+            schema.release(&schema);
+        }
+    }
+    datafusion_result_free(result);
+    datafusion_context_free(source);
+    cleanup_test_files();
+    if (rc != DATAFUSION_OK) {
+        printf("FAILED: Could not export batch: %s\n", datafusion_get_last_error());
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx || datafusion_register_record_batch(ctx, "batch", &array, &schema, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register batch: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (array.release != NULL) {
+        printf("FAILED: Expected the array to be moved from\n");
+        array.release(&array);
+        failed = 1;
+    }
+    schema.release(&schema);
+    result = datafusion_sql(ctx, "SELECT sum(salary) FROM batch WHERE id > 2", NULL);
+    int64_t sum = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &sum, NULL) != DATAFUSION_OK || sum != 220000) {
+        printf("FAILED: Expected a salary total of 220000: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // The array is released even when the call fails
+    if (datafusion_register_record_batch(ctx, "no_schema", &rejected, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a null schema to be rejected\n");
+        failed = 1;
+    }
+    if (rejected.release != NULL) {
+        printf("FAILED: Expected the rejected array to be released\n");
+        rejected.release(&rejected);
+        failed = 1;
+    }
+    rejected_schema.release(&rejected_schema);
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Batch moved into an in-memory table\n");
+    return 0;
+}
+
+int test_table_builder() {
+    printf("Test 93: Table builder from C arrays\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionTableBuilder* builder = datafusion_table_builder_new(NULL);
+    if (!ctx || !builder) {
+        printf("FAILED: Could not create context or builder\n");
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    
+    int64_t ids[] = {1, 2, 3, 4};
+    double scores[] = {1.5, 0.0, 2.5, 4.0};
+    uint8_t scores_validity[] = {0x0D}; // the second score is null
+    bool active[] = {true, false, true, true};
+    int32_t offsets[] = {0, 5, 8, 8, 12};
+    const uint8_t names[] = "AliceBobDave";
+    uint8_t names_validity[] = {0x0B}; // the third name is null
+    
+    int failed = 0;
+    if (datafusion_table_builder_add_int64(builder, "id", ids, NULL, 4, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_add_float64(builder, "score", scores, scores_validity, 4, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_add_bool(builder, "active", active, NULL, 4, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_add_string(builder, "name", offsets, names, names_validity, 4, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_register(builder, ctx, "people", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not build table: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT count(score), sum(CASE WHEN active THEN id END), string_agg(name, ',' ORDER BY id) FROM people", NULL);
+    int64_t scored = 0, active_ids = 0;
+    char* joined = result ? datafusion_result_get_string_copy(result, 0, 0, 2, NULL) : NULL;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &scored, NULL) != DATAFUSION_OK || scored != 3 ||
+        datafusion_result_get_int64(result, 0, 0, 1, &active_ids, NULL) != DATAFUSION_OK || active_ids != 8 ||
+        !joined || strcmp(joined, "Alice,Bob,Dave") != 0) {
+        printf("FAILED: Unexpected table contents: %s\n", joined ? joined : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(joined);
+    datafusion_result_free(result);
+    
+    // Invalid columns are rejected and leave the builder unchanged
+    int64_t short_ids[] = {1, 2};
+    int32_t decreasing[] = {0, 5, 3, 8, 12};
+    if (datafusion_table_builder_add_int64(builder, "short", short_ids, NULL, 2, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_table_builder_add_int64(builder, "id", ids, NULL, 4, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_table_builder_add_string(builder, "bad", decreasing, names, NULL, 4, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected mismatched lengths, duplicate names and bad offsets to be rejected\n");
+        failed = 1;
+    }
+    datafusion_table_builder_free(builder);
+    
+    DataFusionTableBuilder* empty = datafusion_table_builder_new(NULL);
+    if (datafusion_table_builder_register(empty, ctx, "empty", NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected a builder without columns to be rejected\n");
+        failed = 1;
+    }
+    datafusion_table_builder_free(empty);
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Columns copied from C arrays registered as a table\n");
+    return 0;
+}
+
+int test_register_bytes() {
+    printf("Test 94: Registering CSV and JSON from memory\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx) {
+        printf("FAILED: Could not create context\n");
+        return -1;
+    }
+    
+    // The bytes need not be null terminated: only len of them are read
+    const char csv[] = "id;zip;amount\n1;01234;2.5\n2;98765;\n3;00501;4.0\nTRAILING GARBAGE";
+    size_t csv_len = strlen(csv) - strlen("TRAILING GARBAGE");
+    const char json[] = "{\"id\": 1, \"tags\": \"a\"}\n{\"id\": 2}\n{\"id\": 3, \"tags\": \"c\"}\n";
+    
+    int failed = 0;
+    DataFusionCsvReadOptions csv_options = datafusion_csv_read_options_default();
+    csv_options.delimiter = ';';
+    if (datafusion_register_csv_bytes(ctx, "typed", (const uint8_t*)csv, csv_len, &csv_options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV bytes: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    csv_options.infer_types = false;
+    if (datafusion_register_csv_bytes(ctx, "strings", (const uint8_t*)csv, csv_len, &csv_options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV bytes as strings: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (datafusion_register_json_bytes(ctx, "events", (const uint8_t*)json, strlen(json), NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register JSON bytes: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT (SELECT count(*) FROM typed WHERE amount IS NOT NULL AND zip > 1000), "
+        "(SELECT min(zip) FROM strings), (SELECT count(tags) FROM events)", NULL);
+    int64_t typed = 0, tagged = 0;
+    char* zip = result ? datafusion_result_get_string_copy(result, 0, 0, 1, NULL) : NULL;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &typed, NULL) != DATAFUSION_OK || typed != 1 ||
+        !zip || strcmp(zip, "00501") != 0 ||
+        datafusion_result_get_int64(result, 0, 0, 2, &tagged, NULL) != DATAFUSION_OK || tagged != 2) {
+        printf("FAILED: Unexpected table contents: %s\n", zip ? zip : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(zip);
+    datafusion_result_free(result);
+    
+    if (datafusion_register_csv_bytes(ctx, "missing", NULL, 10, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected null data to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: In-memory CSV and JSON registered as tables\n");
+    return 0;
+}
+
+int test_deregister_table() {
+    printf("Test 95: Deregistering and replacing tables\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    // A second registration under the same name fails and leaves the first one in place
+    const char json[] = "{\"id\": 1}\n";
+    if (datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_ALREADY_EXISTS ||
+        datafusion_register_json_bytes(ctx, "employees", (const uint8_t*)json, strlen(json), NULL, NULL) != DATAFUSION_ALREADY_EXISTS) {
+        printf("FAILED: Expected DATAFUSION_ALREADY_EXISTS for a registered name\n");
+        failed = 1;
+    }
+    
+    // A DataFrame planned before the table is deregistered keeps reading it
+    DataFusionDataFrame* df = datafusion_sql_to_dataframe(ctx, "SELECT * FROM employees", NULL);
+    
+    // Replaced by deregistering and registering again
+    if (datafusion_deregister_table(ctx, "employees", NULL) != DATAFUSION_OK ||
+        datafusion_register_json_bytes(ctx, "employees", (const uint8_t*)json, strlen(json), NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not replace table: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM employees", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 1) {
+        printf("FAILED: Expected the replacement table with 1 row: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    result = df ? datafusion_dataframe_collect(df, NULL) : NULL;
+    if (!result || datafusion_result_total_rows(result, NULL) != 5) {
+        printf("FAILED: Expected the planned DataFrame to read the deregistered table: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    datafusion_dataframe_free(df);
+    
+    if (datafusion_deregister_table(ctx, "missing", NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown table to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Duplicate names rejected and tables replaced\n");
+    return 0;
+}
+
+int test_table_names() {
+    printf("Test 96: Listing and checking tables\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK ||
+        datafusion_register_csv(ctx, "departments", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register tables: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    DataFusionStringList* names = datafusion_table_names(ctx, NULL, NULL, NULL);
+    char* first = names ? datafusion_string_list_get(names, 0, NULL) : NULL;
+    char* second = names ? datafusion_string_list_get(names, 1, NULL) : NULL;
+    if (!names || datafusion_string_list_count(names, NULL) != 2 || !first || strcmp(first, "departments") != 0 ||
+        !second || strcmp(second, "employees") != 0) {
+        printf("FAILED: Expected the sorted names departments, employees: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    if (names && datafusion_string_list_get(names, 2, NULL) != NULL) {
+        printf("FAILED: Expected an out of range index to be rejected\n");
+        failed = 1;
+    }
+    datafusion_string_free(first);
+    datafusion_string_free(second);
+    datafusion_string_list_free(names);
+    
+    // The same tables, named explicitly by catalog and schema
+    names = datafusion_table_names(ctx, "datafusion", "public", NULL);
+    if (!names || datafusion_string_list_count(names, NULL) != 2) {
+        printf("FAILED: Expected 2 tables in datafusion.public: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_list_free(names);
+    
+    if (datafusion_table_names(ctx, "datafusion", "missing", NULL) != NULL ||
+        last_error_code() != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown schema to be rejected\n");
+        failed = 1;
+    }
+    
+    if (!datafusion_table_exists(ctx, "employees", NULL) || !datafusion_table_exists(ctx, "datafusion.public.Employees", NULL) ||
+        datafusion_table_exists(ctx, "missing", NULL)) {
+        printf("FAILED: Expected employees to exist and missing not to\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Table names listed and looked up\n");
+    return 0;
+}
+
+// A minimal HTTP server answering HEAD and GET requests, including byte ranges, for one file
+typedef struct {
+    int listener;
+    const char* body;
+} HttpFileServer;
+
+static void* serve_http_file(void* arg) {
+    HttpFileServer* server = (HttpFileServer*)arg;
+    size_t size = strlen(server->body);
+    int client;
+    while ((client = accept(server->listener, NULL, NULL)) >= 0) {
+        char request[4096];
+        ssize_t received = recv(client, request, sizeof(request) - 1, 0);
+        request[received > 0 ? received : 0] = '\0';
+        
+        size_t start = 0, end = size - 1;
+        const char* range = strstr(request, "Range: bytes=");
+        if (range) {
+            unsigned long range_start = 0, range_end = 0;
+            int parsed = sscanf(range, "Range: bytes=%lu-%lu", &range_start, &range_end);
+            start = range_start;
+            if (parsed == 2 && range_end < size) {
+                end = range_end;
+            }
+        }
+        char header[512];
+        int header_len = snprintf(header, sizeof(header),
+            "HTTP/1.1 %s\r\nContent-Length: %lu\r\nContent-Range: bytes %lu-%lu/%lu\r\n"
+            "Last-Modified: Thu, 01 Jan 2026 00:00:00 GMT\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+            range ? "206 Partial Content" : "200 OK", (unsigned long)(end - start + 1),
+            (unsigned long)start, (unsigned long)end, (unsigned long)size);
+        send(client, header, header_len, 0);
+        if (strncmp(request, "GET ", 4) == 0) {
+            send(client, server->body + start, end - start + 1, 0);
+        }
+        close(client);
+    }
+    return NULL;
+}
+
+int test_object_store_urls() {
+    printf("Test 97: Object store URLs\n");
+    
+    HttpFileServer server = { socket(AF_INET, SOCK_STREAM, 0), "id,name\n1,Alice\n2,Bob\n3,Carol\n" };
+    struct sockaddr_in address;
+    memset(&address, 0, sizeof(address));
+    address.sin_family = AF_INET;
+    address.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+    socklen_t address_len = sizeof(address);
+    pthread_t thread;
+    if (server.listener < 0 || bind(server.listener, (struct sockaddr*)&address, sizeof(address)) != 0 ||
+        listen(server.listener, 8) != 0 || getsockname(server.listener, (struct sockaddr*)&address, &address_len) != 0 ||
+        pthread_create(&thread, NULL, serve_http_file, &server) != 0) {
+        printf("FAILED: Could not start HTTP server\n");
+        return -1;
+    }
+    char url[64];
+    snprintf(url, sizeof(url), "http://127.0.0.1:%d/data/people.csv", ntohs(address.sin_port));
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    
+    // The HTTP object store is created on first use of the URL
+    if (!ctx || datafusion_register_csv(ctx, "people", url, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV over HTTP: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*), max(name) FROM people", NULL);
+    int64_t count = 0;
+    char* name = result ? datafusion_result_get_string_copy(result, 0, 0, 1, NULL) : NULL;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3 ||
+        !name || strcmp(name, "Carol") != 0) {
+        printf("FAILED: Expected 3 rows read over HTTP: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    
+    // Stores registered explicitly are configured with options named as in the object_store crate
+    const char* keys[] = {"aws_region", "aws_access_key_id", "aws_secret_access_key"};
+    const char* values[] = {"us-east-1", "key", "secret"};
+    if (datafusion_register_object_store(ctx, "s3://bucket", keys, values, 3, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register S3 store: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    const char* unknown[] = {"no_such_option"};
+    if (datafusion_register_object_store(ctx, "s3://bucket", unknown, values, 1, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_register_object_store(ctx, "ftp://host", NULL, NULL, 0, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected unknown options and schemes to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    shutdown(server.listener, SHUT_RDWR);
+    close(server.listener);
+    pthread_join(thread, NULL);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Tables registered from HTTP URLs and stores configured with options\n");
+    return 0;
+}
+
+int test_compression_from_extension() {
+    printf("Test 98: Compression inferred from file extensions\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* result = NULL;
+    if (ctx && datafusion_register_csv(ctx, "employees", test_csv_path, NULL) == DATAFUSION_OK) {
+        result = datafusion_sql(ctx, "SELECT * FROM employees", NULL);
+    }
+    if (!result) {
+        printf("FAILED: Could not query employees: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    const char* compressions[] = {"gzip", "bzip2", "xz", "zstd"};
+    const char* json_paths[] = {"test_people.json.gz", "test_people.json.bz2", "test_people.json.xz", "test_people.json.zst"};
+    const char* csv_paths[] = {"test_people.csv.gz", "test_people.csv.bz2", "test_people.csv.xz", "test_people.csv.zst"};
+    int failed = 0;
+    for (int i = 0; i < 4; i++) {
+        char copy[256];
+        snprintf(copy, sizeof(copy),
+            "COPY employees TO '%s' STORED AS CSV OPTIONS ('format.compression' '%s')", csv_paths[i], compressions[i]);
+        char json_table[32], csv_table[32];
+        snprintf(json_table, sizeof(json_table), "json_%s", compressions[i]);
+        snprintf(csv_table, sizeof(csv_table), "csv_%s", compressions[i]);
+        
+        // No compression given: it is inferred from the extension of each file
+        if (datafusion_result_write_ndjson(result, json_paths[i], compressions[i], NULL) != DATAFUSION_OK ||
+            datafusion_execute_update(ctx, copy, NULL, NULL) != DATAFUSION_OK ||
+            datafusion_register_json(ctx, json_table, json_paths[i], NULL, NULL) != DATAFUSION_OK ||
+            datafusion_register_csv(ctx, csv_table, csv_paths[i], NULL) != DATAFUSION_OK) {
+            printf("FAILED: Could not write and register %s files: %s\n", compressions[i], datafusion_get_last_error());
+            failed = 1;
+            continue;
+        }
+        
+        char sql[128];
+        snprintf(sql, sizeof(sql), "SELECT (SELECT sum(salary) FROM %s), (SELECT sum(salary) FROM %s)", json_table, csv_table);
+        DataFusionResult* sums = datafusion_sql(ctx, sql, NULL);
+        int64_t json_sum = 0, csv_sum = 0;
+        if (!sums || datafusion_result_get_int64(sums, 0, 0, 0, &json_sum, NULL) != DATAFUSION_OK || json_sum != 360000 ||
+            datafusion_result_get_int64(sums, 0, 0, 1, &csv_sum, NULL) != DATAFUSION_OK || csv_sum != 360000) {
+            printf("FAILED: Expected salaries totalling 360000 from %s files: %s\n", compressions[i], datafusion_get_last_error());
+            failed = 1;
+        }
+        datafusion_result_free(sums);
+    }
+    
+    // An explicit compression takes precedence over the extension
+    DataFusionJsonReadOptions options = datafusion_json_read_options_default();
+    options.compression = "uncompressed";
+    if (datafusion_register_json(ctx, "misread", json_paths[0], &options, NULL) == DATAFUSION_OK) {
+        DataFusionResult* misread = datafusion_sql(ctx, "SELECT * FROM misread", NULL);
+        if (misread) {
+            printf("FAILED: Expected gzip bytes read as uncompressed JSON to fail\n");
+            failed = 1;
+        }
+        datafusion_result_free(misread);
+    }
+    
+    datafusion_result_free(result);
+    datafusion_context_free(ctx);
+    for (int i = 0; i < 4; i++) {
+        unlink(json_paths[i]);
+        unlink(csv_paths[i]);
+    }
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: gzip, bzip2, xz and zstd files read without naming their compression\n");
+    return 0;
+}
+
+int test_parquet_scan_options() {
+    printf("Test 99: Parquet scan options\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    // Names written as binary, as by writers that do not mark strings as UTF-8
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK ||
+        datafusion_execute_update(ctx,
+            "COPY (SELECT id, arrow_cast(name, 'Binary') AS name, salary FROM employees) "
+            "TO 'test_scan_options.parquet' STORED AS PARQUET", NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write Parquet file: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    DataFusionParquetReadOptions options = datafusion_parquet_read_options_default();
+    if (!options.page_index || !options.bloom_filters || options.binary_as_string || options.metadata_size_hint != 0) {
+        printf("FAILED: Unexpected default scan options\n");
+        failed = 1;
+    }
+    options.binary_as_string = true;
+    options.page_index = false;
+    options.bloom_filters = false;
+    options.metadata_size_hint = 512 * 1024;
+    if (datafusion_register_parquet(ctx, "binary", "test_scan_options.parquet", NULL, NULL) != DATAFUSION_OK ||
+        datafusion_register_parquet(ctx, "strings", "test_scan_options.parquet", &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register Parquet file: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT (SELECT arrow_typeof(name) FROM binary LIMIT 1), (SELECT arrow_typeof(name) FROM strings LIMIT 1), "
+        "(SELECT sum(salary) FROM strings WHERE upper(name) LIKE 'E%')", NULL);
+    char* binary_type = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    char* string_type = result ? datafusion_result_get_string_copy(result, 0, 0, 1, NULL) : NULL;
+    int64_t sum = 0;
+    if (!binary_type || strstr(binary_type, "Binary") == NULL || !string_type || strstr(string_type, "Utf8") == NULL ||
+        datafusion_result_get_int64(result, 0, 0, 2, &sum, NULL) != DATAFUSION_OK || sum != 80000) {
+        printf("FAILED: Expected binary names read as strings: %s, %s: %s\n", binary_type ? binary_type : "(null)",
+            string_type ? string_type : "(null)", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(binary_type);
+    datafusion_string_free(string_type);
+    datafusion_result_free(result);
+    
+    datafusion_context_free(ctx);
+    unlink("test_scan_options.parquet");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Binary columns read as strings with page index and bloom filters disabled\n");
+    return 0;
+}
+
+int test_listing_table_sort_order() {
+    printf("Test 100: Declared file sort order\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK ||
+        datafusion_execute_update(ctx,
+            "COPY (SELECT * FROM employees ORDER BY salary) TO 'test_sorted_dir/part.parquet' STORED AS PARQUET",
+            NULL, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not write sorted Parquet file: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    int failed = 0;
+    const char* sort_columns[] = {"salary"};
+    bool ascending[] = {true};
+    bool nulls_first[] = {false};
+    DataFusionListingTableOptions options = datafusion_listing_table_options_default();
+    options.sort_columns = sort_columns;
+    options.sort_ascending = ascending;
+    options.sort_nulls_first = nulls_first;
+    options.sort_column_count = 1;
+    if (datafusion_register_listing_table(ctx, "unsorted", "test_sorted_dir/", DATAFUSION_FORMAT_PARQUET, NULL, NULL) != DATAFUSION_OK ||
+        datafusion_register_listing_table(ctx, "sorted", "test_sorted_dir/", DATAFUSION_FORMAT_PARQUET, &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register listing tables: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    // Sorting by the declared order needs no sort
+    char* unsorted_plan = NULL;
+    char* sorted_plan = NULL;
+    if (datafusion_explain(ctx, "SELECT name FROM unsorted ORDER BY salary", false, NULL, &unsorted_plan, NULL) != DATAFUSION_OK ||
+        datafusion_explain(ctx, "SELECT name FROM sorted ORDER BY salary", false, NULL, &sorted_plan, NULL) != DATAFUSION_OK ||
+        strstr(unsorted_plan, "SortExec") == NULL || strstr(sorted_plan, "SortExec") != NULL) {
+        printf("FAILED: Expected a sort only without a declared order:\n%s\n%s\n",
+            unsorted_plan ? unsorted_plan : datafusion_get_last_error(), sorted_plan ? sorted_plan : "");
+        failed = 1;
+    }
+    datafusion_string_free(unsorted_plan);
+    datafusion_string_free(sorted_plan);
+    
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT name FROM sorted ORDER BY salary LIMIT 1", NULL);
+    char* name = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!name || strcmp(name, "David") != 0) {
+        printf("FAILED: Expected David to have the lowest salary: %s\n", name ? name : datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(name);
+    datafusion_result_free(result);
+    
+    sort_columns[0] = "missing";
+    if (datafusion_register_listing_table(ctx, "bad_order", "test_sorted_dir/", DATAFUSION_FORMAT_PARQUET, &options, NULL) != DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected an unknown sort column to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    system("rm -rf test_sorted_dir");
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Sorts satisfied by the declared file order skipped\n");
+    return 0;
+}
+
+int test_views() {
+    printf("Test 101: Views\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx || datafusion_register_csv(ctx, "employees", test_csv_path, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register CSV: %s\n", datafusion_get_last_error());
+        datafusion_context_free(ctx);
+        cleanup_test_files();
+        return -1;
+    }
+    
+    // One view registered through the API and one created with SQL on top of it
+    DataFusionResult* created = NULL;
+    if (datafusion_register_view(ctx, "engineers", "SELECT name, salary FROM employees WHERE department = 'Engineering'", NULL) != DATAFUSION_OK ||
+        !(created = datafusion_sql(ctx, "CREATE VIEW top_engineers AS SELECT name FROM engineers WHERE salary > 76000", NULL))) {
+        printf("FAILED: Could not create views: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(created);
+    
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT (SELECT sum(salary) FROM engineers), (SELECT count(*) FROM top_engineers)", NULL);
+    int64_t sum = 0, count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &sum, NULL) != DATAFUSION_OK || sum != 240000 ||
+        datafusion_result_get_int64(result, 0, 0, 1, &count, NULL) != DATAFUSION_OK || count != 2) {
+        printf("FAILED: Expected engineers earning 240000, 2 of them over 76000: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    DataFusionStringList* names = datafusion_table_names(ctx, NULL, NULL, NULL);
+    if (!names || datafusion_string_list_count(names, NULL) != 3) {
+        printf("FAILED: Expected views to be listed with the tables\n");
+        failed = 1;
+    }
+    datafusion_string_list_free(names);
+    
+    // Views are defined by queries, and their names are unique among tables
+    if (datafusion_register_view(ctx, "engineers", "SELECT 1", NULL) != DATAFUSION_ALREADY_EXISTS ||
+        datafusion_register_view(ctx, "created", "CREATE TABLE created AS SELECT 1", NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_table_exists(ctx, "created", NULL) ||
+        datafusion_register_view(ctx, "missing", "SELECT * FROM no_such_table", NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected duplicate names, statements and unknown tables to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Views registered and created with SQL\n");
+    return 0;
+}
+
+int test_ddl_configuration() {
+    printf("Test 102: CREATE EXTERNAL TABLE and disabling DDL\n");
+    
+    if (create_test_csv() != 0) {
+        return -1;
+    }
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    int failed = 0;
+    if (!ctx) {
+        printf("FAILED: Could not create context: %s\n", datafusion_get_last_error());
+        cleanup_test_files();
+        return -1;
+    }
+    
+    // A CSV file registered from SQL with format options, then queried like any other table
+    char ddl[512];
+    snprintf(ddl, sizeof(ddl),
+        "CREATE EXTERNAL TABLE staff STORED AS CSV LOCATION '%s' OPTIONS ('format.has_header' 'true')", test_csv_path);
+    DataFusionResult* created = datafusion_sql(ctx, ddl, NULL);
+    datafusion_result_free(created);
+    DataFusionResult* result = created ? datafusion_sql(ctx, "SELECT sum(salary) FROM staff WHERE department = 'Engineering'", NULL) : NULL;
+    int64_t sum = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &sum, NULL) != DATAFUSION_OK || sum != 240000) {
+        printf("FAILED: Expected the external table to be queried: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // With DDL and DML forbidden, SQL can no longer reach files, while existing tables stay queryable
+    if (datafusion_context_set_allow_ddl(ctx, false, NULL) != DATAFUSION_OK ||
+        datafusion_context_set_allow_dml(ctx, false, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not forbid DDL and DML: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    const char* forbidden[] = {
+        "CREATE EXTERNAL TABLE other STORED AS CSV LOCATION 'test_employees.csv'",
+        "CREATE VIEW engineers AS SELECT * FROM staff",
+        "DROP TABLE staff",
+        "COPY staff TO 'test_ddl_copy.csv' STORED AS CSV",
+    };
+    for (size_t i = 0; i < sizeof(forbidden) / sizeof(forbidden[0]); i++) {
+        result = datafusion_sql(ctx, forbidden[i], NULL);
+        if (result || !strstr(datafusion_get_last_error(), "not supported")) {
+            printf("FAILED: Expected \"%s\" to be forbidden\n", forbidden[i]);
+            failed = 1;
+        }
+        datafusion_result_free(result);
+    }
+    char* diagnostics = datafusion_sql_validate(ctx, forbidden[0], NULL);
+    if (!diagnostics || !strstr(diagnostics, "\"valid\":false")) {
+        printf("FAILED: Expected validation to report forbidden DDL\n");
+        failed = 1;
+    }
+    datafusion_string_free(diagnostics);
+    uint8_t* substrait = NULL;
+    size_t substrait_len = 0;
+    if (datafusion_sql_to_substrait(ctx, "DROP TABLE staff", &substrait, &substrait_len, NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected producing a Substrait plan of forbidden DDL to fail\n");
+        datafusion_buffer_free(substrait, substrait_len);
+        failed = 1;
+    }
+    
+    result = datafusion_sql(ctx, "SELECT count(*) FROM staff", NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 5 ||
+        !datafusion_table_exists(ctx, "staff", NULL) || datafusion_table_exists(ctx, "other", NULL)) {
+        printf("FAILED: Expected queries to run with DDL forbidden: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // Allowed again, DDL runs as before
+    datafusion_context_set_allow_ddl(ctx, true, NULL);
+    result = datafusion_sql(ctx, "DROP TABLE staff", NULL);
+    if (!result || datafusion_table_exists(ctx, "staff", NULL)) {
+        printf("FAILED: Expected DROP TABLE to run once DDL is allowed: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    datafusion_context_free(ctx);
+    cleanup_test_files();
+    remove("test_ddl_copy.csv");
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: External table created with SQL and DDL forbidden\n");
+    return 0;
+}
+
+int test_insert_into_memory_tables() {
+    printf("Test 103: Inserting into in-memory tables\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionTableBuilder* builder = datafusion_table_builder_new(NULL);
+    int failed = 0;
+    int64_t ids[] = {1, 2};
+    int32_t offsets[] = {0, 3, 6};
+    if (!ctx || !builder ||
+        datafusion_table_builder_add_int64(builder, "id", ids, NULL, 2, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_add_string(builder, "code", offsets, (const uint8_t*)"USDEUR", NULL, 2, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_register(builder, ctx, "currencies", NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register table: %s\n", datafusion_get_last_error());
+        datafusion_table_builder_free(builder);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    datafusion_table_builder_free(builder);
+    
+    // Rows inserted with SQL, counted like any other update
+    int64_t inserted = 0;
+    if (datafusion_execute_update(ctx, "INSERT INTO currencies VALUES (3, 'GBP'), (4, NULL)", &inserted, NULL) != DATAFUSION_OK ||
+        inserted != 2 ||
+        datafusion_execute_update(ctx, "INSERT INTO currencies (code, id) SELECT 'JPY', max(id) + 1 FROM currencies", &inserted, NULL) != DATAFUSION_OK ||
+        inserted != 1) {
+        printf("FAILED: Expected 2 and 1 rows inserted with SQL: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    // Rows inserted from C arrays, matched to the table's columns by name
+    builder = datafusion_table_builder_new(NULL);
+    int32_t code_offsets[] = {0, 3};
+    int64_t next_id[] = {6};
+    inserted = 0;
+    if (datafusion_table_builder_add_string(builder, "code", code_offsets, (const uint8_t*)"CHF", NULL, 1, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_add_int64(builder, "id", next_id, NULL, 1, NULL) != DATAFUSION_OK ||
+        datafusion_table_builder_insert(builder, ctx, "currencies", &inserted, NULL) != DATAFUSION_OK || inserted != 1) {
+        printf("FAILED: Expected a row inserted from C arrays: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_table_builder_free(builder);
+    
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT count(*), count(code), sum(id), max(CASE WHEN id = 6 THEN code END) FROM currencies", NULL);
+    int64_t count = 0, codes = 0, sum = 0;
+    const char* last = NULL;
+    size_t last_len = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 6 ||
+        datafusion_result_get_int64(result, 0, 0, 1, &codes, NULL) != DATAFUSION_OK || codes != 5 ||
+        datafusion_result_get_int64(result, 0, 0, 2, &sum, NULL) != DATAFUSION_OK || sum != 21 ||
+        datafusion_result_get_string(result, 0, 0, 3, &last, &last_len, NULL) != DATAFUSION_OK ||
+        last_len != 3 || memcmp(last, "CHF", 3) != 0) {
+        printf("FAILED: Expected 6 rows, 5 codes and ids summing to 21: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // Tables registered from CSV in memory accept inserts too
+    const char* csv = "id,rate\n1,1.0\n";
+    if (datafusion_register_csv_bytes(ctx, "rates", (const uint8_t*)csv, strlen(csv), NULL, NULL) != DATAFUSION_OK ||
+        datafusion_execute_update(ctx, "INSERT INTO rates VALUES (2, 0.9)", &inserted, NULL) != DATAFUSION_OK ||
+        inserted != 1) {
+        printf("FAILED: Expected a row inserted into CSV registered from memory: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    
+    // Mismatched columns, unknown tables and overwrites are rejected
+    builder = datafusion_table_builder_new(NULL);
+    datafusion_table_builder_add_int64(builder, "id", next_id, NULL, 1, NULL);
+    if (datafusion_table_builder_insert(builder, ctx, "currencies", NULL, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_table_builder_insert(builder, ctx, "missing", NULL, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_execute_update(ctx, "INSERT OVERWRITE currencies VALUES (1, 'USD')", NULL, NULL) == DATAFUSION_OK) {
+        printf("FAILED: Expected invalid inserts to be rejected\n");
+        failed = 1;
+    }
+    datafusion_table_builder_free(builder);
+    
+    datafusion_context_free(ctx);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Rows inserted into in-memory tables with SQL and from C arrays\n");
+    return 0;
+}
+
+// A minimal Arrow Flight server over HTTP/2 without TLS, answering Handshake, GetFlightInfo and
+// DoGet on one stream per connection. It streams one IPC stream for the ticket "trades", which
+// Flight SQL queries of the trades table return, to calls with the bearer token that Handshake
+// returns for the user "user" with the password "secret".
+typedef struct {
+    int listener;
+    const uint8_t* ipc;
+    size_t ipc_len;
+    pthread_t connections[32];
+    int connection_count;
+} FlightServer;
+
+typedef struct {
+    FlightServer* server;
+    int client;
+} FlightConnection;
+
+// Code lengths of the HPACK Huffman code, by symbol. The code is canonical: codes of each length
+// are consecutive, in the order of their symbols, and follow the shorter ones.
+static const uint8_t hpack_code_lengths[256] = {
+    13, 23, 28, 28, 28, 28, 28, 28, 28, 24, 30, 28, 28, 30, 28, 28, 28, 28, 28, 28, 28, 28, 30, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    6, 10, 10, 12, 13, 6, 8, 11, 10, 10, 8, 11, 8, 6, 6, 6, 5, 5, 5, 6, 6, 6, 6, 6, 6, 6, 7, 8, 15, 6, 12, 10,
+    13, 6, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 8, 7, 8, 13, 19, 13, 14, 6,
+    15, 5, 6, 5, 6, 5, 6, 6, 6, 5, 7, 7, 6, 6, 6, 5, 6, 7, 6, 5, 5, 6, 7, 7, 7, 7, 7, 15, 11, 14, 13, 28,
+    20, 22, 20, 20, 22, 22, 22, 23, 22, 23, 23, 23, 23, 23, 24, 23, 24, 24, 22, 23, 24, 23, 23, 23, 23, 21, 22, 23, 22, 23, 23, 24,
+    22, 21, 20, 22, 22, 23, 23, 21, 23, 22, 22, 24, 21, 22, 23, 23, 21, 21, 22, 21, 23, 22, 23, 23, 20, 22, 22, 22, 23, 22, 22, 23,
+    26, 26, 20, 19, 22, 23, 22, 25, 26, 26, 26, 27, 27, 26, 24, 25, 19, 21, 26, 27, 27, 26, 27, 24, 21, 21, 26, 26, 28, 27, 27, 27,
+    20, 24, 20, 21, 22, 21, 21, 23, 22, 22, 25, 25, 24, 24, 26, 23, 26, 27, 26, 26, 27, 27, 27, 27, 27, 28, 27, 27, 27, 27, 27, 26,
+};
+
+static void huffman_decode(const uint8_t* in, size_t len, char* out, size_t out_size) {
+    uint32_t codes[256];
+    uint32_t code = 0;
+    for (int bits = 1; bits <= 30; bits++) {
+        for (int symbol = 0; symbol < 256; symbol++) {
+            if (hpack_code_lengths[symbol] == bits) {
+                codes[symbol] = code++;
+            }
+        }
+        code <<= 1;
+    }
+    size_t written = 0;
+    int bits = 0;
+    code = 0;
+    for (size_t i = 0; i < len * 8 && written + 1 < out_size; i++) {
+        code = code << 1 | ((in[i / 8] >> (7 - i % 8)) & 1);
+        bits++;
+        for (int symbol = 0; symbol < 256; symbol++) {
+            if (hpack_code_lengths[symbol] == bits && codes[symbol] == code) {
+                out[written++] = (char)symbol;
+                code = 0;
+                bits = 0;
+                break;
+            }
+        }
+    }
+    out[written] = '\0';
+}
+
+// Decode an HPACK integer with a prefix of `bits` bits
+static size_t hpack_int(const uint8_t* data, size_t len, size_t* pos, int bits) {
+    size_t max = (1u << bits) - 1;
+    size_t value = data[(*pos)++] & max;
+    if (value < max) {
+        return value;
+    }
+    for (int shift = 0; *pos < len; shift += 7) {
+        uint8_t byte = data[(*pos)++];
+        value += (size_t)(byte & 0x7f) << shift;
+        if (!(byte & 0x80)) {
+            break;
+        }
+    }
+    return value;
+}
+
+static void hpack_string(const uint8_t* data, size_t len, size_t* pos, char* out, size_t out_size) {
+    int huffman = data[*pos] & 0x80;
+    size_t string_len = hpack_int(data, len, pos, 7);
+    if (string_len > len - *pos) {
+        string_len = len - *pos;
+    }
+    if (huffman) {
+        huffman_decode(data + *pos, string_len, out, out_size);
+    } else {
+        size_t copied = string_len < out_size - 1 ? string_len : out_size - 1;
+        memcpy(out, data + *pos, copied);
+        out[copied] = '\0';
+    }
+    *pos += string_len;
+}
+
+// The headers of a request the server reads, with the dynamic table of its connection, newest first
+typedef struct {
+    char path[128];
+    char authorization[128];
+    char table[16][2][128];
+    int table_size;
+} FlightRequestHeaders;
+
+// The name or value of an indexed header field. Static entries are only resolved for the names the
+// server reads.
+static const char* hpack_indexed(FlightRequestHeaders* headers, size_t index, int value) {
+    if (index > 61) {
+        return index - 62 < (size_t)headers->table_size ? headers->table[index - 62][value] : "";
+    }
+    if (value) {
+        return "";
+    }
+    return index == 4 || index == 5 ? ":path" : index == 23 ? "authorization" : "";
+}
+
+static void hpack_decode(FlightRequestHeaders* headers, const uint8_t* block, size_t len) {
+    size_t pos = 0;
+    while (pos < len) {
+        char name[128], value[128];
+        uint8_t first = block[pos];
+        if (first & 0x80) {
+            size_t index = hpack_int(block, len, &pos, 7);
+            snprintf(name, sizeof(name), "%s", hpack_indexed(headers, index, 0));
+            snprintf(value, sizeof(value), "%s", hpack_indexed(headers, index, 1));
+        } else if ((first & 0xe0) == 0x20) {
+            hpack_int(block, len, &pos, 5);
+            continue;
+        } else {
+            int indexing = (first & 0xc0) == 0x40;
+            size_t index = hpack_int(block, len, &pos, indexing ? 6 : 4);
+            if (index) {
+                snprintf(name, sizeof(name), "%s", hpack_indexed(headers, index, 0));
+            } else {
+                hpack_string(block, len, &pos, name, sizeof(name));
+            }
+            hpack_string(block, len, &pos, value, sizeof(value));
+            if (indexing) {
+                int kept = headers->table_size < 16 ? headers->table_size : 15;
+                memmove(headers->table[1], headers->table[0], kept * sizeof(headers->table[0]));
+                memcpy(headers->table[0][0], name, sizeof(name));
+                memcpy(headers->table[0][1], value, sizeof(value));
+                headers->table_size = kept + 1;
+            }
+        }
+        if (strcmp(name, ":path") == 0) {
+            memcpy(headers->path, value, sizeof(value));
+        } else if (strcmp(name, "authorization") == 0) {
+            memcpy(headers->authorization, value, sizeof(value));
+        }
+    }
+}
+
+static int read_exact(int fd, uint8_t* buf, size_t len) {
+    while (len > 0) {
+        ssize_t received = recv(fd, buf, len, 0);
+        if (received <= 0) {
+            return -1;
+        }
+        buf += received;
+        len -= received;
+    }
+    return 0;
+}
+
+static void send_frame(int fd, int type, int flags, uint32_t stream, const uint8_t* payload, size_t len) {
+    uint8_t header[9] = {
+        (uint8_t)(len >> 16), (uint8_t)(len >> 8), (uint8_t)len, (uint8_t)type, (uint8_t)flags,
+        (uint8_t)((stream >> 24) & 0x7f), (uint8_t)(stream >> 16), (uint8_t)(stream >> 8), (uint8_t)stream,
+    };
+    send(fd, header, sizeof(header), MSG_NOSIGNAL);
+    if (len > 0) {
+        send(fd, payload, len, MSG_NOSIGNAL);
+    }
+}
+
+// Append a header field literal without indexing, with a new name and neither string Huffman coded
+static size_t hpack_literal(uint8_t* out, const char* name, const char* value) {
+    size_t name_len = strlen(name), value_len = strlen(value);
+    out[0] = 0;
+    out[1] = (uint8_t)name_len;
+    memcpy(out + 2, name, name_len);
+    out[2 + name_len] = (uint8_t)value_len;
+    memcpy(out + 3 + name_len, value, value_len);
+    return 3 + name_len + value_len;
+}
+
+// Respond with gRPC messages, already framed, and a status. `header` names one more header and its
+// value, or is NULL.
+static void send_grpc_response(int fd, uint32_t stream, const char* const* header, const uint8_t* messages,
+                               size_t len, int status, const char* message) {
+    uint8_t block[512];
+    size_t block_len = hpack_literal(block, ":status", "200");
+    block_len += hpack_literal(block + block_len, "content-type", "application/grpc");
+    if (header) {
+        block_len += hpack_literal(block + block_len, header[0], header[1]);
+    }
+    send_frame(fd, 1, 0x4, stream, block, block_len);
+    for (size_t sent = 0; sent < len; sent += 16384) {
+        send_frame(fd, 0, 0, stream, messages + sent, len - sent < 16384 ? len - sent : 16384);
+    }
+    char code[8];
+    snprintf(code, sizeof(code), "%d", status);
+    block_len = hpack_literal(block, "grpc-status", code);
+    if (message) {
+        block_len += hpack_literal(block + block_len, "grpc-message", message);
+    }
+    send_frame(fd, 1, 0x5, stream, block, block_len);
+}
+
+static size_t put_varint(uint8_t* out, size_t value) {
+    size_t len = 0;
+    do {
+        out[len++] = (uint8_t)((value & 0x7f) | (value > 0x7f ? 0x80 : 0));
+        value >>= 7;
+    } while (value > 0);
+    return len;
+}
+
+// Append a length-delimited protobuf field
+static size_t put_field(uint8_t* out, size_t field, const uint8_t* bytes, size_t len) {
+    size_t pos = put_varint(out, field << 3 | 2);
+    pos += put_varint(out + pos, len);
+    memcpy(out + pos, bytes, len);
+    return pos + len;
+}
+
+// Frame a gRPC message: uncompressed, after its big-endian length
+static size_t put_grpc_frame(uint8_t* out, const uint8_t* message, size_t len) {
+    out[0] = 0;
+    out[1] = (uint8_t)(len >> 24);
+    out[2] = (uint8_t)(len >> 16);
+    out[3] = (uint8_t)(len >> 8);
+    out[4] = (uint8_t)len;
+    memmove(out + 5, message, len);
+    return 5 + len;
+}
+
+static uint32_t read_le32(const uint8_t* data) {
+    return (uint32_t)data[0] | (uint32_t)data[1] << 8 | (uint32_t)data[2] << 16 | (uint32_t)data[3] << 24;
+}
+
+// The bodyLength of an IPC Message flatbuffer: its fourth field, after version, header_type and
+// header, read through the table's vtable
+static size_t ipc_body_length(const uint8_t* metadata) {
+    uint32_t table = read_le32(metadata);
+    uint32_t vtable = table - read_le32(metadata + table);
+    uint16_t vtable_len = metadata[vtable] | metadata[vtable + 1] << 8;
+    uint16_t offset = vtable_len >= 12 ? (metadata[vtable + 10] | metadata[vtable + 11] << 8) : 0;
+    return offset ? (size_t)read_le32(metadata + table + offset) : 0;
+}
+
+static void handle_flight_call(FlightServer* server, int fd, uint32_t stream, FlightRequestHeaders* headers,
+                               const uint8_t* body, size_t body_len) {
+    const uint8_t* request = body + 5;
+    size_t request_len = body_len >= 5 ? body_len - 5 : 0;
+    const char* method = strrchr(headers->path, '/');
+    method = method ? method + 1 : "";
+    
+    if (strcmp(method, "Handshake") == 0) {
+        if (strcmp(headers->authorization, "Basic dXNlcjpzZWNyZXQ=") != 0) {
+            send_grpc_response(fd, stream, NULL, NULL, 0, 16, "invalid user name or password");
+            return;
+        }
+        const char* token[] = {"authorization", "Bearer token-1"};
+        const uint8_t empty_response[5] = {0};
+        send_grpc_response(fd, stream, token, empty_response, sizeof(empty_response), 0, NULL);
+        return;
+    }
+    if (strcmp(headers->authorization, "Bearer token-1") != 0) {
+        send_grpc_response(fd, stream, NULL, NULL, 0, 16, "invalid token");
+        return;
+    }
+    
+    uint8_t* response = malloc(2 * server->ipc_len + 256);
+    size_t response_len = 0;
+    if (strcmp(method, "GetFlightInfo") == 0) {
+        const char* query = "SELECT * FROM trades";
+        int known = 0;
+        for (size_t i = 0; i + strlen(query) <= request_len; i++) {
+            known = known || memcmp(request + i, query, strlen(query)) == 0;
+        }
+        if (!known) {
+            send_grpc_response(fd, stream, NULL, NULL, 0, 3, "unknown query");
+            free(response);
+            return;
+        }
+        // The schema is the first message of the IPC stream, with its continuation marker and length
+        uint8_t ticket[16], endpoint[32];
+        size_t ticket_len = put_field(ticket, 1, (const uint8_t*)"trades", 6);
+        size_t endpoint_len = put_field(endpoint, 1, ticket, ticket_len);
+        size_t info_len = put_field(response + 5, 1, server->ipc, 8 + read_le32(server->ipc + 4));
+        info_len += put_field(response + 5 + info_len, 3, endpoint, endpoint_len);
+        response_len = put_grpc_frame(response, response + 5, info_len);
+    } else if (strcmp(method, "DoGet") == 0) {
+        if (request_len != 8 || memcmp(request + 2, "trades", 6) != 0) {
+            send_grpc_response(fd, stream, NULL, NULL, 0, 5, "no such ticket");
+            free(response);
+            return;
+        }
+        // Each IPC message is sent as a FlightData of its metadata and body
+        size_t pos = 0;
+        while (pos + 8 <= server->ipc_len && read_le32(server->ipc + pos + 4) > 0) {
+            const uint8_t* metadata = server->ipc + pos + 8;
+            size_t metadata_len = read_le32(server->ipc + pos + 4);
+            size_t ipc_body_len = ipc_body_length(metadata);
+            uint8_t* data = malloc(metadata_len + ipc_body_len + 32);
+            size_t data_len = put_field(data, 2, metadata, metadata_len);
+            data_len += put_field(data + data_len, 1000, metadata + metadata_len, ipc_body_len);
+            response_len += put_grpc_frame(response + response_len, data, data_len);
+            free(data);
+            pos += 8 + metadata_len + ipc_body_len;
+        }
+    } else {
+        send_grpc_response(fd, stream, NULL, NULL, 0, 12, "unknown method");
+        free(response);
+        return;
+    }
+    send_grpc_response(fd, stream, NULL, response, response_len, 0, NULL);
+    free(response);
+}
+
+static void* serve_flight_connection(void* arg) {
+    FlightConnection* connection = (FlightConnection*)arg;
+    int fd = connection->client;
+    uint8_t preface[24];
+    if (read_exact(fd, preface, sizeof(preface)) == 0) {
+        send_frame(fd, 4, 0, 0, NULL, 0);
+        FlightRequestHeaders headers;
+        memset(&headers, 0, sizeof(headers));
+        uint8_t* body = malloc(65536);
+        size_t body_len = 0;
+        uint8_t* payload = malloc(16384);
+        uint8_t frame[9];
+        while (read_exact(fd, frame, sizeof(frame)) == 0) {
+            size_t len = (size_t)frame[0] << 16 | (size_t)frame[1] << 8 | frame[2];
+            int type = frame[3], flags = frame[4];
+            uint32_t stream = (uint32_t)(frame[5] & 0x7f) << 24 | (uint32_t)frame[6] << 16 | (uint32_t)frame[7] << 8 | frame[8];
+            if (len > 16384 || read_exact(fd, payload, len) != 0 || type == 7) {
+                break;
+            }
+            if (type == 4 && !(flags & 0x1)) {
+                send_frame(fd, 4, 0x1, 0, NULL, 0);
+            } else if (type == 6 && !(flags & 0x1)) {
+                send_frame(fd, 6, 0x1, 0, payload, len);
+            } else if (type == 1) {
+                hpack_decode(&headers, payload, len);
+            } else if (type == 0 && body_len + len <= 65536) {
+                memcpy(body + body_len, payload, len);
+                body_len += len;
+            }
+            if ((type == 0 || type == 1) && (flags & 0x1)) {
+                handle_flight_call(connection->server, fd, stream, &headers, body, body_len);
+                headers.path[0] = headers.authorization[0] = '\0';
+                body_len = 0;
+            }
+        }
+        free(payload);
+        free(body);
+    }
+    close(fd);
+    free(connection);
+    return NULL;
+}
+
+static void* serve_flight(void* arg) {
+    FlightServer* server = (FlightServer*)arg;
+    int client;
+    while ((client = accept(server->listener, NULL, NULL)) >= 0) {
+        FlightConnection* connection = malloc(sizeof(FlightConnection));
+        connection->server = server;
+        connection->client = client;
+        if (server->connection_count == 32 ||
+            pthread_create(&server->connections[server->connection_count], NULL, serve_flight_connection, connection) != 0) {
+            close(client);
+            free(connection);
+            continue;
+        }
+        server->connection_count++;
+    }
+    return NULL;
+}
+
+int test_flight_tables() {
+    printf("Test 104: Arrow Flight tables\n");
+    
+    // The server streams the IPC stream of a result
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    DataFusionResult* trades = ctx ? datafusion_sql(ctx,
+        "SELECT * FROM (VALUES (1, 'AAPL', 10.5), (2, 'MSFT', 20.25), (3, 'AAPL', 11.0)) AS t(id, symbol, price)",
+        NULL) : NULL;
+    uint8_t* ipc = NULL;
+    size_t ipc_len = 0;
+    if (!trades || datafusion_result_to_ipc(trades, DATAFUSION_IPC_STREAM, &ipc, &ipc_len, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not serialize the served rows: %s\n", datafusion_get_last_error());
+        datafusion_result_free(trades);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    datafusion_result_free(trades);
+    
+    FlightServer server = { socket(AF_INET, SOCK_STREAM, 0), ipc, ipc_len, {0}, 0 };
+    struct sockaddr_in address;
+    memset(&address, 0, sizeof(address));
+    address.sin_family = AF_INET;
+    address.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+    socklen_t address_len = sizeof(address);
+    pthread_t thread;
+    if (server.listener < 0 || bind(server.listener, (struct sockaddr*)&address, sizeof(address)) != 0 ||
+        listen(server.listener, 8) != 0 || getsockname(server.listener, (struct sockaddr*)&address, &address_len) != 0 ||
+        pthread_create(&thread, NULL, serve_flight, &server) != 0) {
+        printf("FAILED: Could not start Flight server\n");
+        datafusion_buffer_free(ipc, ipc_len);
+        datafusion_context_free(ctx);
+        return -1;
+    }
+    char uri[64];
+    snprintf(uri, sizeof(uri), "grpc://127.0.0.1:%d", ntohs(address.sin_port));
+    int failed = 0;
+    
+    // A ticket is redeemed by every scan, sending the bearer token with every call
+    DataFusionFlightOptions options = datafusion_flight_options_default();
+    options.token = "token-1";
+    const uint8_t* ticket = (const uint8_t*)"trades";
+    if (datafusion_register_flight_table(ctx, "trades", uri, ticket, 6, NULL, &options, NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register a Flight ticket: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    DataFusionResult* result = datafusion_sql(ctx,
+        "SELECT symbol, sum(price) AS total FROM trades GROUP BY symbol ORDER BY symbol", NULL);
+    double total = 0;
+    char* symbol = result ? datafusion_result_get_string_copy(result, 0, 0, 0, NULL) : NULL;
+    if (!result || datafusion_result_batch_num_rows(result, 0, NULL) != 2 || !symbol || strcmp(symbol, "AAPL") != 0 ||
+        datafusion_result_get_float64(result, 0, 0, 1, &total, NULL) != DATAFUSION_OK || total != 21.5) {
+        printf("FAILED: Expected the totals of the streamed trades: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_string_free(symbol);
+    datafusion_result_free(result);
+    
+    if (datafusion_register_flight_table(ctx, "trades", uri, ticket, 6, NULL, &options, NULL) != DATAFUSION_ALREADY_EXISTS) {
+        printf("FAILED: Expected a registered name to be rejected\n");
+        failed = 1;
+    }
+    
+    // Flight SQL queries are planned for every scan, after basic authentication returned the token
+    options = datafusion_flight_options_default();
+    options.username = "user";
+    options.password = "secret";
+    if (datafusion_register_flight_table(ctx, "remote_trades", uri, NULL, 0, "SELECT * FROM trades", &options,
+                                         NULL) != DATAFUSION_OK) {
+        printf("FAILED: Could not register a Flight SQL query: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    result = datafusion_sql(ctx, "SELECT count(*) FROM remote_trades r JOIN trades t ON r.id = t.id AND r.price = t.price",
+                            NULL);
+    int64_t count = 0;
+    if (!result || datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) != DATAFUSION_OK || count != 3) {
+        printf("FAILED: Expected the query's rows to join the ticket's: %s\n", datafusion_get_last_error());
+        failed = 1;
+    }
+    datafusion_result_free(result);
+    
+    // Errors of the server are reported with their gRPC status
+    options.password = "wrong";
+    char* error = NULL;
+    if (datafusion_register_flight_table(ctx, "denied", uri, NULL, 0, "SELECT * FROM trades", &options, &error) ==
+        DATAFUSION_OK || !error || !strstr(error, "UNAUTHENTICATED")) {
+        printf("FAILED: Expected a wrong password to be rejected: %s\n", error ? error : "no error");
+        failed = 1;
+    }
+    datafusion_string_free(error);
+    error = NULL;
+    options = datafusion_flight_options_default();
+    options.token = "token-1";
+    if (datafusion_register_flight_table(ctx, "missing", uri, (const uint8_t*)"nothing", 7, NULL, &options, &error) ==
+        DATAFUSION_OK || !error || !strstr(error, "NOT_FOUND")) {
+        printf("FAILED: Expected an unknown ticket to be rejected: %s\n", error ? error : "no error");
+        failed = 1;
+    }
+    datafusion_string_free(error);
+    if (datafusion_register_flight_table(ctx, "both", uri, ticket, 6, "SELECT 1", NULL, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_register_flight_table(ctx, "neither", uri, NULL, 0, NULL, NULL, NULL) != DATAFUSION_INVALID_ARGUMENT ||
+        datafusion_register_flight_table(ctx, "http", "http://127.0.0.1:1", ticket, 6, NULL, NULL, NULL) !=
+            DATAFUSION_INVALID_ARGUMENT) {
+        printf("FAILED: Expected invalid tickets, queries and URIs to be rejected\n");
+        failed = 1;
+    }
+    
+    datafusion_context_free(ctx);
+    shutdown(server.listener, SHUT_RDWR);
+    close(server.listener);
+    pthread_join(thread, NULL);
+    for (int i = 0; i < server.connection_count; i++) {
+        pthread_join(server.connections[i], NULL);
+    }
+    datafusion_buffer_free(ipc, ipc_len);
+    
+    if (failed) {
+        return -1;
+    }
+    
+    printf("PASSED: Flight tickets and Flight SQL queries queried as tables\n");
+    return 0;
+}
+
 int main() {
     printf("DataFusion C API Test Suite\n");
     printf("===========================\n\n");
@@ -360,6 +6983,297 @@ int main() {
     if (test_print_result() != 0) failed_tests++;
     printf("\n");
     
+    if (test_error_object() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_log_callback() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_context_log_filter() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_build_info() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_handle_validation() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_handle_tracking() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_metrics() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_schema() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_typed_getters() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_string_getters() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_is_null() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_binary_getter() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_temporal_getters() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_decimal_getters() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_column_buffers() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_export_batch() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_export_stream() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_to_ipc() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_to_csv() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_to_json() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_format() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_stats() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_slice() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_coalesce() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_schema_handle() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dictionary_columns() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_max_result_bytes() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_write_parquet() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_columns_by_name() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_write_ndjson() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_row_cursor() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_nested_values() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_display_values() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_column_statistics() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_result_take() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_streaming_query() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_async_query() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_query_cancellation() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_query_timeouts() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_prepared_statements() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_sql_script() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_collect_partitioned() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_execute_update() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_explain() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_explain_analyze() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_plan_graphviz() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_execute_substrait() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_sql_to_substrait() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_plan_serialization() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_sql_validate() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_query_registry() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_query_progress() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_max_result_rows() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_sql_to_dataframe() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_sql_with_params() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_filter() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_select() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_limit() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_join() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_aggregate() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_sort() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_union() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_distinct() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_with_column() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_stream() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_schema() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_write_parquet() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_copy_to() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_count() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_explain() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_cache() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_repartition() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_expr_builder() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_dataframe_window() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_parquet() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_json() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_avro() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_arrow() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_csv_with_options() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_with_schema() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_schema_inference() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_listing_table() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_parquet_partitions() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_arrow_stream() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_record_batch() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_table_builder() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_register_bytes() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_deregister_table() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_table_names() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_object_store_urls() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_compression_from_extension() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_parquet_scan_options() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_listing_table_sort_order() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_views() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_ddl_configuration() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_insert_into_memory_tables() != 0) failed_tests++;
+    printf("\n");
+    
+    if (test_flight_tables() != 0) failed_tests++;
+    printf("\n");
+    
     printf("===========================\n");
     if (failed_tests == 0) {
         printf("All tests PASSED! ✓\n");
@@ -7,7 +7,7 @@
 void test_iceberg_catalog() {
     printf("Testing Iceberg catalog creation...\n");
     
-    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test");
+    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test", NULL);
     assert(catalog != NULL);
     printf("✓ Catalog created successfully\n");
     
@@ -18,28 +18,28 @@ void test_iceberg_catalog() {
 void test_iceberg_schema() {
     printf("Testing Iceberg schema creation and field addition...\n");
     
-    IcebergSchema* schema = iceberg_schema_new();
+    IcebergSchema* schema = iceberg_schema_new(NULL);
     assert(schema != NULL);
     printf("✓ Schema created successfully\n");
     
     // Add various field types
-    bool result = iceberg_schema_add_long_field(schema, 1, "id", true);
+    bool result = iceberg_schema_add_long_field(schema, 1, "id", true, NULL);
     assert(result == true);
     printf("✓ Long field added successfully\n");
     
-    result = iceberg_schema_add_long_field(schema, 2, "customer_id", true);
+    result = iceberg_schema_add_long_field(schema, 2, "customer_id", true, NULL);
     assert(result == true);
     printf("✓ Second long field added successfully\n");
     
-    result = iceberg_schema_add_long_field(schema, 3, "product_id", true);
+    result = iceberg_schema_add_long_field(schema, 3, "product_id", true, NULL);
     assert(result == true);
     printf("✓ Third long field added successfully\n");
     
-    result = iceberg_schema_add_date_field(schema, 4, "date", true);
+    result = iceberg_schema_add_date_field(schema, 4, "date", true, NULL);
     assert(result == true);
     printf("✓ Date field added successfully\n");
     
-    result = iceberg_schema_add_int_field(schema, 5, "amount", true);
+    result = iceberg_schema_add_int_field(schema, 5, "amount", true, NULL);
     assert(result == true);
     printf("✓ Int field added successfully\n");
     
@@ -50,11 +50,11 @@ void test_iceberg_schema() {
 void test_iceberg_partition_spec() {
     printf("Testing Iceberg partition spec creation...\n");
     
-    IcebergPartitionSpec* spec = iceberg_partition_spec_new();
+    IcebergPartitionSpec* spec = iceberg_partition_spec_new(NULL);
     assert(spec != NULL);
     printf("✓ Partition spec created successfully\n");
     
-    bool result = iceberg_partition_spec_add_day_field(spec, 4, 1000, "day");
+    bool result = iceberg_partition_spec_add_day_field(spec, 4, 1000, "day", NULL);
     assert(result == true);
     printf("✓ Day partition field added successfully\n");
     
@@ -66,26 +66,26 @@ void test_iceberg_table_creation() {
     printf("Testing Iceberg table creation...\n");
     
     // Create catalog
-    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test");
+    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test", NULL);
     assert(catalog != NULL);
     
     // Create schema
-    IcebergSchema* schema = iceberg_schema_new();
+    IcebergSchema* schema = iceberg_schema_new(NULL);
     assert(schema != NULL);
     
-    iceberg_schema_add_long_field(schema, 1, "id", true);
-    iceberg_schema_add_long_field(schema, 2, "customer_id", true);
-    iceberg_schema_add_long_field(schema, 3, "product_id", true);
-    iceberg_schema_add_date_field(schema, 4, "date", true);
-    iceberg_schema_add_int_field(schema, 5, "amount", true);
+    iceberg_schema_add_long_field(schema, 1, "id", true, NULL);
+    iceberg_schema_add_long_field(schema, 2, "customer_id", true, NULL);
+    iceberg_schema_add_long_field(schema, 3, "product_id", true, NULL);
+    iceberg_schema_add_date_field(schema, 4, "date", true, NULL);
+    iceberg_schema_add_int_field(schema, 5, "amount", true, NULL);
     
     // Create partition spec
-    IcebergPartitionSpec* spec = iceberg_partition_spec_new();
+    IcebergPartitionSpec* spec = iceberg_partition_spec_new(NULL);
     assert(spec != NULL);
-    iceberg_partition_spec_add_day_field(spec, 4, 1000, "day");
+    iceberg_partition_spec_add_day_field(spec, 4, 1000, "day", NULL);
     
     // Create table
-    IcebergTable* table = iceberg_table_create("orders", "/test/orders", schema, spec, catalog, "test");
+    IcebergTable* table = iceberg_table_create("orders", "/test/orders", schema, spec, catalog, "test", NULL);
     assert(table != NULL);
     printf("✓ Iceberg table created successfully\n");
     
@@ -101,33 +101,33 @@ void test_datafusion_iceberg_integration() {
     printf("Testing DataFusion + Iceberg integration...\n");
     
     // Create DataFusion context
-    DataFusionContext* ctx = datafusion_context_new();
+    DataFusionContext* ctx = datafusion_context_new(NULL);
     assert(ctx != NULL);
     printf("✓ DataFusion context created\n");
     
     // Create Iceberg components
-    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test");
-    IcebergSchema* schema = iceberg_schema_new();
-    iceberg_schema_add_long_field(schema, 1, "id", true);
-    iceberg_schema_add_long_field(schema, 2, "customer_id", true);
-    iceberg_schema_add_long_field(schema, 3, "product_id", true);
-    iceberg_schema_add_date_field(schema, 4, "date", true);
-    iceberg_schema_add_int_field(schema, 5, "amount", true);
-    
-    IcebergPartitionSpec* spec = iceberg_partition_spec_new();
-    iceberg_partition_spec_add_day_field(spec, 4, 1000, "day");
-    
-    IcebergTable* table = iceberg_table_create("orders", "/test/orders", schema, spec, catalog, "test");
+    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test", NULL);
+    IcebergSchema* schema = iceberg_schema_new(NULL);
+    iceberg_schema_add_long_field(schema, 1, "id", true, NULL);
+    iceberg_schema_add_long_field(schema, 2, "customer_id", true, NULL);
+    iceberg_schema_add_long_field(schema, 3, "product_id", true, NULL);
+    iceberg_schema_add_date_field(schema, 4, "date", true, NULL);
+    iceberg_schema_add_int_field(schema, 5, "amount", true, NULL);
+    
+    IcebergPartitionSpec* spec = iceberg_partition_spec_new(NULL);
+    iceberg_partition_spec_add_day_field(spec, 4, 1000, "day", NULL);
+    
+    IcebergTable* table = iceberg_table_create("orders", "/test/orders", schema, spec, catalog, "test", NULL);
     assert(table != NULL);
     
     // Register table with DataFusion
-    int result = datafusion_register_iceberg_table(ctx, "orders", table);
+    int result = datafusion_register_iceberg_table(ctx, "orders", table, NULL);
     assert(result == DATAFUSION_OK);
     printf("✓ Iceberg table registered with DataFusion\n");
     
     // Try to execute a simple query (this might fail due to async nature, but registration should work)
     printf("Attempting to execute query...\n");
-    DataFusionResult* query_result = datafusion_sql(ctx, "SELECT COUNT(*) FROM orders");
+    DataFusionResult* query_result = datafusion_sql(ctx, "SELECT COUNT(*) FROM orders", NULL);
     if (query_result != NULL) {
         printf("✓ Query executed successfully\n");
         datafusion_result_free(query_result);
@@ -144,6 +144,75 @@ void test_datafusion_iceberg_integration() {
     printf("✓ All resources freed successfully\n");
 }
 
+void test_dataframe_write_iceberg() {
+    printf("Testing DataFrame writes into Iceberg...\n");
+    
+    DataFusionContext* ctx = datafusion_context_new(NULL);
+    assert(ctx != NULL);
+    
+    IcebergCatalog* catalog = iceberg_catalog_new_sql("sqlite://", "test", NULL);
+    IcebergSchema* schema = iceberg_schema_new(NULL);
+    iceberg_schema_add_long_field(schema, 1, "id", true, NULL);
+    iceberg_schema_add_date_field(schema, 2, "date", true, NULL);
+    iceberg_schema_add_int_field(schema, 3, "amount", true, NULL);
+    
+    IcebergPartitionSpec* spec = iceberg_partition_spec_new(NULL);
+    iceberg_partition_spec_add_day_field(spec, 2, 1000, "day", NULL);
+    
+    IcebergTable* table = iceberg_table_create("orders", "/test/orders", schema, spec, catalog, "test", NULL);
+    assert(table != NULL);
+    assert(datafusion_register_iceberg_table(ctx, "orders", table, NULL) == DATAFUSION_OK);
+    
+    // Columns are matched by name and cast to the table's types
+    DataFusionDataFrame* df = datafusion_sql_to_dataframe(ctx,
+        "SELECT * FROM (VALUES (10, 1, '2024-01-01'), (20, 2, '2024-01-02')) AS t(amount, id, date)", NULL);
+    assert(df != NULL);
+    assert(datafusion_dataframe_write_iceberg(df, table, DATAFUSION_WRITE_APPEND, NULL) == DATAFUSION_OK);
+    assert(datafusion_dataframe_write_iceberg(df, table, DATAFUSION_WRITE_APPEND, NULL) == DATAFUSION_OK);
+    
+    int64_t count = 0;
+    DataFusionResult* result = datafusion_sql(ctx, "SELECT count(*) FROM orders", NULL);
+    assert(result != NULL);
+    assert(datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) == DATAFUSION_OK && count == 4);
+    datafusion_result_free(result);
+    printf("✓ DataFrame appended to Iceberg table twice\n");
+    
+    DataFusionDataFrame* february = datafusion_sql_to_dataframe(ctx,
+        "SELECT value AS id, DATE '2024-02-01' AS date, CAST(value * 100 AS INT) AS amount FROM generate_series(1, 3)",
+        NULL);
+    assert(february != NULL);
+    assert(datafusion_dataframe_write_iceberg(february, table, DATAFUSION_WRITE_OVERWRITE, NULL) == DATAFUSION_OK);
+    
+    result = datafusion_sql(ctx, "SELECT count(*), min(date) FROM orders", NULL);
+    assert(result != NULL);
+    char* min_date = datafusion_result_get_display(result, 0, 0, 1, NULL);
+    assert(datafusion_result_get_int64(result, 0, 0, 0, &count, NULL) == DATAFUSION_OK && count == 3);
+    assert(min_date != NULL && strcmp(min_date, "2024-02-01") == 0);
+    datafusion_string_free(min_date);
+    datafusion_result_free(result);
+    printf("✓ Iceberg table overwritten with a DataFrame\n");
+    
+    // Columns missing from the table or the DataFrame are rejected
+    DataFusionDataFrame* incomplete = datafusion_sql_to_dataframe(ctx, "SELECT 1 AS id", NULL);
+    assert(datafusion_dataframe_write_iceberg(incomplete, table, DATAFUSION_WRITE_APPEND, NULL) == DATAFUSION_INVALID_ARGUMENT);
+    DataFusionDataFrame* extra = datafusion_dataframe_with_column(df, "note", "'x'", NULL);
+    assert(datafusion_dataframe_write_iceberg(extra, table, DATAFUSION_WRITE_APPEND, NULL) == DATAFUSION_INVALID_ARGUMENT);
+    assert(datafusion_dataframe_write_iceberg(df, table, 42, NULL) == DATAFUSION_INVALID_ARGUMENT);
+    printf("✓ Mismatched columns and write modes rejected\n");
+    
+    // Clean up
+    datafusion_dataframe_free(extra);
+    datafusion_dataframe_free(incomplete);
+    datafusion_dataframe_free(february);
+    datafusion_dataframe_free(df);
+    iceberg_table_free(table);
+    iceberg_partition_spec_free(spec);
+    iceberg_schema_free(schema);
+    iceberg_catalog_free(catalog);
+    datafusion_context_free(ctx);
+    printf("✓ All resources freed successfully\n");
+}
+
 int main() {
     printf("=== DataFusion C API Iceberg Tests ===\n\n");
     
@@ -162,6 +231,9 @@ int main() {
     test_datafusion_iceberg_integration();
     printf("\n");
     
+    test_dataframe_write_iceberg();
+    printf("\n");
+    
     printf("=== All tests completed! ===\n");
     return 0;
 } 
\ No newline at end of file